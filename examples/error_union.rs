@@ -0,0 +1,64 @@
+//! An error type that can hold any one of several error types without allocating
+//!
+//! The `From` implementation for all variants makes the union work with `?`.
+
+use std::{error::Error, fmt, num::ParseIntError, str::Utf8Error};
+use trait_union::trait_union;
+
+#[derive(Debug)]
+struct OutOfRange(i64);
+
+impl fmt::Display for OutOfRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} is not a valid port", self.0)
+    }
+}
+
+impl Error for OutOfRange {}
+
+trait_union! {
+    /// An error that occurred while parsing a port
+    union PortError: Error = Utf8Error | ParseIntError | OutOfRange;
+}
+
+impl<E: PortErrorVariant> From<E> for PortError {
+    fn from(error: E) -> Self {
+        PortError::new(error)
+    }
+}
+
+impl fmt::Debug for PortError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
+
+impl fmt::Display for PortError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&**self, f)
+    }
+}
+
+fn parse_port(bytes: &[u8]) -> Result<u16, PortError> {
+    let port: i64 = std::str::from_utf8(bytes)?.trim().parse()?;
+    match port {
+        1..=65535 => Ok(port as u16),
+        _ => Err(OutOfRange(port).into()),
+    }
+}
+
+fn main() {
+    assert_eq!(parse_port(b" 8080\n").unwrap(), 8080);
+    for input in [&b"\xff"[..], b"http", b"0", b"100000"] {
+        let error = parse_port(input).unwrap_err();
+        println!("{:?}: {:?}: {}", PortError::kind_of(&error), input, error);
+    }
+    assert_eq!(
+        PortError::kind_of(&parse_port(b"http").unwrap_err()),
+        PortErrorKind::ParseIntError
+    );
+    assert_eq!(
+        parse_port(b"0").unwrap_err().to_string(),
+        "0 is not a valid port"
+    );
+}
@@ -0,0 +1,52 @@
+//! A function that returns one of several iterator types without boxing them
+
+use std::{iter, ops::Range};
+use trait_union::trait_union;
+
+trait_union! {
+    /// The numbers selected by a [Selection]
+    union Numbers: Iterator<Item = u32> =
+        Range<u32> | iter::StepBy<Range<u32>> | iter::Once<u32> | iter::Empty<u32>;
+}
+
+impl Iterator for Numbers {
+    type Item = u32;
+
+    fn next(&mut self) -> Option<u32> {
+        (**self).next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (**self).size_hint()
+    }
+}
+
+/// Which numbers below a limit to select
+enum Selection {
+    All,
+    Even,
+    Only(u32),
+    None,
+}
+
+fn numbers(selection: Selection, limit: u32) -> Numbers {
+    match selection {
+        Selection::All => Numbers::new(0..limit),
+        Selection::Even => Numbers::new((0..limit).step_by(2)),
+        Selection::Only(n) if n < limit => Numbers::new(iter::once(n)),
+        Selection::Only(_) | Selection::None => Numbers::new(iter::empty()),
+    }
+}
+
+fn main() {
+    let sum = |numbers: Numbers| numbers.sum::<u32>();
+    assert_eq!(sum(numbers(Selection::All, 5)), 10);
+    assert_eq!(sum(numbers(Selection::Even, 5)), 6);
+    assert_eq!(sum(numbers(Selection::Only(3), 5)), 3);
+    assert_eq!(sum(numbers(Selection::Only(7), 5)), 0);
+    assert_eq!(sum(numbers(Selection::None, 5)), 0);
+
+    let even: Vec<_> = numbers(Selection::Even, 7).collect();
+    assert_eq!(even, [0, 2, 4, 6]);
+    println!("{:?}", even);
+}
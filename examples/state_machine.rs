@@ -0,0 +1,83 @@
+//! A traffic light whose states are stored in a trait-union instead of a `Box`
+//!
+//! Neither the states nor the machine allocate, so this also works in `no_std` crates.
+
+use trait_union::trait_union;
+
+trait State {
+    /// Returns the name of the state
+    fn name(&self) -> &'static str;
+
+    /// Advances the state by one tick and returns the next state if it changes
+    fn tick(&mut self) -> Option<Light>;
+}
+
+struct Red {
+    remaining: u8,
+}
+
+struct Green {
+    remaining: u8,
+}
+
+struct Yellow;
+
+trait_union! {
+    /// The state of a traffic light
+    union Light: State = Red | Green | Yellow;
+}
+
+impl State for Red {
+    fn name(&self) -> &'static str {
+        "red"
+    }
+
+    fn tick(&mut self) -> Option<Light> {
+        self.remaining -= 1;
+        match self.remaining {
+            0 => Some(Light::new(Green { remaining: 3 })),
+            _ => None,
+        }
+    }
+}
+
+impl State for Green {
+    fn name(&self) -> &'static str {
+        "green"
+    }
+
+    fn tick(&mut self) -> Option<Light> {
+        self.remaining -= 1;
+        match self.remaining {
+            0 => Some(Light::new(Yellow)),
+            _ => None,
+        }
+    }
+}
+
+impl State for Yellow {
+    fn name(&self) -> &'static str {
+        "yellow"
+    }
+
+    fn tick(&mut self) -> Option<Light> {
+        Some(Light::new(Red { remaining: 2 }))
+    }
+}
+
+fn main() {
+    let mut light = Light::new(Red { remaining: 2 });
+    let mut names = [""; 8];
+    for name in &mut names {
+        *name = light.name();
+        if let Some(next) = light.tick() {
+            light = next;
+        }
+    }
+    assert_eq!(
+        names,
+        ["red", "red", "green", "green", "green", "yellow", "red", "red"],
+    );
+    assert_eq!(Light::kind_of(&light), LightKind::Green);
+    println!("{}", names.join(" -> "));
+}
@@ -0,0 +1,23 @@
+use std::{env, process::Command};
+
+fn main() {
+    let rustc = env::var_os("RUSTC").unwrap_or_else(|| "rustc".into());
+    let minor = Command::new(rustc)
+        .arg("--version")
+        .output()
+        .ok()
+        .and_then(|output| String::from_utf8(output.stdout).ok())
+        .and_then(|version| version.split('.').nth(1)?.parse::<u32>().ok());
+    let minor = match minor {
+        Some(minor) => minor,
+        None => return,
+    };
+    // Older versions of cargo warn about unknown keys.
+    if minor >= 80 {
+        println!("cargo:rustc-check-cfg=cfg(trait_union_no_offset_of)");
+    }
+    // `core::mem::offset_of` was stabilized in 1.77.
+    if minor < 77 {
+        println!("cargo:rustc-cfg=trait_union_no_offset_of");
+    }
+}
@@ -0,0 +1,43 @@
+//! Prints the expansion of the trait-union macros in a file
+//!
+//! Install with `cargo install trait-union-proc --features inspect` and run as
+//! `cargo trait-union-inspect FILE`.
+
+#[allow(dead_code)]
+#[path = "../lib.rs"]
+mod trait_union_proc;
+
+use std::{env, fs, process};
+use trait_union_proc::inspect;
+
+fn main() {
+    let mut args = env::args().skip(1).peekable();
+    // Cargo passes the name of the subcommand as the first argument.
+    if args.peek().map(|a| &**a) == Some("trait-union-inspect") {
+        args.next();
+    }
+    let path = match (args.next(), args.next()) {
+        (Some(path), None) => path,
+        _ => {
+            eprintln!("usage: cargo trait-union-inspect FILE");
+            process::exit(2);
+        }
+    };
+    let source = match fs::read_to_string(&path) {
+        Ok(source) => source,
+        Err(e) => {
+            eprintln!("error: could not read {}: {}", path, e);
+            process::exit(1);
+        }
+    };
+    let mut file = match syn::parse_file(&source) {
+        Ok(file) => file,
+        Err(e) => {
+            eprintln!("error: could not parse {}: {}", path, e);
+            process::exit(1);
+        }
+    };
+    let summary = inspect::summary(&file.items);
+    inspect::expand_items(&mut file.items);
+    print!("{}{}", summary, prettyplease::unparse(&file));
+}
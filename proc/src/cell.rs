@@ -0,0 +1,77 @@
+use proc_macro2::TokenStream;
+use syn::{Generics, Ident, Visibility};
+
+/// Generates the swappable field of the `cell` option
+pub fn cell(
+    name: &Ident,
+    cell_name: &Ident,
+    variant_name: &Ident,
+    vis: &Visibility,
+    new_vis: &Visibility,
+    generics: &Generics,
+) -> TokenStream {
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let doc = format!(
+        "A [{}] that can be replaced through a shared reference\n\n\
+         Setting or replacing the value while it is borrowed by `with` panics.",
+        name
+    );
+    quote::quote! {
+        #[doc = #doc]
+        #vis struct #cell_name#impl_generics #where_clause {
+            value: ::core::cell::RefCell<#name#ty_generics>,
+        }
+
+        impl#impl_generics #cell_name#ty_generics #where_clause {
+            /// Creates a new cell holding `value`
+            #[inline]
+            #new_vis fn new<V: #variant_name#ty_generics>(value: V) -> Self {
+                Self {
+                    value: ::core::cell::RefCell::new(<#name#ty_generics>::new(value)),
+                }
+            }
+
+            /// Replaces the value and drops the old value
+            ///
+            /// The old value is dropped after it has been replaced, so its `Drop`
+            /// implementation can access the cell.
+            #[inline]
+            #new_vis fn set<V: #variant_name#ty_generics>(&self, value: V) {
+                ::core::mem::drop(self.replace(value));
+            }
+
+            /// Replaces the value and returns the old value
+            #[inline]
+            #new_vis fn replace<V: #variant_name#ty_generics>(&self, value: V) -> #name#ty_generics {
+                self.value.replace(<#name#ty_generics>::new(value))
+            }
+
+            /// Calls `f` with a mutable reference to the value
+            #[inline]
+            #vis fn with<R>(&self, f: impl ::core::ops::FnOnce(&mut #name#ty_generics) -> R) -> R {
+                f(&mut self.value.borrow_mut())
+            }
+
+            /// Returns a mutable reference to the value
+            #[inline]
+            #vis fn get_mut(&mut self) -> &mut #name#ty_generics {
+                self.value.get_mut()
+            }
+
+            /// Returns the value
+            #[inline]
+            #vis fn into_inner(self) -> #name#ty_generics {
+                self.value.into_inner()
+            }
+        }
+
+        impl#impl_generics ::core::convert::From<#name#ty_generics> for #cell_name#ty_generics #where_clause {
+            #[inline]
+            fn from(value: #name#ty_generics) -> Self {
+                Self {
+                    value: ::core::cell::RefCell::new(value),
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,34 @@
+use proc_macro2::TokenStream;
+use syn::{spanned::Spanned, Ident, Path, Token};
+
+/// The input of `assert_union_covers!(UNION: TRAIT)`
+pub struct AssertCovers {
+    union: Path,
+    trait_: Path,
+}
+
+/// Implements `assert_union_covers!`
+pub fn assert_covers(input: AssertCovers) -> TokenStream {
+    let AssertCovers { union, trait_ } = input;
+    let mut marker = union.clone();
+    let last = marker.segments.last_mut().unwrap();
+    last.ident = Ident::new(&format!("{}Variant", last.ident), last.ident.span());
+    quote::quote_spanned! { trait_.span() =>
+        const _: () = {
+            #[allow(dead_code)]
+            fn __trait_union_covers<T: ?::core::marker::Sized + #trait_>() {
+                fn registered<T: ?::core::marker::Sized + #marker>() {}
+                registered::<T>();
+            }
+        };
+    }
+}
+
+impl syn::parse::Parse for AssertCovers {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let union = input.parse()?;
+        let _t_colon = input.parse::<Token![:]>()?;
+        let trait_ = input.parse()?;
+        Ok(AssertCovers { union, trait_ })
+    }
+}
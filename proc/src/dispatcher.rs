@@ -0,0 +1,218 @@
+use proc_macro2::TokenStream;
+use syn::{punctuated::Punctuated, Generics, Ident, LitInt, Token, Type, Visibility};
+
+/// The `dispatcher(capacity = CAPACITY, method = METHOD, ctx = CTX)` option
+pub struct Dispatcher {
+    pub capacity: LitInt,
+    pub method: Ident,
+    pub ctx: Option<Type>,
+}
+
+/// Generates the ring buffer of the `dispatcher` option
+pub fn dispatcher(
+    name: &Ident,
+    dispatcher_name: &Ident,
+    vis: &Visibility,
+    generics: &Generics,
+    dispatcher: &Dispatcher,
+    no_deref: bool,
+) -> TokenStream {
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let capacity = &dispatcher.capacity;
+    let method = &dispatcher.method;
+    let target = match no_deref {
+        true => quote::quote!(value.as_dyn_mut()),
+        false => quote::quote!((**value)),
+    };
+    let (ctx_param, ctx_arg) = match &dispatcher.ctx {
+        Some(ctx) => (
+            Some(quote::quote!(ctx: &mut #ctx)),
+            Some(quote::quote!(ctx)),
+        ),
+        None => (None, None),
+    };
+    let doc = format!("A ring buffer of up to {} [{}]s", capacity, name);
+    let dispatch_doc = format!(
+        "Calls `{}` on every queued value from the oldest to the newest",
+        method
+    );
+    quote::quote! {
+        #[doc = #doc]
+        #vis struct #dispatcher_name#impl_generics #where_clause {
+            buf: [::core::mem::MaybeUninit<#name#ty_generics>; #capacity],
+            head: usize,
+            len: usize,
+        }
+
+        impl#impl_generics #dispatcher_name#ty_generics #where_clause {
+            /// The maximum number of queued values
+            #vis const CAPACITY: usize = #capacity;
+
+            /// Creates an empty dispatcher
+            #[inline]
+            #vis fn new() -> Self {
+                Self {
+                    // SAFETY: An array of `MaybeUninit` does not have to be initialized.
+                    buf: unsafe {
+                        ::core::mem::MaybeUninit::<
+                            [::core::mem::MaybeUninit<#name#ty_generics>; #capacity],
+                        >::uninit()
+                        .assume_init()
+                    },
+                    head: 0,
+                    len: 0,
+                }
+            }
+
+            /// Returns the number of queued values
+            #[inline]
+            #vis fn len(&self) -> usize {
+                self.len
+            }
+
+            /// Returns whether no value is queued
+            #[inline]
+            #vis fn is_empty(&self) -> bool {
+                self.len == 0
+            }
+
+            /// Returns whether `CAPACITY` values are queued
+            #[inline]
+            #vis fn is_full(&self) -> bool {
+                self.len == Self::CAPACITY
+            }
+
+            /// Appends a value to the queue
+            ///
+            /// The value is returned if the queue is full.
+            #[inline]
+            #vis fn push(
+                &mut self,
+                value: #name#ty_generics,
+            ) -> ::core::result::Result<(), #name#ty_generics> {
+                if self.is_full() {
+                    return ::core::result::Result::Err(value);
+                }
+                let pos = (self.head + self.len) % Self::CAPACITY;
+                self.buf[pos].write(value);
+                self.len += 1;
+                ::core::result::Result::Ok(())
+            }
+
+            /// Removes the oldest value from the queue
+            #[inline]
+            #vis fn pop(&mut self) -> ::core::option::Option<#name#ty_generics> {
+                if self.is_empty() {
+                    return ::core::option::Option::None;
+                }
+                // SAFETY: The `len` values starting at `head` are initialized.
+                let value = unsafe { self.buf[self.head].assume_init_read() };
+                self.head = (self.head + 1) % Self::CAPACITY;
+                self.len -= 1;
+                ::core::option::Option::Some(value)
+            }
+
+            /// Removes all values from the queue
+            #[inline]
+            #vis fn clear(&mut self) {
+                while self.pop().is_some() {}
+            }
+
+            #[doc = #dispatch_doc]
+            #[inline]
+            #vis fn dispatch_all(&mut self, #ctx_param) {
+                for i in 0..self.len {
+                    let pos = (self.head + i) % Self::CAPACITY;
+                    // SAFETY: The `len` values starting at `head` are initialized.
+                    let value = unsafe { self.buf[pos].assume_init_mut() };
+                    #target.#method(#ctx_arg);
+                }
+            }
+        }
+
+        impl#impl_generics ::core::default::Default for #dispatcher_name#ty_generics #where_clause {
+            #[inline]
+            fn default() -> Self {
+                Self::new()
+            }
+        }
+
+        impl#impl_generics ::core::ops::Drop for #dispatcher_name#ty_generics #where_clause {
+            fn drop(&mut self) {
+                self.clear();
+            }
+        }
+    }
+}
+
+impl syn::parse::Parse for Dispatcher {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let span = input.span();
+        let mut capacity = None;
+        let mut method = None;
+        let mut ctx = None;
+        let args = Punctuated::<DispatcherArg, Token![,]>::parse_terminated(input)?;
+        for arg in args {
+            let duplicate = match arg {
+                DispatcherArg::Capacity(name, value) => {
+                    (capacity.replace(value).is_some(), name)
+                }
+                DispatcherArg::Method(name, value) => {
+                    (method.replace(value).is_some(), name)
+                }
+                DispatcherArg::Ctx(name, value) => (ctx.replace(*value).is_some(), name),
+            };
+            if let (true, name) = duplicate {
+                return Err(syn::Error::new(
+                    name.span(),
+                    "duplicate dispatcher argument",
+                ));
+            }
+        }
+        let capacity = match capacity {
+            Some(capacity) => capacity,
+            None => {
+                return Err(syn::Error::new(
+                    span,
+                    "the dispatcher option requires a capacity",
+                ))
+            }
+        };
+        let method = match method {
+            Some(method) => method,
+            None => {
+                return Err(syn::Error::new(
+                    span,
+                    "the dispatcher option requires a method",
+                ))
+            }
+        };
+        Ok(Dispatcher {
+            capacity,
+            method,
+            ctx,
+        })
+    }
+}
+
+enum DispatcherArg {
+    Capacity(Ident, LitInt),
+    Method(Ident, Ident),
+    Ctx(Ident, Box<Type>),
+}
+
+impl syn::parse::Parse for DispatcherArg {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let name = input.parse::<Ident>()?;
+        let _t_equals = input.parse::<Token![=]>()?;
+        match &*name.to_string() {
+            "capacity" => Ok(DispatcherArg::Capacity(name, input.parse()?)),
+            "method" => Ok(DispatcherArg::Method(name, input.parse()?)),
+            "ctx" => Ok(DispatcherArg::Ctx(name, input.parse()?)),
+            _ => Err(syn::Error::new(
+                name.span(),
+                "expected capacity, method, or ctx",
+            )),
+        }
+    }
+}
@@ -0,0 +1,185 @@
+use super::located_at;
+use proc_macro2::{Span, TokenStream};
+use syn::{
+    parenthesized,
+    parse::{Parse, ParseStream},
+    punctuated::Punctuated,
+    GenericArgument, Generics, Ident, Lifetime, PathArguments, ReturnType, Token, Type,
+    TypeParamBound, WhereClause,
+};
+
+/// The `enum_dispatch(fn METHOD(...) -> OUTPUT; ...)` option
+pub struct EnumDispatch {
+    pub span: Span,
+    pub methods: Vec<Method>,
+}
+
+/// A method signature with a `&self` or `&mut self` receiver
+pub struct Method {
+    ident: Ident,
+    generics: Generics,
+    lifetime: Option<Lifetime>,
+    mutability: Option<Token![mut]>,
+    args: Vec<(Ident, Type)>,
+    output: ReturnType,
+}
+
+impl Parse for EnumDispatch {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let methods = Punctuated::<Method, Token![;]>::parse_terminated(input)?;
+        if methods.is_empty() {
+            return Err(
+                input.error("the enum_dispatch option requires at least one method")
+            );
+        }
+        Ok(EnumDispatch {
+            span: Span::call_site(),
+            methods: methods.into_iter().collect(),
+        })
+    }
+}
+
+impl Parse for Method {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let _t_fn = input.parse::<Token![fn]>()?;
+        let ident = input.parse::<Ident>()?;
+        let mut generics = input.parse::<Generics>()?;
+        let content;
+        parenthesized!(content in input);
+        let receiver_span = content.span();
+        let lifetime;
+        let mutability;
+        match content.parse::<Option<Token![&]>>()? {
+            Some(_) => {
+                lifetime = content.parse()?;
+                mutability = content.parse()?;
+                let _t_self = content.parse::<Token![self]>()?;
+            }
+            None => {
+                return Err(syn::Error::new(
+                    receiver_span,
+                    "expected `&self` or `&mut self`",
+                ))
+            }
+        }
+        let mut args = vec![];
+        while !content.is_empty() {
+            let _t_comma = content.parse::<Token![,]>()?;
+            if content.is_empty() {
+                break;
+            }
+            let arg = content.parse::<Ident>()?;
+            let _t_colon = content.parse::<Token![:]>()?;
+            args.push((arg, content.parse()?));
+        }
+        let output = input.parse()?;
+        if input.peek(Token![where]) {
+            generics.where_clause = Some(input.parse::<WhereClause>()?);
+        }
+        Ok(Method {
+            ident,
+            generics,
+            lifetime,
+            mutability,
+            args,
+            output,
+        })
+    }
+}
+
+/// Generates an implementation of the first trait of the bounds for the owned enum that
+/// forwards the methods of the `enum_dispatch` option to the variant with `match`
+///
+/// `indirect` contains whether a variant is boxed or flattened. Such variants are called
+/// through their `Deref` target.
+pub fn enum_dispatch(
+    owned_name: &Ident,
+    generics: &Generics,
+    trait_: &Punctuated<TypeParamBound, Token![+]>,
+    kind_variants: &[Ident],
+    indirect: &[bool],
+    has_phantom: bool,
+    enum_dispatch: &EnumDispatch,
+) -> TokenStream {
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let trait_path = trait_
+        .iter()
+        .find_map(|bound| match bound {
+            TypeParamBound::Trait(bound) => Some(&bound.path),
+            TypeParamBound::Lifetime(_) => None,
+        })
+        .unwrap();
+    // Associated type bindings become type items of the implementation. The remaining
+    // arguments are passed with a turbofish when calling the methods.
+    let mut impl_path = trait_path.clone();
+    let mut call_path = trait_path.clone();
+    let mut assoc_types = vec![];
+    let impl_args = &mut impl_path.segments.last_mut().unwrap().arguments;
+    if let PathArguments::AngleBracketed(args) = impl_args {
+        for arg in std::mem::take(&mut args.args) {
+            match arg {
+                GenericArgument::Binding(binding) => {
+                    let (ident, ty) = (&binding.ident, &binding.ty);
+                    assoc_types.push(quote::quote!(type #ident = #ty;));
+                }
+                arg => args.args.push(arg),
+            }
+        }
+        let mut call_args = args.clone();
+        call_args.colon2_token = Some(Default::default());
+        call_path.segments.last_mut().unwrap().arguments =
+            PathArguments::AngleBracketed(call_args);
+        if args.args.is_empty() {
+            *impl_args = PathArguments::None;
+            call_path.segments.last_mut().unwrap().arguments = PathArguments::None;
+        }
+    }
+    let methods = enum_dispatch.methods.iter().map(|method| {
+        let Method {
+            ident,
+            generics,
+            lifetime,
+            mutability,
+            args,
+            output,
+        } = method;
+        let where_clause = &generics.where_clause;
+        let arg_names = args.iter().map(|(arg, _)| arg).collect::<Vec<_>>();
+        let arg_types = args.iter().map(|(_, ty)| ty);
+        let arms = kind_variants.iter().zip(indirect).map(|(kind, &indirect)| {
+            let target = match indirect {
+                true => quote::quote!(&#mutability **__trait_union_value),
+                false => quote::quote!(__trait_union_value),
+            };
+            quote::quote! {
+                #owned_name::#kind(__trait_union_value) => #call_path::#ident(#target, #(#arg_names),*),
+            }
+        });
+        let phantom_arm = match has_phantom {
+            true => Some(quote::quote! {
+                #owned_name::__TraitUnionPhantom(_, __trait_union_never) => {
+                    match *__trait_union_never {}
+                }
+            }),
+            false => None,
+        };
+        quote::quote_spanned! { located_at(ident.span()) =>
+            #[inline]
+            fn #ident#generics(&#lifetime #mutability self, #(#arg_names: #arg_types),*) #output
+            #where_clause
+            {
+                match self {
+                    #(#arms)*
+                    #phantom_arm
+                }
+            }
+        }
+    });
+    quote::quote_spanned! { located_at(enum_dispatch.span) =>
+        impl#impl_generics #impl_path for #owned_name#ty_generics #where_clause {
+            #(#assoc_types)*
+
+            #(#methods)*
+        }
+    }
+}
@@ -0,0 +1,681 @@
+use proc_macro2::{Span, TokenStream};
+use syn::{
+    punctuated::Punctuated, spanned::Spanned, GenericArgument, Generics, Ident, Path,
+    PathArguments, PathSegment, Token, TypeParamBound,
+};
+
+/// Generates implementations of the traits listed in the `forward` option that call the
+/// active variant
+///
+/// `Write` without a `fmt` qualifier refers to `std::io::Write`, `AsyncRead` and
+/// `AsyncWrite` without a `futures` qualifier to the traits of tokio. Traits whose
+/// methods take a pinned receiver can only be forwarded if `pinned` is set, i.e. if
+/// pinning the union pins the active variant.
+pub fn forward_impls(
+    name: &Ident,
+    generics: &Generics,
+    trait_: &Punctuated<TypeParamBound, Token![+]>,
+    forward: &[Path],
+    no_deref: bool,
+    pinned: bool,
+) -> syn::Result<TokenStream> {
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let (target_ref, target) = match no_deref {
+        true => (
+            quote::quote!(self.as_dyn()),
+            quote::quote!(self.as_dyn_mut()),
+        ),
+        false => (quote::quote!(&**self), quote::quote!(&mut **self)),
+    };
+    let mut tokens = TokenStream::new();
+    let mut error_types = vec![];
+    for path in forward {
+        let (trait_name, qualifier) = trait_name(path);
+        let last = path.segments.last().unwrap();
+        let (forwarded, methods) = match (&*trait_name, qualifier) {
+            (name @ ("Read" | "Write" | "Seek"), "") | (name @ "Write", "fmt") => (
+                fallible_trait(name, qualifier),
+                fallible_methods(name, qualifier, &target),
+            ),
+            ("Hasher", "") => {
+                let ints = [
+                    "u8", "u16", "u32", "u64", "u128", "usize", "i8", "i16", "i32",
+                    "i64", "i128", "isize",
+                ]
+                .iter()
+                .map(|ty| {
+                    let method = Ident::new(&format!("write_{}", ty), Span::call_site());
+                    let ty = Ident::new(ty, Span::call_site());
+                    quote::quote! {
+                        fn #method(&mut self, i: #ty) {
+                            ::core::hash::Hasher::#method(#target, i)
+                        }
+                    }
+                });
+                (
+                    quote::quote!(::core::hash::Hasher),
+                    quote::quote! {
+                        fn finish(&self) -> u64 {
+                            ::core::hash::Hasher::finish(#target_ref)
+                        }
+
+                        fn write(&mut self, bytes: &[u8]) {
+                            ::core::hash::Hasher::write(#target, bytes)
+                        }
+
+                        #(#ints)*
+                    },
+                )
+            }
+            (
+                pinned_trait @ ("Future" | "Stream" | "AsyncRead" | "AsyncWrite"),
+                "" | "futures",
+            ) => {
+                let feature = match (pinned_trait, qualifier) {
+                    ("Future", _) => None,
+                    ("Stream", _) | (_, "futures") => Some("futures"),
+                    _ => Some("tokio"),
+                };
+                let enabled = match feature {
+                    Some("futures") => cfg!(feature = "futures"),
+                    Some(_) => cfg!(feature = "tokio"),
+                    None => true,
+                };
+                if !enabled {
+                    return Err(syn::Error::new(
+                        path.span(),
+                        format!(
+                            "forwarding {} requires the {} feature of trait-union",
+                            pinned_trait,
+                            feature.unwrap(),
+                        ),
+                    ));
+                }
+                if !pinned {
+                    return Err(syn::Error::new(
+                        path.span(),
+                        format!(
+                            "{} cannot be forwarded for unions with box or flattened variants",
+                            pinned_trait
+                        ),
+                    ));
+                }
+                let project = match no_deref {
+                    true => quote::quote!(|s| s.as_dyn_mut()),
+                    false => quote::quote!(|s| &mut **s),
+                };
+                // The variants are stored inline and not moved while the union is alive.
+                let variant = quote::quote!(unsafe { self.map_unchecked_mut(#project) });
+                pinned_methods(pinned_trait, feature, trait_, &variant, &target_ref)
+            }
+            ("Service", "") => {
+                if cfg!(not(feature = "tower")) {
+                    return Err(syn::Error::new(
+                        path.span(),
+                        "forwarding Service requires the tower feature of trait-union",
+                    ));
+                }
+                let request = match type_arg(last)? {
+                    Some(request) => request,
+                    None => {
+                        return Err(syn::Error::new(
+                            path.span(),
+                            "expected the request type as in Service<Request>",
+                        ))
+                    }
+                };
+                let service = quote::quote!(::trait_union::__support::tower_service::Service<#request>);
+                (
+                    service.clone(),
+                    quote::quote! {
+                        type Response = <dyn #trait_ as #service>::Response;
+                        type Error = <dyn #trait_ as #service>::Error;
+                        type Future = <dyn #trait_ as #service>::Future;
+
+                        fn poll_ready(
+                            &mut self,
+                            cx: &mut ::core::task::Context<'_>,
+                        ) -> ::core::task::Poll<::core::result::Result<(), Self::Error>> {
+                            <dyn #trait_ as #service>::poll_ready(#target, cx)
+                        }
+
+                        fn call(&mut self, req: #request) -> Self::Future {
+                            <dyn #trait_ as #service>::call(#target, req)
+                        }
+                    },
+                )
+            }
+            (hal @ ("OutputPin" | "SpiBus" | "I2c" | "DelayNs"), "") => {
+                if cfg!(not(feature = "embedded-hal")) {
+                    return Err(syn::Error::new(
+                        path.span(),
+                        format!(
+                            "forwarding {} requires the embedded-hal feature of trait-union",
+                            hal
+                        ),
+                    ));
+                }
+                let (forwarded, methods, module) = hal_methods(hal, last, &target)?;
+                if let Some(module) = module {
+                    if !error_types.contains(&module) {
+                        error_types.push(module);
+                        let module = Ident::new(module, Span::call_site());
+                        tokens.extend(quote::quote_spanned! { path.span() =>
+                            impl#impl_generics ::trait_union::__support::embedded_hal::#module::ErrorType
+                                for #name#ty_generics #where_clause
+                            {
+                                type Error = <dyn #trait_ as ::trait_union::__support::embedded_hal::#module::ErrorType>::Error;
+                            }
+                        });
+                    }
+                }
+                (forwarded, methods)
+            }
+            _ => {
+                return Err(syn::Error::new(
+                    path.span(),
+                    "expected one of Read, Write, Seek, fmt::Write, Hasher, Future, \
+                     Stream, AsyncRead, AsyncWrite, Service, OutputPin, SpiBus, I2c, and \
+                     DelayNs",
+                ))
+            }
+        };
+        tokens.extend(quote::quote_spanned! { path.span() =>
+            impl#impl_generics #forwarded for #name#ty_generics #where_clause {
+                #methods
+            }
+        });
+    }
+    Ok(tokens)
+}
+
+/// Generates implementations of the traits that single variants forward with
+/// `#[trait_union(forward(TRAIT, ...))]`
+///
+/// `variant_forward` contains the traits forwarded by each variant. `select` returns an
+/// expression of type `Option<&mut dyn TRAIT>` from whether each variant forwards the
+/// trait and the path of the trait. Calls fail if the expression is `None`.
+pub fn partial_forward_impls(
+    name: &Ident,
+    generics: &Generics,
+    forward: &[Path],
+    variant_forward: &[&[Path]],
+    select: impl Fn(&[bool], &TokenStream) -> TokenStream,
+) -> syn::Result<TokenStream> {
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let mut traits = Vec::<(String, &str, &Path)>::new();
+    for path in variant_forward.iter().flat_map(|f| f.iter()) {
+        let (trait_str, qualifier) = trait_name(path);
+        if !matches!(
+            (&*trait_str, qualifier),
+            ("Read" | "Write" | "Seek", "") | ("Write", "fmt")
+        ) {
+            return Err(syn::Error::new(
+                path.span(),
+                "only Read, Write, Seek, and fmt::Write can be forwarded by single variants",
+            ));
+        }
+        if forward
+            .iter()
+            .any(|p| trait_name(p) == (trait_str.clone(), qualifier))
+        {
+            return Err(syn::Error::new(
+                path.span(),
+                "the trait is already forwarded for all variants by the forward option of \
+                 the union",
+            ));
+        }
+        if !traits
+            .iter()
+            .any(|(n, q, _)| *n == trait_str && *q == qualifier)
+        {
+            traits.push((trait_str, qualifier, path));
+        }
+    }
+    let mut tokens = TokenStream::new();
+    for (trait_str, qualifier, path) in traits {
+        let forwards = variant_forward
+            .iter()
+            .map(|f| {
+                f.iter()
+                    .any(|p| trait_name(p) == (trait_str.clone(), qualifier))
+            })
+            .collect::<Vec<_>>();
+        let forwarded = fallible_trait(&trait_str, qualifier);
+        let error = match qualifier {
+            "fmt" => quote::quote!(::core::fmt::Error),
+            _ => {
+                let msg = format!(
+                    "the active variant of {} does not implement {}",
+                    name, trait_str
+                );
+                quote::quote! {
+                    ::std::io::Error::new(::std::io::ErrorKind::Unsupported, #msg)
+                }
+            }
+        };
+        let select = select(&forwards, &forwarded);
+        let target = quote::quote! {
+            match #select {
+                ::core::option::Option::Some(value) => value,
+                ::core::option::Option::None => return ::core::result::Result::Err(#error),
+            }
+        };
+        let methods = fallible_methods(&trait_str, qualifier, &target);
+        tokens.extend(quote::quote_spanned! { path.span() =>
+            impl#impl_generics #forwarded for #name#ty_generics #where_clause {
+                #methods
+            }
+        });
+    }
+    Ok(tokens)
+}
+
+/// Returns the last segment of the path of a forwarded trait and its qualifier
+///
+/// The qualifier is `fmt` for `fmt::Write`, `futures` for the traits of `futures`, and
+/// empty otherwise.
+fn trait_name(path: &Path) -> (String, &'static str) {
+    let segments = path
+        .segments
+        .iter()
+        .map(|s| s.ident.to_string())
+        .collect::<Vec<_>>();
+    let qualifier = match &*segments {
+        [.., q, _] if q == "fmt" => "fmt",
+        [q, ..] if q == "futures" || q == "futures_io" => "futures",
+        _ => "",
+    };
+    (segments[segments.len() - 1].clone(), qualifier)
+}
+
+/// Returns the path of `Read`, `Write`, `Seek`, or `fmt::Write`
+fn fallible_trait(name: &str, qualifier: &str) -> TokenStream {
+    match (name, qualifier) {
+        ("Read", "") => quote::quote!(::std::io::Read),
+        ("Write", "") => quote::quote!(::std::io::Write),
+        ("Seek", "") => quote::quote!(::std::io::Seek),
+        _ => quote::quote!(::core::fmt::Write),
+    }
+}
+
+/// Returns the forwarded methods of `Read`, `Write`, `Seek`, or `fmt::Write`
+///
+/// All methods of these traits return a `Result`, so they can also be forwarded by single
+/// variants. `target` is a mutable reference to the value that implements the trait.
+fn fallible_methods(name: &str, qualifier: &str, target: &TokenStream) -> TokenStream {
+    match (name, qualifier) {
+        ("Read", "") => quote::quote! {
+            fn read(&mut self, buf: &mut [u8]) -> ::std::io::Result<usize> {
+                ::std::io::Read::read(#target, buf)
+            }
+
+            fn read_vectored(
+                &mut self,
+                bufs: &mut [::std::io::IoSliceMut<'_>],
+            ) -> ::std::io::Result<usize> {
+                ::std::io::Read::read_vectored(#target, bufs)
+            }
+
+            fn read_to_end(
+                &mut self,
+                buf: &mut ::std::vec::Vec<u8>,
+            ) -> ::std::io::Result<usize> {
+                ::std::io::Read::read_to_end(#target, buf)
+            }
+
+            fn read_to_string(
+                &mut self,
+                buf: &mut ::std::string::String,
+            ) -> ::std::io::Result<usize> {
+                ::std::io::Read::read_to_string(#target, buf)
+            }
+
+            fn read_exact(&mut self, buf: &mut [u8]) -> ::std::io::Result<()> {
+                ::std::io::Read::read_exact(#target, buf)
+            }
+        },
+        ("Write", "") => quote::quote! {
+            fn write(&mut self, buf: &[u8]) -> ::std::io::Result<usize> {
+                ::std::io::Write::write(#target, buf)
+            }
+
+            fn write_vectored(
+                &mut self,
+                bufs: &[::std::io::IoSlice<'_>],
+            ) -> ::std::io::Result<usize> {
+                ::std::io::Write::write_vectored(#target, bufs)
+            }
+
+            fn flush(&mut self) -> ::std::io::Result<()> {
+                ::std::io::Write::flush(#target)
+            }
+
+            fn write_all(&mut self, buf: &[u8]) -> ::std::io::Result<()> {
+                ::std::io::Write::write_all(#target, buf)
+            }
+
+            fn write_fmt(
+                &mut self,
+                args: ::core::fmt::Arguments<'_>,
+            ) -> ::std::io::Result<()> {
+                ::std::io::Write::write_fmt(#target, args)
+            }
+        },
+        ("Seek", "") => quote::quote! {
+            fn seek(&mut self, pos: ::std::io::SeekFrom) -> ::std::io::Result<u64> {
+                ::std::io::Seek::seek(#target, pos)
+            }
+
+            fn stream_position(&mut self) -> ::std::io::Result<u64> {
+                ::std::io::Seek::stream_position(#target)
+            }
+        },
+        ("Write", "fmt") => quote::quote! {
+            fn write_str(&mut self, s: &str) -> ::core::fmt::Result {
+                ::core::fmt::Write::write_str(#target, s)
+            }
+
+            fn write_char(&mut self, c: char) -> ::core::fmt::Result {
+                ::core::fmt::Write::write_char(#target, c)
+            }
+
+            fn write_fmt(
+                &mut self,
+                args: ::core::fmt::Arguments<'_>,
+            ) -> ::core::fmt::Result {
+                ::core::fmt::Write::write_fmt(#target, args)
+            }
+        },
+        _ => unreachable!(),
+    }
+}
+
+/// Returns the path of the trait `pinned_trait` whose methods take a pinned receiver and
+/// its forwarded methods
+///
+/// `feature` is the feature of trait-union that provides the trait. `variant` is the
+/// pinned active variant.
+fn pinned_methods(
+    pinned_trait: &str,
+    feature: Option<&str>,
+    trait_: &Punctuated<TypeParamBound, Token![+]>,
+    variant: &TokenStream,
+    target_ref: &TokenStream,
+) -> (TokenStream, TokenStream) {
+    let pin = quote::quote!(self: ::core::pin::Pin<&mut Self>);
+    let cx = quote::quote!(cx: &mut ::core::task::Context<'_>);
+    let poll = quote::quote!(::core::task::Poll);
+    let io = quote::quote!(::std::io);
+    match (pinned_trait, feature) {
+        ("Future", _) => (
+            quote::quote!(::core::future::Future),
+            quote::quote! {
+                type Output = <dyn #trait_ as ::core::future::Future>::Output;
+
+                fn poll(#pin, #cx) -> #poll<Self::Output> {
+                    ::core::future::Future::poll(#variant, cx)
+                }
+            },
+        ),
+        ("Stream", _) => {
+            let stream = quote::quote!(::trait_union::__support::futures_core::Stream);
+            (
+                stream.clone(),
+                quote::quote! {
+                    type Item = <dyn #trait_ as #stream>::Item;
+
+                    fn poll_next(#pin, #cx) -> #poll<::core::option::Option<Self::Item>> {
+                        #stream::poll_next(#variant, cx)
+                    }
+
+                    fn size_hint(&self) -> (usize, ::core::option::Option<usize>) {
+                        #stream::size_hint(#target_ref)
+                    }
+                },
+            )
+        }
+        ("AsyncRead", Some("tokio")) => {
+            let tokio = quote::quote!(::trait_union::__support::tokio::io);
+            (
+                quote::quote!(#tokio::AsyncRead),
+                quote::quote! {
+                    fn poll_read(
+                        #pin,
+                        #cx,
+                        buf: &mut #tokio::ReadBuf<'_>,
+                    ) -> #poll<#io::Result<()>> {
+                        #tokio::AsyncRead::poll_read(#variant, cx, buf)
+                    }
+                },
+            )
+        }
+        ("AsyncWrite", Some("tokio")) => {
+            let tokio = quote::quote!(::trait_union::__support::tokio::io);
+            (
+                quote::quote!(#tokio::AsyncWrite),
+                quote::quote! {
+                    fn poll_write(#pin, #cx, buf: &[u8]) -> #poll<#io::Result<usize>> {
+                        #tokio::AsyncWrite::poll_write(#variant, cx, buf)
+                    }
+
+                    fn poll_flush(#pin, #cx) -> #poll<#io::Result<()>> {
+                        #tokio::AsyncWrite::poll_flush(#variant, cx)
+                    }
+
+                    fn poll_shutdown(#pin, #cx) -> #poll<#io::Result<()>> {
+                        #tokio::AsyncWrite::poll_shutdown(#variant, cx)
+                    }
+
+                    fn poll_write_vectored(
+                        #pin,
+                        #cx,
+                        bufs: &[#io::IoSlice<'_>],
+                    ) -> #poll<#io::Result<usize>> {
+                        #tokio::AsyncWrite::poll_write_vectored(#variant, cx, bufs)
+                    }
+
+                    fn is_write_vectored(&self) -> bool {
+                        #tokio::AsyncWrite::is_write_vectored(#target_ref)
+                    }
+                },
+            )
+        }
+        ("AsyncRead", _) => {
+            let futures = quote::quote!(::trait_union::__support::futures_io);
+            (
+                quote::quote!(#futures::AsyncRead),
+                quote::quote! {
+                    fn poll_read(#pin, #cx, buf: &mut [u8]) -> #poll<#io::Result<usize>> {
+                        #futures::AsyncRead::poll_read(#variant, cx, buf)
+                    }
+
+                    fn poll_read_vectored(
+                        #pin,
+                        #cx,
+                        bufs: &mut [#io::IoSliceMut<'_>],
+                    ) -> #poll<#io::Result<usize>> {
+                        #futures::AsyncRead::poll_read_vectored(#variant, cx, bufs)
+                    }
+                },
+            )
+        }
+        _ => {
+            let futures = quote::quote!(::trait_union::__support::futures_io);
+            (
+                quote::quote!(#futures::AsyncWrite),
+                quote::quote! {
+                    fn poll_write(#pin, #cx, buf: &[u8]) -> #poll<#io::Result<usize>> {
+                        #futures::AsyncWrite::poll_write(#variant, cx, buf)
+                    }
+
+                    fn poll_write_vectored(
+                        #pin,
+                        #cx,
+                        bufs: &[#io::IoSlice<'_>],
+                    ) -> #poll<#io::Result<usize>> {
+                        #futures::AsyncWrite::poll_write_vectored(#variant, cx, bufs)
+                    }
+
+                    fn poll_flush(#pin, #cx) -> #poll<#io::Result<()>> {
+                        #futures::AsyncWrite::poll_flush(#variant, cx)
+                    }
+
+                    fn poll_close(#pin, #cx) -> #poll<#io::Result<()>> {
+                        #futures::AsyncWrite::poll_close(#variant, cx)
+                    }
+                },
+            )
+        }
+    }
+}
+
+/// Returns the path of the embedded-hal trait `hal`, its forwarded methods, and the
+/// module of its `ErrorType` trait if it has one
+///
+/// The generic arguments of `last` are passed on to the trait.
+fn hal_methods(
+    hal: &str,
+    last: &PathSegment,
+    target: &TokenStream,
+) -> syn::Result<(TokenStream, TokenStream, Option<&'static str>)> {
+    let hal_path = quote::quote!(::trait_union::__support::embedded_hal);
+    let first_arg = type_arg(last)?;
+    let res = match hal {
+        "OutputPin" => (
+            quote::quote!(#hal_path::digital::OutputPin),
+            quote::quote! {
+                fn set_low(&mut self) -> ::core::result::Result<(), Self::Error> {
+                    #hal_path::digital::OutputPin::set_low(#target)
+                }
+
+                fn set_high(&mut self) -> ::core::result::Result<(), Self::Error> {
+                    #hal_path::digital::OutputPin::set_high(#target)
+                }
+
+                fn set_state(
+                    &mut self,
+                    state: #hal_path::digital::PinState,
+                ) -> ::core::result::Result<(), Self::Error> {
+                    #hal_path::digital::OutputPin::set_state(#target, state)
+                }
+            },
+            Some("digital"),
+        ),
+        "SpiBus" => {
+            let word = first_arg.unwrap_or_else(|| quote::quote!(u8));
+            (
+                quote::quote!(#hal_path::spi::SpiBus<#word>),
+                quote::quote! {
+                    fn read(&mut self, words: &mut [#word]) -> ::core::result::Result<(), Self::Error> {
+                        #hal_path::spi::SpiBus::read(#target, words)
+                    }
+
+                    fn write(&mut self, words: &[#word]) -> ::core::result::Result<(), Self::Error> {
+                        #hal_path::spi::SpiBus::write(#target, words)
+                    }
+
+                    fn transfer(
+                        &mut self,
+                        read: &mut [#word],
+                        write: &[#word],
+                    ) -> ::core::result::Result<(), Self::Error> {
+                        #hal_path::spi::SpiBus::transfer(#target, read, write)
+                    }
+
+                    fn transfer_in_place(
+                        &mut self,
+                        words: &mut [#word],
+                    ) -> ::core::result::Result<(), Self::Error> {
+                        #hal_path::spi::SpiBus::transfer_in_place(#target, words)
+                    }
+
+                    fn flush(&mut self) -> ::core::result::Result<(), Self::Error> {
+                        #hal_path::spi::SpiBus::flush(#target)
+                    }
+                },
+                Some("spi"),
+            )
+        }
+        "I2c" => {
+            let address = first_arg
+                .unwrap_or_else(|| quote::quote!(#hal_path::i2c::SevenBitAddress));
+            (
+                quote::quote!(#hal_path::i2c::I2c<#address>),
+                quote::quote! {
+                    fn transaction(
+                        &mut self,
+                        address: #address,
+                        operations: &mut [#hal_path::i2c::Operation<'_>],
+                    ) -> ::core::result::Result<(), Self::Error> {
+                        #hal_path::i2c::I2c::transaction(#target, address, operations)
+                    }
+
+                    fn read(
+                        &mut self,
+                        address: #address,
+                        read: &mut [u8],
+                    ) -> ::core::result::Result<(), Self::Error> {
+                        #hal_path::i2c::I2c::read(#target, address, read)
+                    }
+
+                    fn write(
+                        &mut self,
+                        address: #address,
+                        write: &[u8],
+                    ) -> ::core::result::Result<(), Self::Error> {
+                        #hal_path::i2c::I2c::write(#target, address, write)
+                    }
+
+                    fn write_read(
+                        &mut self,
+                        address: #address,
+                        write: &[u8],
+                        read: &mut [u8],
+                    ) -> ::core::result::Result<(), Self::Error> {
+                        #hal_path::i2c::I2c::write_read(#target, address, write, read)
+                    }
+                },
+                Some("i2c"),
+            )
+        }
+        _ => (
+            quote::quote!(#hal_path::delay::DelayNs),
+            quote::quote! {
+                fn delay_ns(&mut self, ns: u32) {
+                    #hal_path::delay::DelayNs::delay_ns(#target, ns)
+                }
+
+                fn delay_us(&mut self, us: u32) {
+                    #hal_path::delay::DelayNs::delay_us(#target, us)
+                }
+
+                fn delay_ms(&mut self, ms: u32) {
+                    #hal_path::delay::DelayNs::delay_ms(#target, ms)
+                }
+            },
+            None,
+        ),
+    };
+    Ok(res)
+}
+
+/// Returns the type argument of `last` if it has one
+fn type_arg(last: &PathSegment) -> syn::Result<Option<TokenStream>> {
+    let args = &last.arguments;
+    let arg = match args {
+        PathArguments::None => return Ok(None),
+        PathArguments::AngleBracketed(a) if a.args.len() == 1 => match &a.args[0] {
+            GenericArgument::Type(ty) => Some(quote::quote!(#ty)),
+            _ => None,
+        },
+        _ => None,
+    };
+    match arg {
+        Some(arg) => Ok(Some(arg)),
+        None => Err(syn::Error::new(
+            args.span(),
+            "expected a single type argument",
+        )),
+    }
+}
@@ -0,0 +1,184 @@
+//! Expansion of whole files for the snapshot tests and `cargo-trait-union-inspect`
+
+use super::{
+    expand, expand_covers, expand_cow, expand_derive, kind_names,
+    options::{Impl, Options},
+    variant_options, variant_tags, TraitUnionRequests,
+};
+use proc_macro2::TokenStream;
+use std::fmt::Write;
+use syn::{punctuated::Punctuated, Attribute, Item, Macro, Path, Token};
+
+/// Replaces the invocations of the macros of this crate in `items` by their expansion
+///
+/// Types that derive `TraitUnionVariant` are followed by the derived items. Inline
+/// modules are expanded recursively.
+pub fn expand_items(items: &mut Vec<Item>) {
+    for item in std::mem::take(items) {
+        let expanded = match &item {
+            Item::Macro(mac) => match expand_macro(&mac.mac) {
+                Some(expanded) => expanded,
+                None => {
+                    items.push(item);
+                    continue;
+                }
+            },
+            Item::Struct(s) if derives_variant(&s.attrs) => {
+                expand_derive(quote::quote!(#s))
+            }
+            Item::Enum(e) if derives_variant(&e.attrs) => {
+                expand_derive(quote::quote!(#e))
+            }
+            Item::Mod(m) if m.content.is_some() => {
+                let mut m = m.clone();
+                expand_items(&mut m.content.as_mut().unwrap().1);
+                items.push(Item::Mod(m));
+                continue;
+            }
+            _ => {
+                items.push(item);
+                continue;
+            }
+        };
+        if !matches!(item, Item::Macro(_)) {
+            items.push(item);
+        }
+        match syn::parse2::<syn::File>(expanded) {
+            Ok(file) => items.extend(file.items),
+            Err(e) => items.push(Item::Verbatim(e.to_compile_error())),
+        }
+    }
+}
+
+/// Returns a comment that describes the trait-unions declared in `items`
+///
+/// The sizes of the variants are only known to the compiler. Use `debug_layout` to
+/// retrieve them.
+pub fn summary(items: &[Item]) -> String {
+    let mut res = String::new();
+    for item in items {
+        let mac = match item {
+            Item::Macro(mac)
+                if is_macro(&mac.mac, &["trait_union", "trait_union_copy"]) =>
+            {
+                &mac.mac
+            }
+            Item::Mod(m) => {
+                if let Some((_, items)) = &m.content {
+                    res.push_str(&summary(items));
+                }
+                continue;
+            }
+            _ => continue,
+        };
+        let requests = match mac.parse_body::<TraitUnionRequests>() {
+            Ok(TraitUnionRequests(requests)) => requests,
+            Err(e) => {
+                let _ = writeln!(res, "// error: {}", e);
+                continue;
+            }
+        };
+        for mut request in requests {
+            let trait_ = &request.trait_;
+            let _ = writeln!(
+                res,
+                "// {}: {}",
+                request.ident,
+                pretty(quote::quote!(#trait_))
+            );
+            if let Err(e) = summarize(&mut res, &mut request) {
+                let _ = writeln!(res, "//     error: {}", e);
+            }
+        }
+    }
+    res
+}
+
+fn summarize(
+    res: &mut String,
+    request: &mut super::TraitUnionRequest,
+) -> syn::Result<()> {
+    let options = Options::extract(&mut request.attr)?;
+    if let Some(open) = &options.open {
+        let align = open
+            .align
+            .as_ref()
+            .map_or("8".to_string(), |a| a.to_string());
+        let _ = writeln!(
+            res,
+            "//     open, at most {} bytes with an alignment of at most {}",
+            open.size, align
+        );
+        return Ok(());
+    }
+    let storage = match options.impl_ {
+        Impl::Union => "a union",
+        Impl::Enum => "an enum",
+    };
+    let _ = writeln!(
+        res,
+        "//     {} variants stored in {}",
+        request.variants.len(),
+        storage
+    );
+    let variant_options = variant_options(&mut request.variants)?;
+    let kinds = kind_names(&request.variants, &variant_options)?;
+    let tags = variant_tags(&request.variants, options.tag)?;
+    for (pos, variant) in request.variants.iter().enumerate() {
+        let (boxed, ty) = (&variant.boxed, &variant.ty);
+        let _ = writeln!(
+            res,
+            "//     {}: {}, kind {}, tag {}",
+            pos,
+            pretty(quote::quote!(#boxed #ty)),
+            kinds[pos],
+            tags[pos]
+        );
+    }
+    Ok(())
+}
+
+/// Removes most of the whitespace that `TokenStream::to_string` inserts into a type
+fn pretty(tokens: TokenStream) -> String {
+    tokens
+        .to_string()
+        .replace("& ", "&")
+        .replace(" <", "<")
+        .replace("< ", "<")
+        .replace(" >", ">")
+        .replace(" ,", ",")
+        .replace(" ::", "::")
+        .replace(":: ", "::")
+}
+
+fn expand_macro(mac: &Macro) -> Option<TokenStream> {
+    let tokens = mac.tokens.clone();
+    let name = mac.path.segments.last()?.ident.to_string();
+    match &*name {
+        "trait_union" => Some(expand(tokens, false)),
+        "trait_union_copy" => Some(expand(tokens, true)),
+        "trait_union_cow" => Some(expand_cow(tokens)),
+        "assert_union_covers" => Some(expand_covers(tokens)),
+        _ => None,
+    }
+}
+
+fn is_macro(mac: &Macro, names: &[&str]) -> bool {
+    match mac.path.segments.last() {
+        Some(segment) => names.iter().any(|n| segment.ident == n),
+        None => false,
+    }
+}
+
+fn derives_variant(attrs: &[Attribute]) -> bool {
+    attrs.iter().filter(|a| a.path.is_ident("derive")).any(|a| {
+        let paths = a.parse_args_with(Punctuated::<Path, Token![,]>::parse_terminated);
+        match paths {
+            Ok(paths) => paths.iter().any(|p| match p.segments.last() {
+                Some(segment) => segment.ident == "TraitUnionVariant",
+                None => false,
+            }),
+            Err(_) => false,
+        }
+    })
+}
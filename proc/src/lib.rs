@@ -1,37 +1,129 @@
-use proc_macro2::TokenStream;
+mod cell;
+mod covers;
+mod dispatcher;
+mod enum_dispatch;
+mod forward;
+mod open;
+mod options;
+
+#[cfg(not(proc_macro))]
+pub(crate) mod inspect;
+
+use self::{
+    cell::cell,
+    dispatcher::dispatcher,
+    enum_dispatch::enum_dispatch,
+    forward::{forward_impls, partial_forward_impls},
+    options::{Impl, Options, Tag, VarianceParam, VariantOptions},
+};
+use proc_macro2::{Group, Literal, Span, TokenStream, TokenTree};
 use syn::{
+    parenthesized,
     parse::{Parse, ParseStream},
-    parse_macro_input,
     punctuated::Punctuated,
     spanned::Spanned,
-    Attribute, Generics, Ident, Lifetime, Token, Type, TypeParamBound, Visibility,
-    WhereClause,
+    token::{Brace, Paren},
+    Attribute, Field, FieldsNamed, GenericArgument, GenericParam, Generics, Ident,
+    Lifetime, LifetimeDef, LitInt, ParenthesizedGenericArguments, Path, PathArguments,
+    Token, TraitBound, TraitBoundModifier, Type, TypeParamBound, Visibility, WhereClause,
 };
 
 // https://github.com/intellij-rust/intellij-rust/issues/6236
 #[allow(unused_imports)]
 use syn::token::Token;
 
+#[cfg(proc_macro)]
 #[proc_macro]
 pub fn trait_union(tokens: proc_macro::TokenStream) -> proc_macro::TokenStream {
-    trait_union_common(tokens, false)
+    expand(tokens.into(), false).into()
 }
 
+#[cfg(proc_macro)]
 #[proc_macro]
 pub fn trait_union_copy(tokens: proc_macro::TokenStream) -> proc_macro::TokenStream {
-    trait_union_common(tokens, true)
+    expand(tokens.into(), true).into()
+}
+
+#[cfg(proc_macro)]
+#[proc_macro]
+pub fn trait_union_cow(tokens: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    expand_cow(tokens.into()).into()
+}
+
+#[cfg(proc_macro)]
+#[proc_macro]
+pub fn assert_union_covers(tokens: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    expand_covers(tokens.into()).into()
 }
 
-fn trait_union_common(
+#[cfg(proc_macro)]
+#[proc_macro_derive(TraitUnionVariant, attributes(trait_union))]
+pub fn derive_trait_union_variant(
     tokens: proc_macro::TokenStream,
-    copy: bool,
 ) -> proc_macro::TokenStream {
-    let TraitUnionRequests(requests) = parse_macro_input!(tokens as TraitUnionRequests);
+    expand_derive(tokens.into()).into()
+}
+
+/// Expands `trait_union!` or, if `copy` is set, `trait_union_copy!`
+fn expand(tokens: TokenStream, copy: bool) -> TokenStream {
+    let TraitUnionRequests(requests) = match syn::parse2(tokens) {
+        Ok(requests) => requests,
+        Err(e) => return e.to_compile_error(),
+    };
+    let mut tokens = TokenStream::new();
+    let mut declared = DeclaredNames::default();
+    for request in requests {
+        if let Err(e) = declared.declare(&request.ident, &request.attr) {
+            tokens.extend(e.to_compile_error());
+            continue;
+        }
+        match handle_request(request, copy) {
+            Ok(t) => tokens.extend(t),
+            Err(e) => tokens.extend(e.to_compile_error()),
+        }
+    }
+    tokens
+}
+
+/// Expands `trait_union_cow!`
+fn expand_cow(tokens: TokenStream) -> TokenStream {
+    let TraitUnionCowRequests(requests) = match syn::parse2(tokens) {
+        Ok(requests) => requests,
+        Err(e) => return e.to_compile_error(),
+    };
     let mut tokens = TokenStream::new();
+    let mut declared = DeclaredNames::default();
     for request in requests {
-        tokens.extend(handle_request(request, copy));
+        let declare = declared
+            .declare(&request.borrowed, &request.attr)
+            .and_then(|_| declared.declare(&request.owned, &request.attr));
+        if let Err(e) = declare {
+            tokens.extend(e.to_compile_error());
+            continue;
+        }
+        match handle_cow_request(request) {
+            Ok(t) => tokens.extend(t),
+            Err(e) => tokens.extend(e.to_compile_error()),
+        }
+    }
+    tokens
+}
+
+/// Expands `assert_union_covers!`
+fn expand_covers(tokens: TokenStream) -> TokenStream {
+    match syn::parse2(tokens) {
+        Ok(input) => covers::assert_covers(input),
+        Err(e) => e.to_compile_error(),
+    }
+}
+
+/// Expands `#[derive(TraitUnionVariant)]`
+fn expand_derive(tokens: TokenStream) -> TokenStream {
+    let result = syn::parse2(tokens).and_then(open::derive_variant);
+    match result {
+        Ok(t) => t,
+        Err(e) => e.to_compile_error(),
     }
-    tokens.into()
 }
 
 struct TraitUnionRequest {
@@ -40,35 +132,239 @@ struct TraitUnionRequest {
     ident: Ident,
     generics: Generics,
     trait_: Punctuated<TypeParamBound, Token![+]>,
-    variants: Punctuated<Type, Token![|]>,
+    fields: Vec<Field>,
+    variants: Punctuated<Variant, Token![|]>,
+}
+
+struct Variant {
+    attrs: Vec<Attribute>,
+    tag: Option<LitInt>,
+    boxed: Option<Token![box]>,
+    ty: Type,
+}
+
+impl Parse for Variant {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let attrs = input.call(Attribute::parse_outer)?;
+        let mut tag = None;
+        if input.peek(LitInt) {
+            tag = Some(input.parse()?);
+            let _t_colon = input.parse::<Token![:]>()?;
+        }
+        let boxed = input.parse()?;
+        let ty = ungroup(input.parse()?);
+        Ok(Variant {
+            attrs,
+            tag,
+            boxed,
+            ty,
+        })
+    }
+}
+
+/// Returns a span at the location of `span` that resolves names at the call site
+///
+/// Generated code is spanned to variants and options so that errors point to them. If
+/// those were passed through the fragments of a `macro_rules!` macro, their own spans
+/// have the hygiene of that macro's caller and locals such as `value` would not resolve.
+fn located_at(span: Span) -> Span {
+    Span::call_site().located_at(span)
+}
+
+/// Removes the invisible groups around types that were passed through a `$ty:ty`
+/// fragment of a `macro_rules!` macro
+///
+/// Without this, such variants are not recognized as references, `Self`, etc.
+fn ungroup(mut ty: Type) -> Type {
+    while let Type::Group(group) = ty {
+        ty = *group.elem;
+    }
+    ty
+}
+
+/// Parses `union` or its alternative `enumlike`
+///
+/// `enumlike` is not a keyword and therefore does not confuse tools that parse the macro
+/// input as Rust code.
+fn parse_union_keyword(input: ParseStream) -> syn::Result<()> {
+    if input.peek(Token![union]) {
+        let _t_union = input.parse::<Token![union]>()?;
+        return Ok(());
+    }
+    let span = input.span();
+    match input.parse::<Ident>() {
+        Ok(ident) if ident == "enumlike" => Ok(()),
+        _ => Err(syn::Error::new(span, "expected `union` or `enumlike`")),
+    }
 }
 
 impl Parse for TraitUnionRequest {
     fn parse(input: ParseStream) -> syn::Result<Self> {
         let attr = input.call(Attribute::parse_outer)?;
         let vis = input.parse::<Visibility>()?;
-        let _t_union = input.parse::<Token![union]>()?;
+        parse_union_keyword(input)?;
         let ident = input.parse::<Ident>()?;
         let mut generics = input.parse::<Generics>()?;
         let _t_colon = input.parse::<Token![:]>()?;
-        let mut trait_ = Punctuated::new();
+        let trait_ = parse_trait_bounds(input)?;
+        if input.peek(Token![where]) {
+            generics.where_clause = Some(input.parse::<WhereClause>()?);
+        }
+        let mut fields = vec![];
+        if input.peek(Brace) {
+            fields = input.parse::<FieldsNamed>()?.named.into_iter().collect();
+        }
+        let mut variants = Punctuated::new();
+        if input.peek(Token![;]) {
+            let _t_semicolon = input.parse::<Token![;]>()?;
+            return Ok(TraitUnionRequest {
+                attr,
+                vis,
+                ident,
+                generics,
+                trait_,
+                fields,
+                variants,
+            });
+        }
+        let _t_equals = input.parse::<Token![=]>()?;
         loop {
-            trait_.push_value(input.parse()?);
-            if input.peek(Token![where]) || input.peek(Token![=]) {
+            variants.push_value(input.parse::<Variant>()?);
+            if !input.peek(Token![|]) {
                 break;
             }
-            trait_.push_punct(input.parse()?);
-            if input.peek(Token![where]) || input.peek(Token![=]) {
+            variants.push_punct(input.parse::<Token![|]>()?);
+            if input.peek(Token![;]) {
                 break;
             }
         }
+        let _t_semicolon = input.parse::<Token![;]>()?;
+        Ok(TraitUnionRequest {
+            attr,
+            vis,
+            ident,
+            generics,
+            trait_,
+            fields,
+            variants,
+        })
+    }
+}
+
+/// Parses `+`-separated trait bounds up to a top-level `where`, `=`, or the braces of
+/// the fields
+///
+/// Each bound is parsed as a whole by syn. Tokens inside its generic arguments, such as
+/// the `=` in `Iterator<Item = u32>`, therefore never terminate the bounds. The bounds
+/// can be preceded by `dyn` and parenthesized bounds are flattened.
+fn parse_trait_bounds(
+    input: ParseStream,
+) -> syn::Result<Punctuated<TypeParamBound, Token![+]>> {
+    let mut trait_ = Punctuated::new();
+    parse_trait_bounds_into(input, &mut trait_, |input| {
+        input.peek(Token![where])
+            || input.peek(Token![=])
+            || input.peek(Token![;])
+            || input.peek(Brace)
+    })?;
+    let mut lifetimes = 0;
+    for bound in &trait_ {
+        match bound {
+            TypeParamBound::Trait(bound) => {
+                if let TraitBoundModifier::Maybe(q) = bound.modifier {
+                    return Err(syn::Error::new(
+                        q.span(),
+                        "`?Sized` is not allowed: trait-union targets are always sized",
+                    ));
+                }
+            }
+            TypeParamBound::Lifetime(lt) => {
+                lifetimes += 1;
+                if lifetimes > 1 {
+                    return Err(syn::Error::new(
+                        lt.span(),
+                        "only one lifetime bound is allowed; to borrow from several \
+                         lifetimes, bound them by a common lifetime parameter, e.g. \
+                         `union U<'r, 'a: 'r, 'b: 'r>: Trait + 'r`",
+                    ));
+                }
+            }
+        }
+    }
+    Ok(trait_)
+}
+
+fn parse_trait_bounds_into(
+    input: ParseStream,
+    trait_: &mut Punctuated<TypeParamBound, Token![+]>,
+    end: fn(ParseStream) -> bool,
+) -> syn::Result<()> {
+    let _t_dyn = input.parse::<Option<Token![dyn]>>()?;
+    loop {
+        if input.peek(Paren) {
+            let content;
+            parenthesized!(content in input);
+            parse_trait_bounds_into(&content, trait_, |content| content.is_empty())?;
+        } else {
+            trait_.push_value(input.parse()?);
+        }
+        if end(input) {
+            break;
+        }
+        let plus = input.parse()?;
+        if !trait_.empty_or_trailing() {
+            trait_.push_punct(plus);
+        }
+        if end(input) {
+            break;
+        }
+    }
+    Ok(())
+}
+
+struct TraitUnionRequests(Vec<TraitUnionRequest>);
+
+impl Parse for TraitUnionRequests {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut res = vec![];
+        while !input.is_empty() {
+            res.push(TraitUnionRequest::parse(input)?);
+        }
+        Ok(TraitUnionRequests(res))
+    }
+}
+
+struct TraitUnionCowRequest {
+    attr: Vec<Attribute>,
+    vis: Visibility,
+    borrowed: Ident,
+    generics: Generics,
+    owned: Ident,
+    trait_: Punctuated<TypeParamBound, Token![+]>,
+    variants: Punctuated<(Type, Type), Token![|]>,
+}
+
+impl Parse for TraitUnionCowRequest {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let attr = input.call(Attribute::parse_outer)?;
+        let vis = input.parse::<Visibility>()?;
+        parse_union_keyword(input)?;
+        let borrowed = input.parse::<Ident>()?;
+        let mut generics = input.parse::<Generics>()?;
+        let _t_comma = input.parse::<Token![,]>()?;
+        let owned = input.parse::<Ident>()?;
+        let _t_colon = input.parse::<Token![:]>()?;
+        let trait_ = parse_trait_bounds(input)?;
         if input.peek(Token![where]) {
             generics.where_clause = Some(input.parse::<WhereClause>()?);
         }
         let _t_equals = input.parse::<Token![=]>()?;
         let mut variants = Punctuated::new();
         loop {
-            variants.push_value(input.parse::<Type>()?);
+            let borrowed = ungroup(input.parse::<Type>()?);
+            let _t_arrow = input.parse::<Token![=>]>()?;
+            let owned = ungroup(input.parse::<Type>()?);
+            variants.push_value((borrowed, owned));
             if !input.peek(Token![|]) {
                 break;
             }
@@ -78,45 +374,149 @@ impl Parse for TraitUnionRequest {
             }
         }
         let _t_semicolon = input.parse::<Token![;]>()?;
-        Ok(TraitUnionRequest {
+        Ok(TraitUnionCowRequest {
             attr,
             vis,
-            ident,
+            borrowed,
             generics,
+            owned,
             trait_,
             variants,
         })
     }
 }
 
-struct TraitUnionRequests(Vec<TraitUnionRequest>);
+struct TraitUnionCowRequests(Vec<TraitUnionCowRequest>);
 
-impl Parse for TraitUnionRequests {
+impl Parse for TraitUnionCowRequests {
     fn parse(input: ParseStream) -> syn::Result<Self> {
         let mut res = vec![];
         while !input.is_empty() {
-            res.push(TraitUnionRequest::parse(input)?);
+            res.push(TraitUnionCowRequest::parse(input)?);
         }
-        Ok(TraitUnionRequests(res))
+        Ok(TraitUnionCowRequests(res))
     }
 }
 
-fn handle_request(request: TraitUnionRequest, copy: bool) -> TokenStream {
-    let attr = request.attr;
-    let vis = request.vis;
-    let name = request.ident;
-    let name_as_str = format!("{}", name);
-    let prefix = format!("__trait_union_{}_", name);
-    let data_name = Ident::new(&format!("{}data", prefix), name.span());
-    let vtable_name = Ident::new(&format!("{}vtable", prefix), name.span());
-    let variant_name = Ident::new(&format!("{}Variant", name), name.span());
-    let union_name = Ident::new(&format!("{}Union", prefix), name.span());
-    let trait_object_name = Ident::new(&format!("{}TraitObject", prefix), name.span());
-    let vtable_container_name =
-        Ident::new(&format!("{}VtableContainer", prefix), name.span());
-    let to_trait_object_name =
-        Ident::new(&format!("{}to_trait_object", prefix), name.span());
-    let mut trait_ = request.trait_;
+struct Names {
+    data: Ident,
+    index: Ident,
+    variant: Ident,
+    kind: Ident,
+    ref_: Ident,
+    mut_: Ident,
+    owned: Ident,
+    atomic: Ident,
+    metrics: Ident,
+    dispatcher: Ident,
+    manual_variant: Ident,
+    cell: Ident,
+    visitor: Ident,
+    handle: Ident,
+    send: Ident,
+    union: Ident,
+    index_type: Ident,
+}
+
+impl Names {
+    fn new(name: &Ident) -> Self {
+        let prefix = format!("__trait_union_{}_", name);
+        let ident = |s: &str| Ident::new(s, name.span());
+        Names {
+            data: ident(&format!("{}data", prefix)),
+            index: ident(&format!("{}index", prefix)),
+            variant: ident(&format!("{}Variant", name)),
+            kind: ident(&format!("{}Kind", name)),
+            ref_: ident(&format!("{}Ref", name)),
+            mut_: ident(&format!("{}Mut", name)),
+            owned: ident(&format!("{}Owned", name)),
+            atomic: ident(&format!("Atomic{}", name)),
+            metrics: ident(&format!("{}Metrics", name)),
+            dispatcher: ident(&format!("{}Dispatcher", name)),
+            manual_variant: ident(&format!("{}ManualVariant", name)),
+            cell: ident(&format!("{}Cell", name)),
+            visitor: ident(&format!("{}Visitor", name)),
+            handle: ident(&format!("{}Handle", name)),
+            send: ident(&format!("{}Send", name)),
+            union: ident(&format!("{}Union", prefix)),
+            index_type: ident(&format!("{}Index", prefix)),
+        }
+    }
+}
+
+/// The names of the public items generated by one macro invocation
+///
+/// Declaring the same name twice would otherwise be reported by rustc once for every
+/// generated item.
+#[derive(Default)]
+struct DeclaredNames {
+    /// The names and the unions that generate them
+    names: Vec<(Ident, Ident)>,
+}
+
+impl DeclaredNames {
+    /// Records the names generated for the union `name` or fails if one is already taken
+    fn declare(&mut self, name: &Ident, attr: &[Attribute]) -> syn::Result<()> {
+        let options = Options::extract(&mut attr.to_vec())?;
+        let names = Names::new(name);
+        let mut generated = vec![name.clone(), names.variant];
+        if options.open.is_none() {
+            generated.extend([names.kind, names.ref_, names.mut_, names.owned]);
+        }
+        if options.atomic.is_some() {
+            generated.push(names.atomic);
+        }
+        if options.metrics.is_some() {
+            generated.push(names.metrics);
+        }
+        if options.dispatcher.is_some() {
+            generated.push(names.dispatcher);
+        }
+        if !options.manual_impl.is_empty() {
+            generated.push(names.manual_variant);
+        }
+        if options.cell.is_some() {
+            generated.push(names.cell);
+        }
+        if options.visitor {
+            generated.push(names.visitor);
+        }
+        if options.external {
+            generated.push(names.handle);
+        }
+        if options.send {
+            generated.push(names.send);
+        }
+        for item in &generated {
+            if let Some((_, other)) = self.names.iter().find(|(n, _)| n == item) {
+                let msg = if other == name {
+                    format!("the trait-union `{}` is declared more than once", name)
+                } else if other == item {
+                    format!("`{}` is already declared as a trait-union", item)
+                } else {
+                    format!(
+                        "`{}` is already generated for the trait-union `{}`",
+                        item, other
+                    )
+                };
+                return Err(syn::Error::new(name.span(), msg));
+            }
+        }
+        self.names
+            .extend(generated.into_iter().map(|item| (item, name.clone())));
+        Ok(())
+    }
+}
+
+fn variant_field(pos: usize) -> Ident {
+    Ident::new(&format!("variant{}", pos), Span::call_site())
+}
+
+fn variant_index(pos: usize) -> Ident {
+    Ident::new(&format!("V{}", pos), Span::call_site())
+}
+
+fn add_default_lifetime(trait_: &mut Punctuated<TypeParamBound, Token![+]>, lt: &str) {
     let has_lifetime = trait_
         .iter()
         .any(|b| matches!(b, TypeParamBound::Lifetime(_)));
@@ -124,134 +524,3471 @@ fn handle_request(request: TraitUnionRequest, copy: bool) -> TokenStream {
         if !trait_.empty_or_trailing() {
             trait_.push_punct(syn::token::Add(trait_.span()));
         }
-        trait_.push_value(TypeParamBound::Lifetime(Lifetime::new(
-            "'static",
-            trait_.span(),
-        )));
+        trait_.push_value(TypeParamBound::Lifetime(Lifetime::new(lt, trait_.span())));
     }
-    let (impl_generics, ty_generics, where_clause) = request.generics.split_for_impl();
-    let mut union_fields = vec![];
-    for (pos, variant) in request.variants.iter().enumerate() {
-        let ident = Ident::new(&format!("variant{}", pos), variant.span());
-        union_fields.push(
-            quote::quote_spanned!( variant.span() => #ident: ::core::mem::ManuallyDrop<#variant>),
-        );
+}
+
+/// Fails if the trait bounds do not contain a lifetime
+///
+/// Used by the `no_implicit_static` option instead of adding `'static`.
+fn check_explicit_lifetime(
+    trait_: &Punctuated<TypeParamBound, Token![+]>,
+) -> syn::Result<()> {
+    if trait_
+        .iter()
+        .any(|b| matches!(b, TypeParamBound::Lifetime(_)))
+    {
+        return Ok(());
     }
-    let mut variant_impls = vec![];
-    for variant in &request.variants {
-        variant_impls.push(quote::quote_spanned! { variant.span() =>
-            unsafe impl#impl_generics #variant_name#ty_generics for #variant #where_clause { }
-        })
+    Err(syn::Error::new(
+        trait_.span(),
+        "the trait bounds must contain a lifetime because of the no_implicit_static option, \
+         e.g. `+ 'static`",
+    ))
+}
+
+/// Replaces all occurrences of the lifetime `from` in `tokens` by `to`
+fn replace_lifetime(tokens: TokenStream, from: &Lifetime, to: &Lifetime) -> TokenStream {
+    let mut res = vec![];
+    for tt in tokens {
+        match tt {
+            TokenTree::Group(g) => {
+                let mut group =
+                    Group::new(g.delimiter(), replace_lifetime(g.stream(), from, to));
+                group.set_span(g.span());
+                res.push(TokenTree::Group(group));
+            }
+            TokenTree::Ident(i)
+                if i == from.ident
+                    && matches!(res.last(), Some(TokenTree::Punct(p)) if p.as_char() == '\'') =>
+            {
+                res.push(TokenTree::Ident(to.ident.clone()));
+            }
+            tt => res.push(tt),
+        }
     }
-    let mut drop_impl = None;
-    if !copy {
-        drop_impl = Some(quote::quote! {
-            impl#impl_generics ::core::ops::Drop for #name#ty_generics #where_clause {
-                #[inline(always)]
-                fn drop(&mut self) {
-                    unsafe {
-                        let t: &mut (dyn #trait_) = ::core::mem::transmute(#to_trait_object_name(self));
-                        ::core::ptr::drop_in_place(t);
-                    }
-                }
+    res.into_iter().collect()
+}
+
+/// Generates a function that only compiles if `name` is covariant in `lt`
+fn covariance_check(name: &Ident, generics: &Generics, lt: &Lifetime) -> TokenStream {
+    let short = Lifetime::new("'__trait_union_short", Span::call_site());
+    let mut check_generics = generics.clone();
+    check_generics
+        .params
+        .insert(0, GenericParam::Lifetime(LifetimeDef::new(short.clone())));
+    let (_, ty_generics, _) = generics.split_for_impl();
+    let (check_impl_generics, _, _) = check_generics.split_for_impl();
+    // The shortened type must be well-formed, so the bounds of all parameters are
+    // repeated with `lt` replaced by the shorter lifetime.
+    let mut predicates = vec![];
+    for param in &generics.params {
+        match param {
+            GenericParam::Lifetime(l) if !l.bounds.is_empty() => {
+                let (lifetime, bounds) = (&l.lifetime, &l.bounds);
+                predicates.push(quote::quote!(#lifetime: #bounds));
             }
-        });
+            GenericParam::Type(t) if !t.bounds.is_empty() => {
+                let (ident, bounds) = (&t.ident, &t.bounds);
+                predicates.push(quote::quote!(#ident: #bounds));
+            }
+            _ => {}
+        }
     }
-    let mut copy_impl = None;
-    if copy {
-        copy_impl = Some(quote::quote! {
-            impl#impl_generics ::core::marker::Copy for #union_name#ty_generics #where_clause { }
-            impl#impl_generics ::core::clone::Clone for #union_name#ty_generics #where_clause {
-                fn clone(&self) -> Self {
-                    *self
-                }
+    if let Some(where_clause) = &generics.where_clause {
+        predicates.extend(where_clause.predicates.iter().map(|p| quote::quote!(#p)));
+    }
+    let predicates = quote::quote!(#(#predicates,)*);
+    let short_predicates = replace_lifetime(predicates.clone(), lt, &short);
+    let short_ty_generics = replace_lifetime(quote::quote!(#ty_generics), lt, &short);
+    let value = quote::quote_spanned!(located_at(lt.span()) => value);
+    quote::quote! {
+        const _: () = {
+            #[allow(dead_code)]
+            fn __trait_union_covariant#check_impl_generics(
+                value: #name#ty_generics,
+            ) -> #name#short_ty_generics
+            where
+                #lt: #short,
+                #predicates
+                #short_predicates
+            {
+                #value
             }
-        });
+        };
     }
-    let tokens = quote::quote! {
-        #(#attr)*
-        #[allow(non_snake_case)]
-        #vis struct #name#impl_generics #where_clause {
-            #data_name: #union_name#ty_generics,
-            #vtable_name: #vtable_container_name,
-        }
+}
 
-        #drop_impl
+/// Checks that a field can be initialized with its default and ignored when dropping
+fn field_check(generics: &Generics, field: &Field) -> TokenStream {
+    let (impl_generics, _, where_clause) = generics.split_for_impl();
+    let ty = &field.ty;
+    quote::quote_spanned! { located_at(ty.span()) =>
+        const _: () = {
+            fn __trait_union_require<
+                T: ::core::marker::Copy + ::core::default::Default,
+            >() {}
 
-        /// Marker trait for types that can be stored in a [
-        #[doc = #name_as_str]
-        ///]
-        ///
-        /// # Safety
-        ///
-        /// This trait must not be implemented manually.
-        #vis unsafe trait #variant_name#impl_generics: #trait_ {}
+            #[allow(dead_code)]
+            fn __trait_union_check#impl_generics() #where_clause {
+                __trait_union_require::<#ty>();
+            }
+        };
+    }
+}
 
-        #[repr(C)]
-        #[allow(non_snake_case)]
-        struct #trait_object_name {
-            data: *mut (),
-            vtable: *mut (),
-        }
+/// Returns a statement that copies the fields `names` out of `this`
+///
+/// The fields are restored with [restore_fields]. Nothing is generated without fields.
+fn save_fields(this: TokenStream, names: &[&Option<Ident>]) -> TokenStream {
+    if names.is_empty() {
+        return quote::quote!();
+    }
+    // Reading the fields instead of copying them does not repeat the error of a field
+    // whose type is not `Copy`.
+    quote::quote! {
+        let __trait_union_fields = {
+            let this = #this;
+            // SAFETY: The fields are `Copy`.
+            unsafe { (#(::core::ptr::read(&this.#names),)*) }
+        };
+    }
+}
 
-        #[repr(C)]
-        #[allow(non_snake_case)]
-        union #union_name#impl_generics #where_clause {
-            #(#union_fields),*
+/// Returns an expression that evaluates to `value` with the fields saved by [save_fields]
+fn restore_fields(value: TokenStream, names: &[&Option<Ident>]) -> TokenStream {
+    if names.is_empty() {
+        return value;
+    }
+    let indices = (0..names.len()).map(syn::Index::from);
+    quote::quote! {{
+        let mut value = #value;
+        #(value.#names = __trait_union_fields.#indices;)*
+        value
+    }}
+}
+
+/// Generates a function that only compiles if `name` implements `trait_`
+fn require_check(name: &Ident, generics: &Generics, trait_: &Path) -> TokenStream {
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    quote::quote_spanned! { located_at(trait_.span()) =>
+        const _: () = {
+            fn __trait_union_require<T: ?::core::marker::Sized + #trait_>() {}
+
+            #[allow(dead_code)]
+            fn __trait_union_check#impl_generics() #where_clause {
+                __trait_union_require::<#name#ty_generics>();
+            }
+        };
+    }
+}
+
+/// Returns whether `tokens` contain the lifetime `lt`
+fn contains_lifetime(tokens: TokenStream, lt: &Lifetime) -> bool {
+    let mut after_quote = false;
+    for tt in tokens {
+        match &tt {
+            TokenTree::Group(g) if contains_lifetime(g.stream(), lt) => return true,
+            TokenTree::Ident(i) if after_quote && *i == lt.ident => return true,
+            _ => {}
         }
+        after_quote = matches!(&tt, TokenTree::Punct(p) if p.as_char() == '\'');
+    }
+    false
+}
 
-        #copy_impl
+fn contains_ident(tokens: TokenStream, ident: &Ident) -> bool {
+    let mut after_quote = false;
+    for tt in tokens {
+        match &tt {
+            TokenTree::Group(g) if contains_ident(g.stream(), ident) => return true,
+            TokenTree::Ident(i) if !after_quote && i == ident => return true,
+            _ => {}
+        }
+        after_quote = matches!(&tt, TokenTree::Punct(p) if p.as_char() == '\'');
+    }
+    false
+}
 
-        #[allow(non_camel_case_types)]
-        #[derive(Copy, Clone)]
-        struct #vtable_container_name(::core::ptr::NonNull<()>);
-        unsafe impl ::core::marker::Send for #vtable_container_name { }
-        unsafe impl ::core::marker::Sync for #vtable_container_name { }
+/// Generates `From` implementations that convert references to `name` into trait object
+/// references
+///
+/// The lifetime of the trait object is generic so that the target can be written as
+/// `&'r dyn Trait` with its default object lifetime.
+fn trait_object_from(
+    name: &Ident,
+    generics: &Generics,
+    trait_: &Punctuated<TypeParamBound, Token![+]>,
+    no_deref: bool,
+    inline: &TokenStream,
+) -> TokenStream {
+    let reference = Lifetime::new("'__trait_union_ref", Span::call_site());
+    let object = Lifetime::new("'__trait_union_object", Span::call_site());
+    let mut object_trait = trait_.clone();
+    let mut bound = None;
+    for b in object_trait.iter_mut() {
+        if let TypeParamBound::Lifetime(lt) = b {
+            bound = Some(std::mem::replace(lt, object.clone()));
+        }
+    }
+    let mut from_generics = generics.clone();
+    from_generics
+        .params
+        .insert(0, GenericParam::Lifetime(LifetimeDef::new(object.clone())));
+    from_generics.params.insert(
+        0,
+        GenericParam::Lifetime(LifetimeDef::new(reference.clone())),
+    );
+    from_generics
+        .make_where_clause()
+        .predicates
+        .push(syn::parse_quote!(#bound: #object));
+    let (_, ty_generics, _) = generics.split_for_impl();
+    let (impl_generics, _, where_clause) = from_generics.split_for_impl();
+    let (as_dyn, as_dyn_mut) = match no_deref {
+        true => (
+            quote::quote!(value.as_dyn()),
+            quote::quote!(value.as_dyn_mut()),
+        ),
+        false => (quote::quote!(&**value), quote::quote!(&mut **value)),
+    };
+    quote::quote! {
+        impl#impl_generics ::core::convert::From<&#reference #name#ty_generics>
+            for &#reference (dyn #object_trait) #where_clause
+        {
+            #inline
+            fn from(value: &#reference #name#ty_generics) -> Self {
+                #as_dyn
+            }
+        }
 
-        impl#impl_generics #name#ty_generics #where_clause {
-            /// Creates a new instance
-            #[inline(always)]
-            #vis fn new(value: impl #variant_name#ty_generics) -> Self {
-                let mut slf = ::core::mem::MaybeUninit::<Self>::uninit();
-                let vtable = {
-                    let trait_object: &(dyn #trait_) = &value;
-                    let trait_object: #trait_object_name = unsafe { ::core::mem::transmute(trait_object) };
-                    trait_object.vtable
-                };
-                unsafe {
-                    ::core::ptr::write(&mut (*slf.as_mut_ptr()).#data_name as *mut _ as *mut _, value);
-                    (*slf.as_mut_ptr()).#vtable_name = #vtable_container_name(::core::ptr::NonNull::new_unchecked(vtable));
-                    slf.assume_init()
-                }
+        impl#impl_generics ::core::convert::From<&#reference mut #name#ty_generics>
+            for &#reference mut (dyn #object_trait) #where_clause
+        {
+            #inline
+            fn from(value: &#reference mut #name#ty_generics) -> Self {
+                #as_dyn_mut
             }
         }
+    }
+}
 
-        #[inline(always)]
-        #[allow(non_snake_case)]
-        fn #to_trait_object_name#impl_generics(x: &#name#ty_generics) -> #trait_object_name #where_clause {
-            #trait_object_name {
-                data: &x.#data_name as *const _ as *mut _,
-                vtable: x.#vtable_name.0.as_ptr(),
+/// Returns the trait if it is `Fn(...)` or `FnMut(...)`, its arguments, and whether it is
+/// `FnMut`
+fn fn_trait(
+    trait_: &Punctuated<TypeParamBound, Token![+]>,
+) -> Option<(&TraitBound, &ParenthesizedGenericArguments, bool)> {
+    trait_.iter().find_map(|bound| {
+        let bound = match bound {
+            TypeParamBound::Trait(bound) => bound,
+            _ => return None,
+        };
+        let segment = bound.path.segments.last()?;
+        match &segment.arguments {
+            PathArguments::Parenthesized(args) if segment.ident == "Fn" => {
+                Some((bound, args, false))
+            }
+            PathArguments::Parenthesized(args) if segment.ident == "FnMut" => {
+                Some((bound, args, true))
             }
+            _ => None,
         }
+    })
+}
 
-        impl#impl_generics ::core::ops::Deref for #name#ty_generics #where_clause {
-            type Target = dyn #trait_;
+/// Generates a `call` method that calls the active variant if the trait is `Fn(...)` or
+/// `FnMut(...)`
+fn call_method(
+    vis: &Visibility,
+    trait_: &Punctuated<TypeParamBound, Token![+]>,
+    no_deref: bool,
+    inline: &TokenStream,
+) -> Option<TokenStream> {
+    let (bound, args, mutable) = fn_trait(trait_)?;
+    let lifetimes = bound.lifetimes.as_ref().map(|l| {
+        let lifetimes = &l.lifetimes;
+        quote::quote!(<#lifetimes>)
+    });
+    let names = (0..args.inputs.len())
+        .map(|i| Ident::new(&format!("arg{}", i), Span::call_site()))
+        .collect::<Vec<_>>();
+    let inputs = args.inputs.iter();
+    let output = &args.output;
+    let (receiver, target) = match (mutable, no_deref) {
+        (false, false) => (quote::quote!(&self), quote::quote!(&**self)),
+        (false, true) => (quote::quote!(&self), quote::quote!(self.as_dyn())),
+        (true, false) => (quote::quote!(&mut self), quote::quote!(&mut **self)),
+        (true, true) => (quote::quote!(&mut self), quote::quote!(self.as_dyn_mut())),
+    };
+    Some(quote::quote! {
+        /// Calls the active variant
+        #inline
+        #vis fn call#lifetimes(#receiver, #(#names: #inputs),*) #output {
+            (#target)(#(#names),*)
+        }
+    })
+}
 
-            #[inline(always)]
-            fn deref(&self) -> &Self::Target {
-                unsafe { ::core::mem::transmute(#to_trait_object_name(self)) }
+/// Replaces all `Self` tokens by `ty`
+fn replace_self(tokens: TokenStream, ty: &TokenStream) -> TokenStream {
+    let mut res = TokenStream::new();
+    for tt in tokens {
+        match tt {
+            TokenTree::Group(g) => {
+                let mut group = Group::new(g.delimiter(), replace_self(g.stream(), ty));
+                group.set_span(g.span());
+                res.extend(Some(TokenTree::Group(group)));
             }
+            TokenTree::Ident(i) if i == "Self" => res.extend(ty.clone()),
+            tt => res.extend(Some(tt)),
         }
+    }
+    res
+}
 
-        impl#impl_generics ::core::ops::DerefMut for #name#ty_generics #where_clause {
-            #[inline(always)]
-            fn deref_mut(&mut self) -> &mut Self::Target {
-                unsafe { ::core::mem::transmute(#to_trait_object_name(self)) }
+/// Rejects variants that contain the union without indirection
+///
+/// Only the syntax of the variant is inspected: `name` and `Self` are found in tuples,
+/// arrays, and wrappers that store their argument inline such as `Option`. Other generic
+/// types such as `Box` are assumed to provide indirection.
+fn check_recursion(name: &Ident, ty: &Type) -> syn::Result<()> {
+    const INLINE: &[&str] = &[
+        "Option",
+        "ManuallyDrop",
+        "MaybeUninit",
+        "Cell",
+        "RefCell",
+        "UnsafeCell",
+        "Wrapping",
+    ];
+    match ty {
+        Type::Paren(ty) => check_recursion(name, &ty.elem),
+        Type::Group(ty) => check_recursion(name, &ty.elem),
+        Type::Array(ty) => check_recursion(name, &ty.elem),
+        Type::Tuple(ty) => ty.elems.iter().try_for_each(|ty| check_recursion(name, ty)),
+        Type::Path(path) if path.qself.is_none() => {
+            let path = &path.path;
+            if path.is_ident("Self") || path.is_ident(name) {
+                return Err(syn::Error::new(
+                    ty.span(),
+                    format!(
+                        "recursive trait-union `{}` has infinite size\n\
+                         help: insert some indirection (e.g., a `Box`, `Rc`, or `&`) to \
+                         break the cycle",
+                        name,
+                    ),
+                ));
             }
+            let segment = match path.segments.last() {
+                Some(segment) if INLINE.iter().any(|i| segment.ident == i) => segment,
+                _ => return Ok(()),
+            };
+            if let PathArguments::AngleBracketed(args) = &segment.arguments {
+                for arg in &args.args {
+                    if let GenericArgument::Type(ty) = arg {
+                        check_recursion(name, ty)?;
+                    }
+                }
+            }
+            Ok(())
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Computes the tags of the variants
+///
+/// Like enum discriminants, variants without an explicit tag use the tag of the previous
+/// variant plus one. With `tag = "type_name_hash"`, they use the hash of their type
+/// instead.
+fn variant_tags(
+    variants: &Punctuated<Variant, Token![|]>,
+    derivation: Tag,
+) -> syn::Result<Vec<u64>> {
+    let mut tags: Vec<u64> = vec![];
+    for variant in variants {
+        let tag = match &variant.tag {
+            Some(tag) => tag.base10_parse()?,
+            None if derivation == Tag::TypeNameHash => type_name_hash(&variant.ty),
+            None => match tags.last() {
+                Some(&last) => last.checked_add(1).ok_or_else(|| {
+                    syn::Error::new(variant.ty.span(), "tag overflows u64")
+                })?,
+                None => 0,
+            },
+        };
+        if tags.contains(&tag) {
+            let msg = format!("tag {} is used by more than one variant", tag);
+            return Err(match &variant.tag {
+                Some(lit) => syn::Error::new(lit.span(), msg),
+                None => syn::Error::new(variant.ty.span(), msg),
+            });
         }
+        tags.push(tag);
+    }
+    Ok(tags)
+}
 
-        #(#variant_impls)*
-    };
-    tokens
+/// Returns the 64-bit FNV-1a hash of `ty` as written, without whitespace
+///
+/// This is the same hash as `__support::hash_bytes`. Removing the whitespace makes the
+/// hash independent of how the compiler formats token streams.
+fn type_name_hash(ty: &Type) -> u64 {
+    let name = quote::quote!(#ty).to_string();
+    let mut h = 0xcbf2_9ce4_8422_2325u64;
+    for b in name.bytes().filter(|b| !b.is_ascii_whitespace()) {
+        h ^= b as u64;
+        h = h.wrapping_mul(0x0000_0100_0000_01b3);
+    }
+    h
+}
+
+/// Appends a CamelCase name derived from `ty` to `name`
+///
+/// Returns `None` if no name can be derived.
+fn append_kind_name(name: &mut String, ty: &Type) -> Option<()> {
+    fn append_ident(name: &mut String, ident: &Ident) {
+        let ident = ident.to_string();
+        let mut upper = true;
+        for c in ident.trim_start_matches("r#").chars() {
+            if c == '_' {
+                upper = true;
+            } else if upper {
+                name.extend(c.to_uppercase());
+                upper = false;
+            } else {
+                name.push(c);
+            }
+        }
+    }
+    fn append_path(name: &mut String, path: &Path) -> Option<()> {
+        let segment = path.segments.last()?;
+        append_ident(name, &segment.ident);
+        if let PathArguments::AngleBracketed(args) = &segment.arguments {
+            for arg in &args.args {
+                match arg {
+                    GenericArgument::Lifetime(lt) => append_ident(name, &lt.ident),
+                    GenericArgument::Type(ty) => append_kind_name(name, ty)?,
+                    GenericArgument::Binding(b) => {
+                        append_ident(name, &b.ident);
+                        append_kind_name(name, &b.ty)?;
+                    }
+                    _ => {}
+                }
+            }
+        }
+        Some(())
+    }
+    match ty {
+        Type::Path(p) if p.qself.is_none() => append_path(name, &p.path)?,
+        Type::Reference(r) => {
+            if let Some(lt) = &r.lifetime {
+                append_ident(name, &lt.ident);
+            }
+            if r.mutability.is_some() {
+                name.push_str("Mut");
+            }
+            append_kind_name(name, &r.elem)?;
+        }
+        Type::Ptr(p) => {
+            name.push_str(if p.mutability.is_some() {
+                "MutPtr"
+            } else {
+                "ConstPtr"
+            });
+            append_kind_name(name, &p.elem)?;
+        }
+        Type::Slice(s) => {
+            append_kind_name(name, &s.elem)?;
+            name.push_str("Slice");
+        }
+        Type::Array(a) => {
+            append_kind_name(name, &a.elem)?;
+            name.push_str("Array");
+        }
+        Type::Tuple(t) if t.elems.is_empty() => name.push_str("Unit"),
+        Type::Tuple(t) => {
+            name.push_str("Tuple");
+            for ty in &t.elems {
+                append_kind_name(name, ty)?;
+            }
+        }
+        Type::TraitObject(t) => {
+            name.push_str("Dyn");
+            for bound in &t.bounds {
+                if let TypeParamBound::Trait(bound) = bound {
+                    append_path(name, &bound.path)?;
+                }
+            }
+        }
+        Type::BareFn(f) => {
+            if f.unsafety.is_some() {
+                name.push_str("Unsafe");
+            }
+            if let Some(abi) = &f.abi {
+                name.push_str("Extern");
+                let abi = abi.name.as_ref().map(|abi| abi.value().replace('-', "_"));
+                if let Some(abi) = abi.and_then(|abi| syn::parse_str::<Ident>(&abi).ok())
+                {
+                    append_ident(name, &abi);
+                }
+            }
+            name.push_str("Fn");
+        }
+        Type::Never(_) => name.push_str("Never"),
+        Type::Paren(p) => append_kind_name(name, &p.elem)?,
+        Type::Group(g) => append_kind_name(name, &g.elem)?,
+        _ => return None,
+    }
+    Some(())
+}
+
+/// Removes the `#[trait_union(...)]` attributes and doc comments from all variants and
+/// parses them
+fn variant_options(
+    variants: &mut Punctuated<Variant, Token![|]>,
+) -> syn::Result<Vec<VariantOptions>> {
+    let mut options = vec![];
+    for variant in variants.iter_mut() {
+        options.push(VariantOptions::extract(&mut variant.attrs)?);
+        if let Some(attr) = variant.attrs.first() {
+            return Err(syn::Error::new(
+                attr.span(),
+                "unsupported attribute on a variant",
+            ));
+        }
+    }
+    Ok(options)
+}
+
+/// Returns the names of the variants of the kind enum
+///
+/// Derived names that are not valid or not unique are replaced by `V{pos}`.
+fn kind_names(
+    variants: &Punctuated<Variant, Token![|]>,
+    options: &[VariantOptions],
+) -> syn::Result<Vec<Ident>> {
+    let explicit = options.iter().map(|o| o.name.clone()).collect::<Vec<_>>();
+    let mut names = Vec::<Ident>::new();
+    for (pos, (variant, name)) in variants.iter().zip(&explicit).enumerate() {
+        let name = match name {
+            Some(name) if names.contains(name) => {
+                return Err(syn::Error::new(name.span(), "duplicate kind name"));
+            }
+            Some(name) => name.clone(),
+            None => {
+                let span = variant.ty.span();
+                let mut derived = String::new();
+                if variant.boxed.is_some() {
+                    derived.push_str("Box");
+                }
+                match append_kind_name(&mut derived, &variant.ty) {
+                    Some(()) if derived != "Self" => {
+                        let derived = Ident::new(&derived, span);
+                        let mut explicit = explicit.iter().flatten();
+                        if names.contains(&derived) || explicit.any(|n| *n == derived) {
+                            Ident::new(&format!("V{}", pos), span)
+                        } else {
+                            derived
+                        }
+                    }
+                    _ => Ident::new(&format!("V{}", pos), span),
+                }
+            }
+        };
+        names.push(name);
+    }
+    Ok(names)
+}
+
+/// Returns the smallest unsigned integer type that can represent all tags
+fn tag_type(tags: &[u64]) -> Ident {
+    let max = tags.iter().copied().max().unwrap_or(0);
+    let ty = if max <= u8::MAX as u64 {
+        "u8"
+    } else if max <= u16::MAX as u64 {
+        "u16"
+    } else if max <= u32::MAX as u64 {
+        "u32"
+    } else {
+        "u64"
+    };
+    Ident::new(ty, Span::call_site())
+}
+
+/// Generates a match on the active variant of `self`
+///
+/// Each arm is evaluated with `value` bound to a reference to the value of its variant.
+/// The reference is mutable if `mutable` is true. `extra_arm` is appended to the arms of
+/// an enum match.
+fn match_variant(
+    impl_: Impl,
+    names: &Names,
+    mutable: bool,
+    arms: Vec<TokenStream>,
+    extra_arm: Option<&TokenStream>,
+) -> TokenStream {
+    let Names {
+        data,
+        index,
+        union,
+        index_type,
+        ..
+    } = names;
+    let reference = match mutable {
+        true => quote::quote!(&mut),
+        false => quote::quote!(&),
+    };
+    let arms = arms.into_iter().enumerate().map(|(pos, arm)| {
+        let variant = variant_index(pos);
+        match impl_ {
+            Impl::Union => {
+                let field = variant_field(pos);
+                quote::quote! {
+                    #index_type::#variant => {
+                        let value = unsafe { #reference *self.#data.#field };
+                        #arm
+                    }
+                }
+            }
+            Impl::Enum => quote::quote!(#union::#variant(value) => #arm),
+        }
+    });
+    match impl_ {
+        Impl::Union => quote::quote! {
+            match self.#index {
+                #(#arms,)*
+                #[cfg(debug_assertions)]
+                #index_type::Dropped => ::core::panic!("use of a dropped trait union"),
+            }
+        },
+        Impl::Enum => {
+            quote::quote!(match #reference self.#data { #(#arms,)* #extra_arm })
+        }
+    }
+}
+
+fn handle_request(
+    mut request: TraitUnionRequest,
+    copy: bool,
+) -> syn::Result<TokenStream> {
+    let mut attr = std::mem::take(&mut request.attr);
+    let options = Options::extract(&mut attr)?;
+    if let Some(field) = request.fields.first() {
+        let conflict = match (options.open.is_some(), options.atomic.is_some()) {
+            (true, _) => Some("open"),
+            (_, true) => Some("atomic"),
+            _ => None,
+        };
+        if let Some(conflict) = conflict {
+            return Err(syn::Error::new(
+                field.span(),
+                format!("fields cannot be combined with the {} option", conflict),
+            ));
+        }
+    }
+    if options.open.is_some() {
+        return open::handle_open_request(request, attr, &options, copy);
+    }
+    if request.variants.is_empty() {
+        return Err(syn::Error::new(
+            request.ident.span(),
+            "expected `= VARIANTS` or the open option",
+        ));
+    }
+    for variant in &request.variants {
+        if variant.boxed.is_none() {
+            check_recursion(&request.ident, &variant.ty)?;
+        }
+    }
+    let mut variant_options = variant_options(&mut request.variants)?;
+    for (variant, options) in request.variants.iter().zip(&mut variant_options) {
+        if let (Some(_), Type::Path(path)) = (&variant.boxed, &variant.ty) {
+            let path = &path.path;
+            if path.is_ident("Self") || path.is_ident(&request.ident) {
+                options.flatten = true;
+            }
+        }
+    }
+    let kind_variants = kind_names(&request.variants, &variant_options)?;
+    // Computed before `Self` and box variants are rewritten since tags can be derived
+    // from the types as written.
+    let tags = variant_tags(&request.variants, options.tag)?;
+    let mut manual = vec![false; request.variants.len()];
+    for ty in &options.manual_impl {
+        let ty_str = quote::quote!(#ty).to_string();
+        let pos = request
+            .variants
+            .iter()
+            .zip(&variant_options)
+            .position(|(v, o)| {
+                let variant = &v.ty;
+                v.boxed.is_none()
+                    && !o.flatten
+                    && quote::quote!(#variant).to_string() == ty_str
+            });
+        match pos {
+            Some(pos) => manual[pos] = true,
+            None => {
+                return Err(syn::Error::new(
+                    ty.span(),
+                    "manual_impl expects variants that are neither boxed nor flattened",
+                ))
+            }
+        }
+    }
+    {
+        let name = &request.ident;
+        let (_, ty_generics, _) = request.generics.split_for_impl();
+        let self_ty = quote::quote!(#name#ty_generics);
+        for variant in request.variants.iter_mut() {
+            let ty = &variant.ty;
+            variant.ty = syn::parse2(replace_self(quote::quote!(#ty), &self_ty))?;
+        }
+    }
+    let declared = request
+        .variants
+        .iter()
+        .map(|v| {
+            let (boxed, ty) = (&v.boxed, &v.ty);
+            quote::quote!(#boxed #ty).to_string()
+        })
+        .collect::<Vec<_>>();
+    for variant in request.variants.iter_mut() {
+        if let Some(boxed) = &variant.boxed {
+            if cfg!(not(feature = "alloc")) {
+                return Err(syn::Error::new(
+                    boxed.span(),
+                    "box variants require the alloc feature of trait-union",
+                ));
+            }
+            let ty = &variant.ty;
+            variant.ty = syn::parse_quote_spanned!(ty.span() => ::trait_union::__support::Box<#ty>);
+        }
+    }
+    let impl_ = options.impl_;
+    let inline = options.inline.attr();
+    let vis = request.vis;
+    let new_vis = options.new_vis.as_ref().unwrap_or(&vis);
+    // The ref, mut, and owned enums contain the variants, which can be private types of
+    // the declaring module. Unless requested, they and the methods that use them are
+    // private.
+    let private = Visibility::Inherited;
+    let (enum_vis, enum_new_vis) = match options.enums {
+        true => (&vis, new_vis),
+        false => (&private, &private),
+    };
+    let name = request.ident;
+    let doc = format!(
+        "Marker trait for types that can be stored in a [{}]\n\n\
+         # Safety\n\n\
+         This trait must not be implemented manually.",
+        name
+    );
+    let names = Names::new(&name);
+    let Names {
+        data: data_name,
+        index: index_name,
+        variant: variant_name,
+        kind: kind_name,
+        ref_: ref_name,
+        mut_: mut_name,
+        owned: owned_name,
+        atomic: atomic_name,
+        metrics: metrics_name,
+        dispatcher: dispatcher_name,
+        manual_variant: manual_variant_name,
+        cell: cell_name,
+        visitor: visitor_name,
+        handle: handle_name,
+        send: send_name,
+        union: union_name,
+        index_type: index_type_name,
+    } = &names;
+    let mut trait_ = request.trait_;
+    let mut lifetimes = request.generics.lifetimes();
+    let default_lifetime = match (lifetimes.next(), lifetimes.next()) {
+        (Some(lt), None) => lt.lifetime.to_string(),
+        _ => "'static".to_string(),
+    };
+    if options.no_implicit_static.is_some() && default_lifetime == "'static" {
+        check_explicit_lifetime(&trait_)?;
+    }
+    add_default_lifetime(&mut trait_, &default_lifetime);
+    let mut union_fields = vec![];
+    let mut phantoms = vec![];
+    let bound_lifetime = trait_.iter().find_map(|b| match b {
+        TypeParamBound::Lifetime(lt) => Some(lt),
+        _ => None,
+    });
+    if let Some(lt) = bound_lifetime {
+        let declared = request.generics.lifetimes().any(|l| l.lifetime == *lt);
+        let used = request.variants.iter().any(|v| {
+            let ty = &v.ty;
+            contains_lifetime(quote::quote!(#ty), lt)
+        });
+        if declared && !used {
+            phantoms.push(quote::quote!(&#lt ()));
+        }
+    }
+    // Parameters that only appear in the arguments of the trait are invariant like the
+    // trait object.
+    let trait_args = trait_
+        .iter()
+        .filter(|b| matches!(b, TypeParamBound::Trait(_)))
+        .collect::<Vec<_>>();
+    let trait_args = quote::quote!(#(#trait_args)*);
+    let variant_tys = request.variants.iter().map(|v| &v.ty);
+    let variant_tys = quote::quote!(#(#variant_tys)*);
+    for param in &request.generics.params {
+        match param {
+            GenericParam::Lifetime(l) => {
+                let lt = &l.lifetime;
+                if contains_lifetime(trait_args.clone(), lt)
+                    && !contains_lifetime(variant_tys.clone(), lt)
+                {
+                    phantoms.push(quote::quote!(fn(&#lt ()) -> &#lt ()));
+                }
+            }
+            GenericParam::Type(t) => {
+                let ty = &t.ident;
+                if contains_ident(trait_args.clone(), ty)
+                    && !contains_ident(variant_tys.clone(), ty)
+                {
+                    phantoms.push(quote::quote!(fn(*const #ty) -> *const #ty));
+                }
+            }
+            GenericParam::Const(_) => {}
+        }
+    }
+    for param in &options.invariant {
+        phantoms.push(match param {
+            VarianceParam::Lifetime(lt) => {
+                if !request.generics.lifetimes().any(|l| l.lifetime == *lt) {
+                    return Err(syn::Error::new(lt.span(), "unknown lifetime parameter"));
+                }
+                quote::quote!(fn(&#lt ()) -> &#lt ())
+            }
+            VarianceParam::Type(ty) => {
+                if !request.generics.type_params().any(|t| t.ident == *ty) {
+                    return Err(syn::Error::new(ty.span(), "unknown type parameter"));
+                }
+                quote::quote!(fn(*const #ty) -> *const #ty)
+            }
+        });
+    }
+    phantoms.extend(options.phantom.iter().map(|ty| quote::quote!(#ty)));
+    // Reported here because rustc would report the parameter once per generated type.
+    let mentioned = quote::quote!(#variant_tys #(#phantoms)*);
+    for param in &request.generics.params {
+        let (used, span, param) = match param {
+            GenericParam::Lifetime(l) => (
+                contains_lifetime(mentioned.clone(), &l.lifetime),
+                l.lifetime.span(),
+                l.lifetime.to_string(),
+            ),
+            GenericParam::Type(t) => (
+                contains_ident(mentioned.clone(), &t.ident),
+                t.ident.span(),
+                t.ident.to_string(),
+            ),
+            GenericParam::Const(_) => continue,
+        };
+        if !used {
+            return Err(syn::Error::new(
+                span,
+                format!(
+                    "`{}` is not used by any variant; mention it with the `phantom` option",
+                    param
+                ),
+            ));
+        }
+    }
+    let mut checks = vec![];
+    for lt in &options.covariant {
+        if !request.generics.lifetimes().any(|l| l.lifetime == *lt) {
+            return Err(syn::Error::new(lt.span(), "unknown lifetime parameter"));
+        }
+        let invariant = options
+            .invariant
+            .iter()
+            .any(|p| matches!(p, VarianceParam::Lifetime(l) if l == lt));
+        if invariant {
+            return Err(syn::Error::new(
+                lt.span(),
+                format!("`{}` cannot be both covariant and invariant", lt),
+            ));
+        }
+        checks.push(covariance_check(&name, &request.generics, lt));
+    }
+    for trait_ in &options.require {
+        checks.push(require_check(&name, &request.generics, trait_));
+    }
+    for field in &request.fields {
+        checks.push(field_check(&request.generics, field));
+    }
+    let fields = &request.fields;
+    let field_names = fields.iter().map(|f| &f.ident).collect::<Vec<_>>();
+    let mut phantom_arm = None;
+    let mut phantom_variant = None;
+    if !phantoms.is_empty() {
+        let phantom = quote::quote!(::core::marker::PhantomData<(#(#phantoms,)*)>);
+        union_fields.push(match impl_ {
+            Impl::Union => quote::quote!(__trait_union_phantom: #phantom),
+            Impl::Enum => quote::quote! {
+                __TraitUnionPhantom(#phantom, ::core::convert::Infallible)
+            },
+        });
+        phantom_variant = Some(quote::quote! {
+            #[doc(hidden)]
+            __TraitUnionPhantom(#phantom, ::core::convert::Infallible),
+        });
+        phantom_arm = Some(quote::quote! {
+            #union_name::__TraitUnionPhantom(_, never) => match *never {},
+        });
+    }
+    let (impl_generics, ty_generics, where_clause) = request.generics.split_for_impl();
+    let num_variants = request.variants.len();
+    let tag_type = tag_type(&tags);
+    let tag_literals = tags
+        .iter()
+        .map(|&t| Literal::u64_unsuffixed(t))
+        .collect::<Vec<_>>();
+    let kind_doc = format!("The kinds of variants of [{}]", name);
+    let kind_variant_docs = declared
+        .iter()
+        .zip(&variant_options)
+        .map(|(d, o)| {
+            if o.docs.is_empty() {
+                let doc = format!("`{}`", d);
+                quote::quote!(#[doc = #doc])
+            } else {
+                let docs = &o.docs;
+                quote::quote!(#(#docs)*)
+            }
+        })
+        .collect::<Vec<_>>();
+    let ref_doc = format!("A reference to the active variant of a [{}]", name);
+    let mut_doc = format!("A mutable reference to the active variant of a [{}]", name);
+    let owned_doc = format!("The active variant of a [{}] by value", name);
+    let mut ref_generics = request.generics.clone();
+    ref_generics.params.insert(
+        0,
+        GenericParam::Lifetime(LifetimeDef::new(Lifetime::new(
+            "'__trait_union_ref",
+            Span::call_site(),
+        ))),
+    );
+    let (ref_impl_generics, ref_ty_generics, ref_where_clause) =
+        ref_generics.split_for_impl();
+    let variant_types = request.variants.iter().map(|v| &v.ty).collect::<Vec<_>>();
+    let ref_arms = kind_variants
+        .iter()
+        .map(|v| quote::quote!(#ref_name::#v(value)))
+        .collect();
+    let as_ref_enum = match_variant(impl_, &names, false, ref_arms, phantom_arm.as_ref());
+    let mut_arms = kind_variants
+        .iter()
+        .map(|v| quote::quote!(#mut_name::#v(value)))
+        .collect();
+    let as_mut_enum = match_variant(impl_, &names, true, mut_arms, phantom_arm.as_ref());
+    let mut layout_check = None;
+    let mut eager_layout_check = None;
+    if let Some(Some(_)) = &options.abi_check {
+        layout_check = Some(quote::quote! {
+            #[allow(clippy::let_unit_value)]
+            let () = Self::__TRAIT_UNION_LAYOUT_CHECK;
+        });
+        if request.generics.type_params().next().is_none()
+            && request.generics.const_params().next().is_none()
+        {
+            let lifetimes = request.generics.lifetimes().map(|_| quote::quote!('static));
+            eager_layout_check = Some(quote::quote! {
+                const _: () = <#name<#(#lifetimes),*>>::__TRAIT_UNION_LAYOUT_CHECK;
+            });
+        }
+    }
+    let mut live_counts = None;
+    let mut count_drop = None;
+    // Values of `trait_union_copy` are never dropped and therefore cannot be registered.
+    let registry = cfg!(feature = "debug-registry") && !copy;
+    let track_caller = registry.then(|| quote::quote!(#[track_caller]));
+    let count_new = |pos: TokenStream| {
+        let live_count = options.live_counts.map(|_| {
+            quote::quote! {
+                Self::__trait_union_live_counts()[#pos]
+                    .fetch_add(1, ::core::sync::atomic::Ordering::Relaxed);
+            }
+        });
+        let metrics = options.metrics.map(|_| {
+            quote::quote! {
+                if let ::core::option::Option::Some(metrics) = Self::__trait_union_metrics().get() {
+                    metrics.on_new(Self::__TRAIT_UNION_KINDS[#pos]);
+                }
+            }
+        });
+        let register = registry.then(|| {
+            quote::quote! {
+                ::trait_union::__support::register_live(
+                    &Self::__TRAIT_UNION_LAYOUT,
+                    #pos,
+                    ::core::panic::Location::caller(),
+                );
+            }
+        });
+        match (live_count, metrics, register) {
+            (None, None, None) => None,
+            (live_count, metrics, register) => {
+                Some(quote::quote!(#live_count #metrics #register))
+            }
+        }
+    };
+    if let Some(span) = options.live_counts {
+        if copy {
+            return Err(syn::Error::new(
+                span,
+                "the live_counts option is not supported by trait_union_copy",
+            ));
+        }
+        live_counts = Some(quote::quote! {
+            #inline
+            fn __trait_union_live_counts(
+            ) -> &'static [::core::sync::atomic::AtomicUsize; #num_variants] {
+                #[allow(clippy::declare_interior_mutable_const)]
+                const ZERO: ::core::sync::atomic::AtomicUsize =
+                    ::core::sync::atomic::AtomicUsize::new(0);
+                static COUNTS: [::core::sync::atomic::AtomicUsize; #num_variants] =
+                    [ZERO; #num_variants];
+                &COUNTS
+            }
+
+            /// Returns the number of live values of each variant in declaration order
+            ///
+            /// The counts are shared by all instantiations of the generic parameters.
+            #vis fn live_counts() -> [usize; #num_variants] {
+                let counts = Self::__trait_union_live_counts();
+                let mut res = [0; #num_variants];
+                for (res, count) in res.iter_mut().zip(counts) {
+                    *res = count.load(::core::sync::atomic::Ordering::Relaxed);
+                }
+                res
+            }
+        });
+        count_drop = Some(quote::quote! {
+            Self::__trait_union_live_counts()[self.__trait_union_position()]
+                .fetch_sub(1, ::core::sync::atomic::Ordering::Relaxed);
+        });
+    }
+    let mut count_into = None;
+    if options.live_counts.is_some() {
+        count_into = Some(quote::quote! {
+            Self::__trait_union_live_counts()[this.__trait_union_position()]
+                .fetch_sub(1, ::core::sync::atomic::Ordering::Relaxed);
+        });
+    }
+    let mut metrics = None;
+    let mut metrics_methods = None;
+    if let Some(span) = options.metrics {
+        if copy {
+            return Err(syn::Error::new(
+                span,
+                "the metrics option is not supported by trait_union_copy",
+            ));
+        }
+        let doc = format!(
+            "Observes the construction and destruction of [{}] values\n\n\
+             Register an implementation with [{}::set_metrics].",
+            name, name
+        );
+        metrics = Some(quote::quote! {
+            #[doc = #doc]
+            #vis trait #metrics_name: ::core::marker::Sync {
+                /// Called when a value of kind `kind` is created
+                #inline
+                fn on_new(&self, kind: #kind_name) {
+                    let _ = kind;
+                }
+
+                /// Called when a value of kind `kind` is dropped or taken by `into_enum`
+                #inline
+                fn on_drop(&self, kind: #kind_name) {
+                    let _ = kind;
+                }
+            }
+        });
+        let on_drop = |this: TokenStream| {
+            quote::quote! {
+                if let ::core::option::Option::Some(metrics) = Self::__trait_union_metrics().get() {
+                    metrics.on_drop(Self::kind_of(&#this));
+                }
+            }
+        };
+        let metrics_drop = on_drop(quote::quote!(self));
+        count_drop = Some(quote::quote!(#count_drop #metrics_drop));
+        let metrics_into = on_drop(quote::quote!(this));
+        count_into = Some(quote::quote!(#count_into #metrics_into));
+        metrics_methods = Some(quote::quote! {
+            #inline
+            fn __trait_union_metrics(
+            ) -> &'static ::trait_union::__support::MetricsCell<dyn #metrics_name> {
+                static METRICS: ::trait_union::__support::MetricsCell<dyn #metrics_name> =
+                    ::trait_union::__support::MetricsCell::new();
+                &METRICS
+            }
+            /// Registers the metrics hook of this type
+            ///
+            /// The hook is shared by all instantiations of the generic parameters.
+            /// Returns `metrics` if a hook is already registered.
+            #vis fn set_metrics(
+                metrics: &'static dyn #metrics_name,
+            ) -> ::core::result::Result<(), &'static dyn #metrics_name> {
+                Self::__trait_union_metrics().set(metrics)
+            }
+        });
+    }
+    if registry {
+        let unregister = |this: TokenStream| {
+            quote::quote! {
+                ::trait_union::__support::unregister_live(
+                    &Self::__TRAIT_UNION_LAYOUT,
+                    #this.__trait_union_position(),
+                );
+            }
+        };
+        let unregister_drop = unregister(quote::quote!(self));
+        count_drop = Some(quote::quote!(#count_drop #unregister_drop));
+        let unregister_into = unregister(quote::quote!(this));
+        count_into = Some(quote::quote!(#count_into #unregister_into));
+    }
+    let ref_phantom_arm = phantom_arm.as_ref().map(
+        |_| quote::quote!(#ref_name::__TraitUnionPhantom(_, never) => match never {},),
+    );
+    let mut indices = vec![];
+    let mut vtables = vec![];
+    let mut sizes = vec![];
+    let mut variant_impls = vec![];
+    let mut flattened = vec![];
+    let mut flattened_mut = vec![];
+    let mut flattened_drop = vec![];
+    let mut deref_exprs = vec![];
+    let mut deref_mut_exprs = vec![];
+    // Code spanned by the variants counts as code of the caller. `unsafe` keeps the call
+    // site span so that the expansion does not trip `#![forbid(unsafe_code)]`.
+    let unsafe_ = quote::quote!(unsafe);
+    // The vtable does not depend on the lifetime bound. Casting to the bound would report
+    // a variant that does not outlive it a second time after the marker trait impl.
+    let mut vtable_trait: Punctuated<TypeParamBound, Token![+]> = trait_
+        .iter()
+        .filter(|b| !matches!(b, TypeParamBound::Lifetime(_)))
+        .cloned()
+        .collect();
+    add_default_lifetime(&mut vtable_trait, "'_");
+    for (pos, (variant, variant_options)) in
+        request.variants.iter().zip(&variant_options).enumerate()
+    {
+        let boxed = variant.boxed.is_some();
+        let variant = &variant.ty;
+        let ident = variant_field(pos);
+        let index = variant_index(pos);
+        union_fields.push(match impl_ {
+            Impl::Union => quote::quote_spanned! { located_at(variant.span()) =>
+                #ident: ::core::mem::ManuallyDrop<#variant>
+            },
+            Impl::Enum => {
+                quote::quote_spanned!(located_at(variant.span()) => #index(#variant))
+            }
+        });
+        indices.push(quote::quote!(#pos => #index_type_name::#index));
+        sizes.push(
+            quote::quote_spanned!(located_at(variant.span()) => ::core::mem::size_of::<#variant>()),
+        );
+        if variant_options.flatten || boxed {
+            if copy {
+                let msg = match boxed {
+                    true => "box variants are not supported by trait_union_copy",
+                    false => "flattened variants are not supported by trait_union_copy",
+                };
+                return Err(syn::Error::new(variant.span(), msg));
+            }
+            if options.new_vis.is_some() {
+                let msg = match boxed {
+                    true => "box variants cannot be combined with new_vis",
+                    false => "flattened variants cannot be combined with new_vis",
+                };
+                return Err(syn::Error::new(variant.span(), msg));
+            }
+            let field = variant_field(pos);
+            // A flattened trait-union is converted with `From` since it might not
+            // implement `Deref`. A boxed one is dereferenced once more.
+            let derefs = match boxed || !variant_options.flatten {
+                true => quote::quote!(**),
+                false => quote::quote!(*),
+            };
+            let convert = |inner: TokenStream| match variant_options.flatten {
+                true => quote::quote!(::core::convert::From::from(#inner)),
+                false => inner,
+            };
+            let inner = convert(quote::quote!(&#derefs self.#data_name.#field));
+            let inner_mut = convert(quote::quote!(&mut #derefs self.#data_name.#field));
+            let value = convert(quote::quote!(&#derefs value));
+            let value_mut = convert(quote::quote!(&mut #derefs value));
+            vtables.push(quote::quote!(::trait_union::__support::VtablePtr::NULL));
+            flattened.push(quote::quote_spanned! { located_at(variant.span()) =>
+                if self.#index_name == #index_type_name::#index {
+                    let inner: &(dyn #trait_) = #unsafe_ { #inner };
+                    return #unsafe_ {
+                        ::core::mem::transmute::<&(dyn #trait_), ::trait_union::__support::RawTraitObject>(inner)
+                    };
+                }
+            });
+            flattened_drop.push(quote::quote_spanned! { located_at(variant.span()) =>
+                if self.#index_name == #index_type_name::#index {
+                    #unsafe_ { ::core::mem::ManuallyDrop::drop(&mut self.#data_name.#field) };
+                } else
+            });
+            flattened_mut.push(quote::quote_spanned! { located_at(variant.span()) =>
+                if self.#index_name == #index_type_name::#index {
+                    let inner: &mut (dyn #trait_) = #unsafe_ { #inner_mut };
+                    return #unsafe_ {
+                        ::core::mem::transmute::<&mut (dyn #trait_), ::trait_union::__support::RawTraitObject>(inner)
+                    };
+                }
+            });
+            deref_exprs.push(value);
+            deref_mut_exprs.push(value_mut);
+            let new = match impl_ {
+                Impl::Union => {
+                    quote::quote!(unsafe { Self::__trait_union_new(#pos, value) })
+                }
+                Impl::Enum => quote::quote! {
+                    Self {
+                        #data_name: #union_name::#index(value),
+                        #(#field_names: ::core::default::Default::default(),)*
+                    }
+                },
+            };
+            let count_flattened = count_new(quote::quote!(#pos));
+            variant_impls.push(quote::quote_spanned! { located_at(variant.span()) =>
+                impl#impl_generics ::core::convert::From<#variant> for #name#ty_generics #where_clause {
+                    #inline
+                    #track_caller
+                    fn from(value: #variant) -> Self {
+                        #layout_check
+                        #count_flattened
+                        #new
+                    }
+                }
+            });
+            continue;
+        }
+        deref_exprs.push(quote::quote!(value));
+        deref_mut_exprs.push(quote::quote!(value));
+        vtables.push(quote::quote_spanned! { located_at(variant.span()) =>
+            #unsafe_ {
+                ::trait_union::__support::VtablePtr::of(
+                    ::core::ptr::null::<#variant>() as *const (dyn #vtable_trait),
+                )
+            }
+        });
+        let docs = &variant_options.docs;
+        let kind = &kind_variants[pos];
+        let kind_str = kind.to_string();
+        let mut variant_generics = request.generics.clone();
+        if manual[pos] {
+            variant_generics
+                .make_where_clause()
+                .predicates
+                .push(syn::parse_quote!(#variant: #manual_variant_name#ty_generics));
+        }
+        let (_, _, variant_where_clause) = variant_generics.split_for_impl();
+        variant_impls.push(quote::quote_spanned! { located_at(variant.span()) =>
+            #(#docs)*
+            #unsafe_ impl#impl_generics #variant_name#ty_generics for #variant #variant_where_clause {
+                const __TRAIT_UNION_INDEX: usize = #pos;
+                const KIND: #kind_name = #kind_name::#kind;
+                const SIZE: usize = ::core::mem::size_of::<Self>();
+                const NAME: &'static str = #kind_str;
+            }
+        });
+    }
+    let index_variants = (0..num_variants).map(variant_index);
+    let indices_pos = 0..num_variants;
+    let mut layout_hash = None;
+    if let Some(pinned) = &options.abi_check {
+        let variants = request
+            .variants
+            .iter()
+            .zip(&tags)
+            .map(|(v, tag)| {
+                let v = &v.ty;
+                quote::quote_spanned! { located_at(v.span()) =>
+                    let h = ::trait_union::__support::hash_u64(h, #tag);
+                    let h = ::trait_union::__support::hash_usize(h, ::core::mem::size_of::<#v>());
+                    let h = ::trait_union::__support::hash_usize(h, ::core::mem::align_of::<#v>());
+                }
+            });
+        let mut check = None;
+        if let Some(pinned) = pinned {
+            let msg = format!("the layout of {} does not match the pinned hash", name);
+            check = Some(quote::quote_spanned! { located_at(pinned.span()) =>
+                const __TRAIT_UNION_LAYOUT_CHECK: () = if Self::LAYOUT_HASH != #pinned {
+                    ::core::panic!(#msg)
+                };
+            });
+        }
+        layout_hash = Some(quote::quote! {
+            /// Hash of the order, tags, sizes, and alignments of the variants
+            #vis const LAYOUT_HASH: u64 = {
+                let h = ::trait_union::__support::HASH_OFFSET;
+                #(#variants)*
+                h
+            };
+
+            #check
+        });
+    }
+    let mut size_report = None;
+    let mut size_check = None;
+    if let Some(pinned) = &options.report_size {
+        size_report = Some(quote::quote! {
+            /// The size and alignment of this type and its largest variant
+            #vis const SIZE_REPORT: ::trait_union::SizeReport =
+                ::trait_union::SizeReport::new(Self::__TRAIT_UNION_LAYOUT);
+        });
+        if let Some(pinned) = pinned {
+            if request.generics.type_params().next().is_some()
+                || request.generics.const_params().next().is_some()
+            {
+                return Err(syn::Error::new(
+                    pinned.span(),
+                    "a pinned size is not supported with type or const parameters",
+                ));
+            }
+            let lifetimes = request.generics.lifetimes().map(|_| quote::quote!('static));
+            let msg = format!(
+                "the size of {} is no longer {} bytes, see {}::SIZE_REPORT",
+                name, pinned, name
+            );
+            // The deprecated `check` is only selected if the size has changed, which
+            // turns the change into a warning instead of an error.
+            size_check = Some(quote::quote_spanned! { located_at(pinned.span()) =>
+                const _: () = {
+                    struct Check<const UNCHANGED: bool>;
+
+                    #[allow(dead_code)]
+                    impl Check<true> {
+                        const fn check() {}
+                    }
+
+                    #[allow(dead_code)]
+                    impl Check<false> {
+                        #[deprecated(note = #msg)]
+                        const fn check() {}
+                    }
+
+                    Check::<{ ::core::mem::size_of::<#name<#(#lifetimes),*>>() == #pinned }>::check();
+                };
+            });
+        }
+    }
+    let name_as_str = name.to_string();
+    // Without `offset_of`, the storage is declared first in a `#[repr(C)]` struct.
+    let offset = match cfg!(trait_union_no_offset_of) {
+        true => quote::quote!(0),
+        false => quote::quote!(::core::mem::offset_of!(Self, #data_name)),
+    };
+    let variant_layouts =
+        request
+            .variants
+            .iter()
+            .zip(&tags)
+            .enumerate()
+            .map(|(pos, (v, tag))| {
+                let ty = &v.ty;
+                let ty_as_str = &declared[pos];
+                quote::quote_spanned! { located_at(ty.span()) =>
+                    ::trait_union::VariantLayout {
+                        name: #ty_as_str,
+                        tag: #tag,
+                        size: ::core::mem::size_of::<#ty>(),
+                        align: ::core::mem::align_of::<#ty>(),
+                        offset: #offset,
+                    }
+                }
+            });
+    let mut schema = None;
+    if options.schema {
+        let trait_as_str = quote::quote!(#trait_).to_string();
+        let kinds = kind_variants.iter().map(|v| v.to_string());
+        schema = Some(quote::quote! {
+            /// Machine-readable description of this type
+            #vis const SCHEMA: ::trait_union::Schema = ::trait_union::Schema {
+                trait_: #trait_as_str,
+                layout: Self::__TRAIT_UNION_LAYOUT,
+                kinds: &[#(#kinds),*],
+            };
+        });
+    }
+    let mut pod = None;
+    if let Some(span) = options.pod {
+        let mut decode = vec![];
+        let mut encode = vec![];
+        for (variant, &tag) in request.variants.iter().zip(&tags) {
+            let ty = &variant.ty;
+            let tag = Literal::u64_unsuffixed(tag);
+            decode.push(quote::quote_spanned! { located_at(ty.span()) =>
+                #tag => {
+                    let expected = ::core::mem::size_of::<#ty>();
+                    if bytes.len() != expected {
+                        return ::core::result::Result::Err(::trait_union::DecodeError::Length {
+                            expected,
+                            actual: bytes.len(),
+                        });
+                    }
+                    Self::new(::trait_union::__support::bytemuck::pod_read_unaligned::<#ty>(bytes))
+                }
+            });
+            encode.push(quote::quote_spanned! { located_at(ty.span()) =>
+                ::trait_union::__support::bytemuck::bytes_of::<#ty>(value)
+            });
+        }
+        let encode = match_variant(impl_, &names, false, encode, phantom_arm.as_ref());
+        pod = Some(quote::quote_spanned! { located_at(span) =>
+            /// Decodes a value from the tag of a variant and the bytes of its value
+            #new_vis fn decode(
+                tag: #tag_type,
+                bytes: &[u8],
+            ) -> ::core::result::Result<Self, ::trait_union::DecodeError> {
+                let value = match tag {
+                    #(#decode,)*
+                    _ => return ::core::result::Result::Err(
+                        ::trait_union::DecodeError::UnknownTag(tag as u64),
+                    ),
+                };
+                ::core::result::Result::Ok(value)
+            }
+
+            /// Returns the kind and the bytes of the active variant
+            #[inline]
+            #vis fn as_bytes(&self) -> (#kind_name, &[u8]) {
+                (Self::kind_of(self), #encode)
+            }
+
+            /// Writes the bytes of the active variant to `out` and returns their number
+            ///
+            /// The tag of the variant is not written. Use `tag` to retrieve it.
+            ///
+            /// # Panics
+            ///
+            /// Panics if `out` is shorter than the active variant.
+            #vis fn encode(&self, out: &mut [u8]) -> usize {
+                let (_, bytes) = self.as_bytes();
+                out[..bytes.len()].copy_from_slice(bytes);
+                bytes.len()
+            }
+        });
+    }
+    let mut atomic = None;
+    if let Some(span) = options.atomic {
+        if options.pod.is_none() {
+            return Err(syn::Error::new(
+                span,
+                "the atomic option requires the pod option",
+            ));
+        }
+        if tag_type != "u8" {
+            return Err(syn::Error::new(
+                span,
+                "the atomic option requires all tags to fit in a u8",
+            ));
+        }
+        let size_msg = format!("the variants of {} must be at most 7 bytes large", name);
+        let atomic_doc = format!("An atomic [{}]", name);
+        let mut decode = vec![];
+        for (variant, &tag) in request.variants.iter().zip(&tags) {
+            let ty = &variant.ty;
+            let tag = Literal::u64_unsuffixed(tag);
+            decode.push(quote::quote_spanned! { located_at(ty.span()) =>
+                #tag => #name::new(::trait_union::__support::bytemuck::pod_read_unaligned::<#ty>(
+                    &bytes[..::core::mem::size_of::<#ty>()],
+                ))
+            });
+        }
+        atomic = Some(quote::quote_spanned! { located_at(span) =>
+            #[doc = #atomic_doc]
+            ///
+            /// The tag and bytes of the active variant are packed into an `AtomicU64`.
+            #[cfg(target_has_atomic = "64")]
+            #vis struct #atomic_name#impl_generics #where_clause {
+                bits: ::core::sync::atomic::AtomicU64,
+                _marker: ::core::marker::PhantomData<#name#ty_generics>,
+            }
+
+            #[cfg(target_has_atomic = "64")]
+            impl#impl_generics #atomic_name#ty_generics #where_clause {
+                const __TRAIT_UNION_SIZE_CHECK: () = if false #(|| #sizes > 7)* {
+                    ::core::panic!(#size_msg)
+                };
+
+                #[inline]
+                fn __trait_union_encode(value: #name#ty_generics) -> u64 {
+                    #[allow(clippy::let_unit_value)]
+                    let () = Self::__TRAIT_UNION_SIZE_CHECK;
+                    let mut bytes = [0; 8];
+                    #name::encode(&value, &mut bytes[..7]);
+                    bytes[7] = <#name#ty_generics>::tag_of(&value);
+                    u64::from_ne_bytes(bytes)
+                }
+
+                #[inline]
+                fn __trait_union_decode(bits: u64) -> #name#ty_generics {
+                    let bytes = bits.to_ne_bytes();
+                    match bytes[7] {
+                        #(#decode,)*
+                        _ => ::core::unreachable!(),
+                    }
+                }
+
+                /// Creates a new atomic value
+                #[inline]
+                #vis fn new(value: #name#ty_generics) -> Self {
+                    Self {
+                        bits: ::core::sync::atomic::AtomicU64::new(Self::__trait_union_encode(value)),
+                        _marker: ::core::marker::PhantomData,
+                    }
+                }
+
+                /// Loads the value
+                #[inline]
+                #vis fn load(&self, order: ::core::sync::atomic::Ordering) -> #name#ty_generics {
+                    Self::__trait_union_decode(self.bits.load(order))
+                }
+
+                /// Stores a value
+                #[inline]
+                #vis fn store(&self, value: #name#ty_generics, order: ::core::sync::atomic::Ordering) {
+                    self.bits.store(Self::__trait_union_encode(value), order);
+                }
+
+                /// Stores a value and returns the previous value
+                #[inline]
+                #vis fn swap(
+                    &self,
+                    value: #name#ty_generics,
+                    order: ::core::sync::atomic::Ordering,
+                ) -> #name#ty_generics {
+                    Self::__trait_union_decode(self.bits.swap(Self::__trait_union_encode(value), order))
+                }
+
+                /// Stores `new` if the current value is `current`
+                ///
+                /// Values are compared by tag and bytes. Returns the previous value.
+                #[inline]
+                #vis fn compare_exchange(
+                    &self,
+                    current: #name#ty_generics,
+                    new: #name#ty_generics,
+                    success: ::core::sync::atomic::Ordering,
+                    failure: ::core::sync::atomic::Ordering,
+                ) -> ::core::result::Result<#name#ty_generics, #name#ty_generics> {
+                    match self.bits.compare_exchange(
+                        Self::__trait_union_encode(current),
+                        Self::__trait_union_encode(new),
+                        success,
+                        failure,
+                    ) {
+                        ::core::result::Result::Ok(bits) => {
+                            ::core::result::Result::Ok(Self::__trait_union_decode(bits))
+                        }
+                        ::core::result::Result::Err(bits) => {
+                            ::core::result::Result::Err(Self::__trait_union_decode(bits))
+                        }
+                    }
+                }
+
+                /// Returns the contained value
+                #[inline]
+                #vis fn into_inner(self) -> #name#ty_generics {
+                    Self::__trait_union_decode(self.bits.into_inner())
+                }
+            }
+        });
+    }
+    let default_values = request
+        .variants
+        .iter()
+        .zip(&variant_options)
+        .map(|(v, o)| {
+            let ty = &v.ty;
+            let value = quote::quote_spanned! { located_at(ty.span()) =>
+                <#ty as ::core::default::Default>::default()
+            };
+            if o.flatten || v.boxed.is_some() {
+                quote::quote!(<#name#ty_generics as ::core::convert::From<#ty>>::from(#value))
+            } else {
+                quote::quote!(<#name#ty_generics>::new(#value))
+            }
+        })
+        .collect::<Vec<_>>();
+    // Going through `new` would repeat the errors of invalid variants.
+    let raw_values = request
+        .variants
+        .iter()
+        .enumerate()
+        .map(|(pos, v)| {
+            let ty = &v.ty;
+            let index = variant_index(pos);
+            let count = count_new(quote::quote!(#pos));
+            let new = match impl_ {
+                Impl::Union => {
+                    quote::quote!(unsafe { Self::__trait_union_new(#pos, value) })
+                }
+                Impl::Enum => quote::quote! {
+                    Self {
+                        #data_name: #union_name::#index(value),
+                        #(#field_names: ::core::default::Default::default(),)*
+                    }
+                },
+            };
+            quote::quote! {{
+                let value = unsafe { ::core::ptr::read_unaligned(data as *const #ty) };
+                #layout_check
+                #count
+                #new
+            }}
+        })
+        .collect::<Vec<_>>();
+    let positions = (0..num_variants).collect::<Vec<_>>();
+    let raw_constructors = quote::quote! {
+        /// Creates a new instance of the variant of kind `kind`
+        ///
+        /// # Safety
+        ///
+        /// `V` must be the type of the variant of kind `kind`.
+        #inline
+        #track_caller
+        #new_vis unsafe fn new_unchecked<V>(kind: #kind_name, value: V) -> Self {
+            #[cfg(debug_assertions)]
+            {
+                let pos = match kind {
+                    #(#kind_name::#kind_variants => #positions,)*
+                };
+                let layout = &Self::__TRAIT_UNION_LAYOUT.variants[pos];
+                if ::core::mem::size_of::<V>() != layout.size
+                    || ::core::mem::align_of::<V>() != layout.align
+                {
+                    ::core::panic!("`V` is not the type of the variant of kind `kind`");
+                }
+            }
+            let value = ::core::mem::ManuallyDrop::new(value);
+            unsafe { Self::new_raw(kind, &*value as *const V as *const u8) }
+        }
+
+        /// Creates a new instance of the variant of kind `kind` by reading its value from
+        /// `data`
+        ///
+        /// `data` does not have to be aligned.
+        ///
+        /// # Safety
+        ///
+        /// `data` must point to a valid value of the type of the variant of kind `kind`.
+        /// The value is moved out of `data`.
+        #[inline]
+        #track_caller
+        #new_vis unsafe fn new_raw(kind: #kind_name, data: *const u8) -> Self {
+            match kind {
+                #(#kind_name::#kind_variants => #raw_values,)*
+            }
+        }
+    };
+    let mut constructors = None;
+    if options.constructors {
+        let ctors = default_values.iter().map(|v| quote::quote!(|| #v));
+        let kind_strs = kind_variants.iter().map(|v| v.to_string());
+        constructors = Some(quote::quote! {
+            /// Constructors of the default values of the variants in declaration order
+            #new_vis const CONSTRUCTORS: [fn() -> Self; #num_variants] = [#(#ctors),*];
+
+            /// Creates the default value of the variant of kind `kind`
+            #new_vis fn from_kind(kind: #kind_name) -> Self {
+                let pos = match kind {
+                    #(#kind_name::#kind_variants => #positions,)*
+                };
+                Self::CONSTRUCTORS[pos]()
+            }
+
+            /// Creates the default value of the variant whose kind is named `name`
+            #new_vis fn from_kind_name(name: &str) -> ::core::option::Option<Self> {
+                let pos = match name {
+                    #(#kind_strs => #positions,)*
+                    _ => return ::core::option::Option::None,
+                };
+                ::core::option::Option::Some(Self::CONSTRUCTORS[pos]())
+            }
+        });
+    }
+    let mut drop_tests = None;
+    if let Some(span) = options.drop_tests {
+        if request.generics.type_params().next().is_some()
+            || request.generics.const_params().next().is_some()
+        {
+            return Err(syn::Error::new(
+                span,
+                "the drop_tests option does not support type or const parameters",
+            ));
+        }
+        let lifetimes = request.generics.lifetimes().map(|_| quote::quote!('static));
+        let test_name = Ident::new(&format!("__trait_union_{}_drop_test", name), span);
+        let static_lt = Lifetime::new("'static", Span::call_site());
+        let generics = &request.generics;
+        let values = default_values.iter().map(|v| {
+            generics.lifetimes().fold(v.clone(), |v, l| {
+                replace_lifetime(v, &l.lifetime, &static_lt)
+            })
+        });
+        drop_tests = Some(quote::quote_spanned! { located_at(span) =>
+            #[cfg(test)]
+            #[test]
+            #[allow(non_snake_case)]
+            fn #test_name() {
+                type Union = #name<#(#lifetimes),*>;
+                let constructors: [fn() -> Union; #num_variants] = [#(|| #values),*];
+                for constructor in &constructors {
+                    ::core::mem::drop(constructor());
+                }
+                for first in &constructors {
+                    for second in &constructors {
+                        let mut value = first();
+                        ::core::mem::drop(::core::mem::replace(&mut value, second()));
+                        ::core::mem::drop(value);
+                    }
+                }
+            }
+        });
+    }
+    let count_variant = count_new(quote::quote!(V::__TRAIT_UNION_INDEX));
+    let mut copy_impl = None;
+    let mut cell_methods = None;
+    if copy {
+        let save = save_fields(quote::quote!(Self::get(cell)), &field_names);
+        let restored = restore_fields(quote::quote!(Self::new(value)), &field_names);
+        cell_methods = Some(quote::quote! {
+            /// Returns a copy of the value in `cell`
+            ///
+            /// Unlike `Cell::get`, this does not require the struct to implement `Copy`.
+            #inline
+            #vis fn get(cell: &::core::cell::Cell<Self>) -> Self {
+                // SAFETY: All fields are Copy and the struct is not Drop.
+                unsafe { ::core::ptr::read(cell.as_ptr()) }
+            }
+
+            /// Stores a new value in `cell`
+            #inline
+            #new_vis fn set<V: #variant_name#ty_generics>(cell: &::core::cell::Cell<Self>, value: V) {
+                #save
+                cell.set(#restored);
+            }
+
+            /// Stores a new value in `cell` and returns the old value
+            #inline
+            #new_vis fn replace<V: #variant_name#ty_generics>(
+                cell: &::core::cell::Cell<Self>,
+                value: V,
+            ) -> Self {
+                #save
+                cell.replace(#restored)
+            }
+        });
+        copy_impl = Some(quote::quote! {
+            impl#impl_generics ::core::marker::Copy for #union_name#ty_generics #where_clause { }
+            impl#impl_generics ::core::clone::Clone for #union_name#ty_generics #where_clause {
+                fn clone(&self) -> Self {
+                    *self
+                }
+            }
+        });
+    }
+    let index_variants = index_variants.collect::<Vec<_>>();
+    let dropped_msg = format!("use of a dropped {}", name);
+    let misaligned_msg = format!("misaligned {}", name);
+    let invalid_variant_msg = format!("invalid implementation of {}", variant_name);
+    let check_trait_object = quote::quote! {
+        #[cfg(debug_assertions)]
+        ::trait_union::__support::check_aligned(
+            data,
+            Self::__TRAIT_UNION_LAYOUT.variants[self.__trait_union_position()].align,
+            #misaligned_msg,
+        );
+    };
+    let storage;
+    // The storage types are the types of the fields of the struct and cannot be moved
+    // into the `const _` block. Naming them through a trait projection instead would make
+    // the struct invariant in its parameters.
+    let data_def;
+    let union_impl;
+    let position;
+    let new;
+    let deref;
+    let deref_mut;
+    match impl_ {
+        Impl::Union => {
+            storage = quote::quote! {
+                #data_name: #union_name#ty_generics,
+                #index_name: #index_type_name,
+                #(#fields,)*
+            };
+            data_def = quote::quote! {
+                #[repr(C)]
+                #[allow(non_snake_case)]
+                union #union_name#impl_generics #where_clause {
+                    #(#union_fields),*
+                }
+
+                #[allow(non_camel_case_types)]
+                #[derive(Copy, Clone, PartialEq, Eq)]
+                enum #index_type_name {
+                    #(#index_variants,)*
+                    #[cfg(debug_assertions)]
+                    Dropped,
+                }
+            };
+            let mut needs_drop = None;
+            if !copy {
+                // Variants that do not need to be dropped are skipped without calling the
+                // drop glue through the vtable.
+                needs_drop = Some(quote::quote! {
+                    const __TRAIT_UNION_NEEDS_DROP: [bool; #num_variants] =
+                        [#(::core::mem::needs_drop::<#variant_types>()),*];
+                });
+            }
+            union_impl = quote::quote! {
+                #needs_drop
+
+                const __TRAIT_UNION_VTABLES: [::trait_union::__support::VtablePtr; #num_variants] = [#(#vtables),*];
+
+                #inline
+                fn __trait_union_to_trait_object(&self) -> ::trait_union::__support::RawTraitObject {
+                    #(#flattened)*
+                    let data = &self.#data_name as *const _ as *mut ();
+                    #check_trait_object
+                    ::trait_union::__support::RawTraitObject {
+                        data,
+                        vtable: Self::__TRAIT_UNION_VTABLES[self.#index_name as usize],
+                    }
+                }
+
+                #inline
+                fn __trait_union_to_trait_object_mut(&mut self) -> ::trait_union::__support::RawTraitObject {
+                    #(#flattened_mut)*
+                    let data = &mut self.#data_name as *mut _ as *mut ();
+                    #check_trait_object
+                    ::trait_union::__support::RawTraitObject {
+                        data,
+                        vtable: Self::__TRAIT_UNION_VTABLES[self.#index_name as usize],
+                    }
+                }
+
+                #inline
+                const fn __trait_union_index(index: usize) -> #index_type_name {
+                    match index {
+                        #(#indices,)*
+                        _ => ::core::panic!("invalid variant index"),
+                    }
+                }
+
+                /// Creates a new instance with the variant at position `index`
+                ///
+                /// # Safety
+                ///
+                /// `V` must be the type of the variant at position `index`.
+                #inline
+                unsafe fn __trait_union_new<V>(index: usize, value: V) -> Self {
+                    #[cfg(debug_assertions)]
+                    ::trait_union::__support::check_variant::<V>(
+                        &Self::__TRAIT_UNION_LAYOUT,
+                        index,
+                        #invalid_variant_msg,
+                    );
+                    let mut slf = ::core::mem::MaybeUninit::<Self>::uninit();
+                    unsafe {
+                        // Zero-sized variants have no data to write.
+                        if ::core::mem::size_of::<V>() == 0 {
+                            ::core::mem::forget(value);
+                        } else {
+                            ::core::ptr::write(&mut (*slf.as_mut_ptr()).#data_name as *mut _ as *mut V, value);
+                        }
+                        (*slf.as_mut_ptr()).#index_name = Self::__trait_union_index(index);
+                        #(
+                            ::core::ptr::write(
+                                &mut (*slf.as_mut_ptr()).#field_names,
+                                ::core::default::Default::default(),
+                            );
+                        )*
+                        slf.assume_init()
+                    }
+                }
+            };
+            position = quote::quote! {
+                {
+                    #[cfg(debug_assertions)]
+                    {
+                        if self.#index_name == #index_type_name::Dropped {
+                            ::core::panic!(#dropped_msg);
+                        }
+                    }
+                    self.#index_name as usize
+                }
+            };
+            new = quote::quote! {
+                unsafe { Self::__trait_union_new(V::__TRAIT_UNION_INDEX, value) }
+            };
+            deref = quote::quote! {
+                unsafe { ::core::mem::transmute(self.__trait_union_to_trait_object()) }
+            };
+            deref_mut = quote::quote! {
+                unsafe { ::core::mem::transmute(self.__trait_union_to_trait_object_mut()) }
+            };
+        }
+        Impl::Enum => {
+            storage = quote::quote! {
+                #data_name: #union_name#ty_generics,
+                #(#fields,)*
+            };
+            data_def = quote::quote! {
+                #[allow(non_camel_case_types)]
+                enum #union_name#impl_generics #where_clause {
+                    #(#union_fields),*
+                }
+            };
+            union_impl = quote::quote!();
+            position = quote::quote! {
+                match &self.#data_name {
+                    #(#union_name::#index_variants(_) => #indices_pos,)*
+                    #phantom_arm
+                }
+            };
+            // The storage is private, so the variant is moved into it by its index
+            // instead of through a method of the public marker trait.
+            let new_indices = 0..num_variants;
+            let new_variants = (0..num_variants).map(variant_index);
+            new = quote::quote! {
+                let value = ::core::mem::ManuallyDrop::new(value);
+                let data = &*value as *const V as *const u8;
+                Self {
+                    #data_name: match V::__TRAIT_UNION_INDEX {
+                        #(
+                            #new_indices => #union_name::#new_variants(unsafe {
+                                ::core::ptr::read(data as *const #variant_types)
+                            }),
+                        )*
+                        _ => ::core::unreachable!(),
+                    },
+                    #(#field_names: ::core::default::Default::default(),)*
+                }
+            };
+            deref = quote::quote! {
+                match &self.#data_name {
+                    #(#union_name::#index_variants(value) => #deref_exprs,)*
+                    #phantom_arm
+                }
+            };
+            deref_mut = quote::quote! {
+                match &mut self.#data_name {
+                    #(#union_name::#index_variants(value) => #deref_mut_exprs,)*
+                    #phantom_arm
+                }
+            };
+        }
+    }
+    let mut drop_impl = None;
+    if !copy && impl_ == Impl::Union {
+        drop_impl = Some(quote::quote! {
+            impl#impl_generics ::core::ops::Drop for #name#ty_generics #where_clause {
+                #inline
+                fn drop(&mut self) {
+                    #count_drop
+                    if Self::NEEDS_DROP {
+                        #(#flattened_drop)* if Self::__TRAIT_UNION_NEEDS_DROP[#position] {
+                            unsafe {
+                                let t: &mut (dyn #trait_) = ::core::mem::transmute(self.__trait_union_to_trait_object_mut());
+                                ::core::ptr::drop_in_place(t);
+                            }
+                        }
+                    }
+                    #[cfg(debug_assertions)]
+                    {
+                        self.#index_name = #index_type_name::Dropped;
+                    }
+                }
+            }
+        });
+    }
+    if impl_ == Impl::Enum && count_drop.is_some() {
+        drop_impl = Some(quote::quote! {
+            impl#impl_generics ::core::ops::Drop for #name#ty_generics #where_clause {
+                #inline
+                fn drop(&mut self) {
+                    #count_drop
+                }
+            }
+        });
+    }
+    let trait_object_from =
+        trait_object_from(&name, &request.generics, &trait_, options.no_deref, &inline);
+    let call = call_method(&vis, &trait_, options.no_deref, &inline);
+    let mut default_impl = None;
+    let mut take = None;
+    let mut defaults = request
+        .variants
+        .iter()
+        .zip(&variant_options)
+        .filter_map(|(v, o)| o.default.map(|span| (v, o, span)));
+    if let Some((variant, variant_options, span)) = defaults.next() {
+        if let Some((_, _, span)) = defaults.next() {
+            return Err(syn::Error::new(span, "only one variant can be the default"));
+        }
+        if options.new_vis.is_some() {
+            return Err(syn::Error::new(
+                span,
+                "default variants cannot be combined with new_vis",
+            ));
+        }
+        let ty = &variant.ty;
+        let new = match variant.boxed.is_some() || variant_options.flatten {
+            true => quote::quote!(::core::convert::From::from),
+            false => quote::quote!(Self::new),
+        };
+        default_impl = Some(quote::quote_spanned! { located_at(ty.span()) =>
+            impl#impl_generics ::core::default::Default for #name#ty_generics #where_clause {
+                #[inline]
+                fn default() -> Self {
+                    #new(<#ty as ::core::default::Default>::default())
+                }
+            }
+        });
+        take = Some(quote::quote! {
+            /// Replaces the value by the default variant and returns the previous value
+            #[inline]
+            #vis fn take(&mut self) -> Self {
+                ::core::mem::take(self)
+            }
+        });
+    }
+    // Going through `new` would repeat the errors of invalid variants.
+    let from_owned = kind_variants.iter().map(|kind| {
+        quote::quote! {
+            #owned_name::#kind(value) => {
+                let value = ::core::mem::ManuallyDrop::new(value);
+                unsafe { Self::new_raw(#kind_name::#kind, &*value as *const _ as *const u8) }
+            }
+        }
+    });
+    let owned_phantom_arm = phantom_arm.as_ref().map(
+        |_| quote::quote!(#owned_name::__TraitUnionPhantom(_, never) => match never {},),
+    );
+    let save = save_fields(quote::quote!(&*this), &field_names);
+    let restored = restore_fields(quote::quote!(Self::from_enum(f(value))), &field_names);
+    let transition = quote::quote! {
+        /// Creates a new instance from the moved-out active variant
+        #[inline]
+        #[allow(dead_code)]
+        #enum_new_vis fn from_enum(value: #owned_name#ty_generics) -> Self {
+            match value {
+                #(#from_owned)*
+                #owned_phantom_arm
+            }
+        }
+
+        /// Replaces the value by the result of applying `f` to the active variant
+        ///
+        /// The process is aborted if `f` panics.
+        #[inline]
+        #[allow(dead_code)]
+        #enum_new_vis fn transition(
+            this: &mut Self,
+            f: impl ::core::ops::FnOnce(#owned_name#ty_generics) -> #owned_name#ty_generics,
+        ) {
+            let guard = ::trait_union::__support::AbortOnUnwind;
+            #save
+            // SAFETY: `this` is overwritten before it is used again. If `f` panics, the
+            // guard aborts before the moved-out value can be dropped a second time.
+            unsafe {
+                let value = Self::into_enum(::core::ptr::read(this));
+                ::core::ptr::write(this, #restored);
+            }
+            ::core::mem::forget(guard);
+        }
+    };
+    // Variants are identified by their `TypeId`, which requires them to be `'static`.
+    let mut move_variant = None;
+    let mut accept_impl = None;
+    if request.generics.params.is_empty() {
+        let save = save_fields(quote::quote!(&self), &field_names);
+        let move_arms = request.variants.iter().zip(&kind_variants).map(|(v, kind)| {
+            let ty = &v.ty;
+            let restore = restore_fields(
+                quote::quote!(Self::from_enum(#owned_name::#kind(value))),
+                &field_names,
+            );
+            quote::quote! {
+                #owned_name::#kind(value) => {
+                    let value = ::core::mem::ManuallyDrop::new(value);
+                    let id = ::core::any::TypeId::of::<#ty>();
+                    match unsafe { __TraitUnionTarget::__trait_union_accept(id, &*value as *const _ as *const u8) } {
+                        ::core::option::Option::Some(target) => ::core::result::Result::Ok(target),
+                        ::core::option::Option::None => {
+                            let value = ::core::mem::ManuallyDrop::into_inner(value);
+                            ::core::result::Result::Err(#restore)
+                        }
+                    }
+                }
+            }
+        });
+        move_variant = Some(quote::quote! {
+            /// Moves the active variant into another union with a variant of its type
+            ///
+            /// The value is returned unchanged if the other union has no such variant.
+            #[inline]
+            #vis fn move_variant<__TraitUnionTarget: ::trait_union::__support::AcceptVariant>(
+                self,
+            ) -> ::core::result::Result<__TraitUnionTarget, Self> {
+                #save
+                match Self::into_enum(self) {
+                    #(#move_arms)*
+                    #owned_phantom_arm
+                }
+            }
+        });
+        // Accepting a variant creates a new instance, so this is restricted to unions
+        // whose `new` is as visible as the union.
+        if options.new_vis.is_none() {
+            let accept_arms =
+                request
+                    .variants
+                    .iter()
+                    .zip(&kind_variants)
+                    .map(|(v, kind)| {
+                        let ty = &v.ty;
+                        quote::quote! {
+                            if id == ::core::any::TypeId::of::<#ty>() {
+                                return ::core::option::Option::Some(unsafe {
+                                    Self::new_raw(#kind_name::#kind, data)
+                                });
+                            }
+                        }
+                    });
+            accept_impl = Some(quote::quote! {
+                unsafe impl ::trait_union::__support::AcceptVariant for #name {
+                    #[inline]
+                    unsafe fn __trait_union_accept(
+                        id: ::core::any::TypeId,
+                        data: *const u8,
+                    ) -> ::core::option::Option<Self> {
+                        #(#accept_arms)*
+                        ::core::option::Option::None
+                    }
+                }
+            });
+        }
+    }
+    let mut reborrow = None;
+    if let Some(span) = options.reborrow {
+        let mut lifetimes = request.generics.lifetimes();
+        let lt = match (lifetimes.next(), lifetimes.next()) {
+            (Some(lt), None) => &lt.lifetime,
+            _ => {
+                return Err(syn::Error::new(
+                    span,
+                    "the reborrow option requires exactly one lifetime parameter",
+                ))
+            }
+        };
+        let short = Lifetime::new("'__trait_union_short", Span::call_site());
+        let mut mutable = false;
+        let mut arms = vec![];
+        for (variant, kind) in request.variants.iter().zip(&kind_variants) {
+            let reference = match &variant.ty {
+                Type::Reference(r) if r.lifetime.as_ref() == Some(lt) => r,
+                ty => {
+                    let msg = format!(
+                        "the reborrow option requires all variants to be references with \
+                         the lifetime {}",
+                        lt
+                    );
+                    return Err(syn::Error::new(ty.span(), msg));
+                }
+            };
+            mutable |= reference.mutability.is_some();
+            let ty = &variant.ty;
+            let short_ty = replace_lifetime(quote::quote!(#ty), lt, &short);
+            let value = match reference.mutability {
+                Some(_) => quote::quote!(&mut **value),
+                None => quote::quote!(&**value),
+            };
+            arms.push((kind, short_ty, value));
+        }
+        let short_ty_generics = replace_lifetime(quote::quote!(#ty_generics), lt, &short);
+        let (receiver, enum_name, as_enum) = match mutable {
+            true => (
+                quote::quote!(&#short mut self),
+                mut_name,
+                quote::quote!(as_mut_enum),
+            ),
+            false => (
+                quote::quote!(&#short self),
+                ref_name,
+                quote::quote!(as_ref_enum),
+            ),
+        };
+        // Fields whose types mention the lifetime cannot always be shortened.
+        let kept = fields
+            .iter()
+            .filter(|f| {
+                let ty = &f.ty;
+                !contains_lifetime(quote::quote!(#ty), lt)
+            })
+            .map(|f| &f.ident)
+            .collect::<Vec<_>>();
+        let save = save_fields(quote::quote!(&*self), &kept);
+        let restored =
+            restore_fields(quote::quote!(<#name#short_ty_generics>::new(value)), &kept);
+        let arms = arms.iter().map(|(kind, short_ty, value)| {
+            quote::quote_spanned! { located_at(short_ty.span()) =>
+                #enum_name::#kind(value) => {
+                    // Shortens the lifetime of trait objects such as `dyn Trait + 'a`.
+                    let value: #short_ty = #value;
+                    #restored
+                }
+            }
+        });
+        let phantom_arm = phantom_arm
+            .as_ref()
+            .map(|_| quote::quote!(#enum_name::__TraitUnionPhantom(_, never) => match never {},));
+        let doc = format!(
+            "Reborrows the referenced value for a lifetime shorter than {}",
+            lt
+        );
+        reborrow = Some(quote::quote! {
+            #[doc = #doc]
+            #inline
+            #vis fn reborrow<#short>(#receiver) -> #name#short_ty_generics {
+                #save
+                match Self::#as_enum(self) {
+                    #(#arms)*
+                    #phantom_arm
+                }
+            }
+        });
+    }
+    let mut visitor = None;
+    let mut visit_methods = None;
+    if options.visitor {
+        // Boxed and flattened variants are visited as trait objects.
+        let (target, target_mut) = match options.no_deref {
+            true => (
+                quote::quote!(self.as_dyn()),
+                quote::quote!(self.as_dyn_mut()),
+            ),
+            false => (quote::quote!(&**self), quote::quote!(&mut **self)),
+        };
+        let mut arms = vec![];
+        let mut mut_arms = vec![];
+        for ((variant, variant_options), kind) in request
+            .variants
+            .iter()
+            .zip(&variant_options)
+            .zip(&kind_variants)
+        {
+            if variant.boxed.is_some() || variant_options.flatten {
+                arms.push(quote::quote!(#ref_name::#kind(_) => visitor.visit(#target),));
+                mut_arms.push(
+                    quote::quote!(#mut_name::#kind(_) => visitor.visit_mut(#target_mut),),
+                );
+            } else {
+                arms.push(
+                    quote::quote!(#ref_name::#kind(value) => visitor.visit(value),),
+                );
+                mut_arms.push(
+                    quote::quote!(#mut_name::#kind(value) => visitor.visit_mut(value),),
+                );
+            }
+        }
+        let mut_phantom_arm = phantom_arm.as_ref().map(
+            |_| quote::quote!(#mut_name::__TraitUnionPhantom(_, never) => match never {},),
+        );
+        let doc = format!(
+            "Visitor of the active variant of [{}]\n\n\
+             The methods are instantiated for the type of each variant, so that calls of the \
+             trait methods are dispatched statically and can be inlined.",
+            name
+        );
+        visitor = Some(quote::quote! {
+            #[doc = #doc]
+            #vis trait #visitor_name#impl_generics #where_clause {
+                /// The result of the visit
+                type Output;
+
+                /// Called with a reference to the active variant
+                fn visit<V: ?::core::marker::Sized + #trait_>(self, value: &V) -> Self::Output;
+
+                /// Called with a mutable reference to the active variant
+                ///
+                /// Calls `visit` by default.
+                #[inline]
+                fn visit_mut<V: ?::core::marker::Sized + #trait_>(
+                    self,
+                    value: &mut V,
+                ) -> Self::Output
+                where
+                    Self: ::core::marker::Sized,
+                {
+                    self.visit(value)
+                }
+            }
+        });
+        visit_methods = Some(quote::quote! {
+            /// Calls `visitor` with a reference to the active variant
+            #inline
+            #vis fn visit<__TraitUnionVisitor: #visitor_name#ty_generics>(
+                &self,
+                visitor: __TraitUnionVisitor,
+            ) -> __TraitUnionVisitor::Output {
+                match Self::as_ref_enum(self) {
+                    #(#arms)*
+                    #ref_phantom_arm
+                }
+            }
+
+            /// Calls `visitor` with a mutable reference to the active variant
+            #inline
+            #vis fn visit_mut<__TraitUnionVisitor: #visitor_name#ty_generics>(
+                &mut self,
+                visitor: __TraitUnionVisitor,
+            ) -> __TraitUnionVisitor::Output {
+                match Self::as_mut_enum(self) {
+                    #(#mut_arms)*
+                    #mut_phantom_arm
+                }
+            }
+        });
+    }
+    let mut handle = None;
+    if options.external {
+        let mut handle_generics = request.generics.clone();
+        handle_generics.params.insert(
+            0,
+            GenericParam::Lifetime(LifetimeDef::new(Lifetime::new(
+                "'__trait_union_buf",
+                Span::call_site(),
+            ))),
+        );
+        let (handle_impl_generics, handle_ty_generics, handle_where_clause) =
+            handle_generics.split_for_impl();
+        let mut deref_arms = vec![];
+        let mut deref_mut_arms = vec![];
+        let mut drop_arms = vec![];
+        let mut from_union_arms = vec![];
+        for (((kind, ty), expr), expr_mut) in kind_variants
+            .iter()
+            .zip(&variant_types)
+            .zip(&deref_exprs)
+            .zip(&deref_mut_exprs)
+        {
+            deref_arms.push(quote::quote! {
+                #kind_name::#kind => {
+                    let value = unsafe { &*(self.ptr.0.as_ptr() as *const #ty) };
+                    #expr
+                }
+            });
+            deref_mut_arms.push(quote::quote! {
+                #kind_name::#kind => {
+                    let value = unsafe { &mut *(self.ptr.0.as_ptr() as *mut #ty) };
+                    #expr_mut
+                }
+            });
+            drop_arms.push(quote::quote! {
+                #kind_name::#kind => unsafe {
+                    ::core::ptr::drop_in_place(self.ptr.0.as_ptr() as *mut #ty)
+                },
+            });
+            from_union_arms.push(quote::quote! {
+                #owned_name::#kind(value) => match ::trait_union::__support::place(buf, value) {
+                    ::core::result::Result::Ok(ptr) => ::core::result::Result::Ok(Self {
+                        kind,
+                        ptr,
+                        _marker: ::core::marker::PhantomData,
+                    }),
+                    ::core::result::Result::Err(value) => ::core::result::Result::Err(
+                        <#name#ty_generics>::from_enum(#owned_name::#kind(value)),
+                    ),
+                },
+            });
+        }
+        let deref = quote::quote! {
+            match self.kind {
+                #(#deref_arms)*
+            }
+        };
+        let deref_mut = quote::quote! {
+            match self.kind {
+                #(#deref_mut_arms)*
+            }
+        };
+        let mut handle_as_dyn = None;
+        let mut handle_deref_impls = None;
+        if options.no_deref {
+            handle_as_dyn = Some(quote::quote! {
+                /// Returns a reference to the value as a trait object
+                #inline
+                #vis fn as_dyn(&self) -> &(dyn #trait_) {
+                    #deref
+                }
+
+                /// Returns a mutable reference to the value as a trait object
+                #inline
+                #vis fn as_dyn_mut(&mut self) -> &mut (dyn #trait_) {
+                    #deref_mut
+                }
+            });
+        } else {
+            handle_deref_impls = Some(quote::quote! {
+                impl#handle_impl_generics ::core::ops::Deref for #handle_name#handle_ty_generics #handle_where_clause {
+                    type Target = dyn #trait_;
+
+                    #inline
+                    fn deref(&self) -> &Self::Target {
+                        #deref
+                    }
+                }
+
+                impl#handle_impl_generics ::core::ops::DerefMut for #handle_name#handle_ty_generics #handle_where_clause {
+                    #inline
+                    fn deref_mut(&mut self) -> &mut Self::Target {
+                        #deref_mut
+                    }
+                }
+            });
+        }
+        let doc = format!(
+            "A value of [{}] whose payload is stored in a buffer provided by the caller\n\n\
+             The handle only holds the kind and a pointer, so its size does not depend on \
+             the variants.",
+            name
+        );
+        handle = Some(quote::quote! {
+            #[doc = #doc]
+            #vis struct #handle_name#handle_impl_generics #handle_where_clause {
+                kind: #kind_name,
+                // The handle owns a value of the union, so it is `Send` and `Sync` if the
+                // union is.
+                ptr: ::trait_union::__support::PayloadPtr,
+                _marker: ::core::marker::PhantomData<(
+                    &'__trait_union_buf mut [::core::mem::MaybeUninit<u8>],
+                    #name#ty_generics,
+                )>,
+            }
+
+            impl#handle_impl_generics #handle_name#handle_ty_generics #handle_where_clause {
+                /// Moves `value` into `buf`
+                ///
+                /// The value is stored at the first offset of `buf` that is suitably
+                /// aligned. Returns `value` if it does not fit.
+                #inline
+                #new_vis fn new_in<V: #variant_name#ty_generics>(
+                    buf: &'__trait_union_buf mut [::core::mem::MaybeUninit<u8>],
+                    value: V,
+                ) -> ::core::result::Result<Self, V> {
+                    let kind = <#name#ty_generics>::__TRAIT_UNION_KINDS[V::__TRAIT_UNION_INDEX];
+                    let ptr = ::trait_union::__support::place(buf, value)?;
+                    ::core::result::Result::Ok(Self {
+                        kind,
+                        ptr,
+                        _marker: ::core::marker::PhantomData,
+                    })
+                }
+
+                /// Moves the active variant of `value` into `buf`
+                ///
+                /// Returns `value` if the active variant does not fit.
+                #inline
+                #new_vis fn from_union(
+                    buf: &'__trait_union_buf mut [::core::mem::MaybeUninit<u8>],
+                    value: #name#ty_generics,
+                ) -> ::core::result::Result<Self, #name#ty_generics> {
+                    let kind = <#name#ty_generics>::kind_of(&value);
+                    match <#name#ty_generics>::into_enum(value) {
+                        #(#from_union_arms)*
+                        #owned_phantom_arm
+                    }
+                }
+
+                /// Moves the value out of the buffer
+                #inline
+                #new_vis fn into_union(self) -> #name#ty_generics {
+                    let this = ::core::mem::ManuallyDrop::new(self);
+                    // SAFETY: `this` is not dropped, so the value is moved out only once.
+                    unsafe { <#name#ty_generics>::new_raw(this.kind, this.ptr.0.as_ptr()) }
+                }
+
+                /// Returns the kind of the value of `this`
+                #inline
+                #vis fn kind_of(this: &Self) -> #kind_name {
+                    this.kind
+                }
+
+                /// Returns the tag of the value of `this`
+                #inline
+                #vis fn tag_of(this: &Self) -> #tag_type {
+                    this.kind as #tag_type
+                }
+
+                #handle_as_dyn
+            }
+
+            #handle_deref_impls
+
+            impl#handle_impl_generics ::core::ops::Drop for #handle_name#handle_ty_generics #handle_where_clause {
+                #inline
+                fn drop(&mut self) {
+                    match self.kind {
+                        #(#drop_arms)*
+                    }
+                }
+            }
+        });
+    }
+    let mut send = None;
+    let mut new_send = None;
+    if options.send {
+        let as_dyn_mut = match options.no_deref {
+            true => quote::quote!(self.0.as_dyn_mut()),
+            false => quote::quote!(&mut *self.0),
+        };
+        let doc = format!(
+            "A [{}] that only holds `Send` variants\n\n\
+             It is `Send` even if other variants of the union are not.",
+            name
+        );
+        send = Some(quote::quote! {
+            #[doc = #doc]
+            #vis struct #send_name#impl_generics(#name#ty_generics) #where_clause;
+
+            // SAFETY: Values can only be created from `Send` variants and the union
+            // cannot be replaced through a shared reference.
+            unsafe impl#impl_generics ::core::marker::Send for #send_name#ty_generics #where_clause {}
+
+            impl#impl_generics #send_name#ty_generics #where_clause {
+                /// Creates a new instance
+                #inline
+                #track_caller
+                #new_vis fn new<V: #variant_name#ty_generics + ::core::marker::Send>(value: V) -> Self {
+                    Self(<#name#ty_generics>::new(value))
+                }
+
+                /// Returns a mutable reference to the active variant as a trait object
+                #inline
+                #vis fn as_dyn_mut(&mut self) -> &mut (dyn #trait_) {
+                    #as_dyn_mut
+                }
+
+                /// Returns the union
+                #inline
+                #vis fn into_inner(self) -> #name#ty_generics {
+                    self.0
+                }
+            }
+
+            impl#impl_generics ::core::ops::Deref for #send_name#ty_generics #where_clause {
+                type Target = #name#ty_generics;
+
+                #inline
+                fn deref(&self) -> &Self::Target {
+                    &self.0
+                }
+            }
+
+            impl#impl_generics ::core::convert::From<#send_name#ty_generics>
+                for #name#ty_generics #where_clause
+            {
+                #[inline]
+                fn from(value: #send_name#ty_generics) -> Self {
+                    value.0
+                }
+            }
+        });
+        new_send = Some(quote::quote! {
+            /// Creates a new instance that is `Send`
+            #inline
+            #track_caller
+            #new_vis fn new_send<V: #variant_name#ty_generics + ::core::marker::Send>(
+                value: V,
+            ) -> #send_name#ty_generics {
+                #send_name::new(value)
+            }
+        });
+    }
+    let mut box_error_impl = None;
+    if options.box_error.is_some() {
+        let boxed = quote::quote! {
+            ::trait_union::__support::Box<
+                dyn ::std::error::Error + ::core::marker::Send + ::core::marker::Sync,
+            >
+        };
+        let mut box_generics = request.generics.clone();
+        let predicates = &mut box_generics.make_where_clause().predicates;
+        for ty in &variant_types {
+            predicates.push(syn::parse_quote_spanned!(ty.span() => #boxed: ::core::convert::From<#ty>));
+        }
+        let (box_impl_generics, _, box_where_clause) = box_generics.split_for_impl();
+        // The active variant is boxed instead of the struct so that the error can be
+        // downcast to the type of the variant.
+        box_error_impl = Some(quote::quote! {
+            impl#box_impl_generics ::core::convert::From<#name#ty_generics> for #boxed
+                #box_where_clause
+            {
+                #[inline]
+                fn from(value: #name#ty_generics) -> Self {
+                    match <#name#ty_generics>::into_enum(value) {
+                        #(#owned_name::#kind_variants(value) => ::core::convert::From::from(value),)*
+                        #owned_phantom_arm
+                    }
+                }
+            }
+        });
+    }
+    let mut from_variant_impl = None;
+    if options.new_vis.is_none() {
+        let mut from_generics = request.generics.clone();
+        from_generics
+            .params
+            .push(syn::parse_quote!(__TraitUnionVariant: #variant_name#ty_generics));
+        let (from_impl_generics, _, from_where_clause) = from_generics.split_for_impl();
+        from_variant_impl = Some(quote::quote! {
+            impl#from_impl_generics ::trait_union::FromVariant<__TraitUnionVariant>
+                for #name#ty_generics #from_where_clause
+            {
+                #inline
+                #track_caller
+                fn from_variant(value: __TraitUnionVariant) -> Self {
+                    Self::new(value)
+                }
+            }
+
+            impl#impl_generics ::core::convert::From<#owned_name#ty_generics>
+                for #name#ty_generics #where_clause
+            {
+                #[inline]
+                fn from(value: #owned_name#ty_generics) -> Self {
+                    Self::from_enum(value)
+                }
+            }
+        });
+    }
+    let into_enum_impl = quote::quote! {
+        impl#impl_generics ::core::convert::From<#name#ty_generics>
+            for #owned_name#ty_generics #where_clause
+        {
+            #[inline]
+            fn from(value: #name#ty_generics) -> Self {
+                <#name#ty_generics>::into_enum(value)
+            }
+        }
+    };
+    let mut manual_variant_trait = None;
+    if !options.manual_impl.is_empty() {
+        let doc = format!(
+            "Implemented manually by the variants of [{}] listed in the `manual_impl` option\n\n\
+             # Safety\n\n\
+             The implementation must uphold the invariants that the variant requires to be \
+             stored in a [{}].",
+            name, name
+        );
+        manual_variant_trait = Some(quote::quote! {
+            #[doc = #doc]
+            #[allow(clippy::missing_safety_doc)]
+            #vis unsafe trait #manual_variant_name#impl_generics {}
+        });
+    }
+    let pinned = request.variants.iter().all(|v| v.boxed.is_none())
+        && variant_options.iter().all(|o| !o.flatten);
+    // Spelled out instead of derived from the storage so that pinning the struct can be
+    // relied on to pin the active variant, e.g. by `forward(Future)`. The unused lifetime
+    // keeps the bound from being rejected as trivially false for concrete variants.
+    let unpin_lt = Lifetime::new("'__trait_union_pin", Span::call_site());
+    let unpin_tys = request.variants.iter().map(|v| &v.ty);
+    let mut unpin_generics = request.generics.clone();
+    unpin_generics.params.insert(
+        0,
+        GenericParam::Lifetime(LifetimeDef::new(unpin_lt.clone())),
+    );
+    unpin_generics
+        .make_where_clause()
+        .predicates
+        .push(syn::parse_quote! {
+            ::core::marker::PhantomData<(&#unpin_lt (), #(#unpin_tys,)* #(#phantoms,)*)>:
+                ::core::marker::Unpin
+        });
+    let (unpin_impl_generics, _, unpin_where_clause) = unpin_generics.split_for_impl();
+    let unpin_impl = quote::quote! {
+        impl#unpin_impl_generics ::core::marker::Unpin for #name#ty_generics
+            #unpin_where_clause
+        {
+        }
+    };
+    let non_exhaustive = match options.non_exhaustive {
+        true => Some(quote::quote!(#[non_exhaustive])),
+        false => None,
+    };
+    let dispatcher = match &options.dispatcher {
+        Some(d) => Some(dispatcher(
+            &name,
+            dispatcher_name,
+            &vis,
+            &request.generics,
+            d,
+            options.no_deref,
+        )),
+        None => None,
+    };
+    let mut enum_dispatch_impl = None;
+    if let Some(e) = &options.enum_dispatch {
+        let indirect = request
+            .variants
+            .iter()
+            .zip(&variant_options)
+            .map(|(v, o)| v.boxed.is_some() || o.flatten)
+            .collect::<Vec<_>>();
+        enum_dispatch_impl = Some(enum_dispatch(
+            owned_name,
+            &request.generics,
+            &trait_,
+            &kind_variants,
+            &indirect,
+            phantom_variant.is_some(),
+            e,
+        ));
+    }
+    let mut cell_type = None;
+    let mut set_method = None;
+    if let Some(span) = options.cell {
+        if copy {
+            return Err(syn::Error::new(
+                span,
+                "the cell option is not supported by trait_union_copy",
+            ));
+        }
+        cell_type = Some(cell(
+            &name,
+            cell_name,
+            variant_name,
+            &vis,
+            new_vis,
+            &request.generics,
+        ));
+        let save = save_fields(quote::quote!(&*self), &field_names);
+        let restored = restore_fields(quote::quote!(Self::new(value)), &field_names);
+        set_method = Some(quote::quote! {
+            /// Replaces the value and drops the old value
+            #[inline]
+            #new_vis fn set<V: #variant_name#ty_generics>(&mut self, value: V) {
+                #save
+                *self = #restored;
+            }
+        });
+    }
+    let forward_impls = forward_impls(
+        &name,
+        &request.generics,
+        &trait_,
+        &options.forward,
+        options.no_deref,
+        pinned,
+    )?;
+    let variants = &request.variants;
+    let variant_forward = variant_options
+        .iter()
+        .map(|o| &*o.forward)
+        .collect::<Vec<_>>();
+    let partial_forward_impls = partial_forward_impls(
+        &name,
+        &request.generics,
+        &options.forward,
+        &variant_forward,
+        |forwards, forwarded| {
+            let arms = variants
+                .iter()
+                .zip(forwards)
+                .map(|(v, &forwards)| match (forwards, v.boxed.is_some()) {
+                    (true, false) => quote::quote! {
+                        ::core::option::Option::Some::<&mut dyn #forwarded>(value)
+                    },
+                    (true, true) => quote::quote! {
+                        ::core::option::Option::Some::<&mut dyn #forwarded>(&mut **value)
+                    },
+                    (false, _) => quote::quote!({
+                        let _ = value;
+                        ::core::option::Option::None
+                    }),
+                })
+                .collect();
+            match_variant(impl_, &names, true, arms, phantom_arm.as_ref())
+        },
+    )?;
+    let describe_arms = request
+        .variants
+        .iter()
+        .zip(&variant_options)
+        .map(|(v, o)| match (o.flatten, v.boxed.is_some()) {
+            (true, false) => {
+                let ty = &v.ty;
+                quote::quote!(<#ty>::describe(value, f))
+            }
+            // Box variants have already been rewritten to `Box<_>`.
+            (true, true) => {
+                let ty = &v.ty;
+                quote::quote!(<<#ty as ::core::ops::Deref>::Target>::describe(value, f))
+            }
+            (false, _) => quote::quote! {
+                (&&&::trait_union::__support::Describe(value)).describe_value(f)
+            },
+        })
+        .collect();
+    let describe_value =
+        match_variant(impl_, &names, false, describe_arms, phantom_arm.as_ref());
+    // Flattened variants forward the request to their own active variant.
+    let provide_arms = request
+        .variants
+        .iter()
+        .zip(&variant_options)
+        .map(|(v, o)| match (o.provide || o.flatten, v.boxed.is_some()) {
+            (true, false) => {
+                quote::quote!(::trait_union::Provide::provide(value, request))
+            }
+            (true, true) => {
+                quote::quote!(::trait_union::Provide::provide(&**value, request))
+            }
+            (false, _) => quote::quote!({
+                let _ = value;
+            }),
+        })
+        .collect();
+    let provide_value =
+        match_variant(impl_, &names, false, provide_arms, phantom_arm.as_ref());
+    // The inherent method would shadow trait methods of the same name, so it is only
+    // generated for unions that opted in with a `provide` variant.
+    let provide_fn = match variant_options.iter().any(|o| o.provide) {
+        true => quote::quote! {
+            /// Returns a reference to `__T` if the active variant provides one
+            ///
+            /// Only variants marked with `#[trait_union(provide)]` and flattened variants
+            /// provide references.
+            #[inline]
+            #vis fn provide<__T: ?::core::marker::Sized + 'static>(&self) -> ::core::option::Option<&__T> {
+                ::trait_union::request_ref(self)
+            }
+        },
+        false => quote::quote!(),
+    };
+    // `Deref` is implemented outside of the `const _` block so that rust-analyzer finds
+    // the target when completing methods of the trait.
+    let mut deref_impls = None;
+    let mut as_dyn = None;
+    if options.no_deref {
+        as_dyn = Some(quote::quote! {
+            /// Returns a reference to the active variant as a trait object
+            #inline
+            #vis fn as_dyn(&self) -> &(dyn #trait_) {
+                #deref
+            }
+
+            /// Returns a mutable reference to the active variant as a trait object
+            #inline
+            #vis fn as_dyn_mut(&mut self) -> &mut (dyn #trait_) {
+                #deref_mut
+            }
+        });
+    } else {
+        deref_impls = Some(quote::quote! {
+            impl#impl_generics ::core::ops::Deref for #name#ty_generics #where_clause {
+                type Target = dyn #trait_;
+
+                #inline
+                fn deref(&self) -> &Self::Target {
+                    #deref
+                }
+            }
+
+            impl#impl_generics ::core::ops::DerefMut for #name#ty_generics #where_clause {
+                #inline
+                fn deref_mut(&mut self) -> &mut Self::Target {
+                    #deref_mut
+                }
+            }
+        });
+    }
+    let repr_c = match cfg!(trait_union_no_offset_of) {
+        true => Some(quote::quote!(#[repr(C)])),
+        false => None,
+    };
+    let tokens = quote::quote! {
+        #(#attr)*
+        #[allow(non_snake_case)]
+        #repr_c
+        #vis struct #name#impl_generics #where_clause {
+            #storage
+        }
+
+        #[doc = #doc]
+        #[allow(clippy::missing_safety_doc)]
+        #vis unsafe trait #variant_name#impl_generics: #trait_ {
+            #[doc(hidden)]
+            const __TRAIT_UNION_INDEX: usize;
+
+            /// The kind of the variant
+            const KIND: #kind_name;
+
+            /// The size of the variant in bytes
+            const SIZE: usize;
+
+            /// The name of the kind of the variant
+            const NAME: &'static str;
+        }
+
+        #manual_variant_trait
+
+        #data_def
+
+        #[doc = #ref_doc]
+        #non_exhaustive
+        #[allow(dead_code)]
+        #enum_vis enum #ref_name#ref_impl_generics #ref_where_clause {
+            #(
+                #kind_variant_docs
+                #kind_variants(&'__trait_union_ref #variant_types),
+            )*
+            #phantom_variant
+        }
+
+        #[doc = #mut_doc]
+        #non_exhaustive
+        #[allow(dead_code)]
+        #enum_vis enum #mut_name#ref_impl_generics #ref_where_clause {
+            #(
+                #kind_variant_docs
+                #kind_variants(&'__trait_union_ref mut #variant_types),
+            )*
+            #phantom_variant
+        }
+
+        #[doc = #owned_doc]
+        #non_exhaustive
+        #[allow(dead_code)]
+        #enum_vis enum #owned_name#impl_generics #where_clause {
+            #(
+                #kind_variant_docs
+                #kind_variants(#variant_types),
+            )*
+            #phantom_variant
+        }
+
+        #enum_dispatch_impl
+
+        #atomic
+
+        #metrics
+
+        #dispatcher
+
+        #cell_type
+
+        #visitor
+
+        #handle
+
+        #send
+
+        #[doc = #kind_doc]
+        #non_exhaustive
+        #[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+        #[repr(#tag_type)]
+        #vis enum #kind_name {
+            #(
+                #kind_variant_docs
+                #kind_variants = #tag_literals,
+            )*
+        }
+
+        impl#impl_generics #name#ty_generics #where_clause {
+            const __TRAIT_UNION_TAGS: [#tag_type; #num_variants] = [#(#tag_literals),*];
+
+            const __TRAIT_UNION_KINDS: [#kind_name; #num_variants] =
+                [#(#kind_name::#kind_variants),*];
+
+            #layout_hash
+
+            #size_report
+
+            #pod
+
+            #constructors
+
+            #live_counts
+
+            #metrics_methods
+
+            #schema
+
+            const __TRAIT_UNION_LAYOUT: ::trait_union::LayoutInfo = ::trait_union::LayoutInfo {
+                name: #name_as_str,
+                size: ::core::mem::size_of::<Self>(),
+                align: ::core::mem::align_of::<Self>(),
+                variants: &[#(#variant_layouts),*],
+            };
+
+            /// Whether any variant needs to be dropped
+            ///
+            /// If this is `false`, dropping a value does nothing.
+            #vis const NEEDS_DROP: bool =
+                false #(|| ::core::mem::needs_drop::<#variant_types>())*;
+
+            /// Returns the layout of this type
+            #vis const fn debug_layout() -> ::trait_union::LayoutInfo {
+                Self::__TRAIT_UNION_LAYOUT
+            }
+
+            #inline
+            fn __trait_union_position(&self) -> usize {
+                #position
+            }
+
+            /// Returns the tag of the active variant of `this`
+            #inline
+            #vis fn tag_of(this: &Self) -> #tag_type {
+                Self::__TRAIT_UNION_TAGS[this.__trait_union_position()]
+            }
+
+            /// Returns a reference to the active variant of `this`
+            #inline
+            #[allow(dead_code)]
+            #enum_vis fn as_ref_enum<'__trait_union_ref>(
+                this: &'__trait_union_ref Self,
+            ) -> #ref_name#ref_ty_generics {
+                this.__trait_union_as_ref_enum()
+            }
+
+            #inline
+            fn __trait_union_as_ref_enum<'__trait_union_ref>(
+                &'__trait_union_ref self,
+            ) -> #ref_name#ref_ty_generics {
+                #as_ref_enum
+            }
+
+            /// Returns a mutable reference to the active variant of `this`
+            #inline
+            #[allow(dead_code)]
+            #enum_vis fn as_mut_enum<'__trait_union_ref>(
+                this: &'__trait_union_ref mut Self,
+            ) -> #mut_name#ref_ty_generics {
+                this.__trait_union_as_mut_enum()
+            }
+
+            #inline
+            #[allow(dead_code)]
+            fn __trait_union_as_mut_enum<'__trait_union_ref>(
+                &'__trait_union_ref mut self,
+            ) -> #mut_name#ref_ty_generics {
+                #as_mut_enum
+            }
+
+            /// Moves the active variant out of `this`
+            #inline
+            #[allow(dead_code)]
+            #enum_vis fn into_enum(this: Self) -> #owned_name#ty_generics {
+                let this = ::core::mem::ManuallyDrop::new(this);
+                #count_into
+                // SAFETY: `this` is never dropped, so the active variant is moved out
+                // exactly once.
+                unsafe {
+                    match Self::as_ref_enum(&this) {
+                        #(
+                            #ref_name::#kind_variants(value) => {
+                                #owned_name::#kind_variants(::core::ptr::read(value))
+                            }
+                        )*
+                        #ref_phantom_arm
+                    }
+                }
+            }
+
+            /// Returns the kind of the active variant of `this`
+            #inline
+            #vis fn kind_of(this: &Self) -> #kind_name {
+                Self::__TRAIT_UNION_KINDS[this.__trait_union_position()]
+            }
+
+            /// Returns whether `this` and `other` have the same active variant
+            #inline
+            #vis fn same_variant(this: &Self, other: &Self) -> bool {
+                this.__trait_union_position() == other.__trait_union_position()
+            }
+
+            /// Writes the kind of the active variant of `this` followed by its value in
+            /// parentheses
+            ///
+            /// The value is formatted with `Display` if its type implements it, otherwise
+            /// with `Debug`, and otherwise written as `..`. Nothing is allocated.
+            #inline
+            #vis fn describe(this: &Self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                this.__trait_union_describe(f)
+            }
+
+            #inline
+            fn __trait_union_describe(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                #[allow(unused_imports)]
+                use ::trait_union::__support::{DescribeDebug, DescribeDisplay, DescribeOther};
+
+                ::core::fmt::Debug::fmt(&Self::kind_of(self), f)?;
+                f.write_str("(")?;
+                (#describe_value)?;
+                f.write_str(")")
+            }
+
+            #provide_fn
+
+            /// Sorts `values` by the declaration order of their active variants
+            ///
+            /// The sort is unstable.
+            #[inline]
+            #vis fn sort_by_kind(values: &mut [Self]) {
+                values.sort_unstable_by_key(Self::__trait_union_position);
+            }
+
+            /// Returns an iterator over the maximal runs of values of the same kind
+            #[inline]
+            #vis fn kind_runs(values: &[Self]) -> ::trait_union::KindRuns<'_, Self, #kind_name> {
+                ::trait_union::KindRuns::new(values, Self::kind_of)
+            }
+
+            /// Returns whether the active variant of `this` is a zero-sized type
+            ///
+            /// The bytes of a zero-sized variant do not have to be copied.
+            #inline
+            #vis fn is_zst_variant(this: &Self) -> bool {
+                Self::__TRAIT_UNION_LAYOUT.variants[this.__trait_union_position()].size == 0
+            }
+
+            /// Creates a new instance
+            #inline
+            #track_caller
+            #new_vis fn new<V: #variant_name#ty_generics>(value: V) -> Self {
+                #layout_check
+                #count_variant
+                #new
+            }
+
+            #new_send
+
+            #raw_constructors
+
+            #set_method
+
+            #reborrow
+
+            #visit_methods
+
+            #as_dyn
+
+            #call
+
+            #take
+
+            #transition
+
+            #move_variant
+
+            #cell_methods
+        }
+
+        #deref_impls
+
+        #drop_tests
+
+        #size_check
+
+        const _: () = {
+            #drop_impl
+
+            #copy_impl
+
+            impl#impl_generics #name#ty_generics #where_clause {
+                #union_impl
+            }
+
+            #unpin_impl
+
+            #default_impl
+
+            #accept_impl
+
+            impl#impl_generics ::trait_union::Provide for #name#ty_generics #where_clause {
+                fn provide<'__trait_union_provide>(
+                    &'__trait_union_provide self,
+                    request: &mut ::trait_union::Request<'__trait_union_provide>,
+                ) {
+                    #provide_value
+                }
+            }
+
+            #from_variant_impl
+
+            #into_enum_impl
+
+            #box_error_impl
+
+            #forward_impls
+
+            #partial_forward_impls
+
+            #trait_object_from
+
+            #(#variant_impls)*
+
+            #eager_layout_check
+
+            #(#checks)*
+        };
+    };
+    Ok(tokens)
+}
+
+fn handle_cow_request(request: TraitUnionCowRequest) -> syn::Result<TokenStream> {
+    let options = Options::extract(&mut request.attr.clone())?;
+    if let Some(span) = options.no_implicit_static {
+        return Err(syn::Error::new(
+            span,
+            "the no_implicit_static option is not supported by trait_union_cow",
+        ));
+    }
+    if let Some(ty) = options.manual_impl.first() {
+        return Err(syn::Error::new(
+            ty.span(),
+            "the manual_impl option is not supported by trait_union_cow",
+        ));
+    }
+    if let Some(span) = options.box_error {
+        return Err(syn::Error::new(
+            span,
+            "the box_error option is not supported by trait_union_cow",
+        ));
+    }
+    let impl_ = options.impl_;
+    let vis = request.vis;
+    let borrowed_name = request.borrowed;
+    let owned_name = request.owned;
+    let lifetime = match request.generics.lifetimes().next() {
+        Some(lt) => lt.lifetime.clone(),
+        None => {
+            return Err(syn::Error::new(
+                borrowed_name.span(),
+                "the borrowed union must have a lifetime parameter",
+            ))
+        }
+    };
+    if let Some(lt) = request.trait_.iter().find_map(|b| match b {
+        TypeParamBound::Lifetime(lt) => Some(lt),
+        _ => None,
+    }) {
+        return Err(syn::Error::new(
+            lt.span(),
+            "the trait bounds of a cow union must not contain a lifetime",
+        ));
+    }
+    let mut pointees = vec![];
+    for (borrowed, _) in &request.variants {
+        match borrowed {
+            Type::Reference(r) if r.mutability.is_none() => pointees.push(&*r.elem),
+            _ => {
+                return Err(syn::Error::new(
+                    borrowed.span(),
+                    "borrowed variants must be shared references",
+                ))
+            }
+        }
+    }
+    let mut owned_generics = request.generics.clone();
+    owned_generics.params = request
+        .generics
+        .params
+        .iter()
+        .filter(|p| !matches!(p, GenericParam::Lifetime(l) if l.lifetime == lifetime))
+        .cloned()
+        .collect();
+    let reborrowed_params = request.generics.params.iter().map(|p| match p {
+        GenericParam::Lifetime(l) if l.lifetime == lifetime => quote::quote!('_),
+        GenericParam::Lifetime(l) => {
+            let lt = &l.lifetime;
+            quote::quote!(#lt)
+        }
+        GenericParam::Type(t) => {
+            let ident = &t.ident;
+            quote::quote!(#ident)
+        }
+        GenericParam::Const(c) => {
+            let ident = &c.ident;
+            quote::quote!(#ident)
+        }
+    });
+    let mut borrowed_trait = request.trait_.clone();
+    add_default_lifetime(&mut borrowed_trait, &lifetime.to_string());
+    let borrowed_names = Names::new(&borrowed_name);
+    let owned_names = Names::new(&owned_name);
+    let mut to_owned = vec![];
+    let mut as_borrowed = vec![];
+    for ((borrowed, owned), pointee) in request.variants.iter().zip(pointees) {
+        to_owned.push(quote::quote_spanned! { located_at(owned.span()) =>
+            #owned_name::new(<#owned as ::core::convert::From<#borrowed>>::from(*value))
+        });
+        as_borrowed.push(quote::quote_spanned! { located_at(pointee.span()) =>
+            #borrowed_name::new(::core::borrow::Borrow::<#pointee>::borrow(value))
+        });
+    }
+    let to_owned = match_variant(impl_, &borrowed_names, false, to_owned, None);
+    let as_borrowed = match_variant(impl_, &owned_names, false, as_borrowed, None);
+    let borrowed_request = TraitUnionRequest {
+        attr: request.attr.clone(),
+        vis: vis.clone(),
+        ident: borrowed_name.clone(),
+        generics: request.generics.clone(),
+        trait_: borrowed_trait,
+        fields: vec![],
+        variants: request
+            .variants
+            .iter()
+            .map(|(b, _)| Variant {
+                attrs: vec![],
+                tag: None,
+                boxed: None,
+                ty: b.clone(),
+            })
+            .collect(),
+    };
+    let owned_request = TraitUnionRequest {
+        attr: request.attr,
+        vis: vis.clone(),
+        ident: owned_name.clone(),
+        generics: owned_generics.clone(),
+        trait_: request.trait_,
+        fields: vec![],
+        variants: request
+            .variants
+            .iter()
+            .map(|(_, o)| Variant {
+                attrs: vec![],
+                tag: None,
+                boxed: None,
+                ty: o.clone(),
+            })
+            .collect(),
+    };
+    let mut tokens = handle_request(borrowed_request, false)?;
+    tokens.extend(handle_request(owned_request, false)?);
+    let (impl_generics, ty_generics, where_clause) = request.generics.split_for_impl();
+    let (owned_impl_generics, owned_ty_generics, owned_where_clause) =
+        owned_generics.split_for_impl();
+    tokens.extend(quote::quote! {
+        impl#impl_generics #borrowed_name#ty_generics #where_clause {
+            /// Converts the active variant into its owned counterpart
+            #[inline]
+            #vis fn to_owned(&self) -> #owned_name#owned_ty_generics {
+                #to_owned
+            }
+        }
+
+        impl#owned_impl_generics #owned_name#owned_ty_generics #owned_where_clause {
+            /// Borrows the active variant as its borrowed counterpart
+            #[inline]
+            #vis fn as_borrowed(&self) -> #borrowed_name<#(#reborrowed_params),*> {
+                #as_borrowed
+            }
+        }
+    });
+    Ok(tokens)
+}
+
+#[cfg(test)]
+mod test {
+    use proc_macro2::{TokenStream, TokenTree};
+    use std::{env, fs, path::Path, time::Instant};
+
+    /// Compares the expansions of `tests/expand/NAME.rs` with `NAME.expanded.rs`
+    ///
+    /// Run with `EXPAND=overwrite` to update the expanded files after an intended change.
+    #[test]
+    // The registry calls are added to every union and are not part of the snapshots.
+    #[cfg_attr(feature = "debug-registry", ignore)]
+    fn expand() {
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/expand");
+        let overwrite = env::var_os("EXPAND").map_or(false, |v| v == "overwrite");
+        let mut inputs = fs::read_dir(&dir)
+            .unwrap()
+            .map(|e| e.unwrap().path())
+            .filter(|p| !p.to_str().unwrap().ends_with(".expanded.rs"))
+            .collect::<Vec<_>>();
+        inputs.sort();
+        assert!(!inputs.is_empty());
+        let mut mismatches = vec![];
+        for input in inputs {
+            let mut file = syn::parse_file(&fs::read_to_string(&input).unwrap()).unwrap();
+            let summary = super::inspect::summary(&file.items);
+            super::inspect::expand_items(&mut file.items);
+            let actual = summary + &prettyplease::unparse(&file);
+            let output = input.with_extension("expanded.rs");
+            if overwrite {
+                fs::write(&output, actual).unwrap();
+            } else if fs::read_to_string(&output).ok().as_deref() != Some(&*actual) {
+                mismatches.push(output);
+            }
+        }
+        assert!(
+            mismatches.is_empty(),
+            "the expansions in {:?} changed, run with EXPAND=overwrite to update them",
+            mismatches,
+        );
+    }
+
+    /// Measures the expansion of many unions over the same trait
+    ///
+    /// Run with `cargo test -p trait-union-proc -r -- --ignored --nocapture codegen`.
+    #[test]
+    #[ignore]
+    fn codegen_benchmark() {
+        const UNIONS: usize = 500;
+        fn count(tokens: TokenStream) -> usize {
+            tokens
+                .into_iter()
+                .map(|tt| match tt {
+                    TokenTree::Group(g) => 1 + count(g.stream()),
+                    _ => 1,
+                })
+                .sum()
+        }
+        let input = (0..UNIONS)
+            .map(|i| format!("union U{}: Display = i32 | u64 | &'static str | bool;", i))
+            .collect::<String>();
+        let input: TokenStream = input.parse().unwrap();
+        let start = Instant::now();
+        let expanded = super::expand(input, false);
+        let elapsed = start.elapsed();
+        let tokens = count(expanded.clone());
+        let start = Instant::now();
+        syn::parse2::<syn::File>(expanded).unwrap();
+        let parse = start.elapsed();
+        println!(
+            "{} unions: {} tokens per union, expanded in {:?}, parsed in {:?}",
+            UNIONS,
+            tokens / UNIONS,
+            elapsed,
+            parse,
+        );
+    }
 }
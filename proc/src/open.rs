@@ -0,0 +1,293 @@
+use super::{
+    add_default_lifetime, call_method, check_explicit_lifetime, fn_trait,
+    options::Options, trait_object_from, TraitUnionRequest,
+};
+use proc_macro2::{Span, TokenStream};
+use syn::{
+    punctuated::Punctuated, spanned::Spanned, DeriveInput, Ident, LitInt, Path, Token,
+};
+
+/// The `open(size = SIZE, align = ALIGN)` option
+pub struct Open {
+    pub span: Span,
+    pub size: LitInt,
+    pub align: Option<LitInt>,
+}
+
+/// Generates a trait-union whose variants register with `#[derive(TraitUnionVariant)]`
+pub(crate) fn handle_open_request(
+    request: TraitUnionRequest,
+    attr: Vec<syn::Attribute>,
+    options: &Options,
+    copy: bool,
+) -> syn::Result<TokenStream> {
+    let open = options.open.as_ref().unwrap();
+    if let Some(variant) = request.variants.first() {
+        return Err(syn::Error::new(
+            variant.ty.span(),
+            "an open trait-union cannot list variants",
+        ));
+    }
+    if !request.generics.params.is_empty() {
+        return Err(syn::Error::new(
+            request.generics.span(),
+            "open trait-unions cannot have generic parameters",
+        ));
+    }
+    if !options.is_open_only() {
+        return Err(syn::Error::new(
+            open.span,
+            "the open option cannot be combined with options other than new_vis and \
+             no_implicit_static",
+        ));
+    }
+    let vis = request.vis;
+    let new_vis = options.new_vis.as_ref().unwrap_or(&vis);
+    let name = request.ident;
+    let mut trait_ = request.trait_;
+    if options.no_implicit_static.is_some() {
+        check_explicit_lifetime(&trait_)?;
+    }
+    add_default_lifetime(&mut trait_, "'static");
+    let variant_name = Ident::new(&format!("{}Variant", name), name.span());
+    let storage_name =
+        Ident::new(&format!("__trait_union_{}_Storage", name), name.span());
+    let size = &open.size;
+    let align = match &open.align {
+        Some(align) => align.clone(),
+        None => LitInt::new("8", open.span),
+    };
+    let doc = format!(
+        "Marker trait for types that can be stored in a [{}]\n\n\
+         # Safety\n\n\
+         This trait must not be implemented manually. Use `#[derive(TraitUnionVariant)]`.",
+        name
+    );
+    let mut copy_bound = None;
+    let mut drop_impl = None;
+    if copy {
+        copy_bound = Some(quote::quote!(+ ::core::marker::Copy));
+    } else {
+        drop_impl = Some(quote::quote! {
+            impl ::core::ops::Drop for #name {
+                #[inline(always)]
+                fn drop(&mut self) {
+                    unsafe {
+                        ::core::ptr::drop_in_place((self.cast)(self.data.as_mut_ptr() as *mut ()));
+                    }
+                }
+            }
+        });
+    }
+    let inline = options.inline.attr();
+    let trait_object_from =
+        trait_object_from(&name, &request.generics, &trait_, false, &inline);
+    let mut from_fn = None;
+    if fn_trait(&trait_).is_some() {
+        from_fn = Some(quote::quote! {
+            /// Creates a new instance from a closure or another implementor of the trait
+            ///
+            /// Fails to compile when instantiated with a type that does not fit into the
+            /// union.
+            #[inline(always)]
+            #new_vis fn from_fn<F: #trait_ #copy_bound>(f: F) -> Self {
+                #[allow(clippy::let_unit_value)]
+                let () = ::trait_union::__support::Fits::<F, #size, #align>::CHECK;
+                unsafe { Self::__trait_union_new(f) }
+            }
+        });
+    }
+    let call = call_method(&vis, &trait_, false, &inline);
+    let mut from_variant_impl = None;
+    if options.new_vis.is_none() {
+        from_variant_impl = Some(quote::quote! {
+            impl<V: #variant_name> ::trait_union::FromVariant<V> for #name {
+                #inline
+                fn from_variant(value: V) -> Self {
+                    Self::new(value)
+                }
+            }
+        });
+    }
+    Ok(quote::quote! {
+        #(#attr)*
+        #vis struct #name {
+            data: ::core::mem::MaybeUninit<#storage_name>,
+            cast: unsafe fn(*mut ()) -> *mut (dyn #trait_),
+            _marker: ::core::marker::PhantomData<dyn #trait_>,
+        }
+
+        #[doc(hidden)]
+        #[allow(non_camel_case_types)]
+        #[derive(Copy, Clone)]
+        #[repr(C, align(#align))]
+        struct #storage_name([::core::mem::MaybeUninit<u8>; #size]);
+
+        #[doc = #doc]
+        #[allow(clippy::missing_safety_doc)]
+        #vis unsafe trait #variant_name: #trait_ #copy_bound {
+            #[doc(hidden)]
+            const __TRAIT_UNION_OPEN_CHECK: ();
+        }
+
+        impl #name {
+            #[doc(hidden)]
+            pub const __TRAIT_UNION_SIZE: usize = #size;
+
+            #[doc(hidden)]
+            pub const __TRAIT_UNION_ALIGN: usize = #align;
+
+            /// Creates a new instance
+            #[inline(always)]
+            #new_vis fn new<V: #variant_name>(value: V) -> Self {
+                #[allow(clippy::let_unit_value)]
+                let () = V::__TRAIT_UNION_OPEN_CHECK;
+                unsafe { Self::__trait_union_new(value) }
+            }
+
+            #from_fn
+
+            #call
+        }
+
+        #from_variant_impl
+
+        // See `handle_request` for why `Deref` is not implemented in the `const _` block.
+        impl ::core::ops::Deref for #name {
+            type Target = dyn #trait_;
+
+            #[inline(always)]
+            fn deref(&self) -> &Self::Target {
+                unsafe { &*(self.cast)(self.data.as_ptr() as *mut ()) }
+            }
+        }
+
+        impl ::core::ops::DerefMut for #name {
+            #[inline(always)]
+            fn deref_mut(&mut self) -> &mut Self::Target {
+                unsafe { &mut *(self.cast)(self.data.as_mut_ptr() as *mut ()) }
+            }
+        }
+
+        const _: () = {
+            #drop_impl
+
+            impl #name {
+                /// # Safety
+                ///
+                /// `V` must fit into the storage.
+                #[inline(always)]
+                unsafe fn __trait_union_new<V: #trait_>(value: V) -> Self {
+                    let mut data = ::core::mem::MaybeUninit::<#storage_name>::uninit();
+                    unsafe {
+                        ::core::ptr::write(data.as_mut_ptr() as *mut V, value);
+                    }
+                    Self {
+                        data,
+                        cast: Self::__trait_union_cast::<V>,
+                        _marker: ::core::marker::PhantomData,
+                    }
+                }
+
+                unsafe fn __trait_union_cast<V: #trait_>(ptr: *mut ()) -> *mut (dyn #trait_) {
+                    ptr as *mut V
+                }
+            }
+
+            #trait_object_from
+        };
+    })
+}
+
+/// Implements `#[derive(TraitUnionVariant)]`
+pub fn derive_variant(input: DeriveInput) -> syn::Result<TokenStream> {
+    let mut unions = vec![];
+    for attr in &input.attrs {
+        if attr.path.is_ident("trait_union") {
+            let paths =
+                attr.parse_args_with(Punctuated::<Path, Token![,]>::parse_terminated)?;
+            unions.extend(paths);
+        }
+    }
+    if unions.is_empty() {
+        return Err(syn::Error::new(
+            input.ident.span(),
+            "expected a #[trait_union(UNION)] attribute naming the open trait-union",
+        ));
+    }
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let mut tokens = TokenStream::new();
+    for union in unions {
+        let mut marker = union.clone();
+        let last = marker.segments.last_mut().unwrap();
+        last.ident = Ident::new(&format!("{}Variant", last.ident), last.ident.span());
+        let msg = format!(
+            "{} does not fit into {}",
+            ident,
+            quote::quote!(#union).to_string().replace(' ', "")
+        );
+        // See `handle_request` for why `unsafe` is not spanned.
+        let unsafe_ = quote::quote!(unsafe);
+        tokens.extend(quote::quote_spanned! { union.span() =>
+            #unsafe_ impl#impl_generics #marker for #ident#ty_generics #where_clause {
+                const __TRAIT_UNION_OPEN_CHECK: () = if ::core::mem::size_of::<Self>()
+                    > <#union>::__TRAIT_UNION_SIZE
+                    || ::core::mem::align_of::<Self>() > <#union>::__TRAIT_UNION_ALIGN
+                {
+                    ::core::panic!(#msg)
+                };
+            }
+        });
+        if input.generics.params.is_empty() {
+            tokens.extend(quote::quote_spanned! { union.span() =>
+                const _: () = <#ident as #marker>::__TRAIT_UNION_OPEN_CHECK;
+            });
+        }
+    }
+    Ok(tokens)
+}
+
+impl syn::parse::Parse for Open {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let span = input.span();
+        let mut size = None;
+        let mut align = None;
+        let args = Punctuated::<OpenArg, Token![,]>::parse_terminated(input)?;
+        for arg in args {
+            let slot = match arg.name.to_string().as_str() {
+                "size" => &mut size,
+                "align" => &mut align,
+                _ => {
+                    return Err(syn::Error::new(
+                        arg.name.span(),
+                        "expected size or align",
+                    ))
+                }
+            };
+            if slot.is_some() {
+                return Err(syn::Error::new(arg.name.span(), "duplicate open argument"));
+            }
+            *slot = Some(arg.value);
+        }
+        let size = match size {
+            Some(size) => size,
+            None => return Err(syn::Error::new(span, "the open option requires a size")),
+        };
+        Ok(Open { span, size, align })
+    }
+}
+
+struct OpenArg {
+    name: Ident,
+    value: LitInt,
+}
+
+impl syn::parse::Parse for OpenArg {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let name = input.parse()?;
+        let _t_equals = input.parse::<Token![=]>()?;
+        let value = input.parse()?;
+        Ok(OpenArg { name, value })
+    }
+}
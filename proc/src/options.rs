@@ -0,0 +1,620 @@
+use super::{dispatcher::Dispatcher, enum_dispatch::EnumDispatch, open::Open};
+use proc_macro2::{Span, TokenStream};
+use syn::{
+    ext::IdentExt,
+    parenthesized,
+    parse::{Parse, ParseStream},
+    punctuated::Punctuated,
+    Attribute, Ident, Lifetime, LitInt, LitStr, Path, Token, Type, Visibility,
+};
+
+/// Options set with `#[trait_union(...)]` attributes
+#[derive(Default)]
+pub struct Options {
+    /// `abi_check` or `abi_check = HASH`
+    pub abi_check: Option<Option<LitInt>>,
+    /// `report_size` or `report_size = SIZE`
+    pub report_size: Option<Option<LitInt>>,
+    /// `pod`
+    pub pod: Option<Span>,
+    /// `impl = "union"` or `impl = "enum"`
+    pub impl_: Impl,
+    /// `inline = "always"`, `inline = "hint"`, or `inline = "never"`
+    pub inline: Inline,
+    /// `tag = "index"` or `tag = "type_name_hash"`
+    pub tag: Tag,
+    /// `covariant(LIFETIME, ...)`
+    pub covariant: Vec<Lifetime>,
+    /// `invariant(PARAM, ...)`
+    pub invariant: Vec<VarianceParam>,
+    /// `phantom = TYPE`
+    pub phantom: Vec<Type>,
+    /// `constructors`
+    pub constructors: bool,
+    /// `atomic`
+    pub atomic: Option<Span>,
+    /// `drop_tests`
+    pub drop_tests: Option<Span>,
+    /// `live_counts`
+    pub live_counts: Option<Span>,
+    /// `open(size = SIZE, align = ALIGN)`
+    pub open: Option<Open>,
+    /// `require(TRAIT, ...)`
+    pub require: Vec<Path>,
+    /// `metrics`
+    pub metrics: Option<Span>,
+    /// `new_vis = "VISIBILITY"`
+    pub new_vis: Option<Visibility>,
+    /// `no_deref`
+    pub no_deref: bool,
+    /// `non_exhaustive`
+    pub non_exhaustive: bool,
+    /// `no_implicit_static`
+    pub no_implicit_static: Option<Span>,
+    /// `schema`
+    pub schema: bool,
+    /// `forward(TRAIT, ...)`
+    pub forward: Vec<Path>,
+    /// `dispatcher(capacity = CAPACITY, method = METHOD, ctx = CTX)`
+    pub dispatcher: Option<Dispatcher>,
+    /// `manual_impl(TYPE, ...)`
+    pub manual_impl: Vec<Type>,
+    /// `box_error`
+    pub box_error: Option<Span>,
+    /// `cell`
+    pub cell: Option<Span>,
+    /// `reborrow`
+    pub reborrow: Option<Span>,
+    /// `visitor`
+    pub visitor: bool,
+    /// `external`
+    pub external: bool,
+    /// `enum_dispatch(fn METHOD(...) -> OUTPUT; ...)`
+    pub enum_dispatch: Option<EnumDispatch>,
+    /// `send`
+    pub send: bool,
+    /// `enums`
+    pub enums: bool,
+}
+
+/// A lifetime or type parameter named in `invariant(...)`
+pub enum VarianceParam {
+    Lifetime(Lifetime),
+    Type(Ident),
+}
+
+impl Parse for VarianceParam {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        if input.peek(Lifetime) {
+            Ok(VarianceParam::Lifetime(input.parse()?))
+        } else {
+            Ok(VarianceParam::Type(input.parse()?))
+        }
+    }
+}
+
+/// How the value is stored
+#[derive(Copy, Clone, Default, PartialEq, Eq)]
+pub enum Impl {
+    /// In a union with a separate index
+    #[default]
+    Union,
+    /// In an enum with one variant per type
+    Enum,
+}
+
+/// The inline attribute of the generated methods
+#[derive(Copy, Clone, Default, PartialEq, Eq)]
+pub enum Inline {
+    /// `#[inline(always)]`
+    #[default]
+    Always,
+    /// `#[inline]`
+    Hint,
+    /// `#[inline(never)]`
+    Never,
+}
+
+impl Inline {
+    /// Returns the attribute
+    pub fn attr(self) -> TokenStream {
+        match self {
+            Inline::Always => quote::quote!(#[inline(always)]),
+            Inline::Hint => quote::quote!(#[inline]),
+            Inline::Never => quote::quote!(#[inline(never)]),
+        }
+    }
+}
+
+/// How variants without an explicit tag are tagged
+#[derive(Copy, Clone, Default, PartialEq, Eq)]
+pub enum Tag {
+    /// The tag of the previous variant plus one
+    #[default]
+    Index,
+    /// The FNV-1a hash of the type of the variant
+    TypeNameHash,
+}
+
+const OPTION_NAMES: &[&str] = &[
+    "abi_check",
+    "report_size",
+    "pod",
+    "impl",
+    "inline",
+    "tag",
+    "covariant",
+    "invariant",
+    "phantom",
+    "constructors",
+    "atomic",
+    "drop_tests",
+    "live_counts",
+    "open",
+    "require",
+    "metrics",
+    "new_vis",
+    "no_deref",
+    "non_exhaustive",
+    "no_implicit_static",
+    "schema",
+    "forward",
+    "dispatcher",
+    "manual_impl",
+    "box_error",
+    "cell",
+    "reborrow",
+    "visitor",
+    "external",
+    "enum_dispatch",
+    "send",
+    "enums",
+];
+
+/// Options that add to a list and can therefore be given more than once
+const REPEATABLE_OPTION_NAMES: &[&str] = &[
+    "covariant",
+    "invariant",
+    "phantom",
+    "require",
+    "forward",
+    "manual_impl",
+];
+
+enum OptionArg {
+    AbiCheck(Option<LitInt>),
+    ReportSize(Option<LitInt>),
+    Pod(Span),
+    Impl(Impl),
+    Inline(Inline),
+    Tag(Tag),
+    Covariant(Vec<Lifetime>),
+    Invariant(Vec<VarianceParam>),
+    Phantom(Type),
+    Constructors,
+    Atomic(Span),
+    DropTests(Span),
+    LiveCounts(Span),
+    Open(Open),
+    Require(Vec<Path>),
+    Metrics(Span),
+    NewVis(Visibility),
+    NoDeref,
+    NonExhaustive,
+    NoImplicitStatic(Span),
+    Schema,
+    Forward(Vec<Path>),
+    Dispatcher(Dispatcher),
+    ManualImpl(Vec<Type>),
+    BoxError(Span),
+    Cell(Span),
+    Reborrow(Span),
+    Visitor,
+    External,
+    EnumDispatch(EnumDispatch),
+    Send,
+    Enums,
+}
+
+impl Parse for OptionArg {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let ident = input.call(Ident::parse_any)?;
+        match &*ident.to_string() {
+            "abi_check" => {
+                let mut hash = None;
+                if input.peek(Token![=]) {
+                    let _t_equals = input.parse::<Token![=]>()?;
+                    hash = Some(input.parse()?);
+                }
+                Ok(OptionArg::AbiCheck(hash))
+            }
+            "report_size" => {
+                let mut size = None;
+                if input.peek(Token![=]) {
+                    let _t_equals = input.parse::<Token![=]>()?;
+                    size = Some(input.parse()?);
+                }
+                Ok(OptionArg::ReportSize(size))
+            }
+            "pod" => {
+                if cfg!(not(feature = "bytemuck")) {
+                    return Err(syn::Error::new(
+                        ident.span(),
+                        "the pod option requires the bytemuck feature of trait-union",
+                    ));
+                }
+                Ok(OptionArg::Pod(ident.span()))
+            }
+            "impl" => {
+                let _t_equals = input.parse::<Token![=]>()?;
+                let value = input.parse::<LitStr>()?;
+                match &*value.value() {
+                    "union" => Ok(OptionArg::Impl(Impl::Union)),
+                    "enum" => Ok(OptionArg::Impl(Impl::Enum)),
+                    _ => Err(syn::Error::new(
+                        value.span(),
+                        "expected \"union\" or \"enum\"",
+                    )),
+                }
+            }
+            "inline" => {
+                let _t_equals = input.parse::<Token![=]>()?;
+                let value = input.parse::<LitStr>()?;
+                match &*value.value() {
+                    "always" => Ok(OptionArg::Inline(Inline::Always)),
+                    "hint" => Ok(OptionArg::Inline(Inline::Hint)),
+                    "never" => Ok(OptionArg::Inline(Inline::Never)),
+                    _ => Err(syn::Error::new(
+                        value.span(),
+                        "expected \"always\", \"hint\", or \"never\"",
+                    )),
+                }
+            }
+            "tag" => {
+                let _t_equals = input.parse::<Token![=]>()?;
+                let value = input.parse::<LitStr>()?;
+                match &*value.value() {
+                    "index" => Ok(OptionArg::Tag(Tag::Index)),
+                    "type_name_hash" => Ok(OptionArg::Tag(Tag::TypeNameHash)),
+                    _ => Err(syn::Error::new(
+                        value.span(),
+                        "expected \"index\" or \"type_name_hash\"",
+                    )),
+                }
+            }
+            "covariant" => {
+                let content;
+                parenthesized!(content in input);
+                let lifetimes =
+                    Punctuated::<Lifetime, Token![,]>::parse_terminated(&content)?;
+                Ok(OptionArg::Covariant(lifetimes.into_iter().collect()))
+            }
+            "invariant" => {
+                let content;
+                parenthesized!(content in input);
+                let params =
+                    Punctuated::<VarianceParam, Token![,]>::parse_terminated(&content)?;
+                Ok(OptionArg::Invariant(params.into_iter().collect()))
+            }
+            "phantom" => {
+                let _t_equals = input.parse::<Token![=]>()?;
+                Ok(OptionArg::Phantom(input.parse()?))
+            }
+            "constructors" => Ok(OptionArg::Constructors),
+            "atomic" => Ok(OptionArg::Atomic(ident.span())),
+            "drop_tests" => Ok(OptionArg::DropTests(ident.span())),
+            "live_counts" => Ok(OptionArg::LiveCounts(ident.span())),
+            "open" => {
+                let content;
+                parenthesized!(content in input);
+                let mut open = content.parse::<Open>()?;
+                open.span = ident.span();
+                Ok(OptionArg::Open(open))
+            }
+            "require" => {
+                let content;
+                parenthesized!(content in input);
+                let traits = Punctuated::<Path, Token![,]>::parse_terminated(&content)?;
+                Ok(OptionArg::Require(traits.into_iter().collect()))
+            }
+            "metrics" => {
+                if cfg!(not(feature = "metrics")) {
+                    return Err(syn::Error::new(
+                        ident.span(),
+                        "the metrics option requires the metrics feature of trait-union",
+                    ));
+                }
+                Ok(OptionArg::Metrics(ident.span()))
+            }
+            "new_vis" => {
+                let _t_equals = input.parse::<Token![=]>()?;
+                let value = input.parse::<LitStr>()?;
+                Ok(OptionArg::NewVis(value.parse()?))
+            }
+            "no_deref" => Ok(OptionArg::NoDeref),
+            "non_exhaustive" => Ok(OptionArg::NonExhaustive),
+            "no_implicit_static" => Ok(OptionArg::NoImplicitStatic(ident.span())),
+            "schema" => Ok(OptionArg::Schema),
+            "forward" => {
+                let content;
+                parenthesized!(content in input);
+                let traits = Punctuated::<Path, Token![,]>::parse_terminated(&content)?;
+                Ok(OptionArg::Forward(traits.into_iter().collect()))
+            }
+            "dispatcher" => {
+                let content;
+                parenthesized!(content in input);
+                Ok(OptionArg::Dispatcher(content.parse()?))
+            }
+            "manual_impl" => {
+                let content;
+                parenthesized!(content in input);
+                let types = Punctuated::<Type, Token![,]>::parse_terminated(&content)?;
+                Ok(OptionArg::ManualImpl(types.into_iter().collect()))
+            }
+            "box_error" => {
+                if cfg!(not(feature = "alloc")) {
+                    return Err(syn::Error::new(
+                        ident.span(),
+                        "the box_error option requires the alloc feature of trait-union",
+                    ));
+                }
+                Ok(OptionArg::BoxError(ident.span()))
+            }
+            "cell" => Ok(OptionArg::Cell(ident.span())),
+            "reborrow" => Ok(OptionArg::Reborrow(ident.span())),
+            "visitor" => Ok(OptionArg::Visitor),
+            "external" => Ok(OptionArg::External),
+            "enum_dispatch" => {
+                let content;
+                parenthesized!(content in input);
+                let mut enum_dispatch = content.parse::<EnumDispatch>()?;
+                enum_dispatch.span = ident.span();
+                Ok(OptionArg::EnumDispatch(enum_dispatch))
+            }
+            "send" => Ok(OptionArg::Send),
+            "enums" => Ok(OptionArg::Enums),
+            _ => Err(unknown_option(&ident, "trait_union option", OPTION_NAMES)),
+        }
+    }
+}
+
+impl Options {
+    /// Removes all `#[trait_union(...)]` attributes from `attrs` and parses them
+    pub fn extract(attrs: &mut Vec<Attribute>) -> syn::Result<Self> {
+        let mut options = Options::default();
+        extract_args(attrs, REPEATABLE_OPTION_NAMES, |arg| match arg {
+            OptionArg::AbiCheck(hash) => options.abi_check = Some(hash),
+            OptionArg::ReportSize(size) => options.report_size = Some(size),
+            OptionArg::Pod(span) => options.pod = Some(span),
+            OptionArg::Impl(impl_) => options.impl_ = impl_,
+            OptionArg::Inline(inline) => options.inline = inline,
+            OptionArg::Tag(tag) => options.tag = tag,
+            OptionArg::Covariant(lifetimes) => options.covariant.extend(lifetimes),
+            OptionArg::Invariant(params) => options.invariant.extend(params),
+            OptionArg::Phantom(ty) => options.phantom.push(ty),
+            OptionArg::Constructors => options.constructors = true,
+            OptionArg::Atomic(span) => options.atomic = Some(span),
+            OptionArg::DropTests(span) => options.drop_tests = Some(span),
+            OptionArg::LiveCounts(span) => options.live_counts = Some(span),
+            OptionArg::Open(open) => options.open = Some(open),
+            OptionArg::Require(traits) => options.require.extend(traits),
+            OptionArg::Metrics(span) => options.metrics = Some(span),
+            OptionArg::NewVis(vis) => options.new_vis = Some(vis),
+            OptionArg::NoDeref => options.no_deref = true,
+            OptionArg::NonExhaustive => options.non_exhaustive = true,
+            OptionArg::NoImplicitStatic(span) => options.no_implicit_static = Some(span),
+            OptionArg::Schema => options.schema = true,
+            OptionArg::Forward(traits) => options.forward.extend(traits),
+            OptionArg::Dispatcher(dispatcher) => options.dispatcher = Some(dispatcher),
+            OptionArg::ManualImpl(types) => options.manual_impl.extend(types),
+            OptionArg::BoxError(span) => options.box_error = Some(span),
+            OptionArg::Cell(span) => options.cell = Some(span),
+            OptionArg::Reborrow(span) => options.reborrow = Some(span),
+            OptionArg::Visitor => options.visitor = true,
+            OptionArg::External => options.external = true,
+            OptionArg::EnumDispatch(methods) => options.enum_dispatch = Some(methods),
+            OptionArg::Send => options.send = true,
+            OptionArg::Enums => options.enums = true,
+        })?;
+        Ok(options)
+    }
+
+    /// Returns whether no option other than `open`, `new_vis`, and `no_implicit_static`
+    /// is set
+    pub fn is_open_only(&self) -> bool {
+        self.abi_check.is_none()
+            && self.report_size.is_none()
+            && self.pod.is_none()
+            && self.impl_ == Impl::Union
+            && self.inline == Inline::Always
+            && self.tag == Tag::Index
+            && self.covariant.is_empty()
+            && self.invariant.is_empty()
+            && self.phantom.is_empty()
+            && !self.constructors
+            && self.atomic.is_none()
+            && self.drop_tests.is_none()
+            && self.live_counts.is_none()
+            && self.require.is_empty()
+            && self.metrics.is_none()
+            && !self.no_deref
+            && !self.non_exhaustive
+            && !self.schema
+            && self.forward.is_empty()
+            && self.dispatcher.is_none()
+            && self.manual_impl.is_empty()
+            && self.box_error.is_none()
+            && self.cell.is_none()
+            && self.reborrow.is_none()
+            && !self.visitor
+            && !self.external
+            && self.enum_dispatch.is_none()
+            && !self.send
+            && !self.enums
+    }
+}
+
+/// Options set with `#[trait_union(...)]` attributes on variants
+#[derive(Default)]
+pub struct VariantOptions {
+    /// `name = NAME`
+    pub name: Option<Ident>,
+    /// `flatten`
+    pub flatten: bool,
+    /// `default`
+    pub default: Option<Span>,
+    /// `provide`
+    pub provide: bool,
+    /// `forward(TRAIT, ...)`
+    pub forward: Vec<Path>,
+    /// The doc comments of the variant
+    pub docs: Vec<Attribute>,
+}
+
+const VARIANT_OPTION_NAMES: &[&str] =
+    &["name", "flatten", "default", "provide", "forward"];
+
+enum VariantOptionArg {
+    Name(Ident),
+    Flatten,
+    Default(Span),
+    Provide,
+    Forward(Vec<Path>),
+}
+
+impl Parse for VariantOptionArg {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let ident = input.call(Ident::parse_any)?;
+        match &*ident.to_string() {
+            "name" => {
+                let _t_equals = input.parse::<Token![=]>()?;
+                Ok(VariantOptionArg::Name(input.parse()?))
+            }
+            "flatten" => Ok(VariantOptionArg::Flatten),
+            "default" => Ok(VariantOptionArg::Default(ident.span())),
+            "provide" => Ok(VariantOptionArg::Provide),
+            "forward" => {
+                let content;
+                parenthesized!(content in input);
+                let traits = Punctuated::<Path, Token![,]>::parse_terminated(&content)?;
+                Ok(VariantOptionArg::Forward(traits.into_iter().collect()))
+            }
+            _ => Err(unknown_option(
+                &ident,
+                "trait_union variant option",
+                VARIANT_OPTION_NAMES,
+            )),
+        }
+    }
+}
+
+impl VariantOptions {
+    /// Removes all `#[trait_union(...)]` attributes and doc comments from `attrs` and
+    /// parses them
+    pub fn extract(attrs: &mut Vec<Attribute>) -> syn::Result<Self> {
+        let mut options = VariantOptions::default();
+        attrs.retain(|attr| {
+            if attr.path.is_ident("doc") {
+                options.docs.push(attr.clone());
+                return false;
+            }
+            true
+        });
+        extract_args(attrs, &["forward"], |arg| match arg {
+            VariantOptionArg::Name(name) => options.name = Some(name),
+            VariantOptionArg::Flatten => options.flatten = true,
+            VariantOptionArg::Default(span) => options.default = Some(span),
+            VariantOptionArg::Provide => options.provide = true,
+            VariantOptionArg::Forward(traits) => options.forward.extend(traits),
+        })?;
+        Ok(options)
+    }
+}
+
+/// An argument together with its name
+struct NamedArg<T> {
+    name: Ident,
+    arg: T,
+}
+
+impl<T: Parse> Parse for NamedArg<T> {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let name = input.fork().call(Ident::parse_any)?;
+        let arg = input.parse()?;
+        Ok(NamedArg { name, arg })
+    }
+}
+
+/// Removes all `#[trait_union(...)]` attributes from `attrs` and parses their arguments
+///
+/// Arguments whose name is not in `repeatable` must not appear more than once.
+fn extract_args<T: Parse>(
+    attrs: &mut Vec<Attribute>,
+    repeatable: &[&str],
+    mut f: impl FnMut(T),
+) -> syn::Result<()> {
+    let mut res = Ok(());
+    let mut seen = Vec::<String>::new();
+    attrs.retain(|attr| {
+        if !attr.path.is_ident("trait_union") {
+            return true;
+        }
+        if res.is_err() {
+            return false;
+        }
+        let args = match attr
+            .parse_args_with(Punctuated::<NamedArg<T>, Token![,]>::parse_terminated)
+        {
+            Ok(args) => args,
+            Err(e) => {
+                res = Err(e);
+                return false;
+            }
+        };
+        for NamedArg { name, arg } in args {
+            let name_str = name.to_string();
+            if seen.contains(&name_str) && !repeatable.contains(&&*name_str) {
+                res = Err(syn::Error::new(
+                    name.span(),
+                    format!("duplicate trait_union option `{}`", name_str),
+                ));
+                return false;
+            }
+            seen.push(name_str);
+            f(arg);
+        }
+        false
+    });
+    res
+}
+
+/// Creates the error for an unknown option, suggesting a known option with a similar name
+fn unknown_option(ident: &Ident, what: &str, known: &[&str]) -> syn::Error {
+    let name = ident.to_string();
+    let similar = known
+        .iter()
+        .map(|k| (edit_distance(&name, k), k))
+        .filter(|&(d, _)| d <= 2)
+        .min_by_key(|&(d, _)| d);
+    let msg = match similar {
+        Some((_, k)) => format!("unknown {}, did you mean `{}`?", what, k),
+        None => format!("unknown {}, expected one of `{}`", what, known.join("`, `")),
+    };
+    syn::Error::new(ident.span(), msg)
+}
+
+/// Returns the Levenshtein distance between `a` and `b`
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b = b.chars().collect::<Vec<_>>();
+    let mut prev = (0..=b.len()).collect::<Vec<_>>();
+    for (i, ca) in a.chars().enumerate() {
+        let mut cur = vec![i + 1];
+        for (j, &cb) in b.iter().enumerate() {
+            let subst = prev[j] + (ca != cb) as usize;
+            cur.push(subst.min(prev[j + 1] + 1).min(cur[j] + 1));
+        }
+        prev = cur;
+    }
+    prev[b.len()]
+}
@@ -0,0 +1,737 @@
+// Container: Display
+//     3 variants stored in a union
+//     0: i32, kind I32, tag 0
+//     1: &'static str, kind StaticStr, tag 1
+//     2: bool, kind Bool, tag 2
+use std::fmt::Display;
+/// Container can contain either an i32, a &'static str, or a bool.
+#[allow(non_snake_case)]
+struct Container {
+    __trait_union_Container_data: __trait_union_Container_Union,
+    __trait_union_Container_index: __trait_union_Container_Index,
+}
+/**Marker trait for types that can be stored in a [Container]
+
+# Safety
+
+This trait must not be implemented manually.*/
+#[allow(clippy::missing_safety_doc)]
+unsafe trait ContainerVariant: Display + 'static {
+    #[doc(hidden)]
+    const __TRAIT_UNION_INDEX: usize;
+    /// The kind of the variant
+    const KIND: ContainerKind;
+    /// The size of the variant in bytes
+    const SIZE: usize;
+    /// The name of the kind of the variant
+    const NAME: &'static str;
+}
+#[repr(C)]
+#[allow(non_snake_case)]
+union __trait_union_Container_Union {
+    variant0: ::core::mem::ManuallyDrop<i32>,
+    variant1: ::core::mem::ManuallyDrop<&'static str>,
+    variant2: ::core::mem::ManuallyDrop<bool>,
+}
+#[allow(non_camel_case_types)]
+#[derive(Copy, Clone, PartialEq, Eq)]
+enum __trait_union_Container_Index {
+    V0,
+    V1,
+    V2,
+    #[cfg(debug_assertions)]
+    Dropped,
+}
+///A reference to the active variant of a [Container]
+#[allow(dead_code)]
+enum ContainerRef<'__trait_union_ref> {
+    ///`i32`
+    I32(&'__trait_union_ref i32),
+    ///`& 'static str`
+    StaticStr(&'__trait_union_ref &'static str),
+    ///`bool`
+    Bool(&'__trait_union_ref bool),
+}
+///A mutable reference to the active variant of a [Container]
+#[allow(dead_code)]
+enum ContainerMut<'__trait_union_ref> {
+    ///`i32`
+    I32(&'__trait_union_ref mut i32),
+    ///`& 'static str`
+    StaticStr(&'__trait_union_ref mut &'static str),
+    ///`bool`
+    Bool(&'__trait_union_ref mut bool),
+}
+///The active variant of a [Container] by value
+#[allow(dead_code)]
+enum ContainerOwned {
+    ///`i32`
+    I32(i32),
+    ///`& 'static str`
+    StaticStr(&'static str),
+    ///`bool`
+    Bool(bool),
+}
+///The kinds of variants of [Container]
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+#[repr(u8)]
+enum ContainerKind {
+    ///`i32`
+    I32 = 0,
+    ///`& 'static str`
+    StaticStr = 1,
+    ///`bool`
+    Bool = 2,
+}
+impl Container {
+    const __TRAIT_UNION_TAGS: [u8; 3usize] = [0, 1, 2];
+    const __TRAIT_UNION_KINDS: [ContainerKind; 3usize] = [
+        ContainerKind::I32,
+        ContainerKind::StaticStr,
+        ContainerKind::Bool,
+    ];
+    const __TRAIT_UNION_LAYOUT: ::trait_union::LayoutInfo = ::trait_union::LayoutInfo {
+        name: "Container",
+        size: ::core::mem::size_of::<Self>(),
+        align: ::core::mem::align_of::<Self>(),
+        variants: &[
+            ::trait_union::VariantLayout {
+                name: "i32",
+                tag: 0u64,
+                size: ::core::mem::size_of::<i32>(),
+                align: ::core::mem::align_of::<i32>(),
+                offset: ::core::mem::offset_of!(Self, __trait_union_Container_data),
+            },
+            ::trait_union::VariantLayout {
+                name: "& 'static str",
+                tag: 1u64,
+                size: ::core::mem::size_of::<&'static str>(),
+                align: ::core::mem::align_of::<&'static str>(),
+                offset: ::core::mem::offset_of!(Self, __trait_union_Container_data),
+            },
+            ::trait_union::VariantLayout {
+                name: "bool",
+                tag: 2u64,
+                size: ::core::mem::size_of::<bool>(),
+                align: ::core::mem::align_of::<bool>(),
+                offset: ::core::mem::offset_of!(Self, __trait_union_Container_data),
+            },
+        ],
+    };
+    /// Whether any variant needs to be dropped
+    ///
+    /// If this is `false`, dropping a value does nothing.
+    const NEEDS_DROP: bool = false || ::core::mem::needs_drop::<i32>()
+        || ::core::mem::needs_drop::<&'static str>()
+        || ::core::mem::needs_drop::<bool>();
+    /// Returns the layout of this type
+    const fn debug_layout() -> ::trait_union::LayoutInfo {
+        Self::__TRAIT_UNION_LAYOUT
+    }
+    #[inline(always)]
+    fn __trait_union_position(&self) -> usize {
+        {
+            #[cfg(debug_assertions)]
+            {
+                if self.__trait_union_Container_index
+                    == __trait_union_Container_Index::Dropped
+                {
+                    ::core::panic!("use of a dropped Container");
+                }
+            }
+            self.__trait_union_Container_index as usize
+        }
+    }
+    /// Returns the tag of the active variant of `this`
+    #[inline(always)]
+    fn tag_of(this: &Self) -> u8 {
+        Self::__TRAIT_UNION_TAGS[this.__trait_union_position()]
+    }
+    /// Returns a reference to the active variant of `this`
+    #[inline(always)]
+    #[allow(dead_code)]
+    fn as_ref_enum<'__trait_union_ref>(
+        this: &'__trait_union_ref Self,
+    ) -> ContainerRef<'__trait_union_ref> {
+        this.__trait_union_as_ref_enum()
+    }
+    #[inline(always)]
+    fn __trait_union_as_ref_enum<'__trait_union_ref>(
+        &'__trait_union_ref self,
+    ) -> ContainerRef<'__trait_union_ref> {
+        match self.__trait_union_Container_index {
+            __trait_union_Container_Index::V0 => {
+                let value = unsafe { &*self.__trait_union_Container_data.variant0 };
+                ContainerRef::I32(value)
+            }
+            __trait_union_Container_Index::V1 => {
+                let value = unsafe { &*self.__trait_union_Container_data.variant1 };
+                ContainerRef::StaticStr(value)
+            }
+            __trait_union_Container_Index::V2 => {
+                let value = unsafe { &*self.__trait_union_Container_data.variant2 };
+                ContainerRef::Bool(value)
+            }
+            #[cfg(debug_assertions)]
+            __trait_union_Container_Index::Dropped => {
+                ::core::panic!("use of a dropped trait union")
+            }
+        }
+    }
+    /// Returns a mutable reference to the active variant of `this`
+    #[inline(always)]
+    #[allow(dead_code)]
+    fn as_mut_enum<'__trait_union_ref>(
+        this: &'__trait_union_ref mut Self,
+    ) -> ContainerMut<'__trait_union_ref> {
+        this.__trait_union_as_mut_enum()
+    }
+    #[inline(always)]
+    #[allow(dead_code)]
+    fn __trait_union_as_mut_enum<'__trait_union_ref>(
+        &'__trait_union_ref mut self,
+    ) -> ContainerMut<'__trait_union_ref> {
+        match self.__trait_union_Container_index {
+            __trait_union_Container_Index::V0 => {
+                let value = unsafe { &mut *self.__trait_union_Container_data.variant0 };
+                ContainerMut::I32(value)
+            }
+            __trait_union_Container_Index::V1 => {
+                let value = unsafe { &mut *self.__trait_union_Container_data.variant1 };
+                ContainerMut::StaticStr(value)
+            }
+            __trait_union_Container_Index::V2 => {
+                let value = unsafe { &mut *self.__trait_union_Container_data.variant2 };
+                ContainerMut::Bool(value)
+            }
+            #[cfg(debug_assertions)]
+            __trait_union_Container_Index::Dropped => {
+                ::core::panic!("use of a dropped trait union")
+            }
+        }
+    }
+    /// Moves the active variant out of `this`
+    #[inline(always)]
+    #[allow(dead_code)]
+    fn into_enum(this: Self) -> ContainerOwned {
+        let this = ::core::mem::ManuallyDrop::new(this);
+        unsafe {
+            match Self::as_ref_enum(&this) {
+                ContainerRef::I32(value) => ContainerOwned::I32(::core::ptr::read(value)),
+                ContainerRef::StaticStr(value) => {
+                    ContainerOwned::StaticStr(::core::ptr::read(value))
+                }
+                ContainerRef::Bool(value) => {
+                    ContainerOwned::Bool(::core::ptr::read(value))
+                }
+            }
+        }
+    }
+    /// Returns the kind of the active variant of `this`
+    #[inline(always)]
+    fn kind_of(this: &Self) -> ContainerKind {
+        Self::__TRAIT_UNION_KINDS[this.__trait_union_position()]
+    }
+    /// Returns whether `this` and `other` have the same active variant
+    #[inline(always)]
+    fn same_variant(this: &Self, other: &Self) -> bool {
+        this.__trait_union_position() == other.__trait_union_position()
+    }
+    /// Writes the kind of the active variant of `this` followed by its value in
+    /// parentheses
+    ///
+    /// The value is formatted with `Display` if its type implements it, otherwise
+    /// with `Debug`, and otherwise written as `..`. Nothing is allocated.
+    #[inline(always)]
+    fn describe(this: &Self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+        this.__trait_union_describe(f)
+    }
+    #[inline(always)]
+    fn __trait_union_describe(
+        &self,
+        f: &mut ::core::fmt::Formatter<'_>,
+    ) -> ::core::fmt::Result {
+        #[allow(unused_imports)]
+        use ::trait_union::__support::{DescribeDebug, DescribeDisplay, DescribeOther};
+        ::core::fmt::Debug::fmt(&Self::kind_of(self), f)?;
+        f.write_str("(")?;
+        (match self.__trait_union_Container_index {
+            __trait_union_Container_Index::V0 => {
+                let value = unsafe { &*self.__trait_union_Container_data.variant0 };
+                (&&&::trait_union::__support::Describe(value)).describe_value(f)
+            }
+            __trait_union_Container_Index::V1 => {
+                let value = unsafe { &*self.__trait_union_Container_data.variant1 };
+                (&&&::trait_union::__support::Describe(value)).describe_value(f)
+            }
+            __trait_union_Container_Index::V2 => {
+                let value = unsafe { &*self.__trait_union_Container_data.variant2 };
+                (&&&::trait_union::__support::Describe(value)).describe_value(f)
+            }
+            #[cfg(debug_assertions)]
+            __trait_union_Container_Index::Dropped => {
+                ::core::panic!("use of a dropped trait union")
+            }
+        })?;
+        f.write_str(")")
+    }
+    /// Sorts `values` by the declaration order of their active variants
+    ///
+    /// The sort is unstable.
+    #[inline]
+    fn sort_by_kind(values: &mut [Self]) {
+        values.sort_unstable_by_key(Self::__trait_union_position);
+    }
+    /// Returns an iterator over the maximal runs of values of the same kind
+    #[inline]
+    fn kind_runs(values: &[Self]) -> ::trait_union::KindRuns<'_, Self, ContainerKind> {
+        ::trait_union::KindRuns::new(values, Self::kind_of)
+    }
+    /// Returns whether the active variant of `this` is a zero-sized type
+    ///
+    /// The bytes of a zero-sized variant do not have to be copied.
+    #[inline(always)]
+    fn is_zst_variant(this: &Self) -> bool {
+        Self::__TRAIT_UNION_LAYOUT.variants[this.__trait_union_position()].size == 0
+    }
+    /// Creates a new instance
+    #[inline(always)]
+    fn new<V: ContainerVariant>(value: V) -> Self {
+        unsafe { Self::__trait_union_new(V::__TRAIT_UNION_INDEX, value) }
+    }
+    /// Creates a new instance of the variant of kind `kind`
+    ///
+    /// # Safety
+    ///
+    /// `V` must be the type of the variant of kind `kind`.
+    #[inline(always)]
+    unsafe fn new_unchecked<V>(kind: ContainerKind, value: V) -> Self {
+        #[cfg(debug_assertions)]
+        {
+            let pos = match kind {
+                ContainerKind::I32 => 0usize,
+                ContainerKind::StaticStr => 1usize,
+                ContainerKind::Bool => 2usize,
+            };
+            let layout = &Self::__TRAIT_UNION_LAYOUT.variants[pos];
+            if ::core::mem::size_of::<V>() != layout.size
+                || ::core::mem::align_of::<V>() != layout.align
+            {
+                ::core::panic!("`V` is not the type of the variant of kind `kind`");
+            }
+        }
+        let value = ::core::mem::ManuallyDrop::new(value);
+        unsafe { Self::new_raw(kind, &*value as *const V as *const u8) }
+    }
+    /// Creates a new instance of the variant of kind `kind` by reading its value from
+    /// `data`
+    ///
+    /// `data` does not have to be aligned.
+    ///
+    /// # Safety
+    ///
+    /// `data` must point to a valid value of the type of the variant of kind `kind`.
+    /// The value is moved out of `data`.
+    #[inline]
+    unsafe fn new_raw(kind: ContainerKind, data: *const u8) -> Self {
+        match kind {
+            ContainerKind::I32 => {
+                let value = unsafe { ::core::ptr::read_unaligned(data as *const i32) };
+                unsafe { Self::__trait_union_new(0usize, value) }
+            }
+            ContainerKind::StaticStr => {
+                let value = unsafe {
+                    ::core::ptr::read_unaligned(data as *const &'static str)
+                };
+                unsafe { Self::__trait_union_new(1usize, value) }
+            }
+            ContainerKind::Bool => {
+                let value = unsafe { ::core::ptr::read_unaligned(data as *const bool) };
+                unsafe { Self::__trait_union_new(2usize, value) }
+            }
+        }
+    }
+    /// Creates a new instance from the moved-out active variant
+    #[inline]
+    #[allow(dead_code)]
+    fn from_enum(value: ContainerOwned) -> Self {
+        match value {
+            ContainerOwned::I32(value) => {
+                let value = ::core::mem::ManuallyDrop::new(value);
+                unsafe {
+                    Self::new_raw(ContainerKind::I32, &*value as *const _ as *const u8)
+                }
+            }
+            ContainerOwned::StaticStr(value) => {
+                let value = ::core::mem::ManuallyDrop::new(value);
+                unsafe {
+                    Self::new_raw(
+                        ContainerKind::StaticStr,
+                        &*value as *const _ as *const u8,
+                    )
+                }
+            }
+            ContainerOwned::Bool(value) => {
+                let value = ::core::mem::ManuallyDrop::new(value);
+                unsafe {
+                    Self::new_raw(ContainerKind::Bool, &*value as *const _ as *const u8)
+                }
+            }
+        }
+    }
+    /// Replaces the value by the result of applying `f` to the active variant
+    ///
+    /// The process is aborted if `f` panics.
+    #[inline]
+    #[allow(dead_code)]
+    fn transition(
+        this: &mut Self,
+        f: impl ::core::ops::FnOnce(ContainerOwned) -> ContainerOwned,
+    ) {
+        let guard = ::trait_union::__support::AbortOnUnwind;
+        unsafe {
+            let value = Self::into_enum(::core::ptr::read(this));
+            ::core::ptr::write(this, Self::from_enum(f(value)));
+        }
+        ::core::mem::forget(guard);
+    }
+    /// Moves the active variant into another union with a variant of its type
+    ///
+    /// The value is returned unchanged if the other union has no such variant.
+    #[inline]
+    fn move_variant<__TraitUnionTarget: ::trait_union::__support::AcceptVariant>(
+        self,
+    ) -> ::core::result::Result<__TraitUnionTarget, Self> {
+        match Self::into_enum(self) {
+            ContainerOwned::I32(value) => {
+                let value = ::core::mem::ManuallyDrop::new(value);
+                let id = ::core::any::TypeId::of::<i32>();
+                match unsafe {
+                    __TraitUnionTarget::__trait_union_accept(
+                        id,
+                        &*value as *const _ as *const u8,
+                    )
+                } {
+                    ::core::option::Option::Some(target) => {
+                        ::core::result::Result::Ok(target)
+                    }
+                    ::core::option::Option::None => {
+                        let value = ::core::mem::ManuallyDrop::into_inner(value);
+                        ::core::result::Result::Err(
+                            Self::from_enum(ContainerOwned::I32(value)),
+                        )
+                    }
+                }
+            }
+            ContainerOwned::StaticStr(value) => {
+                let value = ::core::mem::ManuallyDrop::new(value);
+                let id = ::core::any::TypeId::of::<&'static str>();
+                match unsafe {
+                    __TraitUnionTarget::__trait_union_accept(
+                        id,
+                        &*value as *const _ as *const u8,
+                    )
+                } {
+                    ::core::option::Option::Some(target) => {
+                        ::core::result::Result::Ok(target)
+                    }
+                    ::core::option::Option::None => {
+                        let value = ::core::mem::ManuallyDrop::into_inner(value);
+                        ::core::result::Result::Err(
+                            Self::from_enum(ContainerOwned::StaticStr(value)),
+                        )
+                    }
+                }
+            }
+            ContainerOwned::Bool(value) => {
+                let value = ::core::mem::ManuallyDrop::new(value);
+                let id = ::core::any::TypeId::of::<bool>();
+                match unsafe {
+                    __TraitUnionTarget::__trait_union_accept(
+                        id,
+                        &*value as *const _ as *const u8,
+                    )
+                } {
+                    ::core::option::Option::Some(target) => {
+                        ::core::result::Result::Ok(target)
+                    }
+                    ::core::option::Option::None => {
+                        let value = ::core::mem::ManuallyDrop::into_inner(value);
+                        ::core::result::Result::Err(
+                            Self::from_enum(ContainerOwned::Bool(value)),
+                        )
+                    }
+                }
+            }
+        }
+    }
+}
+impl ::core::ops::Deref for Container {
+    type Target = dyn Display + 'static;
+    #[inline(always)]
+    fn deref(&self) -> &Self::Target {
+        unsafe { ::core::mem::transmute(self.__trait_union_to_trait_object()) }
+    }
+}
+impl ::core::ops::DerefMut for Container {
+    #[inline(always)]
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        unsafe { ::core::mem::transmute(self.__trait_union_to_trait_object_mut()) }
+    }
+}
+const _: () = {
+    impl ::core::ops::Drop for Container {
+        #[inline(always)]
+        fn drop(&mut self) {
+            if Self::NEEDS_DROP {
+                if Self::__TRAIT_UNION_NEEDS_DROP[{
+                    #[cfg(debug_assertions)]
+                    {
+                        if self.__trait_union_Container_index
+                            == __trait_union_Container_Index::Dropped
+                        {
+                            ::core::panic!("use of a dropped Container");
+                        }
+                    }
+                    self.__trait_union_Container_index as usize
+                }]
+                {
+                    unsafe {
+                        let t: &mut (dyn Display + 'static) = ::core::mem::transmute(
+                            self.__trait_union_to_trait_object_mut(),
+                        );
+                        ::core::ptr::drop_in_place(t);
+                    }
+                }
+            }
+            #[cfg(debug_assertions)]
+            {
+                self
+                    .__trait_union_Container_index = __trait_union_Container_Index::Dropped;
+            }
+        }
+    }
+    impl Container {
+        const __TRAIT_UNION_NEEDS_DROP: [bool; 3usize] = [
+            ::core::mem::needs_drop::<i32>(),
+            ::core::mem::needs_drop::<&'static str>(),
+            ::core::mem::needs_drop::<bool>(),
+        ];
+        const __TRAIT_UNION_VTABLES: [::trait_union::__support::VtablePtr; 3usize] = [
+            unsafe {
+                ::trait_union::__support::VtablePtr::of(
+                    ::core::ptr::null::<i32>() as *const (dyn Display + '_),
+                )
+            },
+            unsafe {
+                ::trait_union::__support::VtablePtr::of(
+                    ::core::ptr::null::<&'static str>() as *const (dyn Display + '_),
+                )
+            },
+            unsafe {
+                ::trait_union::__support::VtablePtr::of(
+                    ::core::ptr::null::<bool>() as *const (dyn Display + '_),
+                )
+            },
+        ];
+        #[inline(always)]
+        fn __trait_union_to_trait_object(
+            &self,
+        ) -> ::trait_union::__support::RawTraitObject {
+            let data = &self.__trait_union_Container_data as *const _ as *mut ();
+            #[cfg(debug_assertions)]
+            ::trait_union::__support::check_aligned(
+                data,
+                Self::__TRAIT_UNION_LAYOUT.variants[self.__trait_union_position()].align,
+                "misaligned Container",
+            );
+            ::trait_union::__support::RawTraitObject {
+                data,
+                vtable: Self::__TRAIT_UNION_VTABLES[self.__trait_union_Container_index
+                    as usize],
+            }
+        }
+        #[inline(always)]
+        fn __trait_union_to_trait_object_mut(
+            &mut self,
+        ) -> ::trait_union::__support::RawTraitObject {
+            let data = &mut self.__trait_union_Container_data as *mut _ as *mut ();
+            #[cfg(debug_assertions)]
+            ::trait_union::__support::check_aligned(
+                data,
+                Self::__TRAIT_UNION_LAYOUT.variants[self.__trait_union_position()].align,
+                "misaligned Container",
+            );
+            ::trait_union::__support::RawTraitObject {
+                data,
+                vtable: Self::__TRAIT_UNION_VTABLES[self.__trait_union_Container_index
+                    as usize],
+            }
+        }
+        #[inline(always)]
+        const fn __trait_union_index(index: usize) -> __trait_union_Container_Index {
+            match index {
+                0usize => __trait_union_Container_Index::V0,
+                1usize => __trait_union_Container_Index::V1,
+                2usize => __trait_union_Container_Index::V2,
+                _ => ::core::panic!("invalid variant index"),
+            }
+        }
+        /// Creates a new instance with the variant at position `index`
+        ///
+        /// # Safety
+        ///
+        /// `V` must be the type of the variant at position `index`.
+        #[inline(always)]
+        unsafe fn __trait_union_new<V>(index: usize, value: V) -> Self {
+            #[cfg(debug_assertions)]
+            ::trait_union::__support::check_variant::<
+                V,
+            >(
+                &Self::__TRAIT_UNION_LAYOUT,
+                index,
+                "invalid implementation of ContainerVariant",
+            );
+            let mut slf = ::core::mem::MaybeUninit::<Self>::uninit();
+            unsafe {
+                if ::core::mem::size_of::<V>() == 0 {
+                    ::core::mem::forget(value);
+                } else {
+                    ::core::ptr::write(
+                        &mut (*slf.as_mut_ptr()).__trait_union_Container_data as *mut _
+                            as *mut V,
+                        value,
+                    );
+                }
+                (*slf.as_mut_ptr())
+                    .__trait_union_Container_index = Self::__trait_union_index(index);
+                slf.assume_init()
+            }
+        }
+    }
+    impl<'__trait_union_pin> ::core::marker::Unpin for Container
+    where
+        ::core::marker::PhantomData<
+            (&'__trait_union_pin (), i32, &'static str, bool),
+        >: ::core::marker::Unpin,
+    {}
+    unsafe impl ::trait_union::__support::AcceptVariant for Container {
+        #[inline]
+        unsafe fn __trait_union_accept(
+            id: ::core::any::TypeId,
+            data: *const u8,
+        ) -> ::core::option::Option<Self> {
+            if id == ::core::any::TypeId::of::<i32>() {
+                return ::core::option::Option::Some(unsafe {
+                    Self::new_raw(ContainerKind::I32, data)
+                });
+            }
+            if id == ::core::any::TypeId::of::<&'static str>() {
+                return ::core::option::Option::Some(unsafe {
+                    Self::new_raw(ContainerKind::StaticStr, data)
+                });
+            }
+            if id == ::core::any::TypeId::of::<bool>() {
+                return ::core::option::Option::Some(unsafe {
+                    Self::new_raw(ContainerKind::Bool, data)
+                });
+            }
+            ::core::option::Option::None
+        }
+    }
+    impl ::trait_union::Provide for Container {
+        fn provide<'__trait_union_provide>(
+            &'__trait_union_provide self,
+            request: &mut ::trait_union::Request<'__trait_union_provide>,
+        ) {
+            match self.__trait_union_Container_index {
+                __trait_union_Container_Index::V0 => {
+                    let value = unsafe { &*self.__trait_union_Container_data.variant0 };
+                    {
+                        let _ = value;
+                    }
+                }
+                __trait_union_Container_Index::V1 => {
+                    let value = unsafe { &*self.__trait_union_Container_data.variant1 };
+                    {
+                        let _ = value;
+                    }
+                }
+                __trait_union_Container_Index::V2 => {
+                    let value = unsafe { &*self.__trait_union_Container_data.variant2 };
+                    {
+                        let _ = value;
+                    }
+                }
+                #[cfg(debug_assertions)]
+                __trait_union_Container_Index::Dropped => {
+                    ::core::panic!("use of a dropped trait union")
+                }
+            }
+        }
+    }
+    impl<
+        __TraitUnionVariant: ContainerVariant,
+    > ::trait_union::FromVariant<__TraitUnionVariant> for Container {
+        #[inline(always)]
+        fn from_variant(value: __TraitUnionVariant) -> Self {
+            Self::new(value)
+        }
+    }
+    impl ::core::convert::From<ContainerOwned> for Container {
+        #[inline]
+        fn from(value: ContainerOwned) -> Self {
+            Self::from_enum(value)
+        }
+    }
+    impl ::core::convert::From<Container> for ContainerOwned {
+        #[inline]
+        fn from(value: Container) -> Self {
+            <Container>::into_enum(value)
+        }
+    }
+    impl<
+        '__trait_union_ref,
+        '__trait_union_object,
+    > ::core::convert::From<&'__trait_union_ref Container>
+    for &'__trait_union_ref (dyn Display + '__trait_union_object)
+    where
+        'static: '__trait_union_object,
+    {
+        #[inline(always)]
+        fn from(value: &'__trait_union_ref Container) -> Self {
+            &**value
+        }
+    }
+    impl<
+        '__trait_union_ref,
+        '__trait_union_object,
+    > ::core::convert::From<&'__trait_union_ref mut Container>
+    for &'__trait_union_ref mut (dyn Display + '__trait_union_object)
+    where
+        'static: '__trait_union_object,
+    {
+        #[inline(always)]
+        fn from(value: &'__trait_union_ref mut Container) -> Self {
+            &mut **value
+        }
+    }
+    unsafe impl ContainerVariant for i32 {
+        const __TRAIT_UNION_INDEX: usize = 0usize;
+        const KIND: ContainerKind = ContainerKind::I32;
+        const SIZE: usize = ::core::mem::size_of::<Self>();
+        const NAME: &'static str = "I32";
+    }
+    unsafe impl ContainerVariant for &'static str {
+        const __TRAIT_UNION_INDEX: usize = 1usize;
+        const KIND: ContainerKind = ContainerKind::StaticStr;
+        const SIZE: usize = ::core::mem::size_of::<Self>();
+        const NAME: &'static str = "StaticStr";
+    }
+    unsafe impl ContainerVariant for bool {
+        const __TRAIT_UNION_INDEX: usize = 2usize;
+        const KIND: ContainerKind = ContainerKind::Bool;
+        const SIZE: usize = ::core::mem::size_of::<Self>();
+        const NAME: &'static str = "Bool";
+    }
+};
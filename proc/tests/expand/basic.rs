@@ -0,0 +1,6 @@
+use std::fmt::Display;
+
+trait_union! {
+    /// Container can contain either an i32, a &'static str, or a bool.
+    union Container: Display = i32 | &'static str | bool;
+}
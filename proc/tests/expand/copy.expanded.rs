@@ -0,0 +1,611 @@
+// C: Debug
+//     2 variants stored in a union
+//     0: u8, kind U8, tag 0
+//     1: &'static str, kind StaticStr, tag 1
+use std::fmt::Debug;
+#[derive(Copy, Clone)]
+#[allow(non_snake_case)]
+struct C {
+    __trait_union_C_data: __trait_union_C_Union,
+    __trait_union_C_index: __trait_union_C_Index,
+}
+/**Marker trait for types that can be stored in a [C]
+
+# Safety
+
+This trait must not be implemented manually.*/
+#[allow(clippy::missing_safety_doc)]
+unsafe trait CVariant: Debug + 'static {
+    #[doc(hidden)]
+    const __TRAIT_UNION_INDEX: usize;
+    /// The kind of the variant
+    const KIND: CKind;
+    /// The size of the variant in bytes
+    const SIZE: usize;
+    /// The name of the kind of the variant
+    const NAME: &'static str;
+}
+#[repr(C)]
+#[allow(non_snake_case)]
+union __trait_union_C_Union {
+    variant0: ::core::mem::ManuallyDrop<u8>,
+    variant1: ::core::mem::ManuallyDrop<&'static str>,
+}
+#[allow(non_camel_case_types)]
+#[derive(Copy, Clone, PartialEq, Eq)]
+enum __trait_union_C_Index {
+    V0,
+    V1,
+    #[cfg(debug_assertions)]
+    Dropped,
+}
+///A reference to the active variant of a [C]
+#[allow(dead_code)]
+enum CRef<'__trait_union_ref> {
+    ///`u8`
+    U8(&'__trait_union_ref u8),
+    ///`& 'static str`
+    StaticStr(&'__trait_union_ref &'static str),
+}
+///A mutable reference to the active variant of a [C]
+#[allow(dead_code)]
+enum CMut<'__trait_union_ref> {
+    ///`u8`
+    U8(&'__trait_union_ref mut u8),
+    ///`& 'static str`
+    StaticStr(&'__trait_union_ref mut &'static str),
+}
+///The active variant of a [C] by value
+#[allow(dead_code)]
+enum COwned {
+    ///`u8`
+    U8(u8),
+    ///`& 'static str`
+    StaticStr(&'static str),
+}
+///The kinds of variants of [C]
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+#[repr(u8)]
+enum CKind {
+    ///`u8`
+    U8 = 0,
+    ///`& 'static str`
+    StaticStr = 1,
+}
+impl C {
+    const __TRAIT_UNION_TAGS: [u8; 2usize] = [0, 1];
+    const __TRAIT_UNION_KINDS: [CKind; 2usize] = [CKind::U8, CKind::StaticStr];
+    const __TRAIT_UNION_LAYOUT: ::trait_union::LayoutInfo = ::trait_union::LayoutInfo {
+        name: "C",
+        size: ::core::mem::size_of::<Self>(),
+        align: ::core::mem::align_of::<Self>(),
+        variants: &[
+            ::trait_union::VariantLayout {
+                name: "u8",
+                tag: 0u64,
+                size: ::core::mem::size_of::<u8>(),
+                align: ::core::mem::align_of::<u8>(),
+                offset: ::core::mem::offset_of!(Self, __trait_union_C_data),
+            },
+            ::trait_union::VariantLayout {
+                name: "& 'static str",
+                tag: 1u64,
+                size: ::core::mem::size_of::<&'static str>(),
+                align: ::core::mem::align_of::<&'static str>(),
+                offset: ::core::mem::offset_of!(Self, __trait_union_C_data),
+            },
+        ],
+    };
+    /// Whether any variant needs to be dropped
+    ///
+    /// If this is `false`, dropping a value does nothing.
+    const NEEDS_DROP: bool = false || ::core::mem::needs_drop::<u8>()
+        || ::core::mem::needs_drop::<&'static str>();
+    /// Returns the layout of this type
+    const fn debug_layout() -> ::trait_union::LayoutInfo {
+        Self::__TRAIT_UNION_LAYOUT
+    }
+    #[inline(always)]
+    fn __trait_union_position(&self) -> usize {
+        {
+            #[cfg(debug_assertions)]
+            {
+                if self.__trait_union_C_index == __trait_union_C_Index::Dropped {
+                    ::core::panic!("use of a dropped C");
+                }
+            }
+            self.__trait_union_C_index as usize
+        }
+    }
+    /// Returns the tag of the active variant of `this`
+    #[inline(always)]
+    fn tag_of(this: &Self) -> u8 {
+        Self::__TRAIT_UNION_TAGS[this.__trait_union_position()]
+    }
+    /// Returns a reference to the active variant of `this`
+    #[inline(always)]
+    #[allow(dead_code)]
+    fn as_ref_enum<'__trait_union_ref>(
+        this: &'__trait_union_ref Self,
+    ) -> CRef<'__trait_union_ref> {
+        this.__trait_union_as_ref_enum()
+    }
+    #[inline(always)]
+    fn __trait_union_as_ref_enum<'__trait_union_ref>(
+        &'__trait_union_ref self,
+    ) -> CRef<'__trait_union_ref> {
+        match self.__trait_union_C_index {
+            __trait_union_C_Index::V0 => {
+                let value = unsafe { &*self.__trait_union_C_data.variant0 };
+                CRef::U8(value)
+            }
+            __trait_union_C_Index::V1 => {
+                let value = unsafe { &*self.__trait_union_C_data.variant1 };
+                CRef::StaticStr(value)
+            }
+            #[cfg(debug_assertions)]
+            __trait_union_C_Index::Dropped => {
+                ::core::panic!("use of a dropped trait union")
+            }
+        }
+    }
+    /// Returns a mutable reference to the active variant of `this`
+    #[inline(always)]
+    #[allow(dead_code)]
+    fn as_mut_enum<'__trait_union_ref>(
+        this: &'__trait_union_ref mut Self,
+    ) -> CMut<'__trait_union_ref> {
+        this.__trait_union_as_mut_enum()
+    }
+    #[inline(always)]
+    #[allow(dead_code)]
+    fn __trait_union_as_mut_enum<'__trait_union_ref>(
+        &'__trait_union_ref mut self,
+    ) -> CMut<'__trait_union_ref> {
+        match self.__trait_union_C_index {
+            __trait_union_C_Index::V0 => {
+                let value = unsafe { &mut *self.__trait_union_C_data.variant0 };
+                CMut::U8(value)
+            }
+            __trait_union_C_Index::V1 => {
+                let value = unsafe { &mut *self.__trait_union_C_data.variant1 };
+                CMut::StaticStr(value)
+            }
+            #[cfg(debug_assertions)]
+            __trait_union_C_Index::Dropped => {
+                ::core::panic!("use of a dropped trait union")
+            }
+        }
+    }
+    /// Moves the active variant out of `this`
+    #[inline(always)]
+    #[allow(dead_code)]
+    fn into_enum(this: Self) -> COwned {
+        let this = ::core::mem::ManuallyDrop::new(this);
+        unsafe {
+            match Self::as_ref_enum(&this) {
+                CRef::U8(value) => COwned::U8(::core::ptr::read(value)),
+                CRef::StaticStr(value) => COwned::StaticStr(::core::ptr::read(value)),
+            }
+        }
+    }
+    /// Returns the kind of the active variant of `this`
+    #[inline(always)]
+    fn kind_of(this: &Self) -> CKind {
+        Self::__TRAIT_UNION_KINDS[this.__trait_union_position()]
+    }
+    /// Returns whether `this` and `other` have the same active variant
+    #[inline(always)]
+    fn same_variant(this: &Self, other: &Self) -> bool {
+        this.__trait_union_position() == other.__trait_union_position()
+    }
+    /// Writes the kind of the active variant of `this` followed by its value in
+    /// parentheses
+    ///
+    /// The value is formatted with `Display` if its type implements it, otherwise
+    /// with `Debug`, and otherwise written as `..`. Nothing is allocated.
+    #[inline(always)]
+    fn describe(this: &Self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+        this.__trait_union_describe(f)
+    }
+    #[inline(always)]
+    fn __trait_union_describe(
+        &self,
+        f: &mut ::core::fmt::Formatter<'_>,
+    ) -> ::core::fmt::Result {
+        #[allow(unused_imports)]
+        use ::trait_union::__support::{DescribeDebug, DescribeDisplay, DescribeOther};
+        ::core::fmt::Debug::fmt(&Self::kind_of(self), f)?;
+        f.write_str("(")?;
+        (match self.__trait_union_C_index {
+            __trait_union_C_Index::V0 => {
+                let value = unsafe { &*self.__trait_union_C_data.variant0 };
+                (&&&::trait_union::__support::Describe(value)).describe_value(f)
+            }
+            __trait_union_C_Index::V1 => {
+                let value = unsafe { &*self.__trait_union_C_data.variant1 };
+                (&&&::trait_union::__support::Describe(value)).describe_value(f)
+            }
+            #[cfg(debug_assertions)]
+            __trait_union_C_Index::Dropped => {
+                ::core::panic!("use of a dropped trait union")
+            }
+        })?;
+        f.write_str(")")
+    }
+    /// Sorts `values` by the declaration order of their active variants
+    ///
+    /// The sort is unstable.
+    #[inline]
+    fn sort_by_kind(values: &mut [Self]) {
+        values.sort_unstable_by_key(Self::__trait_union_position);
+    }
+    /// Returns an iterator over the maximal runs of values of the same kind
+    #[inline]
+    fn kind_runs(values: &[Self]) -> ::trait_union::KindRuns<'_, Self, CKind> {
+        ::trait_union::KindRuns::new(values, Self::kind_of)
+    }
+    /// Returns whether the active variant of `this` is a zero-sized type
+    ///
+    /// The bytes of a zero-sized variant do not have to be copied.
+    #[inline(always)]
+    fn is_zst_variant(this: &Self) -> bool {
+        Self::__TRAIT_UNION_LAYOUT.variants[this.__trait_union_position()].size == 0
+    }
+    /// Creates a new instance
+    #[inline(always)]
+    fn new<V: CVariant>(value: V) -> Self {
+        unsafe { Self::__trait_union_new(V::__TRAIT_UNION_INDEX, value) }
+    }
+    /// Creates a new instance of the variant of kind `kind`
+    ///
+    /// # Safety
+    ///
+    /// `V` must be the type of the variant of kind `kind`.
+    #[inline(always)]
+    unsafe fn new_unchecked<V>(kind: CKind, value: V) -> Self {
+        #[cfg(debug_assertions)]
+        {
+            let pos = match kind {
+                CKind::U8 => 0usize,
+                CKind::StaticStr => 1usize,
+            };
+            let layout = &Self::__TRAIT_UNION_LAYOUT.variants[pos];
+            if ::core::mem::size_of::<V>() != layout.size
+                || ::core::mem::align_of::<V>() != layout.align
+            {
+                ::core::panic!("`V` is not the type of the variant of kind `kind`");
+            }
+        }
+        let value = ::core::mem::ManuallyDrop::new(value);
+        unsafe { Self::new_raw(kind, &*value as *const V as *const u8) }
+    }
+    /// Creates a new instance of the variant of kind `kind` by reading its value from
+    /// `data`
+    ///
+    /// `data` does not have to be aligned.
+    ///
+    /// # Safety
+    ///
+    /// `data` must point to a valid value of the type of the variant of kind `kind`.
+    /// The value is moved out of `data`.
+    #[inline]
+    unsafe fn new_raw(kind: CKind, data: *const u8) -> Self {
+        match kind {
+            CKind::U8 => {
+                let value = unsafe { ::core::ptr::read_unaligned(data as *const u8) };
+                unsafe { Self::__trait_union_new(0usize, value) }
+            }
+            CKind::StaticStr => {
+                let value = unsafe {
+                    ::core::ptr::read_unaligned(data as *const &'static str)
+                };
+                unsafe { Self::__trait_union_new(1usize, value) }
+            }
+        }
+    }
+    /// Creates a new instance from the moved-out active variant
+    #[inline]
+    #[allow(dead_code)]
+    fn from_enum(value: COwned) -> Self {
+        match value {
+            COwned::U8(value) => {
+                let value = ::core::mem::ManuallyDrop::new(value);
+                unsafe { Self::new_raw(CKind::U8, &*value as *const _ as *const u8) }
+            }
+            COwned::StaticStr(value) => {
+                let value = ::core::mem::ManuallyDrop::new(value);
+                unsafe {
+                    Self::new_raw(CKind::StaticStr, &*value as *const _ as *const u8)
+                }
+            }
+        }
+    }
+    /// Replaces the value by the result of applying `f` to the active variant
+    ///
+    /// The process is aborted if `f` panics.
+    #[inline]
+    #[allow(dead_code)]
+    fn transition(this: &mut Self, f: impl ::core::ops::FnOnce(COwned) -> COwned) {
+        let guard = ::trait_union::__support::AbortOnUnwind;
+        unsafe {
+            let value = Self::into_enum(::core::ptr::read(this));
+            ::core::ptr::write(this, Self::from_enum(f(value)));
+        }
+        ::core::mem::forget(guard);
+    }
+    /// Moves the active variant into another union with a variant of its type
+    ///
+    /// The value is returned unchanged if the other union has no such variant.
+    #[inline]
+    fn move_variant<__TraitUnionTarget: ::trait_union::__support::AcceptVariant>(
+        self,
+    ) -> ::core::result::Result<__TraitUnionTarget, Self> {
+        match Self::into_enum(self) {
+            COwned::U8(value) => {
+                let value = ::core::mem::ManuallyDrop::new(value);
+                let id = ::core::any::TypeId::of::<u8>();
+                match unsafe {
+                    __TraitUnionTarget::__trait_union_accept(
+                        id,
+                        &*value as *const _ as *const u8,
+                    )
+                } {
+                    ::core::option::Option::Some(target) => {
+                        ::core::result::Result::Ok(target)
+                    }
+                    ::core::option::Option::None => {
+                        let value = ::core::mem::ManuallyDrop::into_inner(value);
+                        ::core::result::Result::Err(Self::from_enum(COwned::U8(value)))
+                    }
+                }
+            }
+            COwned::StaticStr(value) => {
+                let value = ::core::mem::ManuallyDrop::new(value);
+                let id = ::core::any::TypeId::of::<&'static str>();
+                match unsafe {
+                    __TraitUnionTarget::__trait_union_accept(
+                        id,
+                        &*value as *const _ as *const u8,
+                    )
+                } {
+                    ::core::option::Option::Some(target) => {
+                        ::core::result::Result::Ok(target)
+                    }
+                    ::core::option::Option::None => {
+                        let value = ::core::mem::ManuallyDrop::into_inner(value);
+                        ::core::result::Result::Err(
+                            Self::from_enum(COwned::StaticStr(value)),
+                        )
+                    }
+                }
+            }
+        }
+    }
+    /// Returns a copy of the value in `cell`
+    ///
+    /// Unlike `Cell::get`, this does not require the struct to implement `Copy`.
+    #[inline(always)]
+    fn get(cell: &::core::cell::Cell<Self>) -> Self {
+        unsafe { ::core::ptr::read(cell.as_ptr()) }
+    }
+    /// Stores a new value in `cell`
+    #[inline(always)]
+    fn set<V: CVariant>(cell: &::core::cell::Cell<Self>, value: V) {
+        cell.set(Self::new(value));
+    }
+    /// Stores a new value in `cell` and returns the old value
+    #[inline(always)]
+    fn replace<V: CVariant>(cell: &::core::cell::Cell<Self>, value: V) -> Self {
+        cell.replace(Self::new(value))
+    }
+}
+impl ::core::ops::Deref for C {
+    type Target = dyn Debug + 'static;
+    #[inline(always)]
+    fn deref(&self) -> &Self::Target {
+        unsafe { ::core::mem::transmute(self.__trait_union_to_trait_object()) }
+    }
+}
+impl ::core::ops::DerefMut for C {
+    #[inline(always)]
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        unsafe { ::core::mem::transmute(self.__trait_union_to_trait_object_mut()) }
+    }
+}
+const _: () = {
+    impl ::core::marker::Copy for __trait_union_C_Union {}
+    impl ::core::clone::Clone for __trait_union_C_Union {
+        fn clone(&self) -> Self {
+            *self
+        }
+    }
+    impl C {
+        const __TRAIT_UNION_VTABLES: [::trait_union::__support::VtablePtr; 2usize] = [
+            unsafe {
+                ::trait_union::__support::VtablePtr::of(
+                    ::core::ptr::null::<u8>() as *const (dyn Debug + '_),
+                )
+            },
+            unsafe {
+                ::trait_union::__support::VtablePtr::of(
+                    ::core::ptr::null::<&'static str>() as *const (dyn Debug + '_),
+                )
+            },
+        ];
+        #[inline(always)]
+        fn __trait_union_to_trait_object(
+            &self,
+        ) -> ::trait_union::__support::RawTraitObject {
+            let data = &self.__trait_union_C_data as *const _ as *mut ();
+            #[cfg(debug_assertions)]
+            ::trait_union::__support::check_aligned(
+                data,
+                Self::__TRAIT_UNION_LAYOUT.variants[self.__trait_union_position()].align,
+                "misaligned C",
+            );
+            ::trait_union::__support::RawTraitObject {
+                data,
+                vtable: Self::__TRAIT_UNION_VTABLES[self.__trait_union_C_index as usize],
+            }
+        }
+        #[inline(always)]
+        fn __trait_union_to_trait_object_mut(
+            &mut self,
+        ) -> ::trait_union::__support::RawTraitObject {
+            let data = &mut self.__trait_union_C_data as *mut _ as *mut ();
+            #[cfg(debug_assertions)]
+            ::trait_union::__support::check_aligned(
+                data,
+                Self::__TRAIT_UNION_LAYOUT.variants[self.__trait_union_position()].align,
+                "misaligned C",
+            );
+            ::trait_union::__support::RawTraitObject {
+                data,
+                vtable: Self::__TRAIT_UNION_VTABLES[self.__trait_union_C_index as usize],
+            }
+        }
+        #[inline(always)]
+        const fn __trait_union_index(index: usize) -> __trait_union_C_Index {
+            match index {
+                0usize => __trait_union_C_Index::V0,
+                1usize => __trait_union_C_Index::V1,
+                _ => ::core::panic!("invalid variant index"),
+            }
+        }
+        /// Creates a new instance with the variant at position `index`
+        ///
+        /// # Safety
+        ///
+        /// `V` must be the type of the variant at position `index`.
+        #[inline(always)]
+        unsafe fn __trait_union_new<V>(index: usize, value: V) -> Self {
+            #[cfg(debug_assertions)]
+            ::trait_union::__support::check_variant::<
+                V,
+            >(&Self::__TRAIT_UNION_LAYOUT, index, "invalid implementation of CVariant");
+            let mut slf = ::core::mem::MaybeUninit::<Self>::uninit();
+            unsafe {
+                if ::core::mem::size_of::<V>() == 0 {
+                    ::core::mem::forget(value);
+                } else {
+                    ::core::ptr::write(
+                        &mut (*slf.as_mut_ptr()).__trait_union_C_data as *mut _
+                            as *mut V,
+                        value,
+                    );
+                }
+                (*slf.as_mut_ptr())
+                    .__trait_union_C_index = Self::__trait_union_index(index);
+                slf.assume_init()
+            }
+        }
+    }
+    impl<'__trait_union_pin> ::core::marker::Unpin for C
+    where
+        ::core::marker::PhantomData<
+            (&'__trait_union_pin (), u8, &'static str),
+        >: ::core::marker::Unpin,
+    {}
+    unsafe impl ::trait_union::__support::AcceptVariant for C {
+        #[inline]
+        unsafe fn __trait_union_accept(
+            id: ::core::any::TypeId,
+            data: *const u8,
+        ) -> ::core::option::Option<Self> {
+            if id == ::core::any::TypeId::of::<u8>() {
+                return ::core::option::Option::Some(unsafe {
+                    Self::new_raw(CKind::U8, data)
+                });
+            }
+            if id == ::core::any::TypeId::of::<&'static str>() {
+                return ::core::option::Option::Some(unsafe {
+                    Self::new_raw(CKind::StaticStr, data)
+                });
+            }
+            ::core::option::Option::None
+        }
+    }
+    impl ::trait_union::Provide for C {
+        fn provide<'__trait_union_provide>(
+            &'__trait_union_provide self,
+            request: &mut ::trait_union::Request<'__trait_union_provide>,
+        ) {
+            match self.__trait_union_C_index {
+                __trait_union_C_Index::V0 => {
+                    let value = unsafe { &*self.__trait_union_C_data.variant0 };
+                    {
+                        let _ = value;
+                    }
+                }
+                __trait_union_C_Index::V1 => {
+                    let value = unsafe { &*self.__trait_union_C_data.variant1 };
+                    {
+                        let _ = value;
+                    }
+                }
+                #[cfg(debug_assertions)]
+                __trait_union_C_Index::Dropped => {
+                    ::core::panic!("use of a dropped trait union")
+                }
+            }
+        }
+    }
+    impl<__TraitUnionVariant: CVariant> ::trait_union::FromVariant<__TraitUnionVariant>
+    for C {
+        #[inline(always)]
+        fn from_variant(value: __TraitUnionVariant) -> Self {
+            Self::new(value)
+        }
+    }
+    impl ::core::convert::From<COwned> for C {
+        #[inline]
+        fn from(value: COwned) -> Self {
+            Self::from_enum(value)
+        }
+    }
+    impl ::core::convert::From<C> for COwned {
+        #[inline]
+        fn from(value: C) -> Self {
+            <C>::into_enum(value)
+        }
+    }
+    impl<
+        '__trait_union_ref,
+        '__trait_union_object,
+    > ::core::convert::From<&'__trait_union_ref C>
+    for &'__trait_union_ref (dyn Debug + '__trait_union_object)
+    where
+        'static: '__trait_union_object,
+    {
+        #[inline(always)]
+        fn from(value: &'__trait_union_ref C) -> Self {
+            &**value
+        }
+    }
+    impl<
+        '__trait_union_ref,
+        '__trait_union_object,
+    > ::core::convert::From<&'__trait_union_ref mut C>
+    for &'__trait_union_ref mut (dyn Debug + '__trait_union_object)
+    where
+        'static: '__trait_union_object,
+    {
+        #[inline(always)]
+        fn from(value: &'__trait_union_ref mut C) -> Self {
+            &mut **value
+        }
+    }
+    unsafe impl CVariant for u8 {
+        const __TRAIT_UNION_INDEX: usize = 0usize;
+        const KIND: CKind = CKind::U8;
+        const SIZE: usize = ::core::mem::size_of::<Self>();
+        const NAME: &'static str = "U8";
+    }
+    unsafe impl CVariant for &'static str {
+        const __TRAIT_UNION_INDEX: usize = 1usize;
+        const KIND: CKind = CKind::StaticStr;
+        const SIZE: usize = ::core::mem::size_of::<Self>();
+        const NAME: &'static str = "StaticStr";
+    }
+};
@@ -0,0 +1,6 @@
+use std::fmt::Debug;
+
+trait_union_copy! {
+    #[derive(Copy, Clone)]
+    union C: Debug = u8 | &'static str;
+}
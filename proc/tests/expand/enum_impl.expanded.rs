@@ -0,0 +1,1134 @@
+// E: Debug
+//     2 variants stored in an enum
+//     0: u8, kind U8, tag 0
+//     1: String, kind String, tag 1
+// Outer: Debug
+//     2 variants stored in a union
+//     0: u16, kind U16, tag 0
+//     1: E, kind E, tag 1
+use std::fmt::Debug;
+#[allow(non_snake_case)]
+struct E {
+    __trait_union_E_data: __trait_union_E_Union,
+}
+/**Marker trait for types that can be stored in a [E]
+
+# Safety
+
+This trait must not be implemented manually.*/
+#[allow(clippy::missing_safety_doc)]
+unsafe trait EVariant: Debug + 'static {
+    #[doc(hidden)]
+    const __TRAIT_UNION_INDEX: usize;
+    /// The kind of the variant
+    const KIND: EKind;
+    /// The size of the variant in bytes
+    const SIZE: usize;
+    /// The name of the kind of the variant
+    const NAME: &'static str;
+}
+#[allow(non_camel_case_types)]
+enum __trait_union_E_Union {
+    V0(u8),
+    V1(String),
+}
+///A reference to the active variant of a [E]
+#[allow(dead_code)]
+enum ERef<'__trait_union_ref> {
+    ///`u8`
+    U8(&'__trait_union_ref u8),
+    ///`String`
+    String(&'__trait_union_ref String),
+}
+///A mutable reference to the active variant of a [E]
+#[allow(dead_code)]
+enum EMut<'__trait_union_ref> {
+    ///`u8`
+    U8(&'__trait_union_ref mut u8),
+    ///`String`
+    String(&'__trait_union_ref mut String),
+}
+///The active variant of a [E] by value
+#[allow(dead_code)]
+enum EOwned {
+    ///`u8`
+    U8(u8),
+    ///`String`
+    String(String),
+}
+///The kinds of variants of [E]
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+#[repr(u8)]
+enum EKind {
+    ///`u8`
+    U8 = 0,
+    ///`String`
+    String = 1,
+}
+impl E {
+    const __TRAIT_UNION_TAGS: [u8; 2usize] = [0, 1];
+    const __TRAIT_UNION_KINDS: [EKind; 2usize] = [EKind::U8, EKind::String];
+    const __TRAIT_UNION_LAYOUT: ::trait_union::LayoutInfo = ::trait_union::LayoutInfo {
+        name: "E",
+        size: ::core::mem::size_of::<Self>(),
+        align: ::core::mem::align_of::<Self>(),
+        variants: &[
+            ::trait_union::VariantLayout {
+                name: "u8",
+                tag: 0u64,
+                size: ::core::mem::size_of::<u8>(),
+                align: ::core::mem::align_of::<u8>(),
+                offset: ::core::mem::offset_of!(Self, __trait_union_E_data),
+            },
+            ::trait_union::VariantLayout {
+                name: "String",
+                tag: 1u64,
+                size: ::core::mem::size_of::<String>(),
+                align: ::core::mem::align_of::<String>(),
+                offset: ::core::mem::offset_of!(Self, __trait_union_E_data),
+            },
+        ],
+    };
+    /// Whether any variant needs to be dropped
+    ///
+    /// If this is `false`, dropping a value does nothing.
+    const NEEDS_DROP: bool = false || ::core::mem::needs_drop::<u8>()
+        || ::core::mem::needs_drop::<String>();
+    /// Returns the layout of this type
+    const fn debug_layout() -> ::trait_union::LayoutInfo {
+        Self::__TRAIT_UNION_LAYOUT
+    }
+    #[inline(always)]
+    fn __trait_union_position(&self) -> usize {
+        match &self.__trait_union_E_data {
+            __trait_union_E_Union::V0(_) => 0usize,
+            __trait_union_E_Union::V1(_) => 1usize,
+        }
+    }
+    /// Returns the tag of the active variant of `this`
+    #[inline(always)]
+    fn tag_of(this: &Self) -> u8 {
+        Self::__TRAIT_UNION_TAGS[this.__trait_union_position()]
+    }
+    /// Returns a reference to the active variant of `this`
+    #[inline(always)]
+    #[allow(dead_code)]
+    fn as_ref_enum<'__trait_union_ref>(
+        this: &'__trait_union_ref Self,
+    ) -> ERef<'__trait_union_ref> {
+        this.__trait_union_as_ref_enum()
+    }
+    #[inline(always)]
+    fn __trait_union_as_ref_enum<'__trait_union_ref>(
+        &'__trait_union_ref self,
+    ) -> ERef<'__trait_union_ref> {
+        match &self.__trait_union_E_data {
+            __trait_union_E_Union::V0(value) => ERef::U8(value),
+            __trait_union_E_Union::V1(value) => ERef::String(value),
+        }
+    }
+    /// Returns a mutable reference to the active variant of `this`
+    #[inline(always)]
+    #[allow(dead_code)]
+    fn as_mut_enum<'__trait_union_ref>(
+        this: &'__trait_union_ref mut Self,
+    ) -> EMut<'__trait_union_ref> {
+        this.__trait_union_as_mut_enum()
+    }
+    #[inline(always)]
+    #[allow(dead_code)]
+    fn __trait_union_as_mut_enum<'__trait_union_ref>(
+        &'__trait_union_ref mut self,
+    ) -> EMut<'__trait_union_ref> {
+        match &mut self.__trait_union_E_data {
+            __trait_union_E_Union::V0(value) => EMut::U8(value),
+            __trait_union_E_Union::V1(value) => EMut::String(value),
+        }
+    }
+    /// Moves the active variant out of `this`
+    #[inline(always)]
+    #[allow(dead_code)]
+    fn into_enum(this: Self) -> EOwned {
+        let this = ::core::mem::ManuallyDrop::new(this);
+        unsafe {
+            match Self::as_ref_enum(&this) {
+                ERef::U8(value) => EOwned::U8(::core::ptr::read(value)),
+                ERef::String(value) => EOwned::String(::core::ptr::read(value)),
+            }
+        }
+    }
+    /// Returns the kind of the active variant of `this`
+    #[inline(always)]
+    fn kind_of(this: &Self) -> EKind {
+        Self::__TRAIT_UNION_KINDS[this.__trait_union_position()]
+    }
+    /// Returns whether `this` and `other` have the same active variant
+    #[inline(always)]
+    fn same_variant(this: &Self, other: &Self) -> bool {
+        this.__trait_union_position() == other.__trait_union_position()
+    }
+    /// Writes the kind of the active variant of `this` followed by its value in
+    /// parentheses
+    ///
+    /// The value is formatted with `Display` if its type implements it, otherwise
+    /// with `Debug`, and otherwise written as `..`. Nothing is allocated.
+    #[inline(always)]
+    fn describe(this: &Self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+        this.__trait_union_describe(f)
+    }
+    #[inline(always)]
+    fn __trait_union_describe(
+        &self,
+        f: &mut ::core::fmt::Formatter<'_>,
+    ) -> ::core::fmt::Result {
+        #[allow(unused_imports)]
+        use ::trait_union::__support::{DescribeDebug, DescribeDisplay, DescribeOther};
+        ::core::fmt::Debug::fmt(&Self::kind_of(self), f)?;
+        f.write_str("(")?;
+        (match &self.__trait_union_E_data {
+            __trait_union_E_Union::V0(value) => {
+                (&&&::trait_union::__support::Describe(value)).describe_value(f)
+            }
+            __trait_union_E_Union::V1(value) => {
+                (&&&::trait_union::__support::Describe(value)).describe_value(f)
+            }
+        })?;
+        f.write_str(")")
+    }
+    /// Sorts `values` by the declaration order of their active variants
+    ///
+    /// The sort is unstable.
+    #[inline]
+    fn sort_by_kind(values: &mut [Self]) {
+        values.sort_unstable_by_key(Self::__trait_union_position);
+    }
+    /// Returns an iterator over the maximal runs of values of the same kind
+    #[inline]
+    fn kind_runs(values: &[Self]) -> ::trait_union::KindRuns<'_, Self, EKind> {
+        ::trait_union::KindRuns::new(values, Self::kind_of)
+    }
+    /// Returns whether the active variant of `this` is a zero-sized type
+    ///
+    /// The bytes of a zero-sized variant do not have to be copied.
+    #[inline(always)]
+    fn is_zst_variant(this: &Self) -> bool {
+        Self::__TRAIT_UNION_LAYOUT.variants[this.__trait_union_position()].size == 0
+    }
+    /// Creates a new instance
+    #[inline(always)]
+    fn new<V: EVariant>(value: V) -> Self {
+        let value = ::core::mem::ManuallyDrop::new(value);
+        let data = &*value as *const V as *const u8;
+        Self {
+            __trait_union_E_data: match V::__TRAIT_UNION_INDEX {
+                0usize => {
+                    __trait_union_E_Union::V0(unsafe {
+                        ::core::ptr::read(data as *const u8)
+                    })
+                }
+                1usize => {
+                    __trait_union_E_Union::V1(unsafe {
+                        ::core::ptr::read(data as *const String)
+                    })
+                }
+                _ => ::core::unreachable!(),
+            },
+        }
+    }
+    /// Creates a new instance of the variant of kind `kind`
+    ///
+    /// # Safety
+    ///
+    /// `V` must be the type of the variant of kind `kind`.
+    #[inline(always)]
+    unsafe fn new_unchecked<V>(kind: EKind, value: V) -> Self {
+        #[cfg(debug_assertions)]
+        {
+            let pos = match kind {
+                EKind::U8 => 0usize,
+                EKind::String => 1usize,
+            };
+            let layout = &Self::__TRAIT_UNION_LAYOUT.variants[pos];
+            if ::core::mem::size_of::<V>() != layout.size
+                || ::core::mem::align_of::<V>() != layout.align
+            {
+                ::core::panic!("`V` is not the type of the variant of kind `kind`");
+            }
+        }
+        let value = ::core::mem::ManuallyDrop::new(value);
+        unsafe { Self::new_raw(kind, &*value as *const V as *const u8) }
+    }
+    /// Creates a new instance of the variant of kind `kind` by reading its value from
+    /// `data`
+    ///
+    /// `data` does not have to be aligned.
+    ///
+    /// # Safety
+    ///
+    /// `data` must point to a valid value of the type of the variant of kind `kind`.
+    /// The value is moved out of `data`.
+    #[inline]
+    unsafe fn new_raw(kind: EKind, data: *const u8) -> Self {
+        match kind {
+            EKind::U8 => {
+                let value = unsafe { ::core::ptr::read_unaligned(data as *const u8) };
+                Self {
+                    __trait_union_E_data: __trait_union_E_Union::V0(value),
+                }
+            }
+            EKind::String => {
+                let value = unsafe {
+                    ::core::ptr::read_unaligned(data as *const String)
+                };
+                Self {
+                    __trait_union_E_data: __trait_union_E_Union::V1(value),
+                }
+            }
+        }
+    }
+    /// Creates a new instance from the moved-out active variant
+    #[inline]
+    #[allow(dead_code)]
+    fn from_enum(value: EOwned) -> Self {
+        match value {
+            EOwned::U8(value) => {
+                let value = ::core::mem::ManuallyDrop::new(value);
+                unsafe { Self::new_raw(EKind::U8, &*value as *const _ as *const u8) }
+            }
+            EOwned::String(value) => {
+                let value = ::core::mem::ManuallyDrop::new(value);
+                unsafe { Self::new_raw(EKind::String, &*value as *const _ as *const u8) }
+            }
+        }
+    }
+    /// Replaces the value by the result of applying `f` to the active variant
+    ///
+    /// The process is aborted if `f` panics.
+    #[inline]
+    #[allow(dead_code)]
+    fn transition(this: &mut Self, f: impl ::core::ops::FnOnce(EOwned) -> EOwned) {
+        let guard = ::trait_union::__support::AbortOnUnwind;
+        unsafe {
+            let value = Self::into_enum(::core::ptr::read(this));
+            ::core::ptr::write(this, Self::from_enum(f(value)));
+        }
+        ::core::mem::forget(guard);
+    }
+    /// Moves the active variant into another union with a variant of its type
+    ///
+    /// The value is returned unchanged if the other union has no such variant.
+    #[inline]
+    fn move_variant<__TraitUnionTarget: ::trait_union::__support::AcceptVariant>(
+        self,
+    ) -> ::core::result::Result<__TraitUnionTarget, Self> {
+        match Self::into_enum(self) {
+            EOwned::U8(value) => {
+                let value = ::core::mem::ManuallyDrop::new(value);
+                let id = ::core::any::TypeId::of::<u8>();
+                match unsafe {
+                    __TraitUnionTarget::__trait_union_accept(
+                        id,
+                        &*value as *const _ as *const u8,
+                    )
+                } {
+                    ::core::option::Option::Some(target) => {
+                        ::core::result::Result::Ok(target)
+                    }
+                    ::core::option::Option::None => {
+                        let value = ::core::mem::ManuallyDrop::into_inner(value);
+                        ::core::result::Result::Err(Self::from_enum(EOwned::U8(value)))
+                    }
+                }
+            }
+            EOwned::String(value) => {
+                let value = ::core::mem::ManuallyDrop::new(value);
+                let id = ::core::any::TypeId::of::<String>();
+                match unsafe {
+                    __TraitUnionTarget::__trait_union_accept(
+                        id,
+                        &*value as *const _ as *const u8,
+                    )
+                } {
+                    ::core::option::Option::Some(target) => {
+                        ::core::result::Result::Ok(target)
+                    }
+                    ::core::option::Option::None => {
+                        let value = ::core::mem::ManuallyDrop::into_inner(value);
+                        ::core::result::Result::Err(
+                            Self::from_enum(EOwned::String(value)),
+                        )
+                    }
+                }
+            }
+        }
+    }
+}
+impl ::core::ops::Deref for E {
+    type Target = dyn Debug + 'static;
+    #[inline(always)]
+    fn deref(&self) -> &Self::Target {
+        match &self.__trait_union_E_data {
+            __trait_union_E_Union::V0(value) => value,
+            __trait_union_E_Union::V1(value) => value,
+        }
+    }
+}
+impl ::core::ops::DerefMut for E {
+    #[inline(always)]
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        match &mut self.__trait_union_E_data {
+            __trait_union_E_Union::V0(value) => value,
+            __trait_union_E_Union::V1(value) => value,
+        }
+    }
+}
+const _: () = {
+    impl E {}
+    impl<'__trait_union_pin> ::core::marker::Unpin for E
+    where
+        ::core::marker::PhantomData<
+            (&'__trait_union_pin (), u8, String),
+        >: ::core::marker::Unpin,
+    {}
+    unsafe impl ::trait_union::__support::AcceptVariant for E {
+        #[inline]
+        unsafe fn __trait_union_accept(
+            id: ::core::any::TypeId,
+            data: *const u8,
+        ) -> ::core::option::Option<Self> {
+            if id == ::core::any::TypeId::of::<u8>() {
+                return ::core::option::Option::Some(unsafe {
+                    Self::new_raw(EKind::U8, data)
+                });
+            }
+            if id == ::core::any::TypeId::of::<String>() {
+                return ::core::option::Option::Some(unsafe {
+                    Self::new_raw(EKind::String, data)
+                });
+            }
+            ::core::option::Option::None
+        }
+    }
+    impl ::trait_union::Provide for E {
+        fn provide<'__trait_union_provide>(
+            &'__trait_union_provide self,
+            request: &mut ::trait_union::Request<'__trait_union_provide>,
+        ) {
+            match &self.__trait_union_E_data {
+                __trait_union_E_Union::V0(value) => {
+                    let _ = value;
+                }
+                __trait_union_E_Union::V1(value) => {
+                    let _ = value;
+                }
+            }
+        }
+    }
+    impl<__TraitUnionVariant: EVariant> ::trait_union::FromVariant<__TraitUnionVariant>
+    for E {
+        #[inline(always)]
+        fn from_variant(value: __TraitUnionVariant) -> Self {
+            Self::new(value)
+        }
+    }
+    impl ::core::convert::From<EOwned> for E {
+        #[inline]
+        fn from(value: EOwned) -> Self {
+            Self::from_enum(value)
+        }
+    }
+    impl ::core::convert::From<E> for EOwned {
+        #[inline]
+        fn from(value: E) -> Self {
+            <E>::into_enum(value)
+        }
+    }
+    impl<
+        '__trait_union_ref,
+        '__trait_union_object,
+    > ::core::convert::From<&'__trait_union_ref E>
+    for &'__trait_union_ref (dyn Debug + '__trait_union_object)
+    where
+        'static: '__trait_union_object,
+    {
+        #[inline(always)]
+        fn from(value: &'__trait_union_ref E) -> Self {
+            &**value
+        }
+    }
+    impl<
+        '__trait_union_ref,
+        '__trait_union_object,
+    > ::core::convert::From<&'__trait_union_ref mut E>
+    for &'__trait_union_ref mut (dyn Debug + '__trait_union_object)
+    where
+        'static: '__trait_union_object,
+    {
+        #[inline(always)]
+        fn from(value: &'__trait_union_ref mut E) -> Self {
+            &mut **value
+        }
+    }
+    unsafe impl EVariant for u8 {
+        const __TRAIT_UNION_INDEX: usize = 0usize;
+        const KIND: EKind = EKind::U8;
+        const SIZE: usize = ::core::mem::size_of::<Self>();
+        const NAME: &'static str = "U8";
+    }
+    unsafe impl EVariant for String {
+        const __TRAIT_UNION_INDEX: usize = 1usize;
+        const KIND: EKind = EKind::String;
+        const SIZE: usize = ::core::mem::size_of::<Self>();
+        const NAME: &'static str = "String";
+    }
+};
+#[allow(non_snake_case)]
+struct Outer {
+    __trait_union_Outer_data: __trait_union_Outer_Union,
+    __trait_union_Outer_index: __trait_union_Outer_Index,
+}
+/**Marker trait for types that can be stored in a [Outer]
+
+# Safety
+
+This trait must not be implemented manually.*/
+#[allow(clippy::missing_safety_doc)]
+unsafe trait OuterVariant: Debug + 'static {
+    #[doc(hidden)]
+    const __TRAIT_UNION_INDEX: usize;
+    /// The kind of the variant
+    const KIND: OuterKind;
+    /// The size of the variant in bytes
+    const SIZE: usize;
+    /// The name of the kind of the variant
+    const NAME: &'static str;
+}
+#[repr(C)]
+#[allow(non_snake_case)]
+union __trait_union_Outer_Union {
+    variant0: ::core::mem::ManuallyDrop<u16>,
+    variant1: ::core::mem::ManuallyDrop<E>,
+}
+#[allow(non_camel_case_types)]
+#[derive(Copy, Clone, PartialEq, Eq)]
+enum __trait_union_Outer_Index {
+    V0,
+    V1,
+    #[cfg(debug_assertions)]
+    Dropped,
+}
+///A reference to the active variant of a [Outer]
+#[allow(dead_code)]
+enum OuterRef<'__trait_union_ref> {
+    ///`u16`
+    U16(&'__trait_union_ref u16),
+    ///`E`
+    E(&'__trait_union_ref E),
+}
+///A mutable reference to the active variant of a [Outer]
+#[allow(dead_code)]
+enum OuterMut<'__trait_union_ref> {
+    ///`u16`
+    U16(&'__trait_union_ref mut u16),
+    ///`E`
+    E(&'__trait_union_ref mut E),
+}
+///The active variant of a [Outer] by value
+#[allow(dead_code)]
+enum OuterOwned {
+    ///`u16`
+    U16(u16),
+    ///`E`
+    E(E),
+}
+///The kinds of variants of [Outer]
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+#[repr(u8)]
+enum OuterKind {
+    ///`u16`
+    U16 = 0,
+    ///`E`
+    E = 1,
+}
+impl Outer {
+    const __TRAIT_UNION_TAGS: [u8; 2usize] = [0, 1];
+    const __TRAIT_UNION_KINDS: [OuterKind; 2usize] = [OuterKind::U16, OuterKind::E];
+    const __TRAIT_UNION_LAYOUT: ::trait_union::LayoutInfo = ::trait_union::LayoutInfo {
+        name: "Outer",
+        size: ::core::mem::size_of::<Self>(),
+        align: ::core::mem::align_of::<Self>(),
+        variants: &[
+            ::trait_union::VariantLayout {
+                name: "u16",
+                tag: 0u64,
+                size: ::core::mem::size_of::<u16>(),
+                align: ::core::mem::align_of::<u16>(),
+                offset: ::core::mem::offset_of!(Self, __trait_union_Outer_data),
+            },
+            ::trait_union::VariantLayout {
+                name: "E",
+                tag: 1u64,
+                size: ::core::mem::size_of::<E>(),
+                align: ::core::mem::align_of::<E>(),
+                offset: ::core::mem::offset_of!(Self, __trait_union_Outer_data),
+            },
+        ],
+    };
+    /// Whether any variant needs to be dropped
+    ///
+    /// If this is `false`, dropping a value does nothing.
+    const NEEDS_DROP: bool = false || ::core::mem::needs_drop::<u16>()
+        || ::core::mem::needs_drop::<E>();
+    /// Returns the layout of this type
+    const fn debug_layout() -> ::trait_union::LayoutInfo {
+        Self::__TRAIT_UNION_LAYOUT
+    }
+    #[inline(always)]
+    fn __trait_union_position(&self) -> usize {
+        {
+            #[cfg(debug_assertions)]
+            {
+                if self.__trait_union_Outer_index == __trait_union_Outer_Index::Dropped {
+                    ::core::panic!("use of a dropped Outer");
+                }
+            }
+            self.__trait_union_Outer_index as usize
+        }
+    }
+    /// Returns the tag of the active variant of `this`
+    #[inline(always)]
+    fn tag_of(this: &Self) -> u8 {
+        Self::__TRAIT_UNION_TAGS[this.__trait_union_position()]
+    }
+    /// Returns a reference to the active variant of `this`
+    #[inline(always)]
+    #[allow(dead_code)]
+    fn as_ref_enum<'__trait_union_ref>(
+        this: &'__trait_union_ref Self,
+    ) -> OuterRef<'__trait_union_ref> {
+        this.__trait_union_as_ref_enum()
+    }
+    #[inline(always)]
+    fn __trait_union_as_ref_enum<'__trait_union_ref>(
+        &'__trait_union_ref self,
+    ) -> OuterRef<'__trait_union_ref> {
+        match self.__trait_union_Outer_index {
+            __trait_union_Outer_Index::V0 => {
+                let value = unsafe { &*self.__trait_union_Outer_data.variant0 };
+                OuterRef::U16(value)
+            }
+            __trait_union_Outer_Index::V1 => {
+                let value = unsafe { &*self.__trait_union_Outer_data.variant1 };
+                OuterRef::E(value)
+            }
+            #[cfg(debug_assertions)]
+            __trait_union_Outer_Index::Dropped => {
+                ::core::panic!("use of a dropped trait union")
+            }
+        }
+    }
+    /// Returns a mutable reference to the active variant of `this`
+    #[inline(always)]
+    #[allow(dead_code)]
+    fn as_mut_enum<'__trait_union_ref>(
+        this: &'__trait_union_ref mut Self,
+    ) -> OuterMut<'__trait_union_ref> {
+        this.__trait_union_as_mut_enum()
+    }
+    #[inline(always)]
+    #[allow(dead_code)]
+    fn __trait_union_as_mut_enum<'__trait_union_ref>(
+        &'__trait_union_ref mut self,
+    ) -> OuterMut<'__trait_union_ref> {
+        match self.__trait_union_Outer_index {
+            __trait_union_Outer_Index::V0 => {
+                let value = unsafe { &mut *self.__trait_union_Outer_data.variant0 };
+                OuterMut::U16(value)
+            }
+            __trait_union_Outer_Index::V1 => {
+                let value = unsafe { &mut *self.__trait_union_Outer_data.variant1 };
+                OuterMut::E(value)
+            }
+            #[cfg(debug_assertions)]
+            __trait_union_Outer_Index::Dropped => {
+                ::core::panic!("use of a dropped trait union")
+            }
+        }
+    }
+    /// Moves the active variant out of `this`
+    #[inline(always)]
+    #[allow(dead_code)]
+    fn into_enum(this: Self) -> OuterOwned {
+        let this = ::core::mem::ManuallyDrop::new(this);
+        unsafe {
+            match Self::as_ref_enum(&this) {
+                OuterRef::U16(value) => OuterOwned::U16(::core::ptr::read(value)),
+                OuterRef::E(value) => OuterOwned::E(::core::ptr::read(value)),
+            }
+        }
+    }
+    /// Returns the kind of the active variant of `this`
+    #[inline(always)]
+    fn kind_of(this: &Self) -> OuterKind {
+        Self::__TRAIT_UNION_KINDS[this.__trait_union_position()]
+    }
+    /// Returns whether `this` and `other` have the same active variant
+    #[inline(always)]
+    fn same_variant(this: &Self, other: &Self) -> bool {
+        this.__trait_union_position() == other.__trait_union_position()
+    }
+    /// Writes the kind of the active variant of `this` followed by its value in
+    /// parentheses
+    ///
+    /// The value is formatted with `Display` if its type implements it, otherwise
+    /// with `Debug`, and otherwise written as `..`. Nothing is allocated.
+    #[inline(always)]
+    fn describe(this: &Self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+        this.__trait_union_describe(f)
+    }
+    #[inline(always)]
+    fn __trait_union_describe(
+        &self,
+        f: &mut ::core::fmt::Formatter<'_>,
+    ) -> ::core::fmt::Result {
+        #[allow(unused_imports)]
+        use ::trait_union::__support::{DescribeDebug, DescribeDisplay, DescribeOther};
+        ::core::fmt::Debug::fmt(&Self::kind_of(self), f)?;
+        f.write_str("(")?;
+        (match self.__trait_union_Outer_index {
+            __trait_union_Outer_Index::V0 => {
+                let value = unsafe { &*self.__trait_union_Outer_data.variant0 };
+                (&&&::trait_union::__support::Describe(value)).describe_value(f)
+            }
+            __trait_union_Outer_Index::V1 => {
+                let value = unsafe { &*self.__trait_union_Outer_data.variant1 };
+                <E>::describe(value, f)
+            }
+            #[cfg(debug_assertions)]
+            __trait_union_Outer_Index::Dropped => {
+                ::core::panic!("use of a dropped trait union")
+            }
+        })?;
+        f.write_str(")")
+    }
+    /// Sorts `values` by the declaration order of their active variants
+    ///
+    /// The sort is unstable.
+    #[inline]
+    fn sort_by_kind(values: &mut [Self]) {
+        values.sort_unstable_by_key(Self::__trait_union_position);
+    }
+    /// Returns an iterator over the maximal runs of values of the same kind
+    #[inline]
+    fn kind_runs(values: &[Self]) -> ::trait_union::KindRuns<'_, Self, OuterKind> {
+        ::trait_union::KindRuns::new(values, Self::kind_of)
+    }
+    /// Returns whether the active variant of `this` is a zero-sized type
+    ///
+    /// The bytes of a zero-sized variant do not have to be copied.
+    #[inline(always)]
+    fn is_zst_variant(this: &Self) -> bool {
+        Self::__TRAIT_UNION_LAYOUT.variants[this.__trait_union_position()].size == 0
+    }
+    /// Creates a new instance
+    #[inline(always)]
+    fn new<V: OuterVariant>(value: V) -> Self {
+        unsafe { Self::__trait_union_new(V::__TRAIT_UNION_INDEX, value) }
+    }
+    /// Creates a new instance of the variant of kind `kind`
+    ///
+    /// # Safety
+    ///
+    /// `V` must be the type of the variant of kind `kind`.
+    #[inline(always)]
+    unsafe fn new_unchecked<V>(kind: OuterKind, value: V) -> Self {
+        #[cfg(debug_assertions)]
+        {
+            let pos = match kind {
+                OuterKind::U16 => 0usize,
+                OuterKind::E => 1usize,
+            };
+            let layout = &Self::__TRAIT_UNION_LAYOUT.variants[pos];
+            if ::core::mem::size_of::<V>() != layout.size
+                || ::core::mem::align_of::<V>() != layout.align
+            {
+                ::core::panic!("`V` is not the type of the variant of kind `kind`");
+            }
+        }
+        let value = ::core::mem::ManuallyDrop::new(value);
+        unsafe { Self::new_raw(kind, &*value as *const V as *const u8) }
+    }
+    /// Creates a new instance of the variant of kind `kind` by reading its value from
+    /// `data`
+    ///
+    /// `data` does not have to be aligned.
+    ///
+    /// # Safety
+    ///
+    /// `data` must point to a valid value of the type of the variant of kind `kind`.
+    /// The value is moved out of `data`.
+    #[inline]
+    unsafe fn new_raw(kind: OuterKind, data: *const u8) -> Self {
+        match kind {
+            OuterKind::U16 => {
+                let value = unsafe { ::core::ptr::read_unaligned(data as *const u16) };
+                unsafe { Self::__trait_union_new(0usize, value) }
+            }
+            OuterKind::E => {
+                let value = unsafe { ::core::ptr::read_unaligned(data as *const E) };
+                unsafe { Self::__trait_union_new(1usize, value) }
+            }
+        }
+    }
+    /// Creates a new instance from the moved-out active variant
+    #[inline]
+    #[allow(dead_code)]
+    fn from_enum(value: OuterOwned) -> Self {
+        match value {
+            OuterOwned::U16(value) => {
+                let value = ::core::mem::ManuallyDrop::new(value);
+                unsafe {
+                    Self::new_raw(OuterKind::U16, &*value as *const _ as *const u8)
+                }
+            }
+            OuterOwned::E(value) => {
+                let value = ::core::mem::ManuallyDrop::new(value);
+                unsafe { Self::new_raw(OuterKind::E, &*value as *const _ as *const u8) }
+            }
+        }
+    }
+    /// Replaces the value by the result of applying `f` to the active variant
+    ///
+    /// The process is aborted if `f` panics.
+    #[inline]
+    #[allow(dead_code)]
+    fn transition(
+        this: &mut Self,
+        f: impl ::core::ops::FnOnce(OuterOwned) -> OuterOwned,
+    ) {
+        let guard = ::trait_union::__support::AbortOnUnwind;
+        unsafe {
+            let value = Self::into_enum(::core::ptr::read(this));
+            ::core::ptr::write(this, Self::from_enum(f(value)));
+        }
+        ::core::mem::forget(guard);
+    }
+    /// Moves the active variant into another union with a variant of its type
+    ///
+    /// The value is returned unchanged if the other union has no such variant.
+    #[inline]
+    fn move_variant<__TraitUnionTarget: ::trait_union::__support::AcceptVariant>(
+        self,
+    ) -> ::core::result::Result<__TraitUnionTarget, Self> {
+        match Self::into_enum(self) {
+            OuterOwned::U16(value) => {
+                let value = ::core::mem::ManuallyDrop::new(value);
+                let id = ::core::any::TypeId::of::<u16>();
+                match unsafe {
+                    __TraitUnionTarget::__trait_union_accept(
+                        id,
+                        &*value as *const _ as *const u8,
+                    )
+                } {
+                    ::core::option::Option::Some(target) => {
+                        ::core::result::Result::Ok(target)
+                    }
+                    ::core::option::Option::None => {
+                        let value = ::core::mem::ManuallyDrop::into_inner(value);
+                        ::core::result::Result::Err(
+                            Self::from_enum(OuterOwned::U16(value)),
+                        )
+                    }
+                }
+            }
+            OuterOwned::E(value) => {
+                let value = ::core::mem::ManuallyDrop::new(value);
+                let id = ::core::any::TypeId::of::<E>();
+                match unsafe {
+                    __TraitUnionTarget::__trait_union_accept(
+                        id,
+                        &*value as *const _ as *const u8,
+                    )
+                } {
+                    ::core::option::Option::Some(target) => {
+                        ::core::result::Result::Ok(target)
+                    }
+                    ::core::option::Option::None => {
+                        let value = ::core::mem::ManuallyDrop::into_inner(value);
+                        ::core::result::Result::Err(
+                            Self::from_enum(OuterOwned::E(value)),
+                        )
+                    }
+                }
+            }
+        }
+    }
+}
+impl ::core::ops::Deref for Outer {
+    type Target = dyn Debug + 'static;
+    #[inline(always)]
+    fn deref(&self) -> &Self::Target {
+        unsafe { ::core::mem::transmute(self.__trait_union_to_trait_object()) }
+    }
+}
+impl ::core::ops::DerefMut for Outer {
+    #[inline(always)]
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        unsafe { ::core::mem::transmute(self.__trait_union_to_trait_object_mut()) }
+    }
+}
+const _: () = {
+    impl ::core::ops::Drop for Outer {
+        #[inline(always)]
+        fn drop(&mut self) {
+            if Self::NEEDS_DROP {
+                if self.__trait_union_Outer_index == __trait_union_Outer_Index::V1 {
+                    unsafe {
+                        ::core::mem::ManuallyDrop::drop(
+                            &mut self.__trait_union_Outer_data.variant1,
+                        )
+                    };
+                } else if Self::__TRAIT_UNION_NEEDS_DROP[{
+                    #[cfg(debug_assertions)]
+                    {
+                        if self.__trait_union_Outer_index
+                            == __trait_union_Outer_Index::Dropped
+                        {
+                            ::core::panic!("use of a dropped Outer");
+                        }
+                    }
+                    self.__trait_union_Outer_index as usize
+                }]
+                {
+                    unsafe {
+                        let t: &mut (dyn Debug + 'static) = ::core::mem::transmute(
+                            self.__trait_union_to_trait_object_mut(),
+                        );
+                        ::core::ptr::drop_in_place(t);
+                    }
+                }
+            }
+            #[cfg(debug_assertions)]
+            {
+                self.__trait_union_Outer_index = __trait_union_Outer_Index::Dropped;
+            }
+        }
+    }
+    impl Outer {
+        const __TRAIT_UNION_NEEDS_DROP: [bool; 2usize] = [
+            ::core::mem::needs_drop::<u16>(),
+            ::core::mem::needs_drop::<E>(),
+        ];
+        const __TRAIT_UNION_VTABLES: [::trait_union::__support::VtablePtr; 2usize] = [
+            unsafe {
+                ::trait_union::__support::VtablePtr::of(
+                    ::core::ptr::null::<u16>() as *const (dyn Debug + '_),
+                )
+            },
+            ::trait_union::__support::VtablePtr::NULL,
+        ];
+        #[inline(always)]
+        fn __trait_union_to_trait_object(
+            &self,
+        ) -> ::trait_union::__support::RawTraitObject {
+            if self.__trait_union_Outer_index == __trait_union_Outer_Index::V1 {
+                let inner: &(dyn Debug + 'static) = unsafe {
+                    ::core::convert::From::from(&*self.__trait_union_Outer_data.variant1)
+                };
+                return unsafe {
+                    ::core::mem::transmute::<
+                        &(dyn Debug + 'static),
+                        ::trait_union::__support::RawTraitObject,
+                    >(inner)
+                };
+            }
+            let data = &self.__trait_union_Outer_data as *const _ as *mut ();
+            #[cfg(debug_assertions)]
+            ::trait_union::__support::check_aligned(
+                data,
+                Self::__TRAIT_UNION_LAYOUT.variants[self.__trait_union_position()].align,
+                "misaligned Outer",
+            );
+            ::trait_union::__support::RawTraitObject {
+                data,
+                vtable: Self::__TRAIT_UNION_VTABLES[self.__trait_union_Outer_index
+                    as usize],
+            }
+        }
+        #[inline(always)]
+        fn __trait_union_to_trait_object_mut(
+            &mut self,
+        ) -> ::trait_union::__support::RawTraitObject {
+            if self.__trait_union_Outer_index == __trait_union_Outer_Index::V1 {
+                let inner: &mut (dyn Debug + 'static) = unsafe {
+                    ::core::convert::From::from(
+                        &mut *self.__trait_union_Outer_data.variant1,
+                    )
+                };
+                return unsafe {
+                    ::core::mem::transmute::<
+                        &mut (dyn Debug + 'static),
+                        ::trait_union::__support::RawTraitObject,
+                    >(inner)
+                };
+            }
+            let data = &mut self.__trait_union_Outer_data as *mut _ as *mut ();
+            #[cfg(debug_assertions)]
+            ::trait_union::__support::check_aligned(
+                data,
+                Self::__TRAIT_UNION_LAYOUT.variants[self.__trait_union_position()].align,
+                "misaligned Outer",
+            );
+            ::trait_union::__support::RawTraitObject {
+                data,
+                vtable: Self::__TRAIT_UNION_VTABLES[self.__trait_union_Outer_index
+                    as usize],
+            }
+        }
+        #[inline(always)]
+        const fn __trait_union_index(index: usize) -> __trait_union_Outer_Index {
+            match index {
+                0usize => __trait_union_Outer_Index::V0,
+                1usize => __trait_union_Outer_Index::V1,
+                _ => ::core::panic!("invalid variant index"),
+            }
+        }
+        /// Creates a new instance with the variant at position `index`
+        ///
+        /// # Safety
+        ///
+        /// `V` must be the type of the variant at position `index`.
+        #[inline(always)]
+        unsafe fn __trait_union_new<V>(index: usize, value: V) -> Self {
+            #[cfg(debug_assertions)]
+            ::trait_union::__support::check_variant::<
+                V,
+            >(
+                &Self::__TRAIT_UNION_LAYOUT,
+                index,
+                "invalid implementation of OuterVariant",
+            );
+            let mut slf = ::core::mem::MaybeUninit::<Self>::uninit();
+            unsafe {
+                if ::core::mem::size_of::<V>() == 0 {
+                    ::core::mem::forget(value);
+                } else {
+                    ::core::ptr::write(
+                        &mut (*slf.as_mut_ptr()).__trait_union_Outer_data as *mut _
+                            as *mut V,
+                        value,
+                    );
+                }
+                (*slf.as_mut_ptr())
+                    .__trait_union_Outer_index = Self::__trait_union_index(index);
+                slf.assume_init()
+            }
+        }
+    }
+    impl<'__trait_union_pin> ::core::marker::Unpin for Outer
+    where
+        ::core::marker::PhantomData<
+            (&'__trait_union_pin (), u16, E),
+        >: ::core::marker::Unpin,
+    {}
+    unsafe impl ::trait_union::__support::AcceptVariant for Outer {
+        #[inline]
+        unsafe fn __trait_union_accept(
+            id: ::core::any::TypeId,
+            data: *const u8,
+        ) -> ::core::option::Option<Self> {
+            if id == ::core::any::TypeId::of::<u16>() {
+                return ::core::option::Option::Some(unsafe {
+                    Self::new_raw(OuterKind::U16, data)
+                });
+            }
+            if id == ::core::any::TypeId::of::<E>() {
+                return ::core::option::Option::Some(unsafe {
+                    Self::new_raw(OuterKind::E, data)
+                });
+            }
+            ::core::option::Option::None
+        }
+    }
+    impl ::trait_union::Provide for Outer {
+        fn provide<'__trait_union_provide>(
+            &'__trait_union_provide self,
+            request: &mut ::trait_union::Request<'__trait_union_provide>,
+        ) {
+            match self.__trait_union_Outer_index {
+                __trait_union_Outer_Index::V0 => {
+                    let value = unsafe { &*self.__trait_union_Outer_data.variant0 };
+                    {
+                        let _ = value;
+                    }
+                }
+                __trait_union_Outer_Index::V1 => {
+                    let value = unsafe { &*self.__trait_union_Outer_data.variant1 };
+                    ::trait_union::Provide::provide(value, request)
+                }
+                #[cfg(debug_assertions)]
+                __trait_union_Outer_Index::Dropped => {
+                    ::core::panic!("use of a dropped trait union")
+                }
+            }
+        }
+    }
+    impl<
+        __TraitUnionVariant: OuterVariant,
+    > ::trait_union::FromVariant<__TraitUnionVariant> for Outer {
+        #[inline(always)]
+        fn from_variant(value: __TraitUnionVariant) -> Self {
+            Self::new(value)
+        }
+    }
+    impl ::core::convert::From<OuterOwned> for Outer {
+        #[inline]
+        fn from(value: OuterOwned) -> Self {
+            Self::from_enum(value)
+        }
+    }
+    impl ::core::convert::From<Outer> for OuterOwned {
+        #[inline]
+        fn from(value: Outer) -> Self {
+            <Outer>::into_enum(value)
+        }
+    }
+    impl<
+        '__trait_union_ref,
+        '__trait_union_object,
+    > ::core::convert::From<&'__trait_union_ref Outer>
+    for &'__trait_union_ref (dyn Debug + '__trait_union_object)
+    where
+        'static: '__trait_union_object,
+    {
+        #[inline(always)]
+        fn from(value: &'__trait_union_ref Outer) -> Self {
+            &**value
+        }
+    }
+    impl<
+        '__trait_union_ref,
+        '__trait_union_object,
+    > ::core::convert::From<&'__trait_union_ref mut Outer>
+    for &'__trait_union_ref mut (dyn Debug + '__trait_union_object)
+    where
+        'static: '__trait_union_object,
+    {
+        #[inline(always)]
+        fn from(value: &'__trait_union_ref mut Outer) -> Self {
+            &mut **value
+        }
+    }
+    unsafe impl OuterVariant for u16 {
+        const __TRAIT_UNION_INDEX: usize = 0usize;
+        const KIND: OuterKind = OuterKind::U16;
+        const SIZE: usize = ::core::mem::size_of::<Self>();
+        const NAME: &'static str = "U16";
+    }
+    impl ::core::convert::From<E> for Outer {
+        #[inline(always)]
+        fn from(value: E) -> Self {
+            unsafe { Self::__trait_union_new(1usize, value) }
+        }
+    }
+};
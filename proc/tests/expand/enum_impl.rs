@@ -0,0 +1,8 @@
+use std::fmt::Debug;
+
+trait_union! {
+    #[trait_union(impl = "enum")]
+    union E: Debug = u8 | String;
+
+    union Outer: Debug = u16 | #[trait_union(flatten)] E;
+}
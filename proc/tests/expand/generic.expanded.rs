@@ -0,0 +1,618 @@
+// U: Debug + 'a
+//     2 variants stored in a union
+//     0: &'a str, kind AStr, tag 0
+//     1: Option<T>, kind OptionT, tag 1
+use std::fmt::Debug;
+#[allow(non_snake_case)]
+pub struct U<'a, T: 'a>
+where
+    T: Copy + Debug,
+{
+    __trait_union_U_data: __trait_union_U_Union<'a, T>,
+    __trait_union_U_index: __trait_union_U_Index,
+}
+/**Marker trait for types that can be stored in a [U]
+
+# Safety
+
+This trait must not be implemented manually.*/
+#[allow(clippy::missing_safety_doc)]
+pub unsafe trait UVariant<'a, T: 'a>: Debug + 'a {
+    #[doc(hidden)]
+    const __TRAIT_UNION_INDEX: usize;
+    /// The kind of the variant
+    const KIND: UKind;
+    /// The size of the variant in bytes
+    const SIZE: usize;
+    /// The name of the kind of the variant
+    const NAME: &'static str;
+}
+#[repr(C)]
+#[allow(non_snake_case)]
+union __trait_union_U_Union<'a, T: 'a>
+where
+    T: Copy + Debug,
+{
+    variant0: ::core::mem::ManuallyDrop<&'a str>,
+    variant1: ::core::mem::ManuallyDrop<Option<T>>,
+}
+#[allow(non_camel_case_types)]
+#[derive(Copy, Clone, PartialEq, Eq)]
+enum __trait_union_U_Index {
+    V0,
+    V1,
+    #[cfg(debug_assertions)]
+    Dropped,
+}
+///A reference to the active variant of a [U]
+#[allow(dead_code)]
+enum URef<'__trait_union_ref, 'a, T: 'a>
+where
+    T: Copy + Debug,
+{
+    ///`& 'a str`
+    AStr(&'__trait_union_ref &'a str),
+    ///`Option < T >`
+    OptionT(&'__trait_union_ref Option<T>),
+}
+///A mutable reference to the active variant of a [U]
+#[allow(dead_code)]
+enum UMut<'__trait_union_ref, 'a, T: 'a>
+where
+    T: Copy + Debug,
+{
+    ///`& 'a str`
+    AStr(&'__trait_union_ref mut &'a str),
+    ///`Option < T >`
+    OptionT(&'__trait_union_ref mut Option<T>),
+}
+///The active variant of a [U] by value
+#[allow(dead_code)]
+enum UOwned<'a, T: 'a>
+where
+    T: Copy + Debug,
+{
+    ///`& 'a str`
+    AStr(&'a str),
+    ///`Option < T >`
+    OptionT(Option<T>),
+}
+///The kinds of variants of [U]
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+#[repr(u8)]
+pub enum UKind {
+    ///`& 'a str`
+    AStr = 0,
+    ///`Option < T >`
+    OptionT = 1,
+}
+impl<'a, T: 'a> U<'a, T>
+where
+    T: Copy + Debug,
+{
+    const __TRAIT_UNION_TAGS: [u8; 2usize] = [0, 1];
+    const __TRAIT_UNION_KINDS: [UKind; 2usize] = [UKind::AStr, UKind::OptionT];
+    const __TRAIT_UNION_LAYOUT: ::trait_union::LayoutInfo = ::trait_union::LayoutInfo {
+        name: "U",
+        size: ::core::mem::size_of::<Self>(),
+        align: ::core::mem::align_of::<Self>(),
+        variants: &[
+            ::trait_union::VariantLayout {
+                name: "& 'a str",
+                tag: 0u64,
+                size: ::core::mem::size_of::<&'a str>(),
+                align: ::core::mem::align_of::<&'a str>(),
+                offset: ::core::mem::offset_of!(Self, __trait_union_U_data),
+            },
+            ::trait_union::VariantLayout {
+                name: "Option < T >",
+                tag: 1u64,
+                size: ::core::mem::size_of::<Option<T>>(),
+                align: ::core::mem::align_of::<Option<T>>(),
+                offset: ::core::mem::offset_of!(Self, __trait_union_U_data),
+            },
+        ],
+    };
+    /// Whether any variant needs to be dropped
+    ///
+    /// If this is `false`, dropping a value does nothing.
+    pub const NEEDS_DROP: bool = false || ::core::mem::needs_drop::<&'a str>()
+        || ::core::mem::needs_drop::<Option<T>>();
+    /// Returns the layout of this type
+    pub const fn debug_layout() -> ::trait_union::LayoutInfo {
+        Self::__TRAIT_UNION_LAYOUT
+    }
+    #[inline(always)]
+    fn __trait_union_position(&self) -> usize {
+        {
+            #[cfg(debug_assertions)]
+            {
+                if self.__trait_union_U_index == __trait_union_U_Index::Dropped {
+                    ::core::panic!("use of a dropped U");
+                }
+            }
+            self.__trait_union_U_index as usize
+        }
+    }
+    /// Returns the tag of the active variant of `this`
+    #[inline(always)]
+    pub fn tag_of(this: &Self) -> u8 {
+        Self::__TRAIT_UNION_TAGS[this.__trait_union_position()]
+    }
+    /// Returns a reference to the active variant of `this`
+    #[inline(always)]
+    #[allow(dead_code)]
+    fn as_ref_enum<'__trait_union_ref>(
+        this: &'__trait_union_ref Self,
+    ) -> URef<'__trait_union_ref, 'a, T> {
+        this.__trait_union_as_ref_enum()
+    }
+    #[inline(always)]
+    fn __trait_union_as_ref_enum<'__trait_union_ref>(
+        &'__trait_union_ref self,
+    ) -> URef<'__trait_union_ref, 'a, T> {
+        match self.__trait_union_U_index {
+            __trait_union_U_Index::V0 => {
+                let value = unsafe { &*self.__trait_union_U_data.variant0 };
+                URef::AStr(value)
+            }
+            __trait_union_U_Index::V1 => {
+                let value = unsafe { &*self.__trait_union_U_data.variant1 };
+                URef::OptionT(value)
+            }
+            #[cfg(debug_assertions)]
+            __trait_union_U_Index::Dropped => {
+                ::core::panic!("use of a dropped trait union")
+            }
+        }
+    }
+    /// Returns a mutable reference to the active variant of `this`
+    #[inline(always)]
+    #[allow(dead_code)]
+    fn as_mut_enum<'__trait_union_ref>(
+        this: &'__trait_union_ref mut Self,
+    ) -> UMut<'__trait_union_ref, 'a, T> {
+        this.__trait_union_as_mut_enum()
+    }
+    #[inline(always)]
+    #[allow(dead_code)]
+    fn __trait_union_as_mut_enum<'__trait_union_ref>(
+        &'__trait_union_ref mut self,
+    ) -> UMut<'__trait_union_ref, 'a, T> {
+        match self.__trait_union_U_index {
+            __trait_union_U_Index::V0 => {
+                let value = unsafe { &mut *self.__trait_union_U_data.variant0 };
+                UMut::AStr(value)
+            }
+            __trait_union_U_Index::V1 => {
+                let value = unsafe { &mut *self.__trait_union_U_data.variant1 };
+                UMut::OptionT(value)
+            }
+            #[cfg(debug_assertions)]
+            __trait_union_U_Index::Dropped => {
+                ::core::panic!("use of a dropped trait union")
+            }
+        }
+    }
+    /// Moves the active variant out of `this`
+    #[inline(always)]
+    #[allow(dead_code)]
+    fn into_enum(this: Self) -> UOwned<'a, T> {
+        let this = ::core::mem::ManuallyDrop::new(this);
+        unsafe {
+            match Self::as_ref_enum(&this) {
+                URef::AStr(value) => UOwned::AStr(::core::ptr::read(value)),
+                URef::OptionT(value) => UOwned::OptionT(::core::ptr::read(value)),
+            }
+        }
+    }
+    /// Returns the kind of the active variant of `this`
+    #[inline(always)]
+    pub fn kind_of(this: &Self) -> UKind {
+        Self::__TRAIT_UNION_KINDS[this.__trait_union_position()]
+    }
+    /// Returns whether `this` and `other` have the same active variant
+    #[inline(always)]
+    pub fn same_variant(this: &Self, other: &Self) -> bool {
+        this.__trait_union_position() == other.__trait_union_position()
+    }
+    /// Writes the kind of the active variant of `this` followed by its value in
+    /// parentheses
+    ///
+    /// The value is formatted with `Display` if its type implements it, otherwise
+    /// with `Debug`, and otherwise written as `..`. Nothing is allocated.
+    #[inline(always)]
+    pub fn describe(
+        this: &Self,
+        f: &mut ::core::fmt::Formatter<'_>,
+    ) -> ::core::fmt::Result {
+        this.__trait_union_describe(f)
+    }
+    #[inline(always)]
+    fn __trait_union_describe(
+        &self,
+        f: &mut ::core::fmt::Formatter<'_>,
+    ) -> ::core::fmt::Result {
+        #[allow(unused_imports)]
+        use ::trait_union::__support::{DescribeDebug, DescribeDisplay, DescribeOther};
+        ::core::fmt::Debug::fmt(&Self::kind_of(self), f)?;
+        f.write_str("(")?;
+        (match self.__trait_union_U_index {
+            __trait_union_U_Index::V0 => {
+                let value = unsafe { &*self.__trait_union_U_data.variant0 };
+                (&&&::trait_union::__support::Describe(value)).describe_value(f)
+            }
+            __trait_union_U_Index::V1 => {
+                let value = unsafe { &*self.__trait_union_U_data.variant1 };
+                (&&&::trait_union::__support::Describe(value)).describe_value(f)
+            }
+            #[cfg(debug_assertions)]
+            __trait_union_U_Index::Dropped => {
+                ::core::panic!("use of a dropped trait union")
+            }
+        })?;
+        f.write_str(")")
+    }
+    /// Sorts `values` by the declaration order of their active variants
+    ///
+    /// The sort is unstable.
+    #[inline]
+    pub fn sort_by_kind(values: &mut [Self]) {
+        values.sort_unstable_by_key(Self::__trait_union_position);
+    }
+    /// Returns an iterator over the maximal runs of values of the same kind
+    #[inline]
+    pub fn kind_runs(values: &[Self]) -> ::trait_union::KindRuns<'_, Self, UKind> {
+        ::trait_union::KindRuns::new(values, Self::kind_of)
+    }
+    /// Returns whether the active variant of `this` is a zero-sized type
+    ///
+    /// The bytes of a zero-sized variant do not have to be copied.
+    #[inline(always)]
+    pub fn is_zst_variant(this: &Self) -> bool {
+        Self::__TRAIT_UNION_LAYOUT.variants[this.__trait_union_position()].size == 0
+    }
+    /// Creates a new instance
+    #[inline(always)]
+    pub fn new<V: UVariant<'a, T>>(value: V) -> Self {
+        unsafe { Self::__trait_union_new(V::__TRAIT_UNION_INDEX, value) }
+    }
+    /// Creates a new instance of the variant of kind `kind`
+    ///
+    /// # Safety
+    ///
+    /// `V` must be the type of the variant of kind `kind`.
+    #[inline(always)]
+    pub unsafe fn new_unchecked<V>(kind: UKind, value: V) -> Self {
+        #[cfg(debug_assertions)]
+        {
+            let pos = match kind {
+                UKind::AStr => 0usize,
+                UKind::OptionT => 1usize,
+            };
+            let layout = &Self::__TRAIT_UNION_LAYOUT.variants[pos];
+            if ::core::mem::size_of::<V>() != layout.size
+                || ::core::mem::align_of::<V>() != layout.align
+            {
+                ::core::panic!("`V` is not the type of the variant of kind `kind`");
+            }
+        }
+        let value = ::core::mem::ManuallyDrop::new(value);
+        unsafe { Self::new_raw(kind, &*value as *const V as *const u8) }
+    }
+    /// Creates a new instance of the variant of kind `kind` by reading its value from
+    /// `data`
+    ///
+    /// `data` does not have to be aligned.
+    ///
+    /// # Safety
+    ///
+    /// `data` must point to a valid value of the type of the variant of kind `kind`.
+    /// The value is moved out of `data`.
+    #[inline]
+    pub unsafe fn new_raw(kind: UKind, data: *const u8) -> Self {
+        match kind {
+            UKind::AStr => {
+                let value = unsafe {
+                    ::core::ptr::read_unaligned(data as *const &'a str)
+                };
+                unsafe { Self::__trait_union_new(0usize, value) }
+            }
+            UKind::OptionT => {
+                let value = unsafe {
+                    ::core::ptr::read_unaligned(data as *const Option<T>)
+                };
+                unsafe { Self::__trait_union_new(1usize, value) }
+            }
+        }
+    }
+    /// Creates a new instance from the moved-out active variant
+    #[inline]
+    #[allow(dead_code)]
+    fn from_enum(value: UOwned<'a, T>) -> Self {
+        match value {
+            UOwned::AStr(value) => {
+                let value = ::core::mem::ManuallyDrop::new(value);
+                unsafe { Self::new_raw(UKind::AStr, &*value as *const _ as *const u8) }
+            }
+            UOwned::OptionT(value) => {
+                let value = ::core::mem::ManuallyDrop::new(value);
+                unsafe {
+                    Self::new_raw(UKind::OptionT, &*value as *const _ as *const u8)
+                }
+            }
+        }
+    }
+    /// Replaces the value by the result of applying `f` to the active variant
+    ///
+    /// The process is aborted if `f` panics.
+    #[inline]
+    #[allow(dead_code)]
+    fn transition(
+        this: &mut Self,
+        f: impl ::core::ops::FnOnce(UOwned<'a, T>) -> UOwned<'a, T>,
+    ) {
+        let guard = ::trait_union::__support::AbortOnUnwind;
+        unsafe {
+            let value = Self::into_enum(::core::ptr::read(this));
+            ::core::ptr::write(this, Self::from_enum(f(value)));
+        }
+        ::core::mem::forget(guard);
+    }
+}
+impl<'a, T: 'a> ::core::ops::Deref for U<'a, T>
+where
+    T: Copy + Debug,
+{
+    type Target = dyn Debug + 'a;
+    #[inline(always)]
+    fn deref(&self) -> &Self::Target {
+        unsafe { ::core::mem::transmute(self.__trait_union_to_trait_object()) }
+    }
+}
+impl<'a, T: 'a> ::core::ops::DerefMut for U<'a, T>
+where
+    T: Copy + Debug,
+{
+    #[inline(always)]
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        unsafe { ::core::mem::transmute(self.__trait_union_to_trait_object_mut()) }
+    }
+}
+const _: () = {
+    impl<'a, T: 'a> ::core::ops::Drop for U<'a, T>
+    where
+        T: Copy + Debug,
+    {
+        #[inline(always)]
+        fn drop(&mut self) {
+            if Self::NEEDS_DROP {
+                if Self::__TRAIT_UNION_NEEDS_DROP[{
+                    #[cfg(debug_assertions)]
+                    {
+                        if self.__trait_union_U_index == __trait_union_U_Index::Dropped {
+                            ::core::panic!("use of a dropped U");
+                        }
+                    }
+                    self.__trait_union_U_index as usize
+                }]
+                {
+                    unsafe {
+                        let t: &mut (dyn Debug + 'a) = ::core::mem::transmute(
+                            self.__trait_union_to_trait_object_mut(),
+                        );
+                        ::core::ptr::drop_in_place(t);
+                    }
+                }
+            }
+            #[cfg(debug_assertions)]
+            {
+                self.__trait_union_U_index = __trait_union_U_Index::Dropped;
+            }
+        }
+    }
+    impl<'a, T: 'a> U<'a, T>
+    where
+        T: Copy + Debug,
+    {
+        const __TRAIT_UNION_NEEDS_DROP: [bool; 2usize] = [
+            ::core::mem::needs_drop::<&'a str>(),
+            ::core::mem::needs_drop::<Option<T>>(),
+        ];
+        const __TRAIT_UNION_VTABLES: [::trait_union::__support::VtablePtr; 2usize] = [
+            unsafe {
+                ::trait_union::__support::VtablePtr::of(
+                    ::core::ptr::null::<&'a str>() as *const (dyn Debug + '_),
+                )
+            },
+            unsafe {
+                ::trait_union::__support::VtablePtr::of(
+                    ::core::ptr::null::<Option<T>>() as *const (dyn Debug + '_),
+                )
+            },
+        ];
+        #[inline(always)]
+        fn __trait_union_to_trait_object(
+            &self,
+        ) -> ::trait_union::__support::RawTraitObject {
+            let data = &self.__trait_union_U_data as *const _ as *mut ();
+            #[cfg(debug_assertions)]
+            ::trait_union::__support::check_aligned(
+                data,
+                Self::__TRAIT_UNION_LAYOUT.variants[self.__trait_union_position()].align,
+                "misaligned U",
+            );
+            ::trait_union::__support::RawTraitObject {
+                data,
+                vtable: Self::__TRAIT_UNION_VTABLES[self.__trait_union_U_index as usize],
+            }
+        }
+        #[inline(always)]
+        fn __trait_union_to_trait_object_mut(
+            &mut self,
+        ) -> ::trait_union::__support::RawTraitObject {
+            let data = &mut self.__trait_union_U_data as *mut _ as *mut ();
+            #[cfg(debug_assertions)]
+            ::trait_union::__support::check_aligned(
+                data,
+                Self::__TRAIT_UNION_LAYOUT.variants[self.__trait_union_position()].align,
+                "misaligned U",
+            );
+            ::trait_union::__support::RawTraitObject {
+                data,
+                vtable: Self::__TRAIT_UNION_VTABLES[self.__trait_union_U_index as usize],
+            }
+        }
+        #[inline(always)]
+        const fn __trait_union_index(index: usize) -> __trait_union_U_Index {
+            match index {
+                0usize => __trait_union_U_Index::V0,
+                1usize => __trait_union_U_Index::V1,
+                _ => ::core::panic!("invalid variant index"),
+            }
+        }
+        /// Creates a new instance with the variant at position `index`
+        ///
+        /// # Safety
+        ///
+        /// `V` must be the type of the variant at position `index`.
+        #[inline(always)]
+        unsafe fn __trait_union_new<V>(index: usize, value: V) -> Self {
+            #[cfg(debug_assertions)]
+            ::trait_union::__support::check_variant::<
+                V,
+            >(&Self::__TRAIT_UNION_LAYOUT, index, "invalid implementation of UVariant");
+            let mut slf = ::core::mem::MaybeUninit::<Self>::uninit();
+            unsafe {
+                if ::core::mem::size_of::<V>() == 0 {
+                    ::core::mem::forget(value);
+                } else {
+                    ::core::ptr::write(
+                        &mut (*slf.as_mut_ptr()).__trait_union_U_data as *mut _
+                            as *mut V,
+                        value,
+                    );
+                }
+                (*slf.as_mut_ptr())
+                    .__trait_union_U_index = Self::__trait_union_index(index);
+                slf.assume_init()
+            }
+        }
+    }
+    impl<'__trait_union_pin, 'a, T: 'a> ::core::marker::Unpin for U<'a, T>
+    where
+        T: Copy + Debug,
+        ::core::marker::PhantomData<
+            (&'__trait_union_pin (), &'a str, Option<T>),
+        >: ::core::marker::Unpin,
+    {}
+    impl<'a, T: 'a> ::trait_union::Provide for U<'a, T>
+    where
+        T: Copy + Debug,
+    {
+        fn provide<'__trait_union_provide>(
+            &'__trait_union_provide self,
+            request: &mut ::trait_union::Request<'__trait_union_provide>,
+        ) {
+            match self.__trait_union_U_index {
+                __trait_union_U_Index::V0 => {
+                    let value = unsafe { &*self.__trait_union_U_data.variant0 };
+                    {
+                        let _ = value;
+                    }
+                }
+                __trait_union_U_Index::V1 => {
+                    let value = unsafe { &*self.__trait_union_U_data.variant1 };
+                    {
+                        let _ = value;
+                    }
+                }
+                #[cfg(debug_assertions)]
+                __trait_union_U_Index::Dropped => {
+                    ::core::panic!("use of a dropped trait union")
+                }
+            }
+        }
+    }
+    impl<
+        'a,
+        T: 'a,
+        __TraitUnionVariant: UVariant<'a, T>,
+    > ::trait_union::FromVariant<__TraitUnionVariant> for U<'a, T>
+    where
+        T: Copy + Debug,
+    {
+        #[inline(always)]
+        fn from_variant(value: __TraitUnionVariant) -> Self {
+            Self::new(value)
+        }
+    }
+    impl<'a, T: 'a> ::core::convert::From<UOwned<'a, T>> for U<'a, T>
+    where
+        T: Copy + Debug,
+    {
+        #[inline]
+        fn from(value: UOwned<'a, T>) -> Self {
+            Self::from_enum(value)
+        }
+    }
+    impl<'a, T: 'a> ::core::convert::From<U<'a, T>> for UOwned<'a, T>
+    where
+        T: Copy + Debug,
+    {
+        #[inline]
+        fn from(value: U<'a, T>) -> Self {
+            <U<'a, T>>::into_enum(value)
+        }
+    }
+    impl<
+        '__trait_union_ref,
+        '__trait_union_object,
+        'a,
+        T: 'a,
+    > ::core::convert::From<&'__trait_union_ref U<'a, T>>
+    for &'__trait_union_ref (dyn Debug + '__trait_union_object)
+    where
+        T: Copy + Debug,
+        'a: '__trait_union_object,
+    {
+        #[inline(always)]
+        fn from(value: &'__trait_union_ref U<'a, T>) -> Self {
+            &**value
+        }
+    }
+    impl<
+        '__trait_union_ref,
+        '__trait_union_object,
+        'a,
+        T: 'a,
+    > ::core::convert::From<&'__trait_union_ref mut U<'a, T>>
+    for &'__trait_union_ref mut (dyn Debug + '__trait_union_object)
+    where
+        T: Copy + Debug,
+        'a: '__trait_union_object,
+    {
+        #[inline(always)]
+        fn from(value: &'__trait_union_ref mut U<'a, T>) -> Self {
+            &mut **value
+        }
+    }
+    unsafe impl<'a, T: 'a> UVariant<'a, T> for &'a str
+    where
+        T: Copy + Debug,
+    {
+        const __TRAIT_UNION_INDEX: usize = 0usize;
+        const KIND: UKind = UKind::AStr;
+        const SIZE: usize = ::core::mem::size_of::<Self>();
+        const NAME: &'static str = "AStr";
+    }
+    unsafe impl<'a, T: 'a> UVariant<'a, T> for Option<T>
+    where
+        T: Copy + Debug,
+    {
+        const __TRAIT_UNION_INDEX: usize = 1usize;
+        const KIND: UKind = UKind::OptionT;
+        const SIZE: usize = ::core::mem::size_of::<Self>();
+        const NAME: &'static str = "OptionT";
+    }
+};
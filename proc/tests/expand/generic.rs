@@ -0,0 +1,5 @@
+use std::fmt::Debug;
+
+trait_union! {
+    pub union U<'a, T: 'a>: Debug + 'a where T: Copy + Debug = &'a str | Option<T>;
+}
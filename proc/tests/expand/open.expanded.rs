@@ -0,0 +1,129 @@
+// Handler: Debug
+//     open, at most 16 bytes with an alignment of at most 8
+use std::fmt::Debug;
+pub struct Handler {
+    data: ::core::mem::MaybeUninit<__trait_union_Handler_Storage>,
+    cast: unsafe fn(*mut ()) -> *mut (dyn Debug + 'static),
+    _marker: ::core::marker::PhantomData<dyn Debug + 'static>,
+}
+#[doc(hidden)]
+#[allow(non_camel_case_types)]
+#[derive(Copy, Clone)]
+#[repr(C, align(8))]
+struct __trait_union_Handler_Storage([::core::mem::MaybeUninit<u8>; 16]);
+/**Marker trait for types that can be stored in a [Handler]
+
+# Safety
+
+This trait must not be implemented manually. Use `#[derive(TraitUnionVariant)]`.*/
+#[allow(clippy::missing_safety_doc)]
+pub unsafe trait HandlerVariant: Debug + 'static {
+    #[doc(hidden)]
+    const __TRAIT_UNION_OPEN_CHECK: ();
+}
+impl Handler {
+    #[doc(hidden)]
+    pub const __TRAIT_UNION_SIZE: usize = 16;
+    #[doc(hidden)]
+    pub const __TRAIT_UNION_ALIGN: usize = 8;
+    /// Creates a new instance
+    #[inline(always)]
+    pub fn new<V: HandlerVariant>(value: V) -> Self {
+        #[allow(clippy::let_unit_value)]
+        let () = V::__TRAIT_UNION_OPEN_CHECK;
+        unsafe { Self::__trait_union_new(value) }
+    }
+}
+impl<V: HandlerVariant> ::trait_union::FromVariant<V> for Handler {
+    #[inline(always)]
+    fn from_variant(value: V) -> Self {
+        Self::new(value)
+    }
+}
+impl ::core::ops::Deref for Handler {
+    type Target = dyn Debug + 'static;
+    #[inline(always)]
+    fn deref(&self) -> &Self::Target {
+        unsafe { &*(self.cast)(self.data.as_ptr() as *mut ()) }
+    }
+}
+impl ::core::ops::DerefMut for Handler {
+    #[inline(always)]
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        unsafe { &mut *(self.cast)(self.data.as_mut_ptr() as *mut ()) }
+    }
+}
+const _: () = {
+    impl ::core::ops::Drop for Handler {
+        #[inline(always)]
+        fn drop(&mut self) {
+            unsafe {
+                ::core::ptr::drop_in_place(
+                    (self.cast)(self.data.as_mut_ptr() as *mut ()),
+                );
+            }
+        }
+    }
+    impl Handler {
+        /// # Safety
+        ///
+        /// `V` must fit into the storage.
+        #[inline(always)]
+        unsafe fn __trait_union_new<V: Debug + 'static>(value: V) -> Self {
+            let mut data = ::core::mem::MaybeUninit::<
+                __trait_union_Handler_Storage,
+            >::uninit();
+            unsafe {
+                ::core::ptr::write(data.as_mut_ptr() as *mut V, value);
+            }
+            Self {
+                data,
+                cast: Self::__trait_union_cast::<V>,
+                _marker: ::core::marker::PhantomData,
+            }
+        }
+        unsafe fn __trait_union_cast<V: Debug + 'static>(
+            ptr: *mut (),
+        ) -> *mut (dyn Debug + 'static) {
+            ptr as *mut V
+        }
+    }
+    impl<
+        '__trait_union_ref,
+        '__trait_union_object,
+    > ::core::convert::From<&'__trait_union_ref Handler>
+    for &'__trait_union_ref (dyn Debug + '__trait_union_object)
+    where
+        'static: '__trait_union_object,
+    {
+        #[inline(always)]
+        fn from(value: &'__trait_union_ref Handler) -> Self {
+            &**value
+        }
+    }
+    impl<
+        '__trait_union_ref,
+        '__trait_union_object,
+    > ::core::convert::From<&'__trait_union_ref mut Handler>
+    for &'__trait_union_ref mut (dyn Debug + '__trait_union_object)
+    where
+        'static: '__trait_union_object,
+    {
+        #[inline(always)]
+        fn from(value: &'__trait_union_ref mut Handler) -> Self {
+            &mut **value
+        }
+    }
+};
+#[derive(Debug, TraitUnionVariant)]
+#[trait_union(Handler)]
+struct Ping(u64);
+unsafe impl HandlerVariant for Ping {
+    const __TRAIT_UNION_OPEN_CHECK: () = if ::core::mem::size_of::<Self>()
+        > <Handler>::__TRAIT_UNION_SIZE
+        || ::core::mem::align_of::<Self>() > <Handler>::__TRAIT_UNION_ALIGN
+    {
+        ::core::panic!("Ping does not fit into Handler")
+    };
+}
+const _: () = <Ping as HandlerVariant>::__TRAIT_UNION_OPEN_CHECK;
@@ -0,0 +1,10 @@
+use std::fmt::Debug;
+
+trait_union! {
+    #[trait_union(open(size = 16))]
+    pub union Handler: Debug;
+}
+
+#[derive(Debug, TraitUnionVariant)]
+#[trait_union(Handler)]
+struct Ping(u64);
@@ -0,0 +1,1280 @@
+// Tagged: Debug
+//     2 variants stored in a union
+//     0: u8, kind U8, tag 1
+//     1: String, kind Text, tag 2
+// State: Debug
+//     2 variants stored in a union
+//     0: Idle, kind Idle, tag 0
+//     1: Running, kind Running, tag 1
+use std::fmt::Debug;
+#[allow(non_snake_case)]
+pub struct Tagged {
+    __trait_union_Tagged_data: __trait_union_Tagged_Union,
+    __trait_union_Tagged_index: __trait_union_Tagged_Index,
+}
+/**Marker trait for types that can be stored in a [Tagged]
+
+# Safety
+
+This trait must not be implemented manually.*/
+#[allow(clippy::missing_safety_doc)]
+pub unsafe trait TaggedVariant: Debug + 'static {
+    #[doc(hidden)]
+    const __TRAIT_UNION_INDEX: usize;
+    /// The kind of the variant
+    const KIND: TaggedKind;
+    /// The size of the variant in bytes
+    const SIZE: usize;
+    /// The name of the kind of the variant
+    const NAME: &'static str;
+}
+#[repr(C)]
+#[allow(non_snake_case)]
+union __trait_union_Tagged_Union {
+    variant0: ::core::mem::ManuallyDrop<u8>,
+    variant1: ::core::mem::ManuallyDrop<String>,
+}
+#[allow(non_camel_case_types)]
+#[derive(Copy, Clone, PartialEq, Eq)]
+enum __trait_union_Tagged_Index {
+    V0,
+    V1,
+    #[cfg(debug_assertions)]
+    Dropped,
+}
+///A reference to the active variant of a [Tagged]
+#[non_exhaustive]
+#[allow(dead_code)]
+enum TaggedRef<'__trait_union_ref> {
+    ///`u8`
+    U8(&'__trait_union_ref u8),
+    ///`String`
+    Text(&'__trait_union_ref String),
+}
+///A mutable reference to the active variant of a [Tagged]
+#[non_exhaustive]
+#[allow(dead_code)]
+enum TaggedMut<'__trait_union_ref> {
+    ///`u8`
+    U8(&'__trait_union_ref mut u8),
+    ///`String`
+    Text(&'__trait_union_ref mut String),
+}
+///The active variant of a [Tagged] by value
+#[non_exhaustive]
+#[allow(dead_code)]
+enum TaggedOwned {
+    ///`u8`
+    U8(u8),
+    ///`String`
+    Text(String),
+}
+///The kinds of variants of [Tagged]
+#[non_exhaustive]
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+#[repr(u8)]
+pub enum TaggedKind {
+    ///`u8`
+    U8 = 1,
+    ///`String`
+    Text = 2,
+}
+impl Tagged {
+    const __TRAIT_UNION_TAGS: [u8; 2usize] = [1, 2];
+    const __TRAIT_UNION_KINDS: [TaggedKind; 2usize] = [TaggedKind::U8, TaggedKind::Text];
+    /// Constructors of the default values of the variants in declaration order
+    pub(crate) const CONSTRUCTORS: [fn() -> Self; 2usize] = [
+        || <Tagged>::new(<u8 as ::core::default::Default>::default()),
+        || <Tagged>::new(<String as ::core::default::Default>::default()),
+    ];
+    /// Creates the default value of the variant of kind `kind`
+    pub(crate) fn from_kind(kind: TaggedKind) -> Self {
+        let pos = match kind {
+            TaggedKind::U8 => 0usize,
+            TaggedKind::Text => 1usize,
+        };
+        Self::CONSTRUCTORS[pos]()
+    }
+    /// Creates the default value of the variant whose kind is named `name`
+    pub(crate) fn from_kind_name(name: &str) -> ::core::option::Option<Self> {
+        let pos = match name {
+            "U8" => 0usize,
+            "Text" => 1usize,
+            _ => return ::core::option::Option::None,
+        };
+        ::core::option::Option::Some(Self::CONSTRUCTORS[pos]())
+    }
+    const __TRAIT_UNION_LAYOUT: ::trait_union::LayoutInfo = ::trait_union::LayoutInfo {
+        name: "Tagged",
+        size: ::core::mem::size_of::<Self>(),
+        align: ::core::mem::align_of::<Self>(),
+        variants: &[
+            ::trait_union::VariantLayout {
+                name: "u8",
+                tag: 1u64,
+                size: ::core::mem::size_of::<u8>(),
+                align: ::core::mem::align_of::<u8>(),
+                offset: ::core::mem::offset_of!(Self, __trait_union_Tagged_data),
+            },
+            ::trait_union::VariantLayout {
+                name: "String",
+                tag: 2u64,
+                size: ::core::mem::size_of::<String>(),
+                align: ::core::mem::align_of::<String>(),
+                offset: ::core::mem::offset_of!(Self, __trait_union_Tagged_data),
+            },
+        ],
+    };
+    /// Whether any variant needs to be dropped
+    ///
+    /// If this is `false`, dropping a value does nothing.
+    pub const NEEDS_DROP: bool = false || ::core::mem::needs_drop::<u8>()
+        || ::core::mem::needs_drop::<String>();
+    /// Returns the layout of this type
+    pub const fn debug_layout() -> ::trait_union::LayoutInfo {
+        Self::__TRAIT_UNION_LAYOUT
+    }
+    #[inline(always)]
+    fn __trait_union_position(&self) -> usize {
+        {
+            #[cfg(debug_assertions)]
+            {
+                if self.__trait_union_Tagged_index == __trait_union_Tagged_Index::Dropped
+                {
+                    ::core::panic!("use of a dropped Tagged");
+                }
+            }
+            self.__trait_union_Tagged_index as usize
+        }
+    }
+    /// Returns the tag of the active variant of `this`
+    #[inline(always)]
+    pub fn tag_of(this: &Self) -> u8 {
+        Self::__TRAIT_UNION_TAGS[this.__trait_union_position()]
+    }
+    /// Returns a reference to the active variant of `this`
+    #[inline(always)]
+    #[allow(dead_code)]
+    fn as_ref_enum<'__trait_union_ref>(
+        this: &'__trait_union_ref Self,
+    ) -> TaggedRef<'__trait_union_ref> {
+        this.__trait_union_as_ref_enum()
+    }
+    #[inline(always)]
+    fn __trait_union_as_ref_enum<'__trait_union_ref>(
+        &'__trait_union_ref self,
+    ) -> TaggedRef<'__trait_union_ref> {
+        match self.__trait_union_Tagged_index {
+            __trait_union_Tagged_Index::V0 => {
+                let value = unsafe { &*self.__trait_union_Tagged_data.variant0 };
+                TaggedRef::U8(value)
+            }
+            __trait_union_Tagged_Index::V1 => {
+                let value = unsafe { &*self.__trait_union_Tagged_data.variant1 };
+                TaggedRef::Text(value)
+            }
+            #[cfg(debug_assertions)]
+            __trait_union_Tagged_Index::Dropped => {
+                ::core::panic!("use of a dropped trait union")
+            }
+        }
+    }
+    /// Returns a mutable reference to the active variant of `this`
+    #[inline(always)]
+    #[allow(dead_code)]
+    fn as_mut_enum<'__trait_union_ref>(
+        this: &'__trait_union_ref mut Self,
+    ) -> TaggedMut<'__trait_union_ref> {
+        this.__trait_union_as_mut_enum()
+    }
+    #[inline(always)]
+    #[allow(dead_code)]
+    fn __trait_union_as_mut_enum<'__trait_union_ref>(
+        &'__trait_union_ref mut self,
+    ) -> TaggedMut<'__trait_union_ref> {
+        match self.__trait_union_Tagged_index {
+            __trait_union_Tagged_Index::V0 => {
+                let value = unsafe { &mut *self.__trait_union_Tagged_data.variant0 };
+                TaggedMut::U8(value)
+            }
+            __trait_union_Tagged_Index::V1 => {
+                let value = unsafe { &mut *self.__trait_union_Tagged_data.variant1 };
+                TaggedMut::Text(value)
+            }
+            #[cfg(debug_assertions)]
+            __trait_union_Tagged_Index::Dropped => {
+                ::core::panic!("use of a dropped trait union")
+            }
+        }
+    }
+    /// Moves the active variant out of `this`
+    #[inline(always)]
+    #[allow(dead_code)]
+    fn into_enum(this: Self) -> TaggedOwned {
+        let this = ::core::mem::ManuallyDrop::new(this);
+        unsafe {
+            match Self::as_ref_enum(&this) {
+                TaggedRef::U8(value) => TaggedOwned::U8(::core::ptr::read(value)),
+                TaggedRef::Text(value) => TaggedOwned::Text(::core::ptr::read(value)),
+            }
+        }
+    }
+    /// Returns the kind of the active variant of `this`
+    #[inline(always)]
+    pub fn kind_of(this: &Self) -> TaggedKind {
+        Self::__TRAIT_UNION_KINDS[this.__trait_union_position()]
+    }
+    /// Returns whether `this` and `other` have the same active variant
+    #[inline(always)]
+    pub fn same_variant(this: &Self, other: &Self) -> bool {
+        this.__trait_union_position() == other.__trait_union_position()
+    }
+    /// Writes the kind of the active variant of `this` followed by its value in
+    /// parentheses
+    ///
+    /// The value is formatted with `Display` if its type implements it, otherwise
+    /// with `Debug`, and otherwise written as `..`. Nothing is allocated.
+    #[inline(always)]
+    pub fn describe(
+        this: &Self,
+        f: &mut ::core::fmt::Formatter<'_>,
+    ) -> ::core::fmt::Result {
+        this.__trait_union_describe(f)
+    }
+    #[inline(always)]
+    fn __trait_union_describe(
+        &self,
+        f: &mut ::core::fmt::Formatter<'_>,
+    ) -> ::core::fmt::Result {
+        #[allow(unused_imports)]
+        use ::trait_union::__support::{DescribeDebug, DescribeDisplay, DescribeOther};
+        ::core::fmt::Debug::fmt(&Self::kind_of(self), f)?;
+        f.write_str("(")?;
+        (match self.__trait_union_Tagged_index {
+            __trait_union_Tagged_Index::V0 => {
+                let value = unsafe { &*self.__trait_union_Tagged_data.variant0 };
+                (&&&::trait_union::__support::Describe(value)).describe_value(f)
+            }
+            __trait_union_Tagged_Index::V1 => {
+                let value = unsafe { &*self.__trait_union_Tagged_data.variant1 };
+                (&&&::trait_union::__support::Describe(value)).describe_value(f)
+            }
+            #[cfg(debug_assertions)]
+            __trait_union_Tagged_Index::Dropped => {
+                ::core::panic!("use of a dropped trait union")
+            }
+        })?;
+        f.write_str(")")
+    }
+    /// Sorts `values` by the declaration order of their active variants
+    ///
+    /// The sort is unstable.
+    #[inline]
+    pub fn sort_by_kind(values: &mut [Self]) {
+        values.sort_unstable_by_key(Self::__trait_union_position);
+    }
+    /// Returns an iterator over the maximal runs of values of the same kind
+    #[inline]
+    pub fn kind_runs(values: &[Self]) -> ::trait_union::KindRuns<'_, Self, TaggedKind> {
+        ::trait_union::KindRuns::new(values, Self::kind_of)
+    }
+    /// Returns whether the active variant of `this` is a zero-sized type
+    ///
+    /// The bytes of a zero-sized variant do not have to be copied.
+    #[inline(always)]
+    pub fn is_zst_variant(this: &Self) -> bool {
+        Self::__TRAIT_UNION_LAYOUT.variants[this.__trait_union_position()].size == 0
+    }
+    /// Creates a new instance
+    #[inline(always)]
+    pub(crate) fn new<V: TaggedVariant>(value: V) -> Self {
+        unsafe { Self::__trait_union_new(V::__TRAIT_UNION_INDEX, value) }
+    }
+    /// Creates a new instance of the variant of kind `kind`
+    ///
+    /// # Safety
+    ///
+    /// `V` must be the type of the variant of kind `kind`.
+    #[inline(always)]
+    pub(crate) unsafe fn new_unchecked<V>(kind: TaggedKind, value: V) -> Self {
+        #[cfg(debug_assertions)]
+        {
+            let pos = match kind {
+                TaggedKind::U8 => 0usize,
+                TaggedKind::Text => 1usize,
+            };
+            let layout = &Self::__TRAIT_UNION_LAYOUT.variants[pos];
+            if ::core::mem::size_of::<V>() != layout.size
+                || ::core::mem::align_of::<V>() != layout.align
+            {
+                ::core::panic!("`V` is not the type of the variant of kind `kind`");
+            }
+        }
+        let value = ::core::mem::ManuallyDrop::new(value);
+        unsafe { Self::new_raw(kind, &*value as *const V as *const u8) }
+    }
+    /// Creates a new instance of the variant of kind `kind` by reading its value from
+    /// `data`
+    ///
+    /// `data` does not have to be aligned.
+    ///
+    /// # Safety
+    ///
+    /// `data` must point to a valid value of the type of the variant of kind `kind`.
+    /// The value is moved out of `data`.
+    #[inline]
+    pub(crate) unsafe fn new_raw(kind: TaggedKind, data: *const u8) -> Self {
+        match kind {
+            TaggedKind::U8 => {
+                let value = unsafe { ::core::ptr::read_unaligned(data as *const u8) };
+                unsafe { Self::__trait_union_new(0usize, value) }
+            }
+            TaggedKind::Text => {
+                let value = unsafe {
+                    ::core::ptr::read_unaligned(data as *const String)
+                };
+                unsafe { Self::__trait_union_new(1usize, value) }
+            }
+        }
+    }
+    /// Creates a new instance from the moved-out active variant
+    #[inline]
+    #[allow(dead_code)]
+    fn from_enum(value: TaggedOwned) -> Self {
+        match value {
+            TaggedOwned::U8(value) => {
+                let value = ::core::mem::ManuallyDrop::new(value);
+                unsafe {
+                    Self::new_raw(TaggedKind::U8, &*value as *const _ as *const u8)
+                }
+            }
+            TaggedOwned::Text(value) => {
+                let value = ::core::mem::ManuallyDrop::new(value);
+                unsafe {
+                    Self::new_raw(TaggedKind::Text, &*value as *const _ as *const u8)
+                }
+            }
+        }
+    }
+    /// Replaces the value by the result of applying `f` to the active variant
+    ///
+    /// The process is aborted if `f` panics.
+    #[inline]
+    #[allow(dead_code)]
+    fn transition(
+        this: &mut Self,
+        f: impl ::core::ops::FnOnce(TaggedOwned) -> TaggedOwned,
+    ) {
+        let guard = ::trait_union::__support::AbortOnUnwind;
+        unsafe {
+            let value = Self::into_enum(::core::ptr::read(this));
+            ::core::ptr::write(this, Self::from_enum(f(value)));
+        }
+        ::core::mem::forget(guard);
+    }
+    /// Moves the active variant into another union with a variant of its type
+    ///
+    /// The value is returned unchanged if the other union has no such variant.
+    #[inline]
+    pub fn move_variant<__TraitUnionTarget: ::trait_union::__support::AcceptVariant>(
+        self,
+    ) -> ::core::result::Result<__TraitUnionTarget, Self> {
+        match Self::into_enum(self) {
+            TaggedOwned::U8(value) => {
+                let value = ::core::mem::ManuallyDrop::new(value);
+                let id = ::core::any::TypeId::of::<u8>();
+                match unsafe {
+                    __TraitUnionTarget::__trait_union_accept(
+                        id,
+                        &*value as *const _ as *const u8,
+                    )
+                } {
+                    ::core::option::Option::Some(target) => {
+                        ::core::result::Result::Ok(target)
+                    }
+                    ::core::option::Option::None => {
+                        let value = ::core::mem::ManuallyDrop::into_inner(value);
+                        ::core::result::Result::Err(
+                            Self::from_enum(TaggedOwned::U8(value)),
+                        )
+                    }
+                }
+            }
+            TaggedOwned::Text(value) => {
+                let value = ::core::mem::ManuallyDrop::new(value);
+                let id = ::core::any::TypeId::of::<String>();
+                match unsafe {
+                    __TraitUnionTarget::__trait_union_accept(
+                        id,
+                        &*value as *const _ as *const u8,
+                    )
+                } {
+                    ::core::option::Option::Some(target) => {
+                        ::core::result::Result::Ok(target)
+                    }
+                    ::core::option::Option::None => {
+                        let value = ::core::mem::ManuallyDrop::into_inner(value);
+                        ::core::result::Result::Err(
+                            Self::from_enum(TaggedOwned::Text(value)),
+                        )
+                    }
+                }
+            }
+        }
+    }
+}
+impl ::core::ops::Deref for Tagged {
+    type Target = dyn Debug + 'static;
+    #[inline(always)]
+    fn deref(&self) -> &Self::Target {
+        unsafe { ::core::mem::transmute(self.__trait_union_to_trait_object()) }
+    }
+}
+impl ::core::ops::DerefMut for Tagged {
+    #[inline(always)]
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        unsafe { ::core::mem::transmute(self.__trait_union_to_trait_object_mut()) }
+    }
+}
+const _: () = {
+    impl ::core::ops::Drop for Tagged {
+        #[inline(always)]
+        fn drop(&mut self) {
+            if Self::NEEDS_DROP {
+                if Self::__TRAIT_UNION_NEEDS_DROP[{
+                    #[cfg(debug_assertions)]
+                    {
+                        if self.__trait_union_Tagged_index
+                            == __trait_union_Tagged_Index::Dropped
+                        {
+                            ::core::panic!("use of a dropped Tagged");
+                        }
+                    }
+                    self.__trait_union_Tagged_index as usize
+                }]
+                {
+                    unsafe {
+                        let t: &mut (dyn Debug + 'static) = ::core::mem::transmute(
+                            self.__trait_union_to_trait_object_mut(),
+                        );
+                        ::core::ptr::drop_in_place(t);
+                    }
+                }
+            }
+            #[cfg(debug_assertions)]
+            {
+                self.__trait_union_Tagged_index = __trait_union_Tagged_Index::Dropped;
+            }
+        }
+    }
+    impl Tagged {
+        const __TRAIT_UNION_NEEDS_DROP: [bool; 2usize] = [
+            ::core::mem::needs_drop::<u8>(),
+            ::core::mem::needs_drop::<String>(),
+        ];
+        const __TRAIT_UNION_VTABLES: [::trait_union::__support::VtablePtr; 2usize] = [
+            unsafe {
+                ::trait_union::__support::VtablePtr::of(
+                    ::core::ptr::null::<u8>() as *const (dyn Debug + '_),
+                )
+            },
+            unsafe {
+                ::trait_union::__support::VtablePtr::of(
+                    ::core::ptr::null::<String>() as *const (dyn Debug + '_),
+                )
+            },
+        ];
+        #[inline(always)]
+        fn __trait_union_to_trait_object(
+            &self,
+        ) -> ::trait_union::__support::RawTraitObject {
+            let data = &self.__trait_union_Tagged_data as *const _ as *mut ();
+            #[cfg(debug_assertions)]
+            ::trait_union::__support::check_aligned(
+                data,
+                Self::__TRAIT_UNION_LAYOUT.variants[self.__trait_union_position()].align,
+                "misaligned Tagged",
+            );
+            ::trait_union::__support::RawTraitObject {
+                data,
+                vtable: Self::__TRAIT_UNION_VTABLES[self.__trait_union_Tagged_index
+                    as usize],
+            }
+        }
+        #[inline(always)]
+        fn __trait_union_to_trait_object_mut(
+            &mut self,
+        ) -> ::trait_union::__support::RawTraitObject {
+            let data = &mut self.__trait_union_Tagged_data as *mut _ as *mut ();
+            #[cfg(debug_assertions)]
+            ::trait_union::__support::check_aligned(
+                data,
+                Self::__TRAIT_UNION_LAYOUT.variants[self.__trait_union_position()].align,
+                "misaligned Tagged",
+            );
+            ::trait_union::__support::RawTraitObject {
+                data,
+                vtable: Self::__TRAIT_UNION_VTABLES[self.__trait_union_Tagged_index
+                    as usize],
+            }
+        }
+        #[inline(always)]
+        const fn __trait_union_index(index: usize) -> __trait_union_Tagged_Index {
+            match index {
+                0usize => __trait_union_Tagged_Index::V0,
+                1usize => __trait_union_Tagged_Index::V1,
+                _ => ::core::panic!("invalid variant index"),
+            }
+        }
+        /// Creates a new instance with the variant at position `index`
+        ///
+        /// # Safety
+        ///
+        /// `V` must be the type of the variant at position `index`.
+        #[inline(always)]
+        unsafe fn __trait_union_new<V>(index: usize, value: V) -> Self {
+            #[cfg(debug_assertions)]
+            ::trait_union::__support::check_variant::<
+                V,
+            >(
+                &Self::__TRAIT_UNION_LAYOUT,
+                index,
+                "invalid implementation of TaggedVariant",
+            );
+            let mut slf = ::core::mem::MaybeUninit::<Self>::uninit();
+            unsafe {
+                if ::core::mem::size_of::<V>() == 0 {
+                    ::core::mem::forget(value);
+                } else {
+                    ::core::ptr::write(
+                        &mut (*slf.as_mut_ptr()).__trait_union_Tagged_data as *mut _
+                            as *mut V,
+                        value,
+                    );
+                }
+                (*slf.as_mut_ptr())
+                    .__trait_union_Tagged_index = Self::__trait_union_index(index);
+                slf.assume_init()
+            }
+        }
+    }
+    impl<'__trait_union_pin> ::core::marker::Unpin for Tagged
+    where
+        ::core::marker::PhantomData<
+            (&'__trait_union_pin (), u8, String),
+        >: ::core::marker::Unpin,
+    {}
+    impl ::trait_union::Provide for Tagged {
+        fn provide<'__trait_union_provide>(
+            &'__trait_union_provide self,
+            request: &mut ::trait_union::Request<'__trait_union_provide>,
+        ) {
+            match self.__trait_union_Tagged_index {
+                __trait_union_Tagged_Index::V0 => {
+                    let value = unsafe { &*self.__trait_union_Tagged_data.variant0 };
+                    {
+                        let _ = value;
+                    }
+                }
+                __trait_union_Tagged_Index::V1 => {
+                    let value = unsafe { &*self.__trait_union_Tagged_data.variant1 };
+                    {
+                        let _ = value;
+                    }
+                }
+                #[cfg(debug_assertions)]
+                __trait_union_Tagged_Index::Dropped => {
+                    ::core::panic!("use of a dropped trait union")
+                }
+            }
+        }
+    }
+    impl ::core::convert::From<Tagged> for TaggedOwned {
+        #[inline]
+        fn from(value: Tagged) -> Self {
+            <Tagged>::into_enum(value)
+        }
+    }
+    impl<
+        '__trait_union_ref,
+        '__trait_union_object,
+    > ::core::convert::From<&'__trait_union_ref Tagged>
+    for &'__trait_union_ref (dyn Debug + '__trait_union_object)
+    where
+        'static: '__trait_union_object,
+    {
+        #[inline(always)]
+        fn from(value: &'__trait_union_ref Tagged) -> Self {
+            &**value
+        }
+    }
+    impl<
+        '__trait_union_ref,
+        '__trait_union_object,
+    > ::core::convert::From<&'__trait_union_ref mut Tagged>
+    for &'__trait_union_ref mut (dyn Debug + '__trait_union_object)
+    where
+        'static: '__trait_union_object,
+    {
+        #[inline(always)]
+        fn from(value: &'__trait_union_ref mut Tagged) -> Self {
+            &mut **value
+        }
+    }
+    unsafe impl TaggedVariant for u8 {
+        const __TRAIT_UNION_INDEX: usize = 0usize;
+        const KIND: TaggedKind = TaggedKind::U8;
+        const SIZE: usize = ::core::mem::size_of::<Self>();
+        const NAME: &'static str = "U8";
+    }
+    unsafe impl TaggedVariant for String {
+        const __TRAIT_UNION_INDEX: usize = 1usize;
+        const KIND: TaggedKind = TaggedKind::Text;
+        const SIZE: usize = ::core::mem::size_of::<Self>();
+        const NAME: &'static str = "Text";
+    }
+};
+#[allow(non_snake_case)]
+struct State {
+    __trait_union_State_data: __trait_union_State_Union,
+    __trait_union_State_index: __trait_union_State_Index,
+}
+/**Marker trait for types that can be stored in a [State]
+
+# Safety
+
+This trait must not be implemented manually.*/
+#[allow(clippy::missing_safety_doc)]
+unsafe trait StateVariant: Debug + 'static {
+    #[doc(hidden)]
+    const __TRAIT_UNION_INDEX: usize;
+    /// The kind of the variant
+    const KIND: StateKind;
+    /// The size of the variant in bytes
+    const SIZE: usize;
+    /// The name of the kind of the variant
+    const NAME: &'static str;
+}
+#[repr(C)]
+#[allow(non_snake_case)]
+union __trait_union_State_Union {
+    variant0: ::core::mem::ManuallyDrop<Idle>,
+    variant1: ::core::mem::ManuallyDrop<Running>,
+}
+#[allow(non_camel_case_types)]
+#[derive(Copy, Clone, PartialEq, Eq)]
+enum __trait_union_State_Index {
+    V0,
+    V1,
+    #[cfg(debug_assertions)]
+    Dropped,
+}
+///A reference to the active variant of a [State]
+#[allow(dead_code)]
+enum StateRef<'__trait_union_ref> {
+    ///`Idle`
+    Idle(&'__trait_union_ref Idle),
+    ///`Running`
+    Running(&'__trait_union_ref Running),
+}
+///A mutable reference to the active variant of a [State]
+#[allow(dead_code)]
+enum StateMut<'__trait_union_ref> {
+    ///`Idle`
+    Idle(&'__trait_union_ref mut Idle),
+    ///`Running`
+    Running(&'__trait_union_ref mut Running),
+}
+///The active variant of a [State] by value
+#[allow(dead_code)]
+enum StateOwned {
+    ///`Idle`
+    Idle(Idle),
+    ///`Running`
+    Running(Running),
+}
+///The kinds of variants of [State]
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+#[repr(u8)]
+enum StateKind {
+    ///`Idle`
+    Idle = 0,
+    ///`Running`
+    Running = 1,
+}
+impl State {
+    const __TRAIT_UNION_TAGS: [u8; 2usize] = [0, 1];
+    const __TRAIT_UNION_KINDS: [StateKind; 2usize] = [
+        StateKind::Idle,
+        StateKind::Running,
+    ];
+    const __TRAIT_UNION_LAYOUT: ::trait_union::LayoutInfo = ::trait_union::LayoutInfo {
+        name: "State",
+        size: ::core::mem::size_of::<Self>(),
+        align: ::core::mem::align_of::<Self>(),
+        variants: &[
+            ::trait_union::VariantLayout {
+                name: "Idle",
+                tag: 0u64,
+                size: ::core::mem::size_of::<Idle>(),
+                align: ::core::mem::align_of::<Idle>(),
+                offset: ::core::mem::offset_of!(Self, __trait_union_State_data),
+            },
+            ::trait_union::VariantLayout {
+                name: "Running",
+                tag: 1u64,
+                size: ::core::mem::size_of::<Running>(),
+                align: ::core::mem::align_of::<Running>(),
+                offset: ::core::mem::offset_of!(Self, __trait_union_State_data),
+            },
+        ],
+    };
+    /// Whether any variant needs to be dropped
+    ///
+    /// If this is `false`, dropping a value does nothing.
+    const NEEDS_DROP: bool = false || ::core::mem::needs_drop::<Idle>()
+        || ::core::mem::needs_drop::<Running>();
+    /// Returns the layout of this type
+    const fn debug_layout() -> ::trait_union::LayoutInfo {
+        Self::__TRAIT_UNION_LAYOUT
+    }
+    #[inline(always)]
+    fn __trait_union_position(&self) -> usize {
+        {
+            #[cfg(debug_assertions)]
+            {
+                if self.__trait_union_State_index == __trait_union_State_Index::Dropped {
+                    ::core::panic!("use of a dropped State");
+                }
+            }
+            self.__trait_union_State_index as usize
+        }
+    }
+    /// Returns the tag of the active variant of `this`
+    #[inline(always)]
+    fn tag_of(this: &Self) -> u8 {
+        Self::__TRAIT_UNION_TAGS[this.__trait_union_position()]
+    }
+    /// Returns a reference to the active variant of `this`
+    #[inline(always)]
+    #[allow(dead_code)]
+    fn as_ref_enum<'__trait_union_ref>(
+        this: &'__trait_union_ref Self,
+    ) -> StateRef<'__trait_union_ref> {
+        this.__trait_union_as_ref_enum()
+    }
+    #[inline(always)]
+    fn __trait_union_as_ref_enum<'__trait_union_ref>(
+        &'__trait_union_ref self,
+    ) -> StateRef<'__trait_union_ref> {
+        match self.__trait_union_State_index {
+            __trait_union_State_Index::V0 => {
+                let value = unsafe { &*self.__trait_union_State_data.variant0 };
+                StateRef::Idle(value)
+            }
+            __trait_union_State_Index::V1 => {
+                let value = unsafe { &*self.__trait_union_State_data.variant1 };
+                StateRef::Running(value)
+            }
+            #[cfg(debug_assertions)]
+            __trait_union_State_Index::Dropped => {
+                ::core::panic!("use of a dropped trait union")
+            }
+        }
+    }
+    /// Returns a mutable reference to the active variant of `this`
+    #[inline(always)]
+    #[allow(dead_code)]
+    fn as_mut_enum<'__trait_union_ref>(
+        this: &'__trait_union_ref mut Self,
+    ) -> StateMut<'__trait_union_ref> {
+        this.__trait_union_as_mut_enum()
+    }
+    #[inline(always)]
+    #[allow(dead_code)]
+    fn __trait_union_as_mut_enum<'__trait_union_ref>(
+        &'__trait_union_ref mut self,
+    ) -> StateMut<'__trait_union_ref> {
+        match self.__trait_union_State_index {
+            __trait_union_State_Index::V0 => {
+                let value = unsafe { &mut *self.__trait_union_State_data.variant0 };
+                StateMut::Idle(value)
+            }
+            __trait_union_State_Index::V1 => {
+                let value = unsafe { &mut *self.__trait_union_State_data.variant1 };
+                StateMut::Running(value)
+            }
+            #[cfg(debug_assertions)]
+            __trait_union_State_Index::Dropped => {
+                ::core::panic!("use of a dropped trait union")
+            }
+        }
+    }
+    /// Moves the active variant out of `this`
+    #[inline(always)]
+    #[allow(dead_code)]
+    fn into_enum(this: Self) -> StateOwned {
+        let this = ::core::mem::ManuallyDrop::new(this);
+        unsafe {
+            match Self::as_ref_enum(&this) {
+                StateRef::Idle(value) => StateOwned::Idle(::core::ptr::read(value)),
+                StateRef::Running(value) => StateOwned::Running(::core::ptr::read(value)),
+            }
+        }
+    }
+    /// Returns the kind of the active variant of `this`
+    #[inline(always)]
+    fn kind_of(this: &Self) -> StateKind {
+        Self::__TRAIT_UNION_KINDS[this.__trait_union_position()]
+    }
+    /// Returns whether `this` and `other` have the same active variant
+    #[inline(always)]
+    fn same_variant(this: &Self, other: &Self) -> bool {
+        this.__trait_union_position() == other.__trait_union_position()
+    }
+    /// Writes the kind of the active variant of `this` followed by its value in
+    /// parentheses
+    ///
+    /// The value is formatted with `Display` if its type implements it, otherwise
+    /// with `Debug`, and otherwise written as `..`. Nothing is allocated.
+    #[inline(always)]
+    fn describe(this: &Self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+        this.__trait_union_describe(f)
+    }
+    #[inline(always)]
+    fn __trait_union_describe(
+        &self,
+        f: &mut ::core::fmt::Formatter<'_>,
+    ) -> ::core::fmt::Result {
+        #[allow(unused_imports)]
+        use ::trait_union::__support::{DescribeDebug, DescribeDisplay, DescribeOther};
+        ::core::fmt::Debug::fmt(&Self::kind_of(self), f)?;
+        f.write_str("(")?;
+        (match self.__trait_union_State_index {
+            __trait_union_State_Index::V0 => {
+                let value = unsafe { &*self.__trait_union_State_data.variant0 };
+                (&&&::trait_union::__support::Describe(value)).describe_value(f)
+            }
+            __trait_union_State_Index::V1 => {
+                let value = unsafe { &*self.__trait_union_State_data.variant1 };
+                (&&&::trait_union::__support::Describe(value)).describe_value(f)
+            }
+            #[cfg(debug_assertions)]
+            __trait_union_State_Index::Dropped => {
+                ::core::panic!("use of a dropped trait union")
+            }
+        })?;
+        f.write_str(")")
+    }
+    /// Sorts `values` by the declaration order of their active variants
+    ///
+    /// The sort is unstable.
+    #[inline]
+    fn sort_by_kind(values: &mut [Self]) {
+        values.sort_unstable_by_key(Self::__trait_union_position);
+    }
+    /// Returns an iterator over the maximal runs of values of the same kind
+    #[inline]
+    fn kind_runs(values: &[Self]) -> ::trait_union::KindRuns<'_, Self, StateKind> {
+        ::trait_union::KindRuns::new(values, Self::kind_of)
+    }
+    /// Returns whether the active variant of `this` is a zero-sized type
+    ///
+    /// The bytes of a zero-sized variant do not have to be copied.
+    #[inline(always)]
+    fn is_zst_variant(this: &Self) -> bool {
+        Self::__TRAIT_UNION_LAYOUT.variants[this.__trait_union_position()].size == 0
+    }
+    /// Creates a new instance
+    #[inline(always)]
+    fn new<V: StateVariant>(value: V) -> Self {
+        unsafe { Self::__trait_union_new(V::__TRAIT_UNION_INDEX, value) }
+    }
+    /// Creates a new instance of the variant of kind `kind`
+    ///
+    /// # Safety
+    ///
+    /// `V` must be the type of the variant of kind `kind`.
+    #[inline(always)]
+    unsafe fn new_unchecked<V>(kind: StateKind, value: V) -> Self {
+        #[cfg(debug_assertions)]
+        {
+            let pos = match kind {
+                StateKind::Idle => 0usize,
+                StateKind::Running => 1usize,
+            };
+            let layout = &Self::__TRAIT_UNION_LAYOUT.variants[pos];
+            if ::core::mem::size_of::<V>() != layout.size
+                || ::core::mem::align_of::<V>() != layout.align
+            {
+                ::core::panic!("`V` is not the type of the variant of kind `kind`");
+            }
+        }
+        let value = ::core::mem::ManuallyDrop::new(value);
+        unsafe { Self::new_raw(kind, &*value as *const V as *const u8) }
+    }
+    /// Creates a new instance of the variant of kind `kind` by reading its value from
+    /// `data`
+    ///
+    /// `data` does not have to be aligned.
+    ///
+    /// # Safety
+    ///
+    /// `data` must point to a valid value of the type of the variant of kind `kind`.
+    /// The value is moved out of `data`.
+    #[inline]
+    unsafe fn new_raw(kind: StateKind, data: *const u8) -> Self {
+        match kind {
+            StateKind::Idle => {
+                let value = unsafe { ::core::ptr::read_unaligned(data as *const Idle) };
+                unsafe { Self::__trait_union_new(0usize, value) }
+            }
+            StateKind::Running => {
+                let value = unsafe {
+                    ::core::ptr::read_unaligned(data as *const Running)
+                };
+                unsafe { Self::__trait_union_new(1usize, value) }
+            }
+        }
+    }
+    /// Replaces the value by the default variant and returns the previous value
+    #[inline]
+    fn take(&mut self) -> Self {
+        ::core::mem::take(self)
+    }
+    /// Creates a new instance from the moved-out active variant
+    #[inline]
+    #[allow(dead_code)]
+    fn from_enum(value: StateOwned) -> Self {
+        match value {
+            StateOwned::Idle(value) => {
+                let value = ::core::mem::ManuallyDrop::new(value);
+                unsafe {
+                    Self::new_raw(StateKind::Idle, &*value as *const _ as *const u8)
+                }
+            }
+            StateOwned::Running(value) => {
+                let value = ::core::mem::ManuallyDrop::new(value);
+                unsafe {
+                    Self::new_raw(StateKind::Running, &*value as *const _ as *const u8)
+                }
+            }
+        }
+    }
+    /// Replaces the value by the result of applying `f` to the active variant
+    ///
+    /// The process is aborted if `f` panics.
+    #[inline]
+    #[allow(dead_code)]
+    fn transition(
+        this: &mut Self,
+        f: impl ::core::ops::FnOnce(StateOwned) -> StateOwned,
+    ) {
+        let guard = ::trait_union::__support::AbortOnUnwind;
+        unsafe {
+            let value = Self::into_enum(::core::ptr::read(this));
+            ::core::ptr::write(this, Self::from_enum(f(value)));
+        }
+        ::core::mem::forget(guard);
+    }
+    /// Moves the active variant into another union with a variant of its type
+    ///
+    /// The value is returned unchanged if the other union has no such variant.
+    #[inline]
+    fn move_variant<__TraitUnionTarget: ::trait_union::__support::AcceptVariant>(
+        self,
+    ) -> ::core::result::Result<__TraitUnionTarget, Self> {
+        match Self::into_enum(self) {
+            StateOwned::Idle(value) => {
+                let value = ::core::mem::ManuallyDrop::new(value);
+                let id = ::core::any::TypeId::of::<Idle>();
+                match unsafe {
+                    __TraitUnionTarget::__trait_union_accept(
+                        id,
+                        &*value as *const _ as *const u8,
+                    )
+                } {
+                    ::core::option::Option::Some(target) => {
+                        ::core::result::Result::Ok(target)
+                    }
+                    ::core::option::Option::None => {
+                        let value = ::core::mem::ManuallyDrop::into_inner(value);
+                        ::core::result::Result::Err(
+                            Self::from_enum(StateOwned::Idle(value)),
+                        )
+                    }
+                }
+            }
+            StateOwned::Running(value) => {
+                let value = ::core::mem::ManuallyDrop::new(value);
+                let id = ::core::any::TypeId::of::<Running>();
+                match unsafe {
+                    __TraitUnionTarget::__trait_union_accept(
+                        id,
+                        &*value as *const _ as *const u8,
+                    )
+                } {
+                    ::core::option::Option::Some(target) => {
+                        ::core::result::Result::Ok(target)
+                    }
+                    ::core::option::Option::None => {
+                        let value = ::core::mem::ManuallyDrop::into_inner(value);
+                        ::core::result::Result::Err(
+                            Self::from_enum(StateOwned::Running(value)),
+                        )
+                    }
+                }
+            }
+        }
+    }
+}
+impl ::core::ops::Deref for State {
+    type Target = dyn Debug + 'static;
+    #[inline(always)]
+    fn deref(&self) -> &Self::Target {
+        unsafe { ::core::mem::transmute(self.__trait_union_to_trait_object()) }
+    }
+}
+impl ::core::ops::DerefMut for State {
+    #[inline(always)]
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        unsafe { ::core::mem::transmute(self.__trait_union_to_trait_object_mut()) }
+    }
+}
+const _: () = {
+    impl ::core::ops::Drop for State {
+        #[inline(always)]
+        fn drop(&mut self) {
+            if Self::NEEDS_DROP {
+                if Self::__TRAIT_UNION_NEEDS_DROP[{
+                    #[cfg(debug_assertions)]
+                    {
+                        if self.__trait_union_State_index
+                            == __trait_union_State_Index::Dropped
+                        {
+                            ::core::panic!("use of a dropped State");
+                        }
+                    }
+                    self.__trait_union_State_index as usize
+                }]
+                {
+                    unsafe {
+                        let t: &mut (dyn Debug + 'static) = ::core::mem::transmute(
+                            self.__trait_union_to_trait_object_mut(),
+                        );
+                        ::core::ptr::drop_in_place(t);
+                    }
+                }
+            }
+            #[cfg(debug_assertions)]
+            {
+                self.__trait_union_State_index = __trait_union_State_Index::Dropped;
+            }
+        }
+    }
+    impl State {
+        const __TRAIT_UNION_NEEDS_DROP: [bool; 2usize] = [
+            ::core::mem::needs_drop::<Idle>(),
+            ::core::mem::needs_drop::<Running>(),
+        ];
+        const __TRAIT_UNION_VTABLES: [::trait_union::__support::VtablePtr; 2usize] = [
+            unsafe {
+                ::trait_union::__support::VtablePtr::of(
+                    ::core::ptr::null::<Idle>() as *const (dyn Debug + '_),
+                )
+            },
+            unsafe {
+                ::trait_union::__support::VtablePtr::of(
+                    ::core::ptr::null::<Running>() as *const (dyn Debug + '_),
+                )
+            },
+        ];
+        #[inline(always)]
+        fn __trait_union_to_trait_object(
+            &self,
+        ) -> ::trait_union::__support::RawTraitObject {
+            let data = &self.__trait_union_State_data as *const _ as *mut ();
+            #[cfg(debug_assertions)]
+            ::trait_union::__support::check_aligned(
+                data,
+                Self::__TRAIT_UNION_LAYOUT.variants[self.__trait_union_position()].align,
+                "misaligned State",
+            );
+            ::trait_union::__support::RawTraitObject {
+                data,
+                vtable: Self::__TRAIT_UNION_VTABLES[self.__trait_union_State_index
+                    as usize],
+            }
+        }
+        #[inline(always)]
+        fn __trait_union_to_trait_object_mut(
+            &mut self,
+        ) -> ::trait_union::__support::RawTraitObject {
+            let data = &mut self.__trait_union_State_data as *mut _ as *mut ();
+            #[cfg(debug_assertions)]
+            ::trait_union::__support::check_aligned(
+                data,
+                Self::__TRAIT_UNION_LAYOUT.variants[self.__trait_union_position()].align,
+                "misaligned State",
+            );
+            ::trait_union::__support::RawTraitObject {
+                data,
+                vtable: Self::__TRAIT_UNION_VTABLES[self.__trait_union_State_index
+                    as usize],
+            }
+        }
+        #[inline(always)]
+        const fn __trait_union_index(index: usize) -> __trait_union_State_Index {
+            match index {
+                0usize => __trait_union_State_Index::V0,
+                1usize => __trait_union_State_Index::V1,
+                _ => ::core::panic!("invalid variant index"),
+            }
+        }
+        /// Creates a new instance with the variant at position `index`
+        ///
+        /// # Safety
+        ///
+        /// `V` must be the type of the variant at position `index`.
+        #[inline(always)]
+        unsafe fn __trait_union_new<V>(index: usize, value: V) -> Self {
+            #[cfg(debug_assertions)]
+            ::trait_union::__support::check_variant::<
+                V,
+            >(
+                &Self::__TRAIT_UNION_LAYOUT,
+                index,
+                "invalid implementation of StateVariant",
+            );
+            let mut slf = ::core::mem::MaybeUninit::<Self>::uninit();
+            unsafe {
+                if ::core::mem::size_of::<V>() == 0 {
+                    ::core::mem::forget(value);
+                } else {
+                    ::core::ptr::write(
+                        &mut (*slf.as_mut_ptr()).__trait_union_State_data as *mut _
+                            as *mut V,
+                        value,
+                    );
+                }
+                (*slf.as_mut_ptr())
+                    .__trait_union_State_index = Self::__trait_union_index(index);
+                slf.assume_init()
+            }
+        }
+    }
+    impl<'__trait_union_pin> ::core::marker::Unpin for State
+    where
+        ::core::marker::PhantomData<
+            (&'__trait_union_pin (), Idle, Running),
+        >: ::core::marker::Unpin,
+    {}
+    impl ::core::default::Default for State {
+        #[inline]
+        fn default() -> Self {
+            Self::new(<Idle as ::core::default::Default>::default())
+        }
+    }
+    unsafe impl ::trait_union::__support::AcceptVariant for State {
+        #[inline]
+        unsafe fn __trait_union_accept(
+            id: ::core::any::TypeId,
+            data: *const u8,
+        ) -> ::core::option::Option<Self> {
+            if id == ::core::any::TypeId::of::<Idle>() {
+                return ::core::option::Option::Some(unsafe {
+                    Self::new_raw(StateKind::Idle, data)
+                });
+            }
+            if id == ::core::any::TypeId::of::<Running>() {
+                return ::core::option::Option::Some(unsafe {
+                    Self::new_raw(StateKind::Running, data)
+                });
+            }
+            ::core::option::Option::None
+        }
+    }
+    impl ::trait_union::Provide for State {
+        fn provide<'__trait_union_provide>(
+            &'__trait_union_provide self,
+            request: &mut ::trait_union::Request<'__trait_union_provide>,
+        ) {
+            match self.__trait_union_State_index {
+                __trait_union_State_Index::V0 => {
+                    let value = unsafe { &*self.__trait_union_State_data.variant0 };
+                    {
+                        let _ = value;
+                    }
+                }
+                __trait_union_State_Index::V1 => {
+                    let value = unsafe { &*self.__trait_union_State_data.variant1 };
+                    {
+                        let _ = value;
+                    }
+                }
+                #[cfg(debug_assertions)]
+                __trait_union_State_Index::Dropped => {
+                    ::core::panic!("use of a dropped trait union")
+                }
+            }
+        }
+    }
+    impl<
+        __TraitUnionVariant: StateVariant,
+    > ::trait_union::FromVariant<__TraitUnionVariant> for State {
+        #[inline(always)]
+        fn from_variant(value: __TraitUnionVariant) -> Self {
+            Self::new(value)
+        }
+    }
+    impl ::core::convert::From<StateOwned> for State {
+        #[inline]
+        fn from(value: StateOwned) -> Self {
+            Self::from_enum(value)
+        }
+    }
+    impl ::core::convert::From<State> for StateOwned {
+        #[inline]
+        fn from(value: State) -> Self {
+            <State>::into_enum(value)
+        }
+    }
+    impl<
+        '__trait_union_ref,
+        '__trait_union_object,
+    > ::core::convert::From<&'__trait_union_ref State>
+    for &'__trait_union_ref (dyn Debug + '__trait_union_object)
+    where
+        'static: '__trait_union_object,
+    {
+        #[inline(always)]
+        fn from(value: &'__trait_union_ref State) -> Self {
+            &**value
+        }
+    }
+    impl<
+        '__trait_union_ref,
+        '__trait_union_object,
+    > ::core::convert::From<&'__trait_union_ref mut State>
+    for &'__trait_union_ref mut (dyn Debug + '__trait_union_object)
+    where
+        'static: '__trait_union_object,
+    {
+        #[inline(always)]
+        fn from(value: &'__trait_union_ref mut State) -> Self {
+            &mut **value
+        }
+    }
+    unsafe impl StateVariant for Idle {
+        const __TRAIT_UNION_INDEX: usize = 0usize;
+        const KIND: StateKind = StateKind::Idle;
+        const SIZE: usize = ::core::mem::size_of::<Self>();
+        const NAME: &'static str = "Idle";
+    }
+    unsafe impl StateVariant for Running {
+        const __TRAIT_UNION_INDEX: usize = 1usize;
+        const KIND: StateKind = StateKind::Running;
+        const SIZE: usize = ::core::mem::size_of::<Self>();
+        const NAME: &'static str = "Running";
+    }
+};
@@ -0,0 +1,8 @@
+use std::fmt::Debug;
+
+trait_union! {
+    #[trait_union(constructors, non_exhaustive, new_vis = "pub(crate)")]
+    pub union Tagged: Debug = 1: u8 | #[trait_union(name = Text)] 2: String;
+
+    union State: Debug = #[trait_union(default)] Idle | Running;
+}
@@ -0,0 +1,322 @@
+//! Items used by the generated code
+
+use crate::LayoutInfo;
+use core::{any::TypeId, fmt, marker::PhantomData, mem, ptr};
+
+/// Initial state of [hash_bytes], [hash_usize], and [hash_u64]
+pub const HASH_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+
+/// Feeds `bytes` into the FNV-1a hash state `h`
+pub const fn hash_bytes(mut h: u64, bytes: &[u8]) -> u64 {
+    let mut i = 0;
+    while i < bytes.len() {
+        h ^= bytes[i] as u64;
+        h = h.wrapping_mul(0x0000_0100_0000_01b3);
+        i += 1;
+    }
+    h
+}
+
+/// Feeds `n` into the FNV-1a hash state `h`
+///
+/// The hash does not depend on the size of `usize`.
+pub const fn hash_usize(h: u64, n: usize) -> u64 {
+    hash_u64(h, n as u64)
+}
+
+/// Feeds `n` into the FNV-1a hash state `h`
+pub const fn hash_u64(h: u64, n: u64) -> u64 {
+    hash_bytes(h, &n.to_le_bytes())
+}
+
+/// Checks that `T` fits into `SIZE` bytes with an alignment of `ALIGN`
+pub struct Fits<T, const SIZE: usize, const ALIGN: usize>(PhantomData<T>);
+
+impl<T, const SIZE: usize, const ALIGN: usize> Fits<T, SIZE, ALIGN> {
+    pub const CHECK: () = assert!(
+        mem::size_of::<T>() <= SIZE && mem::align_of::<T>() <= ALIGN,
+        "the value does not fit into the trait-union",
+    );
+}
+
+/// A pointer to the vtable of a trait object
+#[repr(transparent)]
+#[derive(Copy, Clone, Debug)]
+pub struct VtablePtr(*mut ());
+
+impl VtablePtr {
+    /// The vtable of variants whose trait object is not created from a vtable
+    pub const NULL: Self = VtablePtr(ptr::null_mut());
+
+    /// Returns the vtable of the trait object pointer `ptr`
+    ///
+    /// # Safety
+    ///
+    /// `T` must be a trait object type.
+    #[inline(always)]
+    pub const unsafe fn of<T: ?Sized>(ptr: *const T) -> Self {
+        union Repr<T: ?Sized> {
+            ptr: *const T,
+            object: RawTraitObject,
+        }
+        // Old compilers consider this block redundant.
+        #[allow(unused_unsafe)]
+        unsafe {
+            Repr { ptr }.object.vtable
+        }
+    }
+}
+
+/// The layout of a pointer to a trait object
+///
+/// Shared by all trait-unions instead of being generated for each of them.
+#[repr(C)]
+#[derive(Copy, Clone, Debug)]
+pub struct RawTraitObject {
+    pub data: *mut (),
+    pub vtable: VtablePtr,
+}
+
+/// Checks that `V` has the layout of the variant at position `index` of `layout`
+#[inline(always)]
+pub fn check_variant<V>(layout: &LayoutInfo, index: usize, msg: &str) {
+    let valid = match layout.variants.get(index) {
+        Some(v) => mem::size_of::<V>() == v.size && mem::align_of::<V>() == v.align,
+        None => false,
+    };
+    assert!(valid, "{}", msg);
+}
+
+/// Checks that `data` is aligned to `align`
+#[inline(always)]
+pub fn check_aligned(data: *const (), align: usize, msg: &str) {
+    assert!(data as usize % align == 0, "{}", msg);
+}
+
+/// Implemented by trait-unions without generic parameters to accept the variants of
+/// other trait-unions
+///
+/// # Safety
+///
+/// `__trait_union_accept` must only move out of `data` if it returns `Some`.
+pub unsafe trait AcceptVariant: Sized {
+    /// Moves the value out of `data` if its type is the type of a variant
+    ///
+    /// # Safety
+    ///
+    /// `data` must point to a valid value of the type with the id `id`. `data` does not
+    /// have to be aligned.
+    unsafe fn __trait_union_accept(id: TypeId, data: *const u8) -> Option<Self>;
+}
+
+/// Aborts the process when dropped during a panic
+///
+/// Forgotten once the guarded code has completed. Panicking while unwinding aborts.
+pub struct AbortOnUnwind;
+
+impl Drop for AbortOnUnwind {
+    fn drop(&mut self) {
+        panic!("a trait-union transition panicked");
+    }
+}
+
+/// A pointer that does not affect the auto traits of the containing type
+///
+/// Handles of the `external` option derive `Send` and `Sync` from a `PhantomData` of the
+/// union instead.
+#[derive(Copy, Clone)]
+pub struct PayloadPtr(pub ptr::NonNull<u8>);
+
+unsafe impl Send for PayloadPtr {}
+
+unsafe impl Sync for PayloadPtr {}
+
+/// Moves `value` into `buf` at the first offset that is aligned for `T`
+///
+/// Returns `value` if it does not fit. Zero-sized values are placed at a dangling pointer
+/// and do not use the buffer.
+pub fn place<T>(buf: &mut [mem::MaybeUninit<u8>], value: T) -> Result<PayloadPtr, T> {
+    let ptr = if mem::size_of::<T>() == 0 {
+        ptr::NonNull::<T>::dangling().as_ptr()
+    } else {
+        let offset = (buf.as_ptr() as usize).wrapping_neg() % mem::align_of::<T>();
+        if offset > buf.len() || buf.len() - offset < mem::size_of::<T>() {
+            return Err(value);
+        }
+        // SAFETY: `offset` is within `buf`.
+        unsafe { buf.as_mut_ptr().add(offset) as *mut T }
+    };
+    // SAFETY: `ptr` is aligned and valid for writes of `T`.
+    unsafe {
+        ptr::write(ptr, value);
+        Ok(PayloadPtr(ptr::NonNull::new_unchecked(ptr as *mut u8)))
+    }
+}
+
+/// Formats the value of a variant for `describe`
+///
+/// `(&&&Describe(value)).describe_value(f)` uses `Display` if the type implements it,
+/// otherwise `Debug`, and otherwise writes `..`. Method resolution tries the receiver
+/// `&&Describe` of [DescribeDisplay] first and the receiver `Describe` of [DescribeOther]
+/// last.
+pub struct Describe<'a, T: ?Sized>(pub &'a T);
+
+pub trait DescribeDisplay {
+    fn describe_value(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result;
+}
+
+impl<T: ?Sized + fmt::Display> DescribeDisplay for &&Describe<'_, T> {
+    fn describe_value(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self.0, f)
+    }
+}
+
+pub trait DescribeDebug {
+    fn describe_value(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result;
+}
+
+impl<T: ?Sized + fmt::Debug> DescribeDebug for &Describe<'_, T> {
+    fn describe_value(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self.0, f)
+    }
+}
+
+pub trait DescribeOther {
+    fn describe_value(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result;
+}
+
+impl<T: ?Sized> DescribeOther for Describe<'_, T> {
+    fn describe_value(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("..")
+    }
+}
+
+#[cfg(feature = "bytemuck")]
+pub use bytemuck;
+
+#[cfg(feature = "embedded-hal")]
+pub use embedded_hal;
+
+#[cfg(feature = "tower")]
+pub use tower_service;
+
+#[cfg(feature = "futures")]
+pub use futures_core;
+
+#[cfg(feature = "futures")]
+pub use futures_io;
+
+#[cfg(feature = "tokio")]
+pub use tokio;
+
+#[cfg(feature = "alloc")]
+pub use alloc::boxed::Box;
+
+#[cfg(feature = "metrics")]
+pub use metrics::MetricsCell;
+
+#[cfg(feature = "debug-registry")]
+pub use crate::debug_registry::{register_live, unregister_live};
+
+#[cfg(feature = "metrics")]
+mod metrics {
+    use core::{
+        cell::UnsafeCell,
+        sync::atomic::{AtomicU8, Ordering},
+    };
+
+    const UNSET: u8 = 0;
+    const SETTING: u8 = 1;
+    const SET: u8 = 2;
+
+    /// A metrics hook that can be registered once
+    pub struct MetricsCell<T: ?Sized + 'static> {
+        state: AtomicU8,
+        value: UnsafeCell<Option<&'static T>>,
+    }
+
+    // SAFETY: `value` is written once before `state` is set to `SET` and only read
+    // afterwards.
+    unsafe impl<T: ?Sized + Sync + 'static> Sync for MetricsCell<T> {}
+
+    impl<T: ?Sized + 'static> MetricsCell<T> {
+        /// Creates a cell without a hook
+        #[allow(clippy::new_without_default)]
+        pub const fn new() -> Self {
+            Self {
+                state: AtomicU8::new(UNSET),
+                value: UnsafeCell::new(None),
+            }
+        }
+
+        /// Registers `value` unless a hook is already registered
+        pub fn set(&self, value: &'static T) -> Result<(), &'static T> {
+            match self.state.compare_exchange(
+                UNSET,
+                SETTING,
+                Ordering::Acquire,
+                Ordering::Relaxed,
+            ) {
+                Ok(_) => {
+                    unsafe {
+                        *self.value.get() = Some(value);
+                    }
+                    self.state.store(SET, Ordering::Release);
+                    Ok(())
+                }
+                Err(_) => Err(value),
+            }
+        }
+
+        /// Returns the registered hook
+        #[inline(always)]
+        pub fn get(&self) -> Option<&'static T> {
+            match self.state.load(Ordering::Acquire) {
+                SET => unsafe { *self.value.get() },
+                _ => None,
+            }
+        }
+    }
+}
+
+/// Storage of the unions generated by [trait_union_decl](crate::trait_union_decl)
+pub mod decl {
+    use core::marker::PhantomData;
+
+    /// A list of variants, one of which is active
+    pub enum Cons<H, T> {
+        Head(H),
+        Tail(T),
+    }
+
+    /// The empty list of variants
+    pub enum Nil {}
+
+    /// Index of the head of a [Cons]
+    pub struct Here;
+
+    /// Index into the tail of a [Cons]
+    pub struct There<I>(PhantomData<I>);
+
+    /// Storage that can contain a `V` at index `I`
+    pub trait Inject<V, I> {
+        fn inject(value: V) -> Self;
+    }
+
+    impl<V, T> Inject<V, Here> for Cons<V, T> {
+        #[inline]
+        fn inject(value: V) -> Self {
+            Cons::Head(value)
+        }
+    }
+
+    impl<H, V, T, I> Inject<V, There<I>> for Cons<H, T>
+    where
+        T: Inject<V, I>,
+    {
+        #[inline]
+        fn inject(value: V) -> Self {
+            Cons::Tail(T::inject(value))
+        }
+    }
+}
@@ -0,0 +1,37 @@
+//! Array construction helper
+
+/// Creates an array of trait-unions
+///
+/// Each element is created with the `new` function of the trait-union. The result is an
+/// array `[Container; N]` where `N` is the number of elements.
+///
+/// # Syntax
+///
+/// ```txt
+/// TYPE ';' '[' EXPR (',' EXPR)* ','? ']'
+/// ```
+///
+/// # Example
+///
+#[cfg_attr(feature = "proc-macro", doc = "```rust")]
+#[cfg_attr(not(feature = "proc-macro"), doc = "```rust,ignore")]
+/// # use trait_union::{trait_union, trait_union_array};
+/// # use std::fmt::Display;
+/// #
+/// trait_union! {
+///     union Container: Display = i32 | &'static str | bool;
+/// }
+///
+/// let array = trait_union_array!(Container; [1i32, "hi", true]);
+/// let strings: Vec<_> = array.iter().map(|c| c.to_string()).collect();
+/// assert_eq!(strings, ["1", "hi", "true"]);
+/// ```
+///
+/// Flattened and box variants are created with `From` and cannot be used with this
+/// macro.
+#[macro_export]
+macro_rules! trait_union_array {
+    ($union:ty; [$($value:expr),* $(,)?]) => {
+        [$(<$union>::new($value)),*]
+    };
+}
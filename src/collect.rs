@@ -0,0 +1,45 @@
+//! Conversion of iterators of variants into trait-unions
+
+use core::iter::{FromIterator, Map};
+
+/// Trait-unions that can be created from values of type `V`
+///
+/// Implemented by the generated struct for all variants that can be passed to its `new`
+/// function unless the `new_vis` option restricts `new`.
+pub trait FromVariant<V>: Sized {
+    /// Creates a new instance
+    fn from_variant(value: V) -> Self;
+}
+
+/// Extension trait that converts the items of an iterator into trait-unions
+///
+/// # Example
+///
+#[cfg_attr(feature = "proc-macro", doc = "```rust")]
+#[cfg_attr(not(feature = "proc-macro"), doc = "```rust,ignore")]
+/// # use trait_union::{trait_union, CollectUnion};
+/// # use std::fmt::Display;
+/// #
+/// trait_union! {
+///     union Container: Display = i32 | &'static str;
+/// }
+///
+/// let mut containers: Vec<Container> = vec![1, 2].into_iter().collect_union();
+/// containers.extend(vec!["a", "b"].into_iter().unions());
+/// let strings: Vec<_> = containers.iter().map(|c| c.to_string()).collect();
+/// assert_eq!(strings, ["1", "2", "a", "b"]);
+/// ```
+pub trait CollectUnion: Iterator + Sized {
+    /// Returns an iterator that converts each item into the trait-union `U`
+    #[allow(clippy::type_complexity)]
+    fn unions<U: FromVariant<Self::Item>>(self) -> Map<Self, fn(Self::Item) -> U> {
+        self.map(U::from_variant)
+    }
+
+    /// Converts each item into a trait-union and collects them into `C`
+    fn collect_union<U: FromVariant<Self::Item>, C: FromIterator<U>>(self) -> C {
+        self.unions().collect()
+    }
+}
+
+impl<I: Iterator> CollectUnion for I {}
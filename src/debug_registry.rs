@@ -0,0 +1,93 @@
+//! Thread-local registry of live trait-union values
+//!
+//! With the `debug-registry` feature, every value created by a union of [trait_union]
+//! is recorded in a list of the current thread until it is dropped or moved out with
+//! `into_enum`. Values of [trait_union_copy] are not recorded since they are never
+//! dropped.
+//!
+//! Values are moved freely and therefore cannot be identified by their address. Dropping
+//! a value removes the most recently created entry of the same union and variant, so the
+//! creation locations of the remaining entries are only exact if the values of a variant
+//! are dropped in reverse order of their creation. Values that are dropped on another
+//! thread than the one that created them stay in the list of the creating thread.
+//!
+//! [trait_union]: crate::trait_union
+//! [trait_union_copy]: crate::trait_union_copy
+
+use crate::LayoutInfo;
+use core::{
+    cell::RefCell,
+    fmt::{self, Display, Formatter},
+    panic::Location,
+};
+use std::{eprintln, vec::Vec};
+
+/// A live value recorded by the registry
+#[derive(Clone, Copy, Debug)]
+pub struct LiveValue {
+    /// The name of the trait-union type
+    pub union: &'static str,
+    /// The type of the active variant as written in the declaration
+    pub variant: &'static str,
+    /// The location at which the value was created
+    pub location: &'static Location<'static>,
+}
+
+impl Display for LiveValue {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} holding {} created at {}",
+            self.union, self.variant, self.location
+        )
+    }
+}
+
+std::thread_local! {
+    static LIVE: RefCell<Vec<LiveValue>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Returns the live values created on the current thread in order of their creation
+pub fn live_values() -> Vec<LiveValue> {
+    LIVE.with(|live| live.borrow().clone())
+}
+
+/// Prints the live values created on the current thread to stderr
+pub fn dump_live_values() {
+    let live = live_values();
+    eprintln!("{} live trait-union values", live.len());
+    for value in live {
+        eprintln!("    {}", value);
+    }
+}
+
+/// Records the creation of a value of the variant at `index`
+#[doc(hidden)]
+pub fn register_live(
+    layout: &LayoutInfo,
+    index: usize,
+    location: &'static Location<'static>,
+) {
+    let value = LiveValue {
+        union: layout.name,
+        variant: layout.variants[index].name,
+        location,
+    };
+    // The list is gone if a value is created while the thread-local storage is destroyed.
+    let _ = LIVE.try_with(|live| live.borrow_mut().push(value));
+}
+
+/// Records the destruction of a value of the variant at `index`
+#[doc(hidden)]
+pub fn unregister_live(layout: &LayoutInfo, index: usize) {
+    let variant = layout.variants[index].name;
+    let _ = LIVE.try_with(|live| {
+        let mut live = live.borrow_mut();
+        let pos = live
+            .iter()
+            .rposition(|v| v.union == layout.name && v.variant == variant);
+        if let Some(pos) = pos {
+            live.remove(pos);
+        }
+    });
+}
@@ -0,0 +1,169 @@
+//! Declarative fallback for the proc macros
+
+/// Declarative fallback for [trait_union](crate::trait_union)
+///
+/// This macro is available without the `proc-macro` feature. It covers unions without
+/// generic parameters and stores the value in an enum instead of a union.
+///
+/// # Syntax
+///
+/// ```txt
+/// ATTRIBUTE* VISIBILITY? ('union' | 'enumlike') NAME ':' TRAIT_BOUNDS '=' TYPE ('|' TYPE)* ';'
+/// ```
+///
+/// # Output
+///
+/// ```rust,ignore
+/// struct Container {
+///     /* ... */
+/// }
+///
+/// impl Container {
+///     fn new<V, I>(value: V) -> Self where /* V is a variant */ { /* ... */ }
+/// }
+///
+/// impl Deref for Container {
+///     type Target = dyn Display;
+///     /* ... */
+/// }
+///
+/// impl DerefMut for Container {
+///     /* ... */
+/// }
+/// ```
+///
+/// Unlike [trait_union](crate::trait_union), no marker trait is generated. The second
+/// type parameter of `new` is always inferred.
+///
+/// # Example
+///
+/// ```rust
+/// # use trait_union::trait_union_decl;
+/// # use std::fmt::Display;
+/// #
+/// trait_union_decl! {
+///     union Container: Display = i32 | &'static str | bool;
+/// }
+///
+/// let mut container = Container::new(32);
+/// assert_eq!(container.to_string(), "32");
+///
+/// container = Container::new("Hello World");
+/// assert_eq!(container.to_string(), "Hello World");
+/// ```
+#[macro_export]
+macro_rules! trait_union_decl {
+    () => {};
+    (
+        $(#[$attr:meta])*
+        $vis:vis union $name:ident : $($rest:tt)*
+    ) => {
+        $crate::__trait_union_decl_bound! {
+            [$(#[$attr])*] [$vis] [$name] [] $($rest)*
+        }
+    };
+    (
+        $(#[$attr:meta])*
+        $vis:vis enumlike $name:ident : $($rest:tt)*
+    ) => {
+        $crate::__trait_union_decl_bound! {
+            [$(#[$attr])*] [$vis] [$name] [] $($rest)*
+        }
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __trait_union_decl_bound {
+    (
+        [$($attr:tt)*] [$vis:vis] [$name:ident] [$($bound:tt)*]
+        = $($variant:ty)|+ ; $($rest:tt)*
+    ) => {
+        $($attr)*
+        $vis struct $name($crate::__trait_union_decl_storage!($($variant),+));
+
+        impl $name {
+            /// Creates a new instance
+            #[inline]
+            $vis fn new<V, I>(value: V) -> Self
+            where
+                $crate::__trait_union_decl_storage!($($variant),+):
+                    $crate::__support::decl::Inject<V, I>,
+            {
+                $name($crate::__support::decl::Inject::inject(value))
+            }
+        }
+
+        impl ::core::ops::Deref for $name {
+            type Target = dyn $($bound)*;
+
+            #[inline]
+            fn deref(&self) -> &Self::Target {
+                let storage = &self.0;
+                $crate::__trait_union_decl_access!(storage; [$($bound)*]; $($variant),+)
+            }
+        }
+
+        impl ::core::ops::DerefMut for $name {
+            #[inline]
+            fn deref_mut(&mut self) -> &mut Self::Target {
+                let storage = &mut self.0;
+                $crate::__trait_union_decl_access!(storage; [$($bound)*]; $($variant),+)
+            }
+        }
+
+        $crate::trait_union_decl!($($rest)*);
+    };
+    ([$($attr:tt)*] [$vis:vis] [$name:ident] [$($bound:tt)*] $next:tt $($rest:tt)*) => {
+        $crate::__trait_union_decl_bound! {
+            [$($attr)*] [$vis] [$name] [$($bound)* $next] $($rest)*
+        }
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __trait_union_decl_storage {
+    ($head:ty $(, $tail:ty)*) => {
+        $crate::__support::decl::Cons<$head, $crate::__trait_union_decl_storage!($($tail),*)>
+    };
+    () => {
+        $crate::__support::decl::Nil
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __trait_union_decl_access {
+    ($storage:ident; [$($bound:tt)*]; $head:ty $(, $tail:ty)*) => {
+        match $storage {
+            $crate::__support::decl::Cons::Head(value) => value,
+            $crate::__support::decl::Cons::Tail(storage) => {
+                $crate::__trait_union_decl_access!(storage; [$($bound)*]; $($tail),*)
+            }
+        }
+    };
+    ($storage:ident; [$($bound:tt)*];) => {
+        match *$storage {}
+    };
+}
+
+// Not part of the test module of the crate root, which needs the proc macros.
+#[cfg(test)]
+mod test {
+    use std::fmt::Display;
+
+    #[test]
+    fn decl() {
+        crate::trait_union_decl! {
+            union D: Display + Send = u8 | &'static str | String;
+        }
+
+        let mut d = D::new(1u8);
+        assert_eq!(d.to_string(), "1");
+        d = D::new("hello");
+        assert_eq!(d.to_string(), "hello");
+        d = D::new(String::from("world"));
+        assert_eq!(d.to_string(), "world");
+    }
+}
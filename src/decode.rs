@@ -0,0 +1,26 @@
+use core::fmt::{self, Display, Formatter};
+
+/// Error returned by the generated `decode` function
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DecodeError {
+    /// No variant has this tag
+    UnknownTag(u64),
+    /// The number of bytes does not match the size of the variant
+    Length {
+        /// The size of the variant
+        expected: usize,
+        /// The number of bytes
+        actual: usize,
+    },
+}
+
+impl Display for DecodeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match *self {
+            DecodeError::UnknownTag(tag) => write!(f, "no variant has the tag {}", tag),
+            DecodeError::Length { expected, actual } => {
+                write!(f, "expected {} bytes but got {}", expected, actual)
+            }
+        }
+    }
+}
@@ -0,0 +1,114 @@
+use core::fmt::{self, Display, Formatter};
+
+/// Layout of a trait-union type
+///
+/// Returned by the generated `debug_layout` function. The [Display] implementation
+/// prints a table of the variants.
+#[derive(Clone, Copy, Debug)]
+pub struct LayoutInfo {
+    /// The name of the type
+    pub name: &'static str,
+    /// The size of the type
+    pub size: usize,
+    /// The alignment of the type
+    pub align: usize,
+    /// The layout of the variants in declaration order
+    pub variants: &'static [VariantLayout],
+}
+
+/// Layout of a variant of a trait-union type
+#[derive(Clone, Copy, Debug)]
+pub struct VariantLayout {
+    /// The type of the variant as written in the declaration
+    pub name: &'static str,
+    /// The tag of the variant
+    pub tag: u64,
+    /// The size of the variant
+    pub size: usize,
+    /// The alignment of the variant
+    pub align: usize,
+    /// The offset of the variant in the trait-union type
+    pub offset: usize,
+}
+
+/// Size of a trait-union type and its largest variant
+///
+/// Generated as `SIZE_REPORT` by the `report_size` option. The [Display] implementation
+/// prints a single line.
+#[derive(Clone, Copy, Debug)]
+pub struct SizeReport {
+    /// The name of the type
+    pub name: &'static str,
+    /// The size of the type
+    pub size: usize,
+    /// The alignment of the type
+    pub align: usize,
+    /// The first of the largest variants
+    pub largest: VariantLayout,
+}
+
+impl SizeReport {
+    /// Summarizes `layout`
+    ///
+    /// Panics if `layout` has no variants.
+    pub const fn new(layout: LayoutInfo) -> Self {
+        let mut largest = layout.variants[0];
+        let mut i = 1;
+        while i < layout.variants.len() {
+            if layout.variants[i].size > largest.size {
+                largest = layout.variants[i];
+            }
+            i += 1;
+        }
+        SizeReport {
+            name: layout.name,
+            size: layout.size,
+            align: layout.align,
+            largest,
+        }
+    }
+}
+
+impl Display for SizeReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} (size {}, align {}), largest variant {} (size {}, align {})",
+            self.name,
+            self.size,
+            self.align,
+            self.largest.name,
+            self.largest.size,
+            self.largest.align,
+        )
+    }
+}
+
+impl Display for LayoutInfo {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{} (size {}, align {})",
+            self.name, self.size, self.align
+        )?;
+        let width = self
+            .variants
+            .iter()
+            .map(|v| v.name.len())
+            .max()
+            .unwrap_or(0);
+        for v in self.variants {
+            writeln!(
+                f,
+                "  {:width$}  tag {:<4} size {:<4} align {:<4} offset {}",
+                v.name,
+                v.tag,
+                v.size,
+                v.align,
+                v.offset,
+                width = width,
+            )?;
+        }
+        Ok(())
+    }
+}
@@ -1,5 +1,4 @@
 #![cfg_attr(not(test), no_std)]
-#![cfg_attr(test, feature(untagged_unions))]
 
 //! This crate provides a macro that generates a trait-union type. That is, a trait
 //! object type which can contain any one of a pre-determined set of implementors.
@@ -7,13 +6,10 @@
 //! The generated type does not allocate. The size of the type is the size of the largest
 //! variant plus some constant overhead.
 //!
-//! **NOTE**: As of rustc 1.47, you must enable the `untagged_unions` feature to store
-//! non-[Copy] types in a trait-union. This will change
-//! [soon](https://github.com/rust-lang/rust/pull/77547).
-//!
 //! # Example
 //!
-//! ```rust
+#![cfg_attr(feature = "proc-macro", doc = "```rust")]
+#![cfg_attr(not(feature = "proc-macro"), doc = "```rust,ignore")]
 //! # use trait_union::trait_union;
 //! # use std::fmt::Display;
 //! #
@@ -58,19 +54,70 @@
 //! unsafe impl ContainerVariant for &'static str { }
 //! unsafe impl ContainerVariant for bool { }
 //! ```
+//!
+//! The proc macros are enabled by the default `proc-macro` feature. Without it, only the
+//! declarative [trait_union_decl] is available.
+//!
+//! Trait unions are plain sized values without `Default` or `Copy` requirements. They can
+//! be stored in any container, including the fixed-capacity `Vec` and `spsc::Queue` of
+//! the `heapless` crate. This makes it possible to queue heterogeneous commands without
+//! an allocator. Fixed tables of trait-unions are created with [trait_union_array].
+//!
+//! The minimum supported Rust version is 1.62.
+
+extern crate self as trait_union;
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+#[cfg(feature = "debug-registry")]
+extern crate std;
+
+#[doc(hidden)]
+pub mod __support;
+mod array;
+mod collect;
+#[cfg(feature = "debug-registry")]
+pub mod debug_registry;
+mod decl;
+mod decode;
+mod layout;
+mod provide;
+mod runs;
+mod schema;
+
+pub use collect::{CollectUnion, FromVariant};
+pub use decode::DecodeError;
+pub use layout::{LayoutInfo, SizeReport, VariantLayout};
+pub use provide::{request_ref, Provide, Request};
+pub use runs::KindRuns;
+pub use schema::Schema;
 
 /// Macro that generates a trait-union type
 ///
 /// # Syntax
 ///
 /// Each invocation of the macro can generate an arbitrary number of trait-union types.
+/// A declaration whose name or generated item names clash with an earlier declaration
+/// of the same invocation is rejected. Trait-unions with the same name can be declared in
+/// different modules or functions.
 ///
 /// The syntax of each declaration is as follows:
 ///
 /// ```txt
-/// ATTRIBUTE* VISIBILITY? 'union' NAME GENERICS? ':' TRAIT_BOUNDS ('where' WHERE_CLAUSE)? '=' TYPE ('|' TYPE)* '|'? ';'
+/// ATTRIBUTE* VISIBILITY? ('union' | 'enumlike') NAME GENERICS? ':' TRAIT_BOUNDS ('where' WHERE_CLAUSE)? FIELDS? '=' VARIANT ('|' VARIANT)* '|'? ';'
+///
+/// VARIANT = ATTRIBUTE* (TAG ':')? 'box'? TYPE
+///
+/// FIELDS = '{' (ATTRIBUTE* VISIBILITY? NAME ':' TYPE ',')* '}'
 /// ```
 ///
+/// Unions with the [`open`](#open) option omit the `'=' VARIANT ...` part.
+///
+/// `enumlike` can be used instead of `union`. Unlike `union`, it is not a keyword, which
+/// keeps tools such as rustfmt and syntax highlighters from treating the declaration as a
+/// union item.
+///
 /// `?` denotes an optional segment. `*` denotes 0 or more repetitions.
 ///
 /// For example:
@@ -80,6 +127,28 @@
 /// pub(crate) union MyUnion<'a, T: 'a>: Debug+'a where T: Debug+Copy = &'a str | Option<T>;
 /// ```
 ///
+/// The declaration can be assembled by a `macro_rules!` wrapper, e.g. one that adds
+/// standard attributes. Variants passed as `$ty:ty` fragments behave like variants
+/// written directly:
+///
+/// ```rust
+/// # use trait_union::trait_union;
+/// # use std::fmt::Debug;
+/// macro_rules! standard_union {
+///     ($vis:vis $name:ident = $($ty:ty)|+) => {
+///         trait_union! {
+///             #[trait_union(constructors)]
+///             $vis union $name: Debug = $($ty)|+;
+///         }
+///     };
+/// }
+///
+/// standard_union!(pub Value = u8 | &'static str | Option<String>);
+///
+/// assert_eq!(Value::kind_of(&Value::new("a")), ValueKind::StaticStr);
+/// assert_eq!(Value::CONSTRUCTORS.len(), 3);
+/// ```
+///
 /// # Trait bounds
 ///
 /// The `TRAIT_BOUNDS` segment denotes the trait that the trait-union will deref to. As
@@ -92,22 +161,53 @@
 /// Debug+Copy+'a // OK
 /// 'a            // Error: No trait
 /// Debug+Display // Error: More than one non-auto trait
+/// Debug+?Sized  // Error: `?Sized` is not allowed
 /// ```
 ///
-/// If you do not provide a lifetime, the `'static` lifetime will be added automatically.
-/// That is, `Debug` is the same as `Debug+'static`. For example
+/// The bounds can be written with a leading `dyn` and can be parenthesized. That is,
+/// `dyn Debug+Send`, `(dyn Debug+Send)`, and `(Debug)+Send` are all the same as
+/// `Debug+Send`.
+///
+/// If you do not provide a lifetime and the trait-union has exactly one lifetime
+/// parameter, that lifetime will be added automatically. Otherwise the `'static` lifetime
+/// will be added. That is,
 ///
 /// ```rust,ignore
 /// union MyUnion<'a>: Debug = &'a str;
 /// ```
 ///
-/// will not compile because `&'a str` is not `'static`. Write
+/// is the same as
 ///
 /// ```rust,ignore
 /// union MyUnion<'a>: Debug+'a = &'a str;
 /// ```
 ///
-/// instead.
+/// and `union MyUnion: Debug = u8;` is the same as `union MyUnion: Debug+'static = u8;`.
+/// The [`no_implicit_static`](#no_implicit_static) option turns the latter into an error.
+///
+/// At most one lifetime can be provided. Variants that borrow from different lifetimes
+/// require a lifetime parameter that is outlived by all of them:
+///
+/// ```rust,ignore
+/// union MyUnion<'r, 'a: 'r, 'b: 'r>: Debug+'r = &'a str | &'b [u8];
+/// ```
+///
+/// The trait-union derefs to `dyn Debug+'r`, the intersection of `'a` and `'b`. The
+/// variance of the trait-union in its parameters is that of the variants. Here, it is
+/// covariant in `'r`, `'a`, and `'b`.
+///
+/// The non-auto trait can have generic arguments and bind associated types, including to
+/// generic parameters of the trait-union:
+///
+/// ```rust,ignore
+/// union MyUnion: AsRef<[u8]> = &'static str | Vec<u8>;
+/// union MyUnion<'a, T: 'a>: Iterator<Item = T>+'a = Empty<T> | Once<T>;
+/// union MyUnion<E: 'static>: embedded_hal::digital::OutputPin<Error = E> = Gpio1<E> | Gpio2<E>;
+/// ```
+///
+/// The trait can be named by any path. Parameters that appear only in the arguments of
+/// the trait, such as `W` in `serial::Write<W>`, do not have to appear in a variant. The
+/// trait-union is invariant in them like the trait object.
 ///
 /// # Output
 ///
@@ -125,143 +225,4280 @@
 ///     /* ... */
 /// }
 ///
-/// pub(crate) unsafe trait MyUnionVariant<'a, T: 'a>: Debug+'a where T: Debug+Copy { }
+/// pub(crate) unsafe trait MyUnionVariant<'a, T: 'a>: Debug+'a where T: Debug+Copy {
+///     const KIND: MyUnionKind;
+///     const SIZE: usize;
+///     const NAME: &'static str;
+/// }
+/// ```
+///
+/// The trait will automatically be implemented for all specified variants. Its constants
+/// contain the kind of the variant, its size, and the name of its kind, so generic code
+/// can inspect a variant at compile time:
+///
+/// ```rust
+/// # use trait_union::trait_union;
+/// # use std::fmt::Debug;
+/// #
+/// trait_union! {
+///     union Value: Debug = u8 | [u64; 4];
+/// }
+///
+/// fn describe<V: ValueVariant>(_: &V) -> (ValueKind, usize, &'static str) {
+///     (V::KIND, V::SIZE, V::NAME)
+/// }
+///
+/// assert_eq!(describe(&1u8), (ValueKind::U8, 1, "U8"));
+/// assert_eq!(describe(&[0u64; 4]).1, 32);
 /// ```
 ///
-/// The trait will automatically be implemented for all specified variants. The struct has
-/// a single associated method:
+/// Values of the struct are created with
 ///
 /// ```rust,ignore
 /// pub(crate) fn new(value: impl MyUnionVariant<'a, T>) -> Self { /* ... */ }
 /// ```
 ///
-/// The struct implements `Deref` and `DerefMut` with `Target = Debug+'a`.
-pub use trait_union_proc::trait_union;
-
-/// Macro that generates a trait-union type for [Copy] implementors
+/// The same values are accepted by the implementation of [FromVariant], which lets
+/// iterators of variants be collected with [CollectUnion::collect_union]:
 ///
-/// This macro is identical to [trait_union] except that
+/// ```rust,ignore
+/// impl<'a, T: 'a, V: MyUnionVariant<'a, T>> FromVariant<V> for MyUnion<'a, T> where T: Debug+Copy { /* ... */ }
+/// ```
 ///
-/// - all implementors must be [Copy]
-/// - the generated type is not [Drop]
-/// - `#[derive(Copy, Clone)]` can be used as an attribute
-pub use trait_union_proc::trait_union_copy;
-
-#[cfg(test)]
-mod test {
-    use super::{trait_union, trait_union_copy};
-    use std::{
-        fmt,
-        fmt::{Display, Formatter},
-        mem,
-        sync::atomic::{AtomicUsize, Ordering::Relaxed},
-    };
-
-    trait F: Display {
-        fn len(&self) -> usize;
-
-        fn set_len(&mut self, len: usize);
-    }
-
-    impl F for u8 {
-        fn len(&self) -> usize {
-            *self as usize
-        }
-
-        fn set_len(&mut self, len: usize) {
-            *self = len as u8;
-        }
-    }
-
-    impl F for String {
-        fn len(&self) -> usize {
-            self.len()
-        }
-
-        fn set_len(&mut self, len: usize) {
-            self.truncate(len);
-        }
-    }
-
-    #[repr(align(4))]
-    struct X;
-    impl F for X {
-        fn len(&self) -> usize {
-            !0
-        }
-
-        fn set_len(&mut self, len: usize) {
-            X_DROP_COUNT.store(len, Relaxed);
-        }
-    }
-    static X_DROP_COUNT: AtomicUsize = AtomicUsize::new(0);
-    impl Drop for X {
-        fn drop(&mut self) {
-            X_DROP_COUNT.fetch_add(1, Relaxed);
-        }
-    }
-    impl Display for X {
-        fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-            write!(f, "X")
-        }
-    }
-
-    trait_union! {
-        union U: F = u8 | String | X;
-    }
-
-    #[test]
-    fn test1() {
-        let mut c = U::new(33);
-        assert_eq!(mem::align_of_val(&*c), 1);
-        assert_eq!(mem::size_of_val(&*c), 1);
-        assert!(mem::align_of_val(&c) >= 4);
-        assert!(mem::size_of_val(&c) >= 4);
-        assert_eq!(c.len(), 33);
-        c.set_len(22);
-        assert_eq!(c.len(), 22);
-        c = U::new("Hello World".to_string());
-        assert_eq!(c.len(), 11);
-        c.set_len(5);
-        assert_eq!(c.len(), 5);
-        assert_eq!(c.to_string(), "Hello");
-        c = U::new(X);
-        assert_eq!(mem::align_of_val(&*c), 4);
-        assert_eq!(mem::size_of_val(&*c), 0);
-        assert_eq!(c.len(), !0);
-        assert_eq!(X_DROP_COUNT.load(Relaxed), 0);
-        c.set_len(2);
-        assert_eq!(X_DROP_COUNT.load(Relaxed), 2);
-        drop(c);
-        assert_eq!(X_DROP_COUNT.load(Relaxed), 3);
-    }
-
-    #[test]
-    fn size() {
-        assert_eq!(mem::size_of::<U>(), mem::size_of::<Option<U>>());
-    }
-
-    #[test]
-    fn compile() {
-        let t = trybuild::TestCases::new();
-        t.compile_fail("tests/compile-fail/*.rs");
-        t.pass("tests/pass/*.rs");
-    }
-
-    #[test]
-    fn copy() {
-        trait_union_copy! {
-            #[derive(Copy, Clone)]
-            union U: Display = u8 | &'static str;
-        }
-
-        let u = U::new("test");
-        let v = u;
-        assert_eq!(u.to_string(), v.to_string());
-    }
-
-    #[test]
-    fn assert_sync() {
-        let _: &dyn Sync = &U::new(1);
+/// It is not implemented if the `new_vis` option restricts `new`.
+///
+/// The struct implements `Deref` and `DerefMut` with `Target = Debug+'a`. References to
+/// the struct convert to trait object references with `From`, so they can be passed to
+/// generic APIs such as `fn log<'r>(value: impl Into<&'r (dyn Debug+'a)>)`:
+///
+/// ```rust,ignore
+/// impl<'r, 'o, 'a, T: 'a> From<&'r MyUnion<'a, T>> for &'r (dyn Debug+'o) where T: Debug+Copy, 'a: 'o { /* ... */ }
+///
+/// impl<'r, 'o, 'a, T: 'a> From<&'r mut MyUnion<'a, T>> for &'r mut (dyn Debug+'o) where T: Debug+Copy, 'a: 'o { /* ... */ }
+/// ```
+///
+/// The trait object lifetime `'o` is generic, so the target can be written as
+/// `&'r dyn Debug`.
+///
+/// If the trait is `Fn(A) -> R` or `FnMut(A) -> R`, the active variant is called with
+///
+/// ```rust,ignore
+/// pub(crate) fn call(&self, arg0: A) -> R { /* ... */ }
+/// ```
+///
+/// which takes `&mut self` for `FnMut`. Function pointers can be variants. Only those of
+/// the Rust ABI implement the `Fn` traits, so a table that mixes them with
+/// `extern "C" fn` pointers uses a trait implemented for both:
+///
+/// ```rust
+/// # use trait_union::trait_union;
+/// trait Isr {
+///     fn run(&self);
+/// }
+///
+/// impl Isr for fn() {
+///     fn run(&self) {
+///         self()
+///     }
+/// }
+///
+/// impl Isr for extern "C" fn() {
+///     fn run(&self) {
+///         self()
+///     }
+/// }
+///
+/// trait_union! {
+///     union Vector: Isr = fn() | extern "C" fn();
+/// }
+///
+/// fn tick() {}
+/// extern "C" fn timer() {}
+///
+/// let table = [Vector::new(tick as fn()), Vector::new(timer as extern "C" fn())];
+/// table.iter().for_each(|v| v.run());
+/// assert_eq!(Vector::kind_of(&table[1]), VectorKind::ExternCFn);
+/// ```
+///
+/// Unless changed with the `phantom` option, the struct implements the auto traits
+/// `Send`, `Sync`, `Unpin`, `UnwindSafe`, and `RefUnwindSafe` if and only if all variants
+/// implement them. `Box` is always `Unpin`, so box variants do not make the struct
+/// `!Unpin`. Since the values of the variants are stored in place, pinning the struct
+/// pins the active variant.
+///
+/// The layout of the struct can be inspected with
+///
+/// ```rust,ignore
+/// pub(crate) const fn debug_layout() -> LayoutInfo { /* ... */ }
+/// ```
+///
+/// Each variant has a tag that identifies it. The tag of the active variant is returned
+/// by
+///
+/// ```rust,ignore
+/// pub(crate) fn tag_of(this: &Self) -> u8 { /* ... */ }
+/// ```
+///
+/// The functions that are generated for every union, such as `tag_of` and the ones
+/// below, are associated functions that take the value as `this`, e.g.
+/// `MyUnion::tag_of(&value)`. A method call such as `value.tag()` therefore still reaches
+/// a method of the trait with the same name through `Deref`. Only the methods that an
+/// option or a variant attribute asks for take `self`.
+///
+/// The active variant can be inspected without access to its value with
+///
+/// ```rust,ignore
+/// pub(crate) fn kind_of(this: &Self) -> MyUnionKind { /* ... */ }
+/// ```
+///
+/// which returns a variant of the generated fieldless enum
+///
+/// ```rust,ignore
+/// #[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+/// pub(crate) enum MyUnionKind {
+///     Str = 0,
+///     OptionT = 1,
+/// }
+/// ```
+///
+/// See [Kinds](#kinds) for how the names of its variants are chosen.
+///
+/// The active variant can be matched on by reference with
+///
+/// ```rust,ignore
+/// pub(crate) fn as_ref_enum(this: &Self) -> MyUnionRef<'_, 'a, T> { /* ... */ }
+///
+/// pub(crate) fn as_mut_enum(this: &mut Self) -> MyUnionMut<'_, 'a, T> { /* ... */ }
+/// ```
+///
+/// which return variants of the generated enums
+///
+/// ```rust,ignore
+/// pub(crate) enum MyUnionRef<'r, 'a, T: 'a> where T: Debug+Copy {
+///     Str(&'r &'a str),
+///     OptionT(&'r Option<T>),
+/// }
+///
+/// pub(crate) enum MyUnionMut<'r, 'a, T: 'a> where T: Debug+Copy {
+///     Str(&'r mut &'a str),
+///     OptionT(&'r mut Option<T>),
+/// }
+/// ```
+///
+/// Trait methods that take `self` by value cannot be called through a trait object, but
+/// the active variant can be moved out with
+///
+/// ```rust,ignore
+/// pub(crate) fn into_enum(this: Self) -> MyUnionOwned<'a, T> { /* ... */ }
+/// ```
+///
+/// which returns a variant of the generated enum
+///
+/// ```rust,ignore
+/// pub(crate) enum MyUnionOwned<'a, T: 'a> where T: Debug+Copy {
+///     Str(&'a str),
+///     OptionT(Option<T>),
+/// }
+/// ```
+///
+/// Matching on it calls consuming methods without allocating:
+///
+/// ```rust,ignore
+/// match Handler::into_enum(handler) {
+///     HandlerOwned::TcpHandler(h) => h.finish(),
+///     HandlerOwned::UdpHandler(h) => h.finish(),
+/// }
+/// ```
+///
+/// The conversion is reversed by
+///
+/// ```rust,ignore
+/// pub(crate) fn from_enum(value: MyUnionOwned<'a, T>) -> Self { /* ... */ }
+/// ```
+///
+/// and both are combined by
+///
+/// ```rust,ignore
+/// pub(crate) fn transition(
+///     this: &mut Self,
+///     f: impl FnOnce(MyUnionOwned<'a, T>) -> MyUnionOwned<'a, T>,
+/// ) { /* ... */ }
+/// ```
+///
+/// which replaces the value in place. This is the core operation of state machines whose
+/// states consume themselves:
+///
+/// ```rust,ignore
+/// Connection::transition(&mut conn, |state| match state {
+///     ConnectionOwned::Closed(c) => ConnectionOwned::Open(c.open()),
+///     ConnectionOwned::Open(o) => ConnectionOwned::Closed(o.close()),
+/// });
+/// ```
+///
+/// Since the value is moved out while `f` runs, the process is aborted if `f` panics.
+///
+/// The visibilities above assume the [`enums`](#enums) option. Without it, the ref, mut,
+/// and owned enums and the methods that take or return them are private to the module of
+/// the declaration.
+///
+/// `into_enum` and `from_enum` are also available as `From` implementations in both
+/// directions, so the enum can be the type that code outside the union matches on and
+/// builds values from, while the union stores them. Without the `new_vis` option,
+/// `MyUnion: From<MyUnionOwned>` is implemented like `from_enum`.
+///
+/// Unions without generic parameters can hand their active variant to another union:
+///
+/// ```rust,ignore
+/// pub fn move_variant<U>(self) -> Result<U, Self> { /* ... */ }
+/// ```
+///
+/// This succeeds if `U` has a variant of the same type and otherwise returns `self`. The
+/// variants are compared by their `TypeId`. `U` must also be a union without generic
+/// parameters and without the `new_vis` option.
+///
+/// Code that already knows the kind of a value, such as a deserializer, can skip the
+/// marker trait with
+///
+/// ```rust,ignore
+/// pub(crate) unsafe fn new_unchecked<V>(kind: MyUnionKind, value: V) -> Self { /* ... */ }
+///
+/// pub(crate) unsafe fn new_raw(kind: MyUnionKind, data: *const u8) -> Self { /* ... */ }
+/// ```
+///
+/// `new_unchecked` requires `V` to be the type of the variant of kind `kind`. `new_raw`
+/// moves the value of that type out of the possibly unaligned `data`.
+///
+/// Whether two values have the same active variant can be checked cheaply with
+///
+/// ```rust,ignore
+/// pub(crate) fn same_variant(this: &Self, other: &Self) -> bool { /* ... */ }
+/// ```
+///
+/// Values can be grouped by their variant for batch processing with
+///
+/// ```rust,ignore
+/// pub(crate) fn sort_by_kind(values: &mut [Self]) { /* ... */ }
+///
+/// pub(crate) fn kind_runs(values: &[Self]) -> KindRuns<'_, Self, MyUnionKind> { /* ... */ }
+/// ```
+///
+/// `sort_by_kind` sorts the values in the declaration order of their variants. The sort
+/// is unstable and does not allocate. [KindRuns] yields the kind and the values of each
+/// maximal run of values of the same kind, so after sorting there is one run per kind.
+/// The inner loop over a run handles a single variant, so its values can be matched with
+/// `as_ref_enum` or the `visitor` option once per run instead of going through the vtable
+/// for every value.
+///
+/// Whether the active variant is a zero-sized type, whose bytes do not have to be
+/// copied, is returned by
+///
+/// ```rust,ignore
+/// pub(crate) fn is_zst_variant(this: &Self) -> bool { /* ... */ }
+/// ```
+///
+/// A value can be described for logs and panic messages with
+///
+/// ```rust,ignore
+/// pub(crate) fn describe(this: &Self, f: &mut fmt::Formatter<'_>) -> fmt::Result { /* ... */ }
+/// ```
+///
+/// which writes the kind of the active variant followed by its value in parentheses, e.g.
+/// `OptionT(Some(1))`. The value is formatted with `Display` if its type implements it,
+/// otherwise with `Debug`, and otherwise written as `..`. This is decided per variant and
+/// does not depend on the trait. Flattened variants are described by the inner union,
+/// e.g. `Inner(U8(1))`. `describe` does not allocate, so it can be called from a
+/// `Display` implementation in a panic handler.
+///
+/// Whether dropping a value has any effect is available as
+///
+/// ```rust,ignore
+/// pub(crate) const NEEDS_DROP: bool = /* ... */;
+/// ```
+///
+/// If no variant needs to be dropped, the generated `Drop` implementation does nothing
+/// and is optimized away. Otherwise, dropping a value whose active variant does not need
+/// to be dropped only checks a constant table. Constructing a value of a zero-sized
+/// variant writes only the tag.
+///
+/// The generated code refers to this crate as `::trait_union`.
+///
+/// The unsafe code of the expansion does not count as code of the calling crate, so the
+/// macros can be used in crates with `#![forbid(unsafe_code)]`.
+///
+/// Helper items are generated inside `const _: () = { ... };` blocks, so they do not add
+/// names to the surrounding scope. Only the hidden storage types of the struct's fields
+/// are generated next to it. A declaration can be placed wherever items are
+/// allowed, e.g. in a module, a function body, or the expansion of another macro. It
+/// cannot be placed in an `impl` block since it declares types.
+///
+/// # Visibility
+///
+/// The variants can be less visible than the trait-union, e.g. private types of the
+/// declaring module in a `pub` union. The struct, `new`, and the trait object work
+/// wherever the union is visible, and other modules and crates cannot name the private
+/// variants.
+///
+/// The `Ref`, `Mut`, and `Owned` enums contain the variants, so they and the methods that
+/// use them are private to the module of the declaration. The [`enums`](#enums) option
+/// gives them the visibility of the union, which requires all variants to be at least as
+/// visible as the union. Variants must be visible in the module of the declaration.
+///
+/// # Recursive unions
+///
+/// `Self` in a variant refers to the generated type. Like any recursive type, a union
+/// that contains itself needs indirection:
+///
+/// ```rust,ignore
+/// union Expr: Eval = i64 | Box<Self> | Vec<Expr>;
+/// ```
+///
+/// A variant that contains the union inline, e.g. `Self`, `(u8, Expr)`, or
+/// `Option<Self>`, is rejected with an "infinite size" error. The macro cannot see the
+/// fields of other types, so recursion through a struct without indirection is reported
+/// by rustc instead.
+///
+/// # Box variants
+///
+/// A variant prefixed with `box` is stored in a `Box` and requires the `alloc` feature,
+/// which is enabled by default. Dereferencing goes through the box, so only the boxed
+/// type has to implement the trait. This allows trees with unboxed leaves and boxed
+/// interior nodes:
+///
+/// ```rust
+/// # use trait_union::trait_union;
+/// trait Eval {
+///     fn eval(&self) -> i64;
+/// }
+///
+/// impl Eval for i64 {
+///     fn eval(&self) -> i64 {
+///         *self
+///     }
+/// }
+///
+/// struct Add(Expr, Expr);
+///
+/// impl Eval for Add {
+///     fn eval(&self) -> i64 {
+///         self.0.eval() + self.1.eval()
+///     }
+/// }
+///
+/// trait_union! {
+///     union Expr: Eval = i64 | box Add;
+/// }
+///
+/// let sum = Expr::from(Box::new(Add(Expr::new(1), Expr::new(2))));
+/// let sum = Expr::from(Box::new(Add(sum, Expr::new(3))));
+/// assert_eq!(sum.eval(), 6);
+/// assert_eq!(Expr::kind_of(&sum), ExprKind::BoxAdd);
+/// ```
+///
+/// `box Self` boxes a value of the trait-union itself and dereferences to its active
+/// variant like a [flattened](#flattening) variant. Combined with
+/// `#[trait_union(flatten)]`, `box` does the same for another trait-union.
+///
+/// Like flattened variants, box variants are not variants of the marker trait. Values are
+/// created with `From<Box<T>>`. The name of their kind starts with `Box`. Box variants
+/// are not supported by [trait_union_copy].
+///
+/// # Tags
+///
+/// Tags are assigned like enum discriminants: A variant without an explicit tag uses the
+/// tag of the previous variant plus one, the first variant uses 0 by default. For
+/// example
+///
+/// ```rust,ignore
+/// union Handler: Handle = 1: TcpHandler | 2: TlsHandler | UdpHandler;
+/// ```
+///
+/// assigns the tags 1, 2, and 3. Tags must be unique. Explicit tags should be used if the
+/// tags are persisted or sent over the wire, so that reordering or inserting variants
+/// does not change existing tags.
+///
+/// With the [`tag = "type_name_hash"`](#tag) option, variants without an explicit tag use
+/// a hash of their type instead, which does not change when variants are reordered.
+///
+/// Tags are represented by the smallest of `u8`, `u16`, `u32`, and `u64` that fits all
+/// tags.
+///
+/// # Kinds
+///
+/// The variants of the kind enum are named after the types of the variants: The last
+/// segments of paths, lifetimes, and generic arguments are converted to CamelCase and
+/// concatenated. For example, `i32` becomes `I32`, `&'static str` becomes `StaticStr`,
+/// `Vec<u8>` becomes `VecU8`, and `extern "C" fn()` becomes `ExternCFn`. If a name cannot
+/// be derived or is not unique, the variant is named `V` followed by its position. The
+/// discriminants are the tags.
+///
+/// A name can be chosen explicitly with an attribute on the variant:
+///
+/// ```rust,ignore
+/// union Handler: Handle = #[trait_union(name = Tcp)] TcpHandler<Buf> | UdpHandler;
+/// ```
+///
+/// Doc comments on a variant document the variants of the kind, reference, and owned
+/// enums in place of the type name, and the implementation of the marker trait:
+///
+/// ```rust,ignore
+/// union Stream: Read + Write =
+///     /// The plain TCP stream
+///     TcpStream |
+///     /// The TLS-backed stream
+///     TlsStream<TcpStream>;
+/// ```
+///
+/// # Flattening
+///
+/// A trait-union with the same trait can be used as a variant by marking it with
+/// `#[trait_union(flatten)]`:
+///
+/// ```rust,ignore
+/// union NetHandler: Handle = TcpHandler | UdpHandler;
+/// union Handler: Handle = #[trait_union(flatten)] NetHandler | FileHandler;
+/// ```
+///
+/// The inner trait-union does not have to implement the trait. Instead, dereferencing
+/// uses the vtable of its active variant directly. A flattened variant is not a variant
+/// of the marker trait. Values are created with `From`:
+///
+/// ```rust,ignore
+/// let handler = Handler::from(NetHandler::new(TcpHandler::new()));
+/// ```
+///
+/// Flattened variants are not supported by [trait_union_copy].
+///
+/// The variants of a trait-union must be listed in a single invocation. There is no
+/// incremental form that appends variants in separate invocations and computes the layout
+/// in a final one, because macros cannot collect declarations from other invocations.
+/// Flattening is the way to declare variants near their definitions: Each module
+/// declares a trait-union of its own variants and a central trait-union flattens them:
+///
+/// ```rust,ignore
+/// mod net {
+///     trait_union! {
+///         pub(crate) union NetHandler: Handle = TcpHandler | UdpHandler;
+///     }
+/// }
+///
+/// trait_union! {
+///     union Handler: Handle = #[trait_union(flatten)] net::NetHandler | FileHandler;
+/// }
+/// ```
+///
+/// # Default variant
+///
+/// A variant whose type implements `Default` can be marked with
+/// `#[trait_union(default)]`:
+///
+/// ```rust,ignore
+/// union Connection: Handle = #[trait_union(default)] Closed | Open | Failed;
+/// ```
+///
+/// The struct then implements `Default` by creating the default value of this variant,
+/// and
+///
+/// ```rust,ignore
+/// pub(crate) fn take(&mut self) -> Self { /* ... */ }
+/// ```
+///
+/// moves the value out of a mutable reference and leaves the default variant in its
+/// place. State machines that consume their current state use this to transition without
+/// wrapping the state in an `Option`:
+///
+/// ```rust,ignore
+/// *conn = match Connection::into_enum(conn.take()) {
+///     ConnectionOwned::Closed(closed) => Connection::new(closed.open()),
+///     ConnectionOwned::Open(open) => Connection::new(open.close()),
+///     ConnectionOwned::Failed(failed) => Connection::new(failed),
+/// };
+/// ```
+///
+/// At most one variant can be the default. The default variant cannot be combined with
+/// the `new_vis` option.
+///
+/// # Fields
+///
+/// Small values can be stored next to the active variant by declaring them like the
+/// fields of a struct:
+///
+/// ```rust,ignore
+/// union Event: Handle { pub priority: u8 } = Click | Key | Resize;
+/// ```
+///
+/// The fields are fields of the generated struct. Unlike a struct wrapping the
+/// trait-union, they can occupy the padding after the tag, so the field above usually
+/// does not change the size. This matters for dense queues of values.
+///
+/// The types of the fields must implement `Copy` and `Default`. Constructors, including
+/// `from_enum` and `new_raw`, initialize the fields with their defaults, and `take`
+/// leaves the defaults behind. Functions that replace the variant of an existing value,
+/// such as `transition`, `set`, and `move_variant`, keep its fields. `reborrow` keeps the
+/// fields whose types do not mention the lifetime and resets the others. Fields cannot be
+/// combined with the `open` and `atomic` options and are not supported by
+/// [trait_union_cow].
+///
+/// # Provided references
+///
+/// A variant whose type implements [Provide] can be marked with
+/// `#[trait_union(provide)]`:
+///
+/// ```rust,ignore
+/// union Error: Display = #[trait_union(provide)] IoError | ParseError;
+/// ```
+///
+/// The struct implements [Provide] by forwarding requests to its active variant, and
+///
+/// ```rust,ignore
+/// pub fn provide<T: ?Sized + 'static>(&self) -> Option<&T> { /* ... */ }
+/// ```
+///
+/// returns a reference to `T` if the active variant provides one. This discovers
+/// capabilities of a variant, such as a backtrace or a `dyn Debug` view, without naming
+/// its type. Variants that are not marked provide nothing. Flattened variants always
+/// forward requests.
+///
+/// The method is only generated if at least one variant is marked, so that it does not
+/// shadow a `provide` method of the trait. Other unions still implement [Provide] and can
+/// be queried with [request_ref].
+///
+/// # Options
+///
+/// The generated code can be configured with `#[trait_union(...)]` attributes. These
+/// attributes are not forwarded to the struct. Unknown options are rejected. Except for
+/// `covariant`, `invariant`, `phantom`, `require`, and `forward`, each option can be
+/// given at most once. The following options are available:
+///
+/// ## `abi_check`
+///
+/// ```rust,ignore
+/// #[trait_union(abi_check)]
+/// #[trait_union(abi_check = 0x1234_5678_9abc_def0)]
+/// ```
+///
+/// Generates an associated constant
+///
+/// ```rust,ignore
+/// pub(crate) const LAYOUT_HASH: u64 = /* ... */;
+/// ```
+///
+/// which is a hash of the order, tags, sizes, and alignments of the variants. The types
+/// of the variants are not part of the hash, so replacing a variant by a type with the
+/// same layout, or spelling its type differently, does not change it. If a hash is given,
+/// compilation fails unless it matches `LAYOUT_HASH`. For unions with type parameters,
+/// this check happens when `new` is instantiated.
+///
+/// ## `report_size`
+///
+/// ```rust,ignore
+/// #[trait_union(report_size)]
+/// #[trait_union(report_size = 32)]
+/// ```
+///
+/// Generates an associated constant
+///
+/// ```rust,ignore
+/// pub(crate) const SIZE_REPORT: SizeReport = /* ... */;
+/// ```
+///
+/// with the size and alignment of the union and its largest variant. Its [SizeReport]
+/// implementation of `Display` prints them in a single line, e.g. for a test that records
+/// the sizes of all unions of a crate.
+///
+/// The macro runs before the sizes are known, so it cannot print them itself. If a size
+/// is given, a change of the size of the union emits a deprecation warning such as "the
+/// size of U is no longer 32 bytes, see U::SIZE_REPORT". Updating the pinned size makes
+/// the change visible in code review. Unlike `abi_check`, the build still succeeds unless
+/// warnings are denied. A pinned size is not supported for unions with type or const
+/// parameters.
+///
+/// ## `pod`
+///
+/// ```rust,ignore
+/// #[trait_union(pod)]
+/// ```
+///
+/// Requires the `bytemuck` feature. All variants must implement `bytemuck::Pod`.
+/// Generates a tagged binary codec:
+///
+/// ```rust,ignore
+/// pub(crate) fn decode(tag: u8, bytes: &[u8]) -> Result<Self, DecodeError> { /* ... */ }
+///
+/// pub(crate) fn encode(&self, out: &mut [u8]) -> usize { /* ... */ }
+///
+/// pub(crate) fn as_bytes(&self) -> (MyUnionKind, &[u8]) { /* ... */ }
+/// ```
+///
+/// `decode` creates the variant with the tag `tag` from its unaligned bytes. `encode`
+/// writes the bytes of the active variant and returns their number. It panics if `out`
+/// is too short. `as_bytes` borrows the bytes of the active variant without copying
+/// them, e.g. to checksum or hash values without matching on their kind.
+///
+/// ## `atomic`
+///
+/// ```rust,ignore
+/// #[trait_union(pod, atomic)]
+/// ```
+///
+/// Requires the `pod` option, tags that fit in a `u8`, and variants of at most 7 bytes.
+/// Generates `AtomicMyUnion` which packs the tag and the bytes of the active variant into
+/// an `AtomicU64`:
+///
+/// ```rust,ignore
+/// pub(crate) struct AtomicMyUnion { /* ... */ }
+///
+/// impl AtomicMyUnion {
+///     pub(crate) fn new(value: MyUnion) -> Self { /* ... */ }
+///
+///     pub(crate) fn load(&self, order: Ordering) -> MyUnion { /* ... */ }
+///
+///     pub(crate) fn store(&self, value: MyUnion, order: Ordering) { /* ... */ }
+///
+///     pub(crate) fn swap(&self, value: MyUnion, order: Ordering) -> MyUnion { /* ... */ }
+///
+///     pub(crate) fn compare_exchange(
+///         &self,
+///         current: MyUnion,
+///         new: MyUnion,
+///         success: Ordering,
+///         failure: Ordering,
+///     ) -> Result<MyUnion, MyUnion> { /* ... */ }
+///
+///     pub(crate) fn into_inner(self) -> MyUnion { /* ... */ }
+/// }
+/// ```
+///
+/// `compare_exchange` compares values by their tags and bytes. The type is only available
+/// on targets with 64-bit atomics.
+///
+/// ## `impl`
+///
+/// ```rust,ignore
+/// #[trait_union(impl = "enum")]
+/// ```
+///
+/// Stores the value in a plain enum and dispatches with `match` instead of using a union
+/// and a vtable lookup. The public API is the same. This is useful to rule out the unsafe
+/// union code when debugging. The default is `impl = "union"`.
+///
+/// ## `inline`
+///
+/// ```rust,ignore
+/// #[trait_union(inline = "never")]
+/// ```
+///
+/// Sets the inline attribute of the generated methods and trait implementations, such as
+/// `new`, `deref`, and `drop`. The default is `inline = "always"`, which gives the best
+/// run-time performance. `inline = "hint"` uses a plain `#[inline]` and leaves the
+/// decision to the compiler. `inline = "never"` reduces code size when a union is
+/// constructed or dereferenced at many call sites.
+///
+/// ## `tag`
+///
+/// ```rust,ignore
+/// #[trait_union(tag = "type_name_hash")]
+/// ```
+///
+/// Selects the tags of variants without an explicit tag. The default is `tag = "index"`,
+/// which assigns tags like enum discriminants. With `tag = "type_name_hash"`, the tag is
+/// the 64-bit FNV-1a hash of the type as written, without whitespace and without `box`,
+/// e.g. of the bytes `Option<String>` for `Option < String >`. Such tags survive
+/// reordering, inserting, and removing variants without manual tag assignment, which
+/// suits tags that are exchanged between processes. Since the type is hashed as written,
+/// `String` and `std::string::String` have different tags. A collision is reported as a
+/// duplicate tag and can be resolved with an explicit tag.
+///
+/// ## `covariant` and `invariant`
+///
+/// ```rust,ignore
+/// #[trait_union(covariant('a, 'b))]
+/// #[trait_union(invariant('c, T))]
+/// ```
+///
+/// By default, the variance of the generated type over its parameters follows from the
+/// variant types. `covariant` fails compilation unless the type is covariant in the
+/// listed lifetimes. `invariant` makes the type invariant in the listed lifetime and type
+/// parameters. Use these options to commit to a variance in a public API that does not
+/// change when variants are added or removed.
+///
+/// ## `reborrow`
+///
+/// ```rust,ignore
+/// #[trait_union(reborrow)]
+/// union MyUnion<'a>: Write + 'a = &'a mut Vec<u8> | &'a mut (dyn Write + 'a);
+/// ```
+///
+/// Requires exactly one lifetime parameter and variants that are references with that
+/// lifetime. Generates
+///
+/// ```rust,ignore
+/// pub(crate) fn reborrow<'s>(&'s mut self) -> MyUnion<'s> { /* ... */ }
+/// ```
+///
+/// which takes `&'s self` if all variants are shared references. A union of mutable
+/// references to trait objects is invariant in its lifetime, so a `&mut MyUnion<'a>`
+/// cannot be passed to a function that expects a `&mut MyUnion<'s>`. The reborrowed union
+/// can, and the original union is usable again once the reborrow ends.
+///
+/// ## `visitor`
+///
+/// ```rust,ignore
+/// #[trait_union(visitor)]
+/// ```
+///
+/// Generates a visitor trait whose methods are generic over the type of the variant
+///
+/// ```rust,ignore
+/// pub(crate) trait MyUnionVisitor<'a, T: 'a> where T: Debug+Copy {
+///     type Output;
+///
+///     fn visit<V: ?Sized + Debug+'a>(self, value: &V) -> Self::Output;
+///
+///     fn visit_mut<V: ?Sized + Debug+'a>(self, value: &mut V) -> Self::Output { /* ... */ }
+/// }
+/// ```
+///
+/// and the methods
+///
+/// ```rust,ignore
+/// pub(crate) fn visit<W: MyUnionVisitor<'a, T>>(&self, visitor: W) -> W::Output { /* ... */ }
+///
+/// pub(crate) fn visit_mut<W: MyUnionVisitor<'a, T>>(&mut self, visitor: W) -> W::Output { /* ... */ }
+/// ```
+///
+/// which match on the active variant and call the visitor with a reference to its value.
+/// The visitor is instantiated for each variant, so the trait methods it calls are
+/// dispatched statically and can be inlined into it. This can be faster than going
+/// through the vtable in hot loops. Boxed and flattened variants are visited as trait
+/// objects.
+///
+/// ```rust
+/// # use trait_union::trait_union;
+/// trait Op {
+///     fn apply(&self, acc: i64) -> i64;
+/// }
+///
+/// struct Add(i64);
+/// struct Neg;
+///
+/// impl Op for Add {
+///     fn apply(&self, acc: i64) -> i64 {
+///         acc + self.0
+///     }
+/// }
+///
+/// impl Op for Neg {
+///     fn apply(&self, acc: i64) -> i64 {
+///         -acc
+///     }
+/// }
+///
+/// trait_union! {
+///     #[trait_union(visitor)]
+///     union AnyOp: Op = Add | Neg;
+/// }
+///
+/// struct Apply(i64);
+///
+/// impl AnyOpVisitor for Apply {
+///     type Output = i64;
+///
+///     fn visit<V: ?Sized + Op>(self, op: &V) -> i64 {
+///         op.apply(self.0)
+///     }
+/// }
+///
+/// let program = [AnyOp::new(Add(2)), AnyOp::new(Neg), AnyOp::new(Add(5))];
+/// let result = program.iter().fold(0, |acc, op| op.visit(Apply(acc)));
+/// assert_eq!(result, 3);
+/// ```
+///
+/// ## `phantom`
+///
+/// ```rust,ignore
+/// #[trait_union(phantom = PhantomData<*const ()>)]
+/// ```
+///
+/// Adds a zero-sized marker of the given type to the generated type. The marker affects
+/// the auto traits and the variance of the generated type as if it were a field. For
+/// example, the marker above makes the type `!Send` and `!Sync` even if all variants are
+/// `Send` and `Sync`. This option can be given multiple times. A generic parameter that
+/// no variant uses must be mentioned by such a marker.
+///
+/// ## `require`
+///
+/// ```rust,ignore
+/// #[trait_union(require(Send, Sync))]
+/// ```
+///
+/// Fails compilation unless the generated type implements the listed traits. The auto
+/// traits of the type follow from the variants, so adding a variant such as an `Rc` can
+/// silently make it `!Send`. With this option, the error is reported at the declaration
+/// instead of in distant code that sends the values to other threads. For unions with
+/// generic parameters, the check uses the bounds of the declaration.
+///
+/// A slice of a `Sync` trait-union can be processed in parallel, e.g. with
+/// `std::thread::scope` or `par_iter` of the `rayon` crate. Each value dereferences to
+/// the trait object of its variant:
+///
+/// ```rust
+/// # use trait_union::trait_union;
+/// # use std::fmt::Display;
+/// #
+/// trait_union! {
+///     #[trait_union(require(Send, Sync))]
+///     union Container: Display = i32 | &'static str | bool;
+/// }
+///
+/// let values = [Container::new(1), Container::new("a"), Container::new(true)];
+/// let (left, right) = values.split_at(1);
+/// let strings = std::thread::scope(|s| {
+///     let left = s.spawn(|| left.iter().map(|c| c.to_string()).collect::<Vec<_>>());
+///     let mut strings = right.iter().map(|c| c.to_string()).collect::<Vec<_>>();
+///     strings.splice(0..0, left.join().unwrap());
+///     strings
+/// });
+/// assert_eq!(strings, ["1", "a", "true"]);
+/// ```
+///
+/// ## `constructors`
+///
+/// ```rust,ignore
+/// #[trait_union(constructors)]
+/// ```
+///
+/// Requires all variants, including flattened ones, to implement `Default`. Generates
+/// constructors of the default values of the variants for data-driven instantiation:
+///
+/// ```rust,ignore
+/// pub(crate) const CONSTRUCTORS: [fn() -> Self; N] = /* ... */;
+///
+/// pub(crate) fn from_kind(kind: MyUnionKind) -> Self { /* ... */ }
+///
+/// pub(crate) fn from_kind_name(name: &str) -> Option<Self> { /* ... */ }
+/// ```
+///
+/// `CONSTRUCTORS` is in declaration order. `from_kind_name` looks up the variant by the
+/// name of its kind, e.g. a handler name read from a configuration file.
+///
+/// ## `drop_tests`
+///
+/// ```rust,ignore
+/// #[trait_union(drop_tests)]
+/// ```
+///
+/// Requires all variants to implement `Default` and the union to have no type or const
+/// parameters. Generates a `#[cfg(test)]` test that creates and drops the default value
+/// of each variant and replaces each variant by each other variant. Run the test under
+/// Miri with `cargo miri test` to detect leaks and double drops. The test is only found
+/// by the test harness if the union is declared outside of a function.
+///
+/// ## `live_counts`
+///
+/// ```rust,ignore
+/// #[trait_union(live_counts)]
+/// ```
+///
+/// Counts the live values of each variant with an atomic counter that is incremented on
+/// construction and decremented on drop. The counts can be inspected with
+///
+/// ```rust,ignore
+/// pub(crate) fn live_counts() -> [usize; N] { /* ... */ }
+/// ```
+///
+/// which returns them in declaration order. Not supported by [trait_union_copy].
+///
+/// ## `metrics`
+///
+/// ```rust,ignore
+/// #[trait_union(metrics)]
+/// ```
+///
+/// Requires the `metrics` feature. Generates a hook trait whose methods are called with
+/// the kind of each value that is created and each value that is dropped or moved out
+/// with `into_enum`:
+///
+/// ```rust,ignore
+/// pub(crate) trait MyUnionMetrics: Sync {
+///     fn on_new(&self, kind: MyUnionKind) { }
+///
+///     fn on_drop(&self, kind: MyUnionKind) { }
+/// }
+/// ```
+///
+/// A hook is registered once, usually at startup, with
+///
+/// ```rust,ignore
+/// pub(crate) fn set_metrics(metrics: &'static dyn MyUnionMetrics) -> Result<(), &'static dyn MyUnionMetrics> { /* ... */ }
+/// ```
+///
+/// which returns `metrics` if a hook is already registered. Values are not observed
+/// before a hook is registered. Not supported by [trait_union_copy].
+///
+/// ## `schema`
+///
+/// ```rust,ignore
+/// #[trait_union(schema)]
+/// ```
+///
+/// Generates a machine-readable description of the union for code generators:
+///
+/// ```rust,ignore
+/// pub(crate) const SCHEMA: Schema = /* ... */;
+/// ```
+///
+/// It contains the trait, the layout, and the kind and type name of each variant.
+/// [Schema::write_json] serializes it, e.g. in a small binary whose output is read by the
+/// generator:
+///
+/// ```rust
+/// # use trait_union::trait_union;
+/// # use std::fmt::Display;
+/// trait_union! {
+///     #[trait_union(schema)]
+///     union Value: Display = i64 | bool;
+/// }
+///
+/// let mut json = String::new();
+/// Value::SCHEMA.write_json(&mut json).unwrap();
+/// assert!(json.starts_with(r#"{"name":"Value","trait":"Display + 'static","#));
+/// ```
+///
+/// ## `new_vis`
+///
+/// ```rust,ignore
+/// #[trait_union(new_vis = "pub(crate)")]
+/// pub union MyUnion: Debug = u8 | &'static str;
+/// ```
+///
+/// Sets the visibility of the functions that create a value from a variant: `new`,
+/// `from_enum`, `transition`, and, if generated, `CONSTRUCTORS`, `from_kind`,
+/// `from_kind_name`, `decode`, `set`, and `replace`. The visibility of the struct and its
+/// other items is unchanged. This makes the union an opaque handle to code outside the
+/// crate, which receives values only from functions of the crate. Cannot be combined with
+/// flattened or box variants because they are created with public `From` implementations.
+///
+/// ## `enums`
+///
+/// ```rust,ignore
+/// #[trait_union(enums)]
+/// ```
+///
+/// Gives the ref, mut, and owned enums and the methods that take or return them,
+/// `as_ref_enum`, `as_mut_enum`, `into_enum`, `from_enum`, and `transition`, the
+/// visibility of the union. `from_enum` and `transition` keep the visibility of `new`.
+/// Without this option, they are private to the module of the declaration, so variants
+/// can be private types of that module. With it, all variants must be at least as
+/// visible as the union.
+///
+/// ## `non_exhaustive`
+///
+/// ```rust,ignore
+/// #[trait_union(non_exhaustive)]
+/// ```
+///
+/// Marks the kind enum and the ref, mut, and owned enums as `#[non_exhaustive]`. Other
+/// crates then have to add a wildcard arm when matching on them, which allows libraries
+/// to add variants to an exported union in a semver-compatible way. Matches within the
+/// crate that declares the union are not affected.
+///
+/// ## `no_implicit_static`
+///
+/// ```rust,ignore
+/// #[trait_union(no_implicit_static)]
+/// ```
+///
+/// Rejects declarations whose trait bounds would otherwise get the `'static` lifetime
+/// added implicitly:
+///
+/// ```rust,ignore
+/// #[trait_union(no_implicit_static)]
+/// union MyUnion: Debug = u8; // error
+///
+/// #[trait_union(no_implicit_static)]
+/// union MyUnion: Debug + 'static = u8; // ok
+/// ```
+///
+/// An implicit `'static` forbids variants that borrow, which is often only reported at a
+/// distant call of `new`. With this option, the choice of the lifetime is visible at the
+/// declaration. A single lifetime parameter is still added implicitly. Not supported by
+/// [trait_union_cow].
+///
+/// ## `no_deref`
+///
+/// ```rust,ignore
+/// #[trait_union(no_deref)]
+/// ```
+///
+/// Does not implement `Deref` and `DerefMut`. The trait object is instead accessed
+/// explicitly with
+///
+/// ```rust,ignore
+/// pub(crate) fn as_dyn(&self) -> &(dyn Debug+'a) { /* ... */ }
+///
+/// pub(crate) fn as_dyn_mut(&mut self) -> &mut (dyn Debug+'a) { /* ... */ }
+/// ```
+///
+/// Methods of the trait are then not callable on the struct directly. The `From`
+/// conversions to trait object references are still implemented, and the union can still
+/// be flattened into other unions.
+///
+/// ## `forward`
+///
+/// ```rust,ignore
+/// #[trait_union(forward(Read, Write, Seek, fmt::Write, Hasher))]
+/// ```
+///
+/// Implements the listed traits of the standard library for the struct by calling the
+/// active variant. `Read`, `Write`, and `Seek` are the traits of `std::io`, `fmt::Write`
+/// and `Hasher` those of `core`. The trait object must implement the traits, usually
+/// because they are supertraits of the trait. Provided methods that implementations
+/// commonly override, such as `read_vectored`, `read_to_end`, `write_vectored`, and
+/// `write_all`, are forwarded as well so that the variants keep their performance
+/// characteristics. The union can then be passed to generic code:
+///
+/// ```rust
+/// # use trait_union::trait_union;
+/// # use std::io::{self, Cursor, Read, Seek};
+/// trait Input: Read + Seek {}
+///
+/// impl<T: Read + Seek> Input for T {}
+///
+/// trait_union! {
+///     #[trait_union(forward(Read, Seek))]
+///     union AnyInput: Input = Cursor<Vec<u8>> | io::Empty;
+/// }
+///
+/// fn len(mut input: impl Read + Seek) -> u64 {
+///     input.seek(io::SeekFrom::End(0)).unwrap()
+/// }
+///
+/// assert_eq!(len(AnyInput::new(Cursor::new(vec![1, 2, 3]))), 3);
+/// ```
+///
+/// With the `embedded-hal` feature, the traits `OutputPin`, `SpiBus`, `I2c`, and
+/// `DelayNs` of `embedded-hal` 1.0 can be forwarded as well, together with the
+/// corresponding `ErrorType`. The word type of `SpiBus` and the address mode of `I2c` are
+/// given as in `forward(SpiBus<u16>)`. A union of pins or buses can then be passed to
+/// drivers:
+///
+/// ```rust,ignore
+/// trait Led: OutputPin<Error = Infallible> {}
+///
+/// impl<T: OutputPin<Error = Infallible>> Led for T {}
+///
+/// trait_union! {
+///     #[trait_union(forward(OutputPin))]
+///     union AnyLed: Led = GpioA5 | GpioB3 | NoLed;
+/// }
+///
+/// let driver = Blinker::new(AnyLed::new(board.a5));
+/// ```
+///
+/// `forward(Future)` polls the active variant. With the `futures` feature,
+/// `forward(Stream)` does the same for the `Stream` trait of `futures-core`, so that a
+/// function can return one of several streams without boxing them. With the `tokio`
+/// feature, `forward(AsyncRead, AsyncWrite)` implements the traits of `tokio::io`. The
+/// traits of `futures-io` are forwarded with `forward(futures::AsyncRead,
+/// futures::AsyncWrite)` and require the `futures` feature. A union of connection types
+/// can then be used wherever `impl AsyncRead + AsyncWrite` is expected:
+///
+/// ```rust,ignore
+/// trait Conn: AsyncRead + AsyncWrite + Send {}
+///
+/// impl<T: AsyncRead + AsyncWrite + Send> Conn for T {}
+///
+/// trait_union! {
+///     #[trait_union(forward(AsyncRead, AsyncWrite))]
+///     union AnyConn: Conn = TcpStream | TlsStream<TcpStream> | UnixStream;
+/// }
+/// ```
+///
+/// Pinning the struct pins the variant, which is why the union cannot have box or
+/// flattened variants when forwarding these traits.
+///
+/// With the `tower` feature, `forward(Service<Request>)` implements the `Service` trait
+/// of `tower-service`. The associated types are those of the trait object, so all
+/// variants must agree on the response, error, and future types. Services with different
+/// futures can map them into a trait-union of futures that forwards `Future`:
+///
+/// ```rust,ignore
+/// trait Handler: Service<Request, Response = Response, Error = Error, Future = AnyFuture> {}
+///
+/// trait_union! {
+///     #[trait_union(forward(Future))]
+///     union AnyFuture: Future<Output = Result<Response, Error>> = RateLimited | Cached;
+///
+///     #[trait_union(forward(Service<Request>))]
+///     union AnyMiddleware: Handler = RateLimit<Api> | Cache<Api>;
+/// }
+/// ```
+///
+/// `Read`, `Write`, `Seek`, and `fmt::Write` can also be forwarded by single variants
+/// when not all variants implement them:
+///
+/// ```rust,ignore
+/// union Target: Debug = #[trait_union(forward(Write))] File | #[trait_union(forward(fmt::Write))] String | Null;
+/// ```
+///
+/// The struct then implements each trait that a variant forwards by calling the variant
+/// directly, so the trait does not have to be a supertrait. If the active variant does
+/// not forward the trait, the methods return an error of kind `Unsupported`, or
+/// `fmt::Error` for `fmt::Write`. A trait cannot be forwarded by single variants and by
+/// the union at the same time.
+///
+/// ## `dispatcher`
+///
+/// ```rust,ignore
+/// #[trait_union(dispatcher(capacity = 16, method = handle, ctx = Ctx))]
+/// ```
+///
+/// Generates a fixed-size queue of values that calls a method of the trait on each of
+/// them:
+///
+/// ```rust,ignore
+/// pub(crate) struct MyUnionDispatcher { /* ... */ }
+///
+/// impl MyUnionDispatcher {
+///     pub(crate) const CAPACITY: usize = 16;
+///
+///     pub(crate) fn new() -> Self { /* ... */ }
+///
+///     pub(crate) fn push(&mut self, value: MyUnion) -> Result<(), MyUnion> { /* ... */ }
+///
+///     pub(crate) fn pop(&mut self) -> Option<MyUnion> { /* ... */ }
+///
+///     pub(crate) fn dispatch_all(&mut self, ctx: &mut Ctx) { /* ... */ }
+/// }
+/// ```
+///
+/// The values are stored inline in a ring buffer without allocating. `push` returns the
+/// value if the queue is full. `dispatch_all` calls `value.handle(ctx)` on every queued
+/// value from the oldest to the newest and keeps them queued. Without `ctx`, the method
+/// is called without arguments. The dispatcher also has `len`, `is_empty`, `is_full`,
+/// and `clear`.
+///
+/// ```rust
+/// # use trait_union::trait_union;
+/// trait Event {
+///     fn handle(&mut self, log: &mut Vec<String>);
+/// }
+///
+/// struct Click(u32, u32);
+///
+/// struct Key(char);
+///
+/// impl Event for Click {
+///     fn handle(&mut self, log: &mut Vec<String>) {
+///         log.push(format!("click at {}, {}", self.0, self.1));
+///     }
+/// }
+///
+/// impl Event for Key {
+///     fn handle(&mut self, log: &mut Vec<String>) {
+///         log.push(format!("key {}", self.0));
+///     }
+/// }
+///
+/// trait_union! {
+///     #[trait_union(dispatcher(capacity = 4, method = handle, ctx = Vec<String>))]
+///     union AnyEvent: Event = Click | Key;
+/// }
+///
+/// let mut events = AnyEventDispatcher::new();
+/// events.push(AnyEvent::new(Key('a'))).ok().unwrap();
+/// events.push(AnyEvent::new(Click(1, 2))).ok().unwrap();
+/// let mut log = vec![];
+/// events.dispatch_all(&mut log);
+/// assert_eq!(log, ["key a", "click at 1, 2"]);
+/// ```
+///
+/// ## `enum_dispatch`
+///
+/// ```rust,ignore
+/// #[trait_union(enum_dispatch(fn area(&self) -> f64; fn scale(&mut self, by: f64)))]
+/// ```
+///
+/// Implements the trait for the owned enum, `MyUnionOwned`, by matching on the variant
+/// and calling the listed methods on its value, like `#[enum_dispatch]` does. The macro
+/// cannot see the definition of the trait, so its required methods are listed with their
+/// signatures. Methods must take `&self` or `&mut self`, and the trait is the first trait
+/// of the bounds. Associated type bindings, as in `Iterator<Item = u32>`, become
+/// associated types of the implementation. Boxed and flattened variants are called
+/// through their trait objects.
+///
+/// Both representations share the variant list, so a hot path can be measured with either
+/// one by converting with `into_enum` and `from_enum`:
+///
+/// ```rust
+/// # use trait_union::trait_union;
+/// trait Shape {
+///     fn area(&self) -> u32;
+/// }
+///
+/// struct Square(u32);
+///
+/// struct Rect(u32, u32);
+///
+/// impl Shape for Square {
+///     fn area(&self) -> u32 {
+///         self.0 * self.0
+///     }
+/// }
+///
+/// impl Shape for Rect {
+///     fn area(&self) -> u32 {
+///         self.0 * self.1
+///     }
+/// }
+///
+/// trait_union! {
+///     #[trait_union(enum_dispatch(fn area(&self) -> u32))]
+///     union AnyShape: Shape = Square | Rect;
+/// }
+///
+/// let unions = [AnyShape::new(Square(2)), AnyShape::new(Rect(2, 3))];
+/// let enums = unions.map(AnyShape::into_enum);
+/// assert_eq!(enums.iter().map(Shape::area).sum::<u32>(), 10);
+/// ```
+///
+/// ## `manual_impl`
+///
+/// ```rust,ignore
+/// #[trait_union(manual_impl(Wrapper<T>))]
+/// union MyUnion<T: Debug>: Debug = u8 | Wrapper<T>;
+/// ```
+///
+/// Makes the marker trait implementation of the listed variants depend on an unsafe trait
+/// that has to be implemented manually:
+///
+/// ```rust,ignore
+/// pub(crate) unsafe trait MyUnionManualVariant<T: Debug> {}
+///
+/// // SAFETY: ...
+/// unsafe impl<T: Debug + Send> MyUnionManualVariant<T> for Wrapper<T> {}
+/// ```
+///
+/// The bounds of the manual implementation restrict which instances can be stored, and
+/// its `unsafe` marks the place that documents why they are sound. This is useful for
+/// newtypes around foreign types whose invariants the crate cannot check. The listed
+/// types must be written as in the variants and must neither be boxed nor flattened. Not
+/// supported by [trait_union_cow].
+///
+/// ## `box_error`
+///
+/// ```rust,ignore
+/// #[trait_union(box_error)]
+/// union MyError: Error = io::Error | ParseIntError;
+/// ```
+///
+/// Requires the `alloc` feature. Implements
+///
+/// ```rust,ignore
+/// impl From<MyError> for Box<dyn Error + Send + Sync> { /* ... */ }
+/// ```
+///
+/// so that `?` converts the union at the boundary to application code that works with
+/// boxed errors, for example with `anyhow` or `eyre`. The active variant is boxed, not
+/// the union, so the boxed error can be downcast to the type of the variant. All variants
+/// must be `Send + Sync + 'static` and convertible with `From`, which holds for all error
+/// types and for flattened unions with the same option. Not supported by
+/// [trait_union_cow].
+///
+/// ## `cell`
+///
+/// ```rust,ignore
+/// #[trait_union(cell)]
+/// ```
+///
+/// Generates a method that replaces the value of the struct
+///
+/// ```rust,ignore
+/// pub(crate) fn set(&mut self, value: impl MyUnionVariant) { /* ... */ }
+/// ```
+///
+/// and a cell for struct fields that hold a swappable strategy or callback:
+///
+/// ```rust,ignore
+/// pub(crate) struct MyUnionCell { /* ... */ }
+///
+/// impl MyUnionCell {
+///     pub(crate) fn new(value: impl MyUnionVariant) -> Self { /* ... */ }
+///
+///     pub(crate) fn set(&self, value: impl MyUnionVariant) { /* ... */ }
+///
+///     pub(crate) fn replace(&self, value: impl MyUnionVariant) -> MyUnion { /* ... */ }
+///
+///     pub(crate) fn with<R>(&self, f: impl FnOnce(&mut MyUnion) -> R) -> R { /* ... */ }
+///
+///     pub(crate) fn get_mut(&mut self) -> &mut MyUnion { /* ... */ }
+///
+///     pub(crate) fn into_inner(self) -> MyUnion { /* ... */ }
+/// }
+/// ```
+///
+/// The value can be replaced through a shared reference, for example by a callback that
+/// installs its successor. Replacing it from within `with` panics. `set` drops the old
+/// value after the new value has been stored. Not supported by [trait_union_copy].
+///
+/// ## `external`
+///
+/// ```rust,ignore
+/// #[trait_union(external)]
+/// ```
+///
+/// Generates a handle type whose payload is stored in a byte buffer provided by the
+/// caller, e.g. an arena:
+///
+/// ```rust,ignore
+/// pub(crate) struct MyUnionHandle<'buf, 'a, T: 'a> where T: Debug+Copy { /* ... */ }
+///
+/// impl<'buf, 'a, T: 'a> MyUnionHandle<'buf, 'a, T> where T: Debug+Copy {
+///     pub(crate) fn new_in<V: MyUnionVariant<'a, T>>(buf: &'buf mut [MaybeUninit<u8>], value: V) -> Result<Self, V> { /* ... */ }
+///     pub(crate) fn from_union(buf: &'buf mut [MaybeUninit<u8>], value: MyUnion<'a, T>) -> Result<Self, MyUnion<'a, T>> { /* ... */ }
+///     pub(crate) fn into_union(self) -> MyUnion<'a, T> { /* ... */ }
+///     pub(crate) fn kind_of(this: &Self) -> MyUnionKind { /* ... */ }
+///     pub(crate) fn tag_of(this: &Self) -> u8 { /* ... */ }
+/// }
+/// ```
+///
+/// The handle is named `MyUnionHandle` instead of `MyUnionRef` because `MyUnionRef` is
+/// the enum returned by `as_ref_enum`. It holds only the kind and a pointer into the
+/// buffer, so its size does not depend on the largest variant, and each value only uses
+/// as many bytes of the buffer as its own variant needs. This helps if one variant is
+/// occasionally large but values are moved around frequently. `new_in` and `from_union`
+/// store the value at the first suitably aligned offset of the buffer and return it if it
+/// does not fit. The handle dereferences to the trait object like the union, or provides
+/// `as_dyn` and `as_dyn_mut` with the `no_deref` option, and drops the value when it is
+/// dropped. The buffer stays borrowed until then. The handle is `Send` and `Sync` if the
+/// union is.
+///
+/// ## `send`
+///
+/// ```rust,ignore
+/// #[trait_union(send)]
+/// ```
+///
+/// Generates a wrapper that can only be created from variants that implement `Send` and
+/// is therefore `Send` even if other variants are not:
+///
+/// ```rust,ignore
+/// pub(crate) struct MyUnionSend<'a, T: 'a>(MyUnion<'a, T>) where T: Debug+Copy;
+///
+/// impl<'a, T: 'a> MyUnionSend<'a, T> where T: Debug+Copy {
+///     pub(crate) fn new<V: MyUnionVariant<'a, T> + Send>(value: V) -> Self { /* ... */ }
+///     pub(crate) fn as_dyn_mut(&mut self) -> &mut (dyn Debug+'a) { /* ... */ }
+///     pub(crate) fn into_inner(self) -> MyUnion<'a, T> { /* ... */ }
+/// }
+/// ```
+///
+/// and
+///
+/// ```rust,ignore
+/// pub(crate) fn new_send<V: MyUnionVariant<'a, T> + Send>(value: V) -> MyUnionSend<'a, T> { /* ... */ }
+/// ```
+///
+/// The wrapper dereferences to the union and converts into it with `From`. It does not
+/// hand out mutable references to the union since those could replace the value by one
+/// that is not `Send`. Boxed and flattened variants cannot be stored in the wrapper. This
+/// gives a thread pool a `Send` table of handlers while other threads use the same union
+/// with all variants.
+///
+/// ## `open`
+///
+/// ```rust,ignore
+/// #[trait_union(open(size = 32, align = 8))]
+/// pub(crate) union MyUnion: Debug;
+/// ```
+///
+/// Declares a union without variants. Types anywhere in the crate become variants by
+/// deriving [TraitUnionVariant]. The union stores any variant of at most `size` bytes and
+/// an alignment of at most `align` which defaults to 8. Larger variants fail compilation.
+/// Since the variants are not known, the auto traits of the union are those of the trait
+/// object, e.g. the union is `Send` and `Unpin` only if the trait bounds include them.
+///
+/// If the trait is `Fn(...)` or `FnMut(...)`, closures, which cannot derive
+/// [TraitUnionVariant], are stored with
+///
+/// ```rust,ignore
+/// pub(crate) fn from_fn<F: FnMut(u32)>(f: F) -> Self { /* ... */ }
+/// ```
+///
+/// which fails to compile when instantiated with a closure that does not fit into the
+/// union. This is a stack-allocated replacement for `Box<dyn FnMut(u32)>`:
+///
+/// ```rust
+/// # use trait_union::trait_union;
+/// trait_union! {
+///     #[trait_union(open(size = 16))]
+///     union Callback: FnMut(u32) -> u32;
+/// }
+///
+/// let mut total = 0;
+/// let mut callback = Callback::from_fn(move |x| {
+///     total += x;
+///     total
+/// });
+/// callback(1);
+/// assert_eq!(callback(2), 3);
+/// ```
+///
+/// Only `new`, `from_fn`, and the `Deref`/`DerefMut` implementations are generated since
+/// the variants are not known to the macro. The union cannot have generic parameters, and
+/// `open` cannot be combined with options other than `new_vis`.
+///
+/// # Debug checks
+///
+/// With `debug_assertions` enabled, the generated code of `impl = "union"` validates its
+/// invariants and panics if they are violated:
+///
+/// - `new` checks that the variant's marker trait implementation is the generated one.
+/// - Accessing the value checks that it is correctly aligned and has not been dropped.
+/// - `drop` marks the value as dropped so that a second drop panics.
+///
+/// # Live value registry
+///
+/// With the `debug-registry` feature, every value is recorded in a thread-local list
+/// together with its variant and the location of its creation. The list can be
+/// inspected with [debug_registry::live_values] or printed with
+/// [debug_registry::dump_live_values], for example at the end of a soak test to find
+/// leaked values without changing the variant types. The feature slows down every
+/// construction and drop and is only meant for debugging.
+///
+/// # Panics in drop
+///
+/// Dropping the struct drops the active variant exactly once. If the `Drop`
+/// implementation of the variant panics, the panic propagates out of the drop of the
+/// struct. The remaining fields of the variant are dropped during unwinding as usual, and
+/// the struct is not dropped again. Live counts and `on_drop` hooks are updated before
+/// the variant is dropped and are therefore not affected. If an `on_drop` hook panics,
+/// the variant is leaked.
+#[cfg(feature = "proc-macro")]
+pub use trait_union_proc::trait_union;
+
+/// Macro that generates a trait-union type for [Copy] implementors
+///
+/// This macro is identical to [trait_union] except that
+///
+/// - all implementors must be [Copy]
+/// - the generated type is not [Drop]
+/// - `#[derive(Copy, Clone)]` can be used as an attribute
+///
+/// Additionally, the following functions replace the value in a `Cell` without the
+/// overhead of a `RefCell`:
+///
+/// ```rust,ignore
+/// pub(crate) fn get(cell: &Cell<Self>) -> Self { /* ... */ }
+///
+/// pub(crate) fn set(cell: &Cell<Self>, value: impl MyUnionVariant) { /* ... */ }
+///
+/// pub(crate) fn replace(cell: &Cell<Self>, value: impl MyUnionVariant) -> Self { /* ... */ }
+/// ```
+#[cfg(feature = "proc-macro")]
+pub use trait_union_proc::trait_union_copy;
+
+/// Derive macro that makes a type a variant of [open](trait_union#open) trait-unions
+///
+/// The unions are named in a `#[trait_union(...)]` attribute:
+///
+/// ```rust
+/// # use trait_union::{trait_union, TraitUnionVariant};
+/// # use std::fmt::Debug;
+/// #
+/// trait_union! {
+///     #[trait_union(open(size = 16))]
+///     union Handler: Debug;
+/// }
+///
+/// #[derive(Debug, TraitUnionVariant)]
+/// #[trait_union(Handler)]
+/// struct Retry(u32);
+///
+/// let handler = Handler::new(Retry(3));
+/// assert_eq!(format!("{:?}", &*handler), "Retry(3)");
+/// ```
+///
+/// The derive implements the `HandlerVariant` marker trait next to the union, so a path
+/// such as `crate::handlers::Handler` names a union in another module. It fails
+/// compilation if the type does not fit into the union. For generic types, this check
+/// happens when `new` is instantiated.
+#[cfg(feature = "proc-macro")]
+pub use trait_union_proc::TraitUnionVariant;
+
+/// Macro that checks that a trait-union has a variant for every implementor of a trait
+///
+/// Macros cannot see the implementations of a trait. Instead, the implementors register
+/// themselves by implementing the marker trait of the union, which is implemented exactly
+/// for the variants. Declaring the marker trait as a supertrait of a local trait makes
+/// implementations of the local trait for types that are not variants fail to compile:
+///
+/// ```rust
+/// # use trait_union::{assert_union_covers, trait_union};
+/// # use std::fmt::Debug;
+/// #
+/// trait_union! {
+///     union Handler: Debug = Login | Logout;
+/// }
+///
+/// trait Route: HandlerVariant {}
+///
+/// assert_union_covers!(Handler: Route);
+///
+/// #[derive(Debug)]
+/// struct Login;
+///
+/// #[derive(Debug)]
+/// struct Logout;
+///
+/// impl Route for Login {}
+///
+/// impl Route for Logout {}
+/// ```
+///
+/// Adding `impl Route for Signup {}` without listing `Signup` in the union is then an
+/// error. `assert_union_covers!(UNION: TRAIT)` fails to compile if `TRAIT` does not have
+/// this supertrait, so the check cannot silently be removed. Box variants are registered
+/// as `Box<T>`. Since the marker trait has an associated constant, the local trait is not
+/// dyn-compatible and should be a marker separate from the trait of the union.
+#[cfg(feature = "proc-macro")]
+pub use trait_union_proc::assert_union_covers;
+
+/// Macro that generates a pair of borrowed and owned trait-union types
+///
+/// Each declaration generates two trait-unions sharing the same trait. Every variant of
+/// the borrowed union is a shared reference which is paired with an owned type. This is
+/// the trait-union analogue of [Cow](https://doc.rust-lang.org/std/borrow/enum.Cow.html).
+///
+/// # Syntax
+///
+/// ```txt
+/// ATTRIBUTE* VISIBILITY? ('union' | 'enumlike') BORROWED GENERICS? ',' OWNED ':' TRAIT_BOUNDS ('where' WHERE_CLAUSE)? '=' TYPE '=>' TYPE ('|' TYPE '=>' TYPE)* '|'? ';'
+/// ```
+///
+/// The first lifetime parameter of the borrowed union is the lifetime of the borrowed
+/// variants. The owned union has the same generic parameters without this lifetime.
+///
+/// The trait bounds must not contain a lifetime. The borrowed union uses the lifetime of
+/// the borrowed variants, the owned union uses `'static`.
+///
+/// For example:
+///
+/// ```rust
+/// # use trait_union::trait_union_cow;
+/// # use std::fmt::Debug;
+/// #
+/// trait_union_cow! {
+///     union Borrowed<'a>, Owned: Debug = &'a str => String | &'a [u8] => Vec<u8>;
+/// }
+///
+/// let borrowed = Borrowed::new("Hello World");
+/// let owned: Owned = borrowed.to_owned();
+/// assert_eq!(format!("{:?}", &*owned), "\"Hello World\"");
+///
+/// let borrowed = owned.as_borrowed();
+/// assert_eq!(format!("{:?}", &*borrowed), "\"Hello World\"");
+/// ```
+///
+/// # Output
+///
+/// The macro generates both unions as described in [trait_union] and the following
+/// conversions:
+///
+/// ```rust,ignore
+/// impl<'a> Borrowed<'a> {
+///     fn to_owned(&self) -> Owned { /* ... */ }
+/// }
+///
+/// impl Owned {
+///     fn as_borrowed(&self) -> Borrowed<'_> { /* ... */ }
+/// }
+/// ```
+///
+/// `to_owned` converts a `&'a B` variant with [From] and `as_borrowed` uses
+/// [Borrow](core::borrow::Borrow) to borrow `B` from the owned variant.
+#[cfg(feature = "proc-macro")]
+pub use trait_union_proc::trait_union_cow;
+
+#[cfg(all(test, feature = "proc-macro"))]
+mod test {
+    use super::{trait_union, trait_union_copy, trait_union_cow, TraitUnionVariant};
+    use std::{
+        cell::Cell,
+        fmt,
+        fmt::{Display, Formatter},
+        mem,
+        rc::Rc,
+        sync::atomic::{AtomicUsize, Ordering::Relaxed},
+    };
+
+    trait F: Display {
+        fn len(&self) -> usize;
+
+        fn set_len(&mut self, len: usize);
+    }
+
+    impl F for u8 {
+        fn len(&self) -> usize {
+            *self as usize
+        }
+
+        fn set_len(&mut self, len: usize) {
+            *self = len as u8;
+        }
+    }
+
+    impl F for String {
+        fn len(&self) -> usize {
+            self.len()
+        }
+
+        fn set_len(&mut self, len: usize) {
+            self.truncate(len);
+        }
+    }
+
+    #[repr(align(4))]
+    struct X;
+    impl F for X {
+        fn len(&self) -> usize {
+            !0
+        }
+
+        fn set_len(&mut self, len: usize) {
+            X_DROP_COUNT.store(len, Relaxed);
+        }
+    }
+    static X_DROP_COUNT: AtomicUsize = AtomicUsize::new(0);
+    impl Drop for X {
+        fn drop(&mut self) {
+            X_DROP_COUNT.fetch_add(1, Relaxed);
+        }
+    }
+    impl Display for X {
+        fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+            write!(f, "X")
+        }
+    }
+
+    trait_union! {
+        union U: F = u8 | String | X;
+    }
+
+    trait_union! {
+        #[trait_union(drop_tests)]
+        union Droppable<'a>: fmt::Debug = u8 | String | Vec<u8> | Box<u32> | &'a str;
+
+        #[trait_union(impl = "enum", drop_tests)]
+        union EnumDroppable: fmt::Debug = u8 | String | Box<u32>;
+    }
+
+    #[test]
+    fn test1() {
+        let mut c = U::new(33);
+        assert_eq!(mem::align_of_val(&*c), 1);
+        assert_eq!(mem::size_of_val(&*c), 1);
+        assert!(mem::align_of_val(&c) >= 4);
+        assert!(mem::size_of_val(&c) >= 4);
+        assert_eq!(c.len(), 33);
+        c.set_len(22);
+        assert_eq!(c.len(), 22);
+        c = U::new("Hello World".to_string());
+        assert_eq!(c.len(), 11);
+        c.set_len(5);
+        assert_eq!(c.len(), 5);
+        assert_eq!(c.to_string(), "Hello");
+        c = U::new(X);
+        assert_eq!(mem::align_of_val(&*c), 4);
+        assert_eq!(mem::size_of_val(&*c), 0);
+        assert_eq!(c.len(), !0);
+        assert_eq!(X_DROP_COUNT.load(Relaxed), 0);
+        c.set_len(2);
+        assert_eq!(X_DROP_COUNT.load(Relaxed), 2);
+        drop(c);
+        assert_eq!(X_DROP_COUNT.load(Relaxed), 3);
+    }
+
+    #[test]
+    fn into_trait_object() {
+        fn len<'a>(value: impl Into<&'a dyn F>) -> usize {
+            value.into().len()
+        }
+
+        let mut u = U::new(3);
+        assert_eq!(len(&u), 3);
+        let m: &mut dyn F = (&mut u).into();
+        m.set_len(4);
+        assert_eq!(<&dyn F>::from(&u).len(), 4);
+    }
+
+    #[test]
+    fn size() {
+        assert_eq!(mem::size_of::<U>(), mem::size_of::<Option<U>>());
+    }
+
+    #[test]
+    fn compile() {
+        let t = trybuild::TestCases::new();
+        t.compile_fail("tests/compile-fail/*.rs");
+        t.pass("tests/pass/*.rs");
+        t.pass("examples/*.rs");
+    }
+
+    #[test]
+    fn copy() {
+        trait_union_copy! {
+            #[derive(Copy, Clone)]
+            union U: Display = u8 | &'static str;
+        }
+
+        let u = U::new("test");
+        let v = u;
+        assert_eq!(u.to_string(), v.to_string());
+    }
+
+    #[test]
+    fn copy_cell() {
+        trait_union_copy! {
+            union U: Display = u8 | &'static str;
+
+            #[trait_union(impl = "enum")]
+            union E: Display = u8 | &'static str;
+        }
+
+        let cell = Cell::new(U::new(1));
+        assert_eq!(U::get(&cell).to_string(), "1");
+        U::set(&cell, "a");
+        assert_eq!(U::get(&cell).to_string(), "a");
+        assert_eq!(U::replace(&cell, 2).to_string(), "a");
+        assert_eq!(U::get(&cell).to_string(), "2");
+
+        let cell = Cell::new(E::new("b"));
+        assert_eq!(E::replace(&cell, 3).to_string(), "b");
+        assert_eq!(E::get(&cell).to_string(), "3");
+    }
+
+    #[test]
+    fn cow() {
+        trait_union_cow! {
+            union B<'a>, O: fmt::Debug = &'a str => String | &'a [u8] => Vec<u8>;
+        }
+
+        let s = "test".to_string();
+        let b = B::new(&*s);
+        let o = b.to_owned();
+        drop(b);
+        drop(s);
+        assert_eq!(format!("{:?}", &*o), "\"test\"");
+        assert_eq!(format!("{:?}", &*o.as_borrowed()), "\"test\"");
+
+        let o = B::new(&[1u8, 2][..]).to_owned();
+        assert_eq!(format!("{:?}", &*o.as_borrowed()), "[1, 2]");
+    }
+
+    #[test]
+    fn shadowed_panic() {
+        #[allow(unused_macros)]
+        macro_rules! panic {
+            ($($tt:tt)*) => {};
+        }
+
+        trait_union! {
+            union U: Display = u8 | &'static str;
+        }
+
+        assert_eq!(U::new(1).to_string(), "1");
+    }
+
+    #[test]
+    fn enum_impl() {
+        trait_union! {
+            #[trait_union(impl = "enum")]
+            union E: F = u8 | 2: String;
+        }
+
+        trait_union_cow! {
+            #[trait_union(impl = "enum")]
+            union B<'a>, O: fmt::Debug = &'a str => String | &'a [u8] => Vec<u8>;
+        }
+
+        let mut e = E::new(1);
+        assert_eq!(e.len(), 1);
+        assert_eq!(E::tag_of(&e), 0);
+        e = E::new("hello".to_string());
+        e.set_len(4);
+        assert_eq!(e.to_string(), "hell");
+        assert_eq!(E::tag_of(&e), 2);
+
+        let o = B::new("test").to_owned();
+        assert_eq!(format!("{:?}", &*o.as_borrowed()), "\"test\"");
+    }
+
+    #[test]
+    #[cfg(feature = "debug-registry")]
+    fn debug_registry() {
+        use crate::debug_registry::live_values;
+
+        trait_union! {
+            union R: fmt::Debug = u8 | String;
+        }
+
+        let first = R::new(1);
+        let line = line!() - 1;
+        let second = R::new(String::new());
+        let live = live_values();
+        assert_eq!(live.len(), 2);
+        assert_eq!((live[0].union, live[0].variant), ("R", "u8"));
+        assert_eq!((live[1].union, live[1].variant), ("R", "String"));
+        assert_eq!(live[0].location.line(), line);
+        assert_eq!(live[0].location.file(), file!());
+        drop(first);
+        assert_eq!(live_values()[0].variant, "String");
+        let _ = R::into_enum(second);
+        assert!(live_values().is_empty());
+    }
+
+    #[test]
+    fn collect_union() {
+        use crate::CollectUnion;
+
+        trait_union! {
+            union G<'a, T: 'a + fmt::Debug>: fmt::Debug + 'a = &'a str | Option<T>;
+
+            #[trait_union(open(size = 8))]
+            union O: fmt::Debug;
+        }
+
+        #[derive(Debug, TraitUnionVariant)]
+        #[trait_union(O)]
+        struct P;
+
+        let mut g: Vec<G<u8>> = vec!["a", "b"].into_iter().collect_union();
+        g.extend(vec![Some(1), None].into_iter().unions());
+        let strings: Vec<_> = g.iter().map(|g| format!("{:?}", &**g)).collect();
+        assert_eq!(strings, ["\"a\"", "\"b\"", "Some(1)", "None"]);
+        let o: Vec<O> = vec![P].into_iter().collect_union();
+        assert_eq!(format!("{:?}", &*o[0]), "P");
+    }
+
+    #[test]
+    fn box_error() {
+        use std::{error::Error, io, num::ParseIntError};
+
+        trait_union! {
+            #[trait_union(box_error)]
+            union E: Error = io::Error | ParseIntError;
+        }
+
+        fn parse(s: &str) -> Result<u8, E> {
+            s.parse().map_err(E::new)
+        }
+
+        fn app(s: &str) -> Result<u8, Box<dyn Error + Send + Sync>> {
+            Ok(parse(s)?)
+        }
+
+        assert_eq!(app("1").unwrap(), 1);
+        let err = app("a").unwrap_err();
+        assert!(err.downcast_ref::<ParseIntError>().is_some());
+        let err: Box<dyn Error + Send + Sync> =
+            E::new(io::Error::from(io::ErrorKind::Other)).into();
+        assert_eq!(
+            err.downcast::<io::Error>().unwrap().kind(),
+            io::ErrorKind::Other
+        );
+    }
+
+    #[test]
+    fn cell() {
+        trait_union! {
+            #[trait_union(cell)]
+            union H<'a>: FnMut(u8) -> u8 + 'a = fn(u8) -> u8 | &'a mut dyn FnMut(u8) -> u8;
+        }
+
+        struct Button<'a> {
+            on_click: HCell<'a>,
+        }
+
+        let clicks = Cell::new(0);
+        let mut count = |x| {
+            clicks.set(clicks.get() + 1);
+            x
+        };
+        let button = Button {
+            on_click: HCell::new((|x| x + 1) as fn(u8) -> u8),
+        };
+        assert_eq!(button.on_click.with(|h| h.call(1)), 2);
+        let old = button
+            .on_click
+            .replace(&mut count as &mut dyn FnMut(u8) -> u8);
+        assert_eq!(button.on_click.with(|h| h.call(3)), 3);
+        button.on_click.set((|x| x * 2) as fn(u8) -> u8);
+        assert_eq!(button.on_click.into_inner().call(4), 8);
+        assert_eq!(clicks.get(), 1);
+
+        let mut h = old;
+        h.set((|x| x * 3) as fn(u8) -> u8);
+        assert_eq!(h.call(2), 6);
+        let mut c = HCell::from(h);
+        assert_eq!(c.get_mut().call(1), 3);
+    }
+
+    #[test]
+    fn reborrow() {
+        use std::io::Write;
+
+        trait_union! {
+            #[trait_union(reborrow)]
+            union W<'a>: Write + 'a = &'a mut Vec<u8> | &'a mut (dyn Write + 'a);
+
+            #[trait_union(reborrow)]
+            union R<'a>: fmt::Debug + 'a = &'a str | &'a [u8];
+        }
+
+        fn write<'s>(mut w: W<'s>, s: &'s mut String) {
+            w.write_all(b"x").unwrap();
+            s.push('x');
+        }
+
+        let mut buf = vec![];
+        let mut w = W::new(&mut buf as &mut dyn Write);
+        let mut s = String::new();
+        write(w.reborrow(), &mut s);
+        write(w.reborrow(), &mut s);
+        w.write_all(b"y").unwrap();
+        drop(w);
+        assert_eq!(buf, b"xxy");
+        assert_eq!(s, "xx");
+
+        let mut v = vec![];
+        let mut w = W::new(&mut v);
+        write(w.reborrow(), &mut s);
+        drop(w);
+        assert_eq!(v, b"x");
+
+        let r = R::new("a");
+        let short: R<'_> = r.reborrow();
+        assert_eq!(format!("{:?} {:?}", &*short, &*r), "\"a\" \"a\"");
+    }
+
+    #[test]
+    fn visitor() {
+        trait_union! {
+            #[trait_union(visitor)]
+            union V: F = u8 | String | box u8;
+
+            #[trait_union(visitor, no_deref, impl = "enum")]
+            union N: F = u8 | String;
+        }
+
+        struct Len;
+
+        impl VVisitor for Len {
+            type Output = usize;
+
+            fn visit<T: ?Sized + F>(self, value: &T) -> usize {
+                value.len()
+            }
+        }
+
+        impl NVisitor for Len {
+            type Output = usize;
+
+            fn visit<T: ?Sized + F>(self, value: &T) -> usize {
+                value.len()
+            }
+        }
+
+        struct SetLen(usize);
+
+        impl VVisitor for SetLen {
+            type Output = ();
+
+            fn visit<T: ?Sized + F>(self, _: &T) {
+                unreachable!();
+            }
+
+            fn visit_mut<T: ?Sized + F>(self, value: &mut T) {
+                value.set_len(self.0);
+            }
+        }
+
+        let mut v = V::new("abc".to_string());
+        assert_eq!(v.visit(Len), 3);
+        v.visit_mut(SetLen(1));
+        assert_eq!(v.to_string(), "a");
+        assert_eq!(V::new(2).visit(Len), 2);
+        assert_eq!(V::from(Box::new(4)).visit(Len), 4);
+        assert_eq!(N::new("ab".to_string()).visit(Len), 2);
+        assert_eq!(N::new(1).visit_mut(Len), 1);
+    }
+
+    #[test]
+    fn inline() {
+        trait_union! {
+            #[trait_union(inline = "never")]
+            union N: F = u8 | String;
+
+            #[trait_union(inline = "hint", impl = "enum")]
+            union H: Fn(u8) -> u8 = fn(u8) -> u8;
+        }
+
+        let mut n = N::new(1);
+        n.set_len(2);
+        assert_eq!(n.len(), 2);
+        assert_eq!(N::new("ab".to_string()).to_string(), "ab");
+        assert_eq!(H::new((|x| x + 1) as fn(u8) -> u8).call(1), 2);
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "use of a dropped U")]
+    fn double_drop() {
+        let mut u = mem::ManuallyDrop::new(U::new(1));
+        unsafe {
+            mem::ManuallyDrop::drop(&mut u);
+            mem::ManuallyDrop::drop(&mut u);
+        }
+    }
+
+    #[test]
+    fn assoc_types() {
+        trait_union! {
+            union I<'a, T: 'a + Copy>: Iterator<Item = T> + 'a =
+                std::iter::Once<T> | std::iter::Copied<std::slice::Iter<'a, T>>;
+        }
+
+        let v = [1, 2, 3];
+        let mut i = I::new(v.iter().copied());
+        assert_eq!(i.next(), Some(1));
+        assert_eq!((&mut *i).sum::<i32>(), 5);
+        i = I::new(std::iter::once(4));
+        assert_eq!(i.size_hint(), (1, Some(1)));
+    }
+
+    #[test]
+    fn generic_traits() {
+        trait_union! {
+            union A: AsRef<[u8]> = &'static str | Vec<u8>;
+
+            union E: PartialEq<u32> = u32;
+        }
+
+        assert_eq!(A::new("ab").as_ref(), b"ab");
+        assert_eq!(A::new(vec![1, 2, 3]).as_ref(), &[1, 2, 3]);
+        assert!(*E::new(1) == 1);
+        assert!(*E::new(1) != 2);
+    }
+
+    #[test]
+    fn same_variant() {
+        trait_union! {
+            #[trait_union(impl = "enum")]
+            union E: F = u8 | String;
+        }
+
+        assert!(U::same_variant(&U::new(1), &U::new(2)));
+        assert!(!U::same_variant(&U::new(1), &U::new(String::new())));
+        assert!(E::same_variant(&E::new(1), &E::new(2)));
+        assert!(!E::same_variant(&E::new(1), &E::new(String::new())));
+
+        trait Machine {
+            fn transition(&self) -> &'static str;
+
+            fn as_ref_enum(&self) -> u8;
+        }
+
+        impl Machine for u8 {
+            fn transition(&self) -> &'static str {
+                "step"
+            }
+
+            fn as_ref_enum(&self) -> u8 {
+                *self
+            }
+        }
+
+        trait_union! {
+            union M: Machine = u8;
+        }
+
+        let m = M::new(4);
+        assert_eq!(m.transition(), "step");
+        assert_eq!(m.as_ref_enum(), 4);
+    }
+
+    #[test]
+    fn kind_runs() {
+        trait_union! {
+            union R<'a>: fmt::Debug + 'a = u8 | &'a str;
+        }
+
+        let s = "a".to_string();
+        let mut values = vec![R::new(1), R::new(&*s), R::new(2), R::new(3), R::new("b")];
+        let runs = R::kind_runs(&values)
+            .map(|(k, r)| (k, r.len()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            runs,
+            [
+                (RKind::U8, 1),
+                (RKind::AStr, 1),
+                (RKind::U8, 2),
+                (RKind::AStr, 1)
+            ]
+        );
+        R::sort_by_kind(&mut values);
+        let runs = R::kind_runs(&values)
+            .map(|(k, r)| (k, r.len()))
+            .collect::<Vec<_>>();
+        assert_eq!(runs, [(RKind::U8, 3), (RKind::AStr, 2)]);
+        assert_eq!(R::kind_runs(&[]).count(), 0);
+    }
+
+    #[test]
+    fn is_zst_variant() {
+        trait_union! {
+            union U: fmt::Debug = u8 | ();
+
+            #[trait_union(impl = "enum")]
+            union E: fmt::Debug = u8 | ();
+        }
+
+        assert!(!U::is_zst_variant(&U::new(1)));
+        assert!(U::is_zst_variant(&U::new(())));
+        assert!(!E::is_zst_variant(&E::new(1)));
+        assert!(E::is_zst_variant(&E::new(())));
+    }
+
+    #[test]
+    fn new_raw() {
+        trait_union! {
+            #[trait_union(impl = "enum")]
+            union E: F = u8 | String;
+
+            union Outer: F = u8 | #[trait_union(flatten)] E;
+        }
+
+        let u = unsafe { U::new_unchecked(UKind::String, "ab".to_string()) };
+        assert_eq!(U::kind_of(&u), UKind::String);
+        assert_eq!(u.len(), 2);
+        let bytes = [0, 3];
+        let u = unsafe { U::new_raw(UKind::U8, bytes[1..].as_ptr()) };
+        assert_eq!(u.len(), 3);
+        let e = unsafe { E::new_unchecked(EKind::String, "abc".to_string()) };
+        assert_eq!(e.len(), 3);
+        let o = unsafe { Outer::new_unchecked(OuterKind::E, E::new(4)) };
+        assert_eq!(o.len(), 4);
+    }
+
+    #[test]
+    fn no_deref() {
+        trait_union! {
+            #[trait_union(no_deref)]
+            union N: F = u8 | String;
+
+            #[trait_union(impl = "enum", no_deref)]
+            union E: F = u8 | #[trait_union(flatten)] N;
+
+            union Outer: F = #[trait_union(flatten)] N | #[trait_union(flatten)] E;
+        }
+
+        let mut n = N::new(String::from("abc"));
+        n.as_dyn_mut().set_len(2);
+        assert_eq!(n.as_dyn().len(), 2);
+        let f: &dyn F = (&n).into();
+        assert_eq!(f.to_string(), "ab");
+        let mut e = E::from(N::new(3));
+        assert_eq!(e.as_dyn().len(), 3);
+        <&mut dyn F>::from(&mut e).set_len(4);
+        assert_eq!(e.as_dyn().len(), 4);
+        let mut o = Outer::from(e);
+        o.set_len(5);
+        assert_eq!(o.len(), 5);
+        assert_eq!(Outer::from(n).len(), 2);
+    }
+
+    #[test]
+    fn forward() {
+        use std::{
+            collections::hash_map::DefaultHasher,
+            hash::Hasher,
+            io::{self, Cursor, Read, Seek, SeekFrom},
+        };
+
+        trait ReadSeek: Read + Seek + fmt::Debug {}
+
+        impl<T: Read + Seek + fmt::Debug> ReadSeek for T {}
+
+        trait Sink: io::Write + fmt::Write + fmt::Debug {}
+
+        impl<T: io::Write + fmt::Write + fmt::Debug> Sink for T {}
+
+        trait_union! {
+            #[trait_union(forward(Read, Seek))]
+            union Input: ReadSeek = Cursor<Vec<u8>> | Cursor<&'static [u8]>;
+
+            #[trait_union(forward(Write), forward(fmt::Write))]
+            union Output: Sink = Buf;
+
+            #[trait_union(forward(Hasher), no_deref)]
+            union H: Hasher = DefaultHasher;
+        }
+
+        #[derive(Debug)]
+        struct Buf(String);
+
+        impl fmt::Write for Buf {
+            fn write_str(&mut self, s: &str) -> fmt::Result {
+                self.0.write_str(s)
+            }
+        }
+
+        impl io::Write for Buf {
+            fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+                self.0.push_str(std::str::from_utf8(buf).unwrap());
+                Ok(buf.len())
+            }
+
+            fn flush(&mut self) -> io::Result<()> {
+                Ok(())
+            }
+        }
+
+        fn read_tail(mut r: impl Read + Seek) -> String {
+            r.seek(SeekFrom::Start(1)).unwrap();
+            let mut s = String::new();
+            r.read_to_string(&mut s).unwrap();
+            s
+        }
+
+        fn write_both(mut w: impl io::Write + fmt::Write) {
+            io::Write::write_all(&mut w, b"a").unwrap();
+            fmt::Write::write_str(&mut w, "b").unwrap();
+        }
+
+        assert_eq!(read_tail(Input::new(Cursor::new(b"xyz".to_vec()))), "yz");
+        assert_eq!(read_tail(Input::new(Cursor::new(&b"xyz"[..]))), "yz");
+        let mut o = Output::new(Buf(String::new()));
+        write_both(&mut o);
+        assert_eq!(format!("{:?}", &*o), "Buf(\"ab\")");
+        let mut h = H::new(DefaultHasher::new());
+        let mut d = DefaultHasher::new();
+        h.write_u32(1);
+        d.write_u32(1);
+        assert_eq!(h.finish(), d.finish());
+    }
+
+    #[test]
+    fn forward_provided_methods() {
+        use std::io::{self, IoSlice, IoSliceMut, Read, Write};
+
+        trait Stream: Read + Write {}
+
+        impl<T: Read + Write> Stream for T {}
+
+        #[derive(Default)]
+        struct Vectored {
+            calls: Vec<&'static str>,
+        }
+
+        impl Read for Vectored {
+            fn read(&mut self, _buf: &mut [u8]) -> io::Result<usize> {
+                self.calls.push("read");
+                Ok(0)
+            }
+
+            fn read_vectored(
+                &mut self,
+                _bufs: &mut [IoSliceMut<'_>],
+            ) -> io::Result<usize> {
+                self.calls.push("read_vectored");
+                Ok(0)
+            }
+
+            fn read_to_end(&mut self, _buf: &mut Vec<u8>) -> io::Result<usize> {
+                self.calls.push("read_to_end");
+                Ok(0)
+            }
+        }
+
+        impl Write for Vectored {
+            fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+                self.calls.push("write");
+                Ok(buf.len())
+            }
+
+            fn write_vectored(&mut self, _bufs: &[IoSlice<'_>]) -> io::Result<usize> {
+                self.calls.push("write_vectored");
+                Ok(0)
+            }
+
+            fn flush(&mut self) -> io::Result<()> {
+                self.calls.push("flush");
+                Ok(())
+            }
+        }
+
+        trait_union! {
+            #[trait_union(forward(Read, Write))]
+            union S: Stream = Vectored;
+        }
+
+        fn use_stream(mut s: impl Read + Write) {
+            let n = s.read_vectored(&mut [IoSliceMut::new(&mut [0; 4])]);
+            assert_eq!(n.unwrap(), 0);
+            s.read_to_end(&mut vec![]).unwrap();
+            assert_eq!(s.write_vectored(&[IoSlice::new(b"a")]).unwrap(), 0);
+            s.flush().unwrap();
+        }
+
+        let mut s = S::new(Vectored::default());
+        use_stream(&mut s);
+        match S::into_enum(s) {
+            SOwned::Vectored(v) => assert_eq!(
+                v.calls,
+                ["read_vectored", "read_to_end", "write_vectored", "flush"]
+            ),
+        }
+    }
+
+    #[test]
+    fn forward_variant() {
+        use std::io::{self, Cursor, Read, Write};
+
+        trait_union! {
+            union Target: fmt::Debug =
+                #[trait_union(forward(Write))] Vec<u8> |
+                #[trait_union(forward(fmt::Write))] String |
+                #[trait_union(forward(Read), forward(io::Write))] box Cursor<Vec<u8>> |
+                u8;
+
+            #[trait_union(impl = "enum")]
+            union Outer: fmt::Debug = #[trait_union(flatten, forward(Write))] Target | u16;
+        }
+
+        fn write(mut w: impl Write) -> io::Result<()> {
+            w.write_all(b"ab")?;
+            w.flush()
+        }
+
+        let mut t = Target::new(vec![]);
+        write(&mut t).unwrap();
+        assert_eq!(fmt::Write::write_str(&mut t, "c"), Err(fmt::Error));
+        assert_eq!(format!("{:?}", &*t), "[97, 98]");
+        let mut t = Target::new(String::new());
+        fmt::Write::write_char(&mut t, 'd').unwrap();
+        let e = write(&mut t).unwrap_err();
+        assert_eq!(e.kind(), io::ErrorKind::Unsupported);
+        assert_eq!(
+            e.to_string(),
+            "the active variant of Target does not implement Write"
+        );
+        assert_eq!(format!("{:?}", &*t), "\"d\"");
+        let mut t = Target::from(Box::new(Cursor::new(b"ef".to_vec())));
+        let mut s = String::new();
+        t.read_to_string(&mut s).unwrap();
+        assert_eq!(s, "ef");
+        write(&mut t).unwrap();
+        assert_eq!(fmt::Write::write_str(&mut t, "g"), Err(fmt::Error));
+        let mut t = Target::new(1u8);
+        assert!(t.read(&mut [0]).is_err());
+        assert!(write(&mut t).is_err());
+        let mut o = Outer::from(Target::new(vec![]));
+        write(&mut o).unwrap();
+        assert_eq!(format!("{:?}", &*o), "[97, 98]");
+        assert!(write(Outer::new(2u16)).is_err());
+    }
+
+    /// Returns a waker that does nothing
+    fn noop_waker() -> std::task::Waker {
+        use std::task::{RawWaker, RawWakerVTable, Waker};
+
+        fn clone(_: *const ()) -> RawWaker {
+            RawWaker::new(std::ptr::null(), &VTABLE)
+        }
+
+        fn noop(_: *const ()) {}
+
+        static VTABLE: RawWakerVTable = RawWakerVTable::new(clone, noop, noop, noop);
+
+        unsafe { Waker::from_raw(clone(std::ptr::null())) }
+    }
+
+    #[test]
+    fn forward_future() {
+        use std::{
+            future::{self, Future},
+            pin::pin,
+            task::{Context, Poll},
+        };
+
+        struct Twice(bool);
+
+        impl Future for Twice {
+            type Output = u8;
+
+            fn poll(
+                mut self: std::pin::Pin<&mut Self>,
+                _cx: &mut Context<'_>,
+            ) -> Poll<u8> {
+                match std::mem::replace(&mut self.0, true) {
+                    true => Poll::Ready(2),
+                    false => Poll::Pending,
+                }
+            }
+        }
+
+        trait_union! {
+            #[trait_union(forward(Future))]
+            union Fut: Future<Output = u8> = Twice | future::Ready<u8>;
+        }
+
+        let waker = noop_waker();
+        let mut cx = Context::from_waker(&waker);
+        let mut f = pin!(Fut::new(Twice(false)));
+        assert_eq!(f.as_mut().poll(&mut cx), Poll::Pending);
+        assert_eq!(f.poll(&mut cx), Poll::Ready(2));
+        let f = pin!(Fut::new(future::ready(1)));
+        assert_eq!(f.poll(&mut cx), Poll::Ready(1));
+    }
+
+    #[test]
+    #[cfg(feature = "futures")]
+    fn forward_stream() {
+        use futures_core::Stream;
+        use std::{
+            pin::{pin, Pin},
+            task::{Context, Poll},
+        };
+
+        struct Countdown(u8);
+
+        impl Stream for Countdown {
+            type Item = u8;
+
+            fn poll_next(
+                mut self: Pin<&mut Self>,
+                _cx: &mut Context<'_>,
+            ) -> Poll<Option<u8>> {
+                if self.0 == 0 {
+                    return Poll::Ready(None);
+                }
+                self.0 -= 1;
+                Poll::Ready(Some(self.0))
+            }
+
+            fn size_hint(&self) -> (usize, Option<usize>) {
+                (self.0 as usize, Some(self.0 as usize))
+            }
+        }
+
+        struct Never;
+
+        impl Stream for Never {
+            type Item = u8;
+
+            fn poll_next(
+                self: Pin<&mut Self>,
+                _cx: &mut Context<'_>,
+            ) -> Poll<Option<u8>> {
+                Poll::Pending
+            }
+        }
+
+        trait_union! {
+            #[trait_union(forward(Stream))]
+            union Numbers: Stream<Item = u8> = Countdown | Never;
+        }
+
+        fn numbers(n: u8) -> impl Stream<Item = u8> {
+            match n {
+                0 => Numbers::new(Never),
+                _ => Numbers::new(Countdown(n)),
+            }
+        }
+
+        let waker = noop_waker();
+        let mut cx = Context::from_waker(&waker);
+        let mut s = pin!(numbers(2));
+        assert_eq!(s.size_hint(), (2, Some(2)));
+        assert_eq!(s.as_mut().poll_next(&mut cx), Poll::Ready(Some(1)));
+        assert_eq!(s.as_mut().poll_next(&mut cx), Poll::Ready(Some(0)));
+        assert_eq!(s.as_mut().poll_next(&mut cx), Poll::Ready(None));
+        assert_eq!(pin!(numbers(0)).poll_next(&mut cx), Poll::Pending);
+    }
+
+    #[test]
+    #[cfg(feature = "tokio")]
+    fn forward_tokio() {
+        use std::{
+            io::Cursor,
+            pin::pin,
+            task::{Context, Poll},
+        };
+        use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+
+        trait Conn: AsyncRead + AsyncWrite {}
+
+        impl<T: AsyncRead + AsyncWrite> Conn for T {}
+
+        trait_union! {
+            #[trait_union(forward(AsyncRead, AsyncWrite))]
+            union AnyConn: Conn = Cursor<Vec<u8>> | Cursor<Box<[u8]>>;
+        }
+
+        fn echo(conn: impl AsyncRead + AsyncWrite) -> Vec<u8> {
+            let waker = noop_waker();
+            let mut cx = Context::from_waker(&waker);
+            let mut conn = pin!(conn);
+            let mut buf = [0; 4];
+            let mut buf = ReadBuf::new(&mut buf);
+            let read = conn.as_mut().poll_read(&mut cx, &mut buf);
+            assert!(matches!(read, Poll::Ready(Ok(()))));
+            let filled = buf.filled().to_vec();
+            let written = conn.as_mut().poll_write(&mut cx, &filled);
+            assert!(matches!(written, Poll::Ready(Ok(n)) if n == filled.len()));
+            assert!(conn.as_mut().poll_flush(&mut cx).is_ready());
+            filled
+        }
+
+        assert_eq!(echo(AnyConn::new(Cursor::new(b"abc".to_vec()))), b"abc");
+        let boxed: Box<[u8]> = Box::new(*b"defghijk");
+        assert_eq!(echo(AnyConn::new(Cursor::new(boxed))), b"defg");
+    }
+
+    #[test]
+    #[cfg(feature = "futures")]
+    fn forward_futures_io() {
+        use futures_io::{AsyncRead, AsyncWrite};
+        use std::{
+            pin::{pin, Pin},
+            task::{Context, Poll},
+        };
+
+        trait_union! {
+            #[trait_union(forward(futures::AsyncRead))]
+            union Reader: AsyncRead = &'static [u8];
+
+            #[trait_union(forward(futures::AsyncWrite))]
+            union Writer: AsyncWrite = Vec<u8>;
+        }
+
+        let waker = noop_waker();
+        let mut cx = Context::from_waker(&waker);
+        let mut buf = [0; 2];
+        let read = pin!(Reader::new(&b"abc"[..])).poll_read(&mut cx, &mut buf);
+        assert!(matches!(read, Poll::Ready(Ok(2))));
+        let mut w = Writer::new(vec![]);
+        let written = Pin::new(&mut w).poll_write(&mut cx, &buf);
+        assert!(matches!(written, Poll::Ready(Ok(2))));
+        assert!(Pin::new(&mut w).poll_close(&mut cx).is_ready());
+        match Writer::into_enum(w) {
+            WriterOwned::VecU8(v) => assert_eq!(v, b"ab"),
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "tower")]
+    fn forward_tower() {
+        use std::{
+            future::{self, Ready},
+            task::{Context, Poll},
+        };
+        use tower_service::Service;
+
+        trait Handler:
+            Service<u32, Response = u32, Error = (), Future = Ready<Result<u32, ()>>>
+        {
+        }
+
+        impl<
+                T: Service<u32, Response = u32, Error = (), Future = Ready<Result<u32, ()>>>,
+            > Handler for T
+        {
+        }
+
+        struct Add(u32);
+
+        impl Service<u32> for Add {
+            type Response = u32;
+            type Error = ();
+            type Future = Ready<Result<u32, ()>>;
+
+            fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), ()>> {
+                Poll::Ready(Ok(()))
+            }
+
+            fn call(&mut self, req: u32) -> Self::Future {
+                future::ready(Ok(req + self.0))
+            }
+        }
+
+        struct Reject;
+
+        impl Service<u32> for Reject {
+            type Response = u32;
+            type Error = ();
+            type Future = Ready<Result<u32, ()>>;
+
+            fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), ()>> {
+                Poll::Ready(Err(()))
+            }
+
+            fn call(&mut self, _req: u32) -> Self::Future {
+                future::ready(Err(()))
+            }
+        }
+
+        trait_union! {
+            #[trait_union(forward(Service<u32>))]
+            union Middleware: Handler = Add | Reject;
+        }
+
+        fn run(
+            mut s: impl Service<
+                u32,
+                Response = u32,
+                Error = (),
+                Future = Ready<Result<u32, ()>>,
+            >,
+        ) -> Result<u32, ()> {
+            let ready = s.poll_ready(&mut Context::from_waker(&noop_waker()));
+            match ready {
+                Poll::Ready(Ok(())) => s.call(1).into_inner(),
+                _ => Err(()),
+            }
+        }
+
+        assert_eq!(run(Middleware::new(Add(2))), Ok(3));
+        assert_eq!(run(Middleware::new(Reject)), Err(()));
+    }
+
+    #[test]
+    #[cfg(feature = "embedded-hal")]
+    fn forward_embedded_hal() {
+        use core::convert::Infallible;
+        use embedded_hal::{
+            delay::DelayNs,
+            digital::{ErrorType, OutputPin, PinState},
+            spi::{self, SpiBus},
+        };
+
+        trait Board: OutputPin<Error = Infallible> + DelayNs + fmt::Debug {}
+
+        impl<T: OutputPin<Error = Infallible> + DelayNs + fmt::Debug> Board for T {}
+
+        trait Bus: SpiBus<u16, Error = spi::ErrorKind> {}
+
+        impl<T: SpiBus<u16, Error = spi::ErrorKind>> Bus for T {}
+
+        #[derive(Debug, Default)]
+        struct Pin {
+            high: bool,
+            waited_ns: u32,
+        }
+
+        impl ErrorType for Pin {
+            type Error = Infallible;
+        }
+
+        impl OutputPin for Pin {
+            fn set_low(&mut self) -> Result<(), Infallible> {
+                self.high = false;
+                Ok(())
+            }
+
+            fn set_high(&mut self) -> Result<(), Infallible> {
+                self.high = true;
+                Ok(())
+            }
+        }
+
+        impl DelayNs for Pin {
+            fn delay_ns(&mut self, ns: u32) {
+                self.waited_ns += ns;
+            }
+        }
+
+        struct Loopback;
+
+        impl spi::ErrorType for Loopback {
+            type Error = spi::ErrorKind;
+        }
+
+        impl SpiBus<u16> for Loopback {
+            fn read(&mut self, _words: &mut [u16]) -> Result<(), spi::ErrorKind> {
+                Err(spi::ErrorKind::Overrun)
+            }
+
+            fn write(&mut self, _words: &[u16]) -> Result<(), spi::ErrorKind> {
+                Ok(())
+            }
+
+            fn transfer(
+                &mut self,
+                read: &mut [u16],
+                write: &[u16],
+            ) -> Result<(), spi::ErrorKind> {
+                read.copy_from_slice(write);
+                Ok(())
+            }
+
+            fn transfer_in_place(
+                &mut self,
+                _words: &mut [u16],
+            ) -> Result<(), spi::ErrorKind> {
+                Ok(())
+            }
+
+            fn flush(&mut self) -> Result<(), spi::ErrorKind> {
+                Ok(())
+            }
+        }
+
+        trait_union! {
+            #[trait_union(forward(OutputPin, DelayNs))]
+            union AnyBoard: Board = Pin;
+
+            #[trait_union(forward(SpiBus<u16>))]
+            union AnyBus: Bus = Loopback;
+        }
+
+        fn blink(mut led: impl OutputPin<Error = Infallible> + DelayNs) {
+            led.set_state(PinState::High).unwrap();
+            led.delay_us(2);
+        }
+
+        let mut b = AnyBoard::new(Pin::default());
+        blink(&mut b);
+        assert_eq!(format!("{:?}", &*b), "Pin { high: true, waited_ns: 2000 }");
+
+        fn echo(mut bus: impl SpiBus<u16, Error = spi::ErrorKind>) -> [u16; 2] {
+            assert_eq!(bus.read(&mut [0]), Err(spi::ErrorKind::Overrun));
+            let mut read = [0; 2];
+            bus.transfer(&mut read, &[1, 2]).unwrap();
+            read
+        }
+
+        assert_eq!(echo(AnyBus::new(Loopback)), [1, 2]);
+    }
+
+    #[test]
+    fn heapless() {
+        trait_union! {
+            union Command: F = u8 | String;
+        }
+
+        let mut vec = heapless::Vec::<Command, 2>::new();
+        assert!(vec.push(Command::new(1)).is_ok());
+        assert!(vec.push(Command::new("ab".to_string())).is_ok());
+        assert!(vec.push(Command::new(3)).is_err());
+        assert_eq!(vec.iter().map(|c| c.len()).sum::<usize>(), 3);
+
+        let mut queue = heapless::spsc::Queue::<Command, 4>::new();
+        let (mut producer, mut consumer) = queue.split();
+        assert!(producer.enqueue(Command::new(5)).is_ok());
+        assert!(producer.enqueue(Command::new("abc".to_string())).is_ok());
+        assert_eq!(consumer.dequeue().unwrap().len(), 5);
+        assert_eq!(consumer.dequeue().unwrap().to_string(), "abc");
+        assert!(consumer.dequeue().is_none());
+    }
+
+    #[test]
+    fn constructors() {
+        trait_union! {
+            #[trait_union(constructors)]
+            union C: F = u8 | #[trait_union(name = Text)] String;
+
+            #[trait_union(constructors)]
+            union Outer: F = #[trait_union(flatten)] C | u8;
+        }
+
+        impl Default for C {
+            fn default() -> Self {
+                C::new(7)
+            }
+        }
+
+        assert_eq!(C::CONSTRUCTORS.len(), 2);
+        assert_eq!(C::kind_of(&C::CONSTRUCTORS[1]()), CKind::Text);
+        assert_eq!(C::kind_of(&C::from_kind(CKind::U8)), CKind::U8);
+        assert_eq!(C::from_kind_name("Text").unwrap().to_string(), "");
+        assert!(C::from_kind_name("String").is_none());
+        assert_eq!(Outer::from_kind_name("C").unwrap().len(), 7);
+    }
+
+    #[test]
+    fn into_enum() {
+        trait Finish {
+            fn finish(self) -> String
+            where
+                Self: Sized;
+        }
+
+        impl Finish for u8 {
+            fn finish(self) -> String {
+                self.to_string()
+            }
+        }
+
+        impl Finish for String {
+            fn finish(self) -> String {
+                self
+            }
+        }
+
+        trait_union! {
+            #[trait_union(live_counts)]
+            union H: Finish = u8 | String;
+
+            #[trait_union(impl = "enum")]
+            union E: Finish = u8 | String;
+
+            union L<'a>: fmt::Debug + 'a = &'a str;
+        }
+
+        fn finish(h: H) -> String {
+            match H::into_enum(h) {
+                HOwned::U8(v) => v.finish(),
+                HOwned::String(v) => v.finish(),
+            }
+        }
+
+        assert_eq!(finish(H::new(1)), "1");
+        assert_eq!(finish(H::new("ab".to_string())), "ab");
+        assert_eq!(H::live_counts(), [0, 0]);
+        assert!(
+            matches!(E::into_enum(E::new("c".to_string())), EOwned::String(s) if s == "c")
+        );
+        assert!(matches!(L::into_enum(L::new("d")), LOwned::AStr("d")));
+        assert_eq!(finish(H::from(HOwned::U8(2))), "2");
+        assert!(matches!(EOwned::from(E::new(3u8)), EOwned::U8(3)));
+        let h: H = HOwned::String("e".to_string()).into();
+        assert!(matches!(HOwned::from(h), HOwned::String(s) if s == "e"));
+        assert_eq!(H::live_counts(), [0, 0]);
+    }
+
+    #[test]
+    fn open() {
+        trait_union! {
+            #[trait_union(open(size = 16), new_vis = "pub(crate)")]
+            union O: F;
+        }
+        trait_union_copy! {
+            #[derive(Copy, Clone)]
+            #[trait_union(open(size = 2, align = 2))]
+            union C: Display;
+        }
+
+        #[derive(TraitUnionVariant)]
+        #[trait_union(O)]
+        struct Counted(Rc<Cell<usize>>, u8);
+
+        impl fmt::Display for Counted {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(f, "{}", self.1)
+            }
+        }
+
+        impl F for Counted {
+            fn len(&self) -> usize {
+                self.1 as usize
+            }
+
+            fn set_len(&mut self, len: usize) {
+                self.1 = len as u8;
+            }
+        }
+
+        impl Drop for Counted {
+            fn drop(&mut self) {
+                self.0.set(self.0.get() + 1);
+            }
+        }
+
+        #[derive(Copy, Clone, TraitUnionVariant)]
+        #[trait_union(C)]
+        struct Short(u16);
+
+        impl fmt::Display for Short {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(f, "{}", self.0)
+            }
+        }
+
+        let drops = Rc::new(Cell::new(0));
+        let mut o = O::new(Counted(drops.clone(), 1));
+        o.set_len(2);
+        assert_eq!(o.len(), 2);
+        assert_eq!(<&dyn F>::from(&o).to_string(), "2");
+        drop(o);
+        assert_eq!(drops.get(), 1);
+
+        let c = C::new(Short(7));
+        let d = c;
+        assert_eq!(format!("{}{}", &*c, &*d), "77");
+    }
+
+    #[test]
+    fn fn_pointers() {
+        fn double(x: u32) -> u32 {
+            x * 2
+        }
+
+        extern "C" fn triple(x: u32) -> u32 {
+            x * 3
+        }
+
+        trait Isr {
+            fn handle(&self, x: u32) -> u32;
+        }
+
+        impl Isr for fn(u32) -> u32 {
+            fn handle(&self, x: u32) -> u32 {
+                self(x)
+            }
+        }
+
+        impl Isr for extern "C" fn(u32) -> u32 {
+            fn handle(&self, x: u32) -> u32 {
+                self(x)
+            }
+        }
+
+        trait_union! {
+            union Handler: Fn(u32) -> u32 = fn(u32) -> u32;
+
+            union Vector: Isr = fn(u32) -> u32 | extern "C" fn(u32) -> u32;
+
+            #[trait_union(impl = "enum", no_deref)]
+            union Str: for<'a> Fn(&'a str) -> &'a str = fn(&str) -> &str;
+        }
+
+        let handler = Handler::new(double as fn(_) -> _);
+        assert_eq!(handler.call(2), 4);
+        assert_eq!((*handler)(3), 6);
+        let vectors = [
+            Vector::new(double as fn(_) -> _),
+            Vector::new(triple as extern "C" fn(_) -> _),
+        ];
+        assert_eq!(vectors.each_ref().map(|v| v.handle(2)), [4, 6]);
+        assert_eq!(Vector::kind_of(&vectors[1]), VectorKind::ExternCFn);
+        let s = Str::new(str::trim as fn(&str) -> &str);
+        assert_eq!(s.call(" a "), "a");
+    }
+
+    #[test]
+    fn from_fn() {
+        trait_union! {
+            #[trait_union(open(size = 16))]
+            union Callback: FnMut(u32) -> u32;
+        }
+        trait_union_copy! {
+            #[derive(Copy, Clone)]
+            #[trait_union(open(size = 8))]
+            union Handler: Fn(&str) -> usize;
+        }
+
+        let mut sum = 0;
+        let mut callbacks = [
+            Callback::from_fn(|x| x + 1),
+            Callback::from_fn(move |x| {
+                sum += x;
+                sum
+            }),
+        ];
+        let results: Vec<_> = callbacks.iter_mut().map(|c| c(2)).collect();
+        assert_eq!(results, [3, 2]);
+        assert_eq!(callbacks[1](3), 5);
+        assert_eq!(callbacks[1].call(4), 9);
+
+        let offset = 1;
+        let handlers = [
+            Handler::from_fn(str::len),
+            Handler::from_fn(move |s| s.len() + offset),
+        ];
+        assert_eq!(handlers.map(|h| h("ab")), [2, 3]);
+    }
+
+    #[test]
+    fn live_counts() {
+        trait_union! {
+            #[trait_union(live_counts)]
+            union L: F = u8 | String;
+
+            #[trait_union(impl = "enum", live_counts)]
+            union E: F = u8 | String;
+
+            #[trait_union(live_counts)]
+            union Outer: F = #[trait_union(flatten)] L | u8;
+        }
+
+        let a = L::new(1);
+        let b = L::new(String::new());
+        let mut c = L::new(2);
+        assert_eq!(L::live_counts(), [2, 1]);
+        drop(a);
+        assert_eq!(c.len(), 2);
+        c = L::new(String::new());
+        assert_eq!(L::live_counts(), [0, 2]);
+        drop((b, c));
+        assert_eq!(L::live_counts(), [0, 0]);
+
+        let e = [E::new(1), E::new(2), E::new(String::new())];
+        assert_eq!(E::live_counts(), [2, 1]);
+        drop(e);
+        assert_eq!(E::live_counts(), [0, 0]);
+
+        let o = Outer::from(L::new(1));
+        assert_eq!(Outer::live_counts(), [1, 0]);
+        assert_eq!(L::live_counts(), [1, 0]);
+        drop(o);
+        assert_eq!(Outer::live_counts(), [0, 0]);
+        assert_eq!(L::live_counts(), [0, 0]);
+    }
+
+    #[test]
+    #[cfg(feature = "metrics")]
+    fn metrics() {
+        use std::sync::atomic::{AtomicUsize, Ordering::Relaxed};
+
+        trait_union! {
+            #[trait_union(metrics)]
+            union M: F = u8 | String;
+
+            #[trait_union(impl = "enum", metrics)]
+            union E: F = u8 | String;
+        }
+
+        struct Counts([AtomicUsize; 2], [AtomicUsize; 2]);
+
+        impl MMetrics for Counts {
+            fn on_new(&self, kind: MKind) {
+                self.0[kind as usize].fetch_add(1, Relaxed);
+            }
+
+            fn on_drop(&self, kind: MKind) {
+                self.1[kind as usize].fetch_add(1, Relaxed);
+            }
+        }
+
+        impl EMetrics for Counts {}
+
+        static COUNTS: Counts = Counts(
+            [AtomicUsize::new(0), AtomicUsize::new(0)],
+            [AtomicUsize::new(0), AtomicUsize::new(0)],
+        );
+        let counts = |c: &[AtomicUsize; 2]| [c[0].load(Relaxed), c[1].load(Relaxed)];
+
+        drop(M::new(0));
+        assert!(M::set_metrics(&COUNTS).is_ok());
+        assert!(M::set_metrics(&COUNTS).is_err());
+        let a = M::new(1);
+        let mut b = M::new(String::new());
+        assert_eq!(counts(&COUNTS.0), [1, 1]);
+        assert_eq!(b.len(), 0);
+        b = M::new(2);
+        assert_eq!(counts(&COUNTS.1), [0, 1]);
+        drop(a);
+        assert!(matches!(M::into_enum(b), MOwned::U8(2)));
+        assert_eq!(counts(&COUNTS.0), [2, 1]);
+        assert_eq!(counts(&COUNTS.1), [2, 1]);
+
+        assert!(E::set_metrics(&COUNTS).is_ok());
+        drop(E::new(1));
+    }
+
+    #[test]
+    fn needs_drop() {
+        trait_union! {
+            union Trivial: Display = u8 | &'static str;
+        }
+        trait_union! {
+            #[trait_union(impl = "enum")]
+            union E: F = u8 | String;
+        }
+
+        const _: () = assert!(!Trivial::NEEDS_DROP);
+        const _: () = assert!(U::NEEDS_DROP);
+        const _: () = assert!(E::NEEDS_DROP);
+        drop(Trivial::new(1));
+    }
+
+    #[test]
+    fn zero_sized() {
+        static DROPS: AtomicUsize = AtomicUsize::new(0);
+
+        #[derive(Debug)]
+        struct Marker;
+
+        impl Drop for Marker {
+            fn drop(&mut self) {
+                DROPS.fetch_add(1, Relaxed);
+            }
+        }
+
+        trait_union! {
+            union Z: fmt::Debug = () | Marker | u8 | String;
+        }
+
+        assert_eq!(format!("{:?}", &*Z::new(())), "()");
+        assert_eq!(format!("{:?}", &*Z::new(Marker)), "Marker");
+        assert_eq!(DROPS.load(Relaxed), 1);
+        let mut z = Z::new(Marker);
+        assert_eq!(format!("{:?}", &*z), "Marker");
+        z = Z::new(1u8);
+        assert_eq!(DROPS.load(Relaxed), 2);
+        assert_eq!(format!("{:?}", &*z), "1");
+        drop(z);
+        drop(Z::new(String::from("a")));
+        assert_eq!(DROPS.load(Relaxed), 2);
+    }
+
+    #[test]
+    fn kind() {
+        trait_union! {
+            union K: Display = i32 | &'static str | 3: bool | #[trait_union(name = Text)] String;
+        }
+
+        assert_eq!(K::kind_of(&K::new(1)), KKind::I32);
+        assert_eq!(K::kind_of(&K::new("")), KKind::StaticStr);
+        assert_eq!(K::kind_of(&K::new(true)), KKind::Bool);
+        assert_eq!(K::kind_of(&K::new(String::new())), KKind::Text);
+        assert_eq!(KKind::Bool as u8, 3);
+        assert_eq!(format!("{:?}", KKind::Text), "Text");
+        let kinds: std::collections::HashSet<_> = [K::new(1), K::new(2), K::new(true)]
+            .iter()
+            .map(K::kind_of)
+            .collect();
+        assert_eq!(kinds.len(), 2);
+
+        trait Shape {
+            fn kind(&self) -> &'static str;
+        }
+
+        impl Shape for u8 {
+            fn kind(&self) -> &'static str {
+                "square"
+            }
+        }
+
+        trait_union! {
+            union S: Shape = u8;
+        }
+
+        assert_eq!(S::new(1).kind(), "square");
+        assert_eq!(S::kind_of(&S::new(1)), SKind::U8);
+    }
+
+    #[test]
+    fn variant_consts() {
+        trait_union! {
+            union K: Display = i32 | &'static str | #[trait_union(name = Text)] String;
+        }
+
+        fn consts<V: KVariant>(_: V) -> (KKind, usize, &'static str) {
+            (V::KIND, V::SIZE, V::NAME)
+        }
+
+        assert_eq!(consts(1), (KKind::I32, 4, "I32"));
+        assert_eq!(
+            consts(""),
+            (KKind::StaticStr, mem::size_of::<&str>(), "StaticStr")
+        );
+        assert_eq!(consts(String::new()).2, format!("{:?}", KKind::Text));
+    }
+
+    #[test]
+    fn ref_enum() {
+        trait_union! {
+            #[trait_union(impl = "enum")]
+            union E: F = u8 | String;
+        }
+
+        let mut u = U::new(1);
+        match U::as_mut_enum(&mut u) {
+            UMut::U8(v) => *v = 3,
+            _ => unreachable!(),
+        }
+        assert!(matches!(U::as_ref_enum(&u), URef::U8(&3)));
+        let mut e = E::new("a".to_string());
+        match E::as_mut_enum(&mut e) {
+            EMut::String(s) => s.push('b'),
+            _ => unreachable!(),
+        }
+        assert!(matches!(E::as_ref_enum(&e), ERef::String(s) if s == "ab"));
+    }
+
+    #[test]
+    fn flatten() {
+        trait_union! {
+            union Inner: F = u8 | String;
+
+            union Outer: F = #[trait_union(flatten)] Inner | u8;
+
+            #[trait_union(impl = "enum")]
+            union EnumOuter: F = #[trait_union(flatten)] Inner | u8;
+        }
+
+        let mut o = Outer::from(Inner::new("abc".to_string()));
+        assert_eq!(o.len(), 3);
+        o.set_len(2);
+        assert_eq!(o.to_string(), "ab");
+        assert_eq!(Outer::kind_of(&o), OuterKind::Inner);
+        assert!(
+            matches!(Outer::as_ref_enum(&o), OuterRef::Inner(i) if Inner::kind_of(i) == InnerKind::String)
+        );
+        o = Outer::new(5);
+        assert_eq!(o.len(), 5);
+        assert_eq!(Outer::kind_of(&o), OuterKind::U8);
+
+        let mut e = EnumOuter::from(Inner::new(3));
+        assert_eq!(e.len(), 3);
+        e.set_len(4);
+        assert_eq!(e.len(), 4);
+    }
+
+    #[test]
+    fn recursive() {
+        trait Depth {
+            fn depth(&self) -> usize;
+        }
+
+        impl Depth for u8 {
+            fn depth(&self) -> usize {
+                0
+            }
+        }
+
+        impl Depth for Box<Nested> {
+            fn depth(&self) -> usize {
+                self.as_ref().depth() + 1
+            }
+        }
+
+        impl<'a> Depth for Box<Borrowed<'a>> {
+            fn depth(&self) -> usize {
+                self.as_ref().depth() + 1
+            }
+        }
+
+        impl Depth for &u8 {
+            fn depth(&self) -> usize {
+                0
+            }
+        }
+
+        trait_union! {
+            union Nested: Depth = u8 | Box<Self>;
+
+            union Borrowed<'a>: Depth = &'a u8 | Box<Self>;
+        }
+
+        let n = Nested::new(Box::new(Nested::new(Box::new(Nested::new(0)))));
+        assert_eq!(n.depth(), 2);
+        assert_eq!(Nested::kind_of(&n), NestedKind::BoxSelf);
+        let b = Borrowed::new(Box::new(Borrowed::new(&1)));
+        assert_eq!(b.depth(), 1);
+    }
+
+    #[test]
+    fn boxed() {
+        trait_union! {
+            union B: F = u8 | box u8 | box Self;
+
+            #[trait_union(impl = "enum")]
+            union E: F = box u8 | #[trait_union(flatten)] box B;
+        }
+
+        let mut b = B::from(Box::new(B::new(3)));
+        assert_eq!(b.len(), 3);
+        b.set_len(4);
+        assert_eq!(b.len(), 4);
+        assert_eq!(B::kind_of(&b), BKind::BoxSelf);
+        assert!(
+            matches!(B::as_ref_enum(&b), BRef::BoxSelf(i) if B::kind_of(i) == BKind::U8)
+        );
+        b = B::from(Box::new(B::from(Box::new(1))));
+        b.set_len(2);
+        assert_eq!(b.to_string(), "2");
+        assert!(
+            matches!(B::into_enum(b), BOwned::BoxSelf(i) if B::kind_of(&i) == BKind::BoxU8)
+        );
+        assert_eq!(B::debug_layout().variants[2].name, "box B");
+
+        let mut e = E::from(Box::new(B::from(Box::new(B::new(5)))));
+        assert_eq!(e.len(), 5);
+        e.set_len(6);
+        assert_eq!(e.len(), 6);
+        assert_eq!(E::kind_of(&e), EKind::BoxB);
+        assert_eq!(E::from(Box::new(7)).len(), 7);
+    }
+
+    #[test]
+    fn default_lifetime() {
+        trait_union! {
+            union L<'a>: Display = u8 | &'a str;
+        }
+
+        let s = "abc".to_string();
+        let l = L::new(&*s);
+        assert_eq!(l.to_string(), "abc");
+    }
+
+    #[test]
+    fn abi_check() {
+        trait_union! {
+            #[trait_union(abi_check)]
+            union U1: Display = u8 | u16;
+
+            #[trait_union(abi_check)]
+            union U2: Display = u16 | u8;
+
+            #[trait_union(abi_check)]
+            union U3: Display = u8 | u32;
+        }
+
+        assert_ne!(U1::LAYOUT_HASH, U2::LAYOUT_HASH);
+        assert_ne!(U1::LAYOUT_HASH, U3::LAYOUT_HASH);
+
+        trait_union! {
+            #[trait_union(abi_check = 0x40b9_32b8_9fde_2ea4)]
+            union U4: Display = u8 | u16;
+        }
+
+        assert_eq!(U1::LAYOUT_HASH, U4::LAYOUT_HASH);
+
+        trait_union! {
+            #[trait_union(abi_check)]
+            union U5: Display = u8 | String;
+
+            #[trait_union(abi_check)]
+            union U6: Display = u8 | std::string::String;
+        }
+
+        assert_eq!(U5::LAYOUT_HASH, U6::LAYOUT_HASH);
+    }
+
+    #[test]
+    fn tags() {
+        trait_union! {
+            union U1: Display = u8 | 3: u16 | u32 | 1: &'static str;
+
+            union U2: Display = 256: u8 | 0: u16;
+        }
+
+        assert_eq!(U1::tag_of(&U1::new(1u8)), 0);
+        assert_eq!(U1::tag_of(&U1::new(1u16)), 3);
+        assert_eq!(U1::tag_of(&U1::new(1u32)), 4);
+        assert_eq!(U1::tag_of(&U1::new("")), 1);
+        let tag: u16 = U2::tag_of(&U2::new(1u8));
+        assert_eq!(tag, 256);
+        assert_eq!(U2::tag_of(&U2::new(1u16)), 0);
+
+        trait Tagged {
+            fn tag(&self) -> &'static str;
+        }
+
+        impl Tagged for u8 {
+            fn tag(&self) -> &'static str {
+                "u8"
+            }
+        }
+
+        trait_union! {
+            union T: Tagged = u8;
+        }
+
+        assert_eq!(T::new(1).tag(), "u8");
+        assert_eq!(T::tag_of(&T::new(1)), 0);
+    }
+
+    #[test]
+    fn external() {
+        trait_union! {
+            #[trait_union(external)]
+            union E: fmt::Debug = u8 | [u64; 8] | Rc<u8> | box u16 | ();
+
+            #[trait_union(external, no_deref, impl = "enum")]
+            union N: fmt::Debug = u8 | String;
+        }
+
+        assert_eq!(mem::size_of::<EHandle>(), 2 * mem::size_of::<usize>());
+        let mut buf = [mem::MaybeUninit::uninit(); 128];
+        let handle = EHandle::new_in(&mut buf, [1u64; 8]).unwrap();
+        assert_eq!(EHandle::kind_of(&handle), EKind::U64Array);
+        assert_eq!(EHandle::tag_of(&handle), 1);
+        assert_eq!(format!("{:?}", &*handle), format!("{:?}", [1u64; 8]));
+        let e = handle.into_union();
+        assert_eq!(E::kind_of(&e), EKind::U64Array);
+        assert!(EHandle::new_in(&mut buf[..63], [1u64; 8]).is_err());
+        assert!(EHandle::new_in(&mut [], ()).is_ok());
+
+        let rc = Rc::new(1);
+        let handle = EHandle::new_in(&mut buf, rc.clone()).unwrap();
+        assert_eq!(Rc::strong_count(&rc), 2);
+        drop(handle);
+        assert_eq!(Rc::strong_count(&rc), 1);
+
+        let mut handle = EHandle::from_union(&mut buf, E::from(Box::new(3u16)))
+            .ok()
+            .unwrap();
+        assert_eq!(EHandle::kind_of(&handle), EKind::BoxU16);
+        assert_eq!(format!("{:?}", &mut *handle), "3");
+        let e = EHandle::from_union(&mut [], E::new(4u8)).err().unwrap();
+        assert_eq!(format!("{:?}", &*e), "4");
+
+        let mut buf = [mem::MaybeUninit::uninit(); 32];
+        let mut handle = NHandle::new_in(&mut buf, "a".to_string()).unwrap();
+        assert_eq!(format!("{:?}", handle.as_dyn()), "\"a\"");
+        assert_eq!(format!("{:?}", handle.as_dyn_mut()), "\"a\"");
+        assert_eq!(N::kind_of(&handle.into_union()), NKind::String);
+
+        fn send_sync<T: Send + Sync>() {}
+        send_sync::<NHandle<'static>>();
+
+        trait_union_copy! {
+            #[trait_union(external)]
+            union C: fmt::Debug = u8 | u16;
+        }
+
+        let mut buf = [mem::MaybeUninit::uninit(); 4];
+        let handle = CHandle::new_in(&mut buf, 5u16).unwrap();
+        assert_eq!(format!("{:?}", &*handle), "5");
+    }
+
+    #[test]
+    fn report_size() {
+        trait_union! {
+            #[trait_union(report_size = 4)]
+            union S1: fmt::Debug = u8 | u16;
+
+            #[trait_union(report_size)]
+            union S2<'a>: fmt::Debug = u8 | String | &'a str;
+        }
+
+        let report = S1::SIZE_REPORT;
+        assert_eq!((report.name, report.size, report.align), ("S1", 4, 2));
+        assert_eq!((report.largest.name, report.largest.size), ("u16", 2));
+        let report = S2::SIZE_REPORT;
+        assert_eq!(report.size, mem::size_of::<S2>());
+        assert_eq!(report.largest.name, "String");
+        assert_eq!(
+            report.to_string(),
+            format!(
+                "S2 (size {}, align {}), largest variant String (size {}, align {})",
+                mem::size_of::<S2>(),
+                mem::align_of::<S2>(),
+                mem::size_of::<String>(),
+                mem::align_of::<String>(),
+            )
+        );
+    }
+
+    #[test]
+    fn describe() {
+        struct Describe<F>(F);
+
+        impl<F: Fn(&mut Formatter) -> fmt::Result> Display for Describe<F> {
+            fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+                (self.0)(f)
+            }
+        }
+
+        trait Opaque {}
+
+        impl<T> Opaque for T {}
+
+        struct NoFmt;
+
+        trait_union! {
+            union D1: Opaque = u8 | &'static str | Option<u8> | box u16 | NoFmt;
+
+            #[trait_union(no_deref, impl = "enum")]
+            union D2<T: fmt::Debug + 'static>: Opaque = Option<T> | #[trait_union(flatten)] D1;
+        }
+
+        fn describe(describe: impl Fn(&mut Formatter) -> fmt::Result) -> String {
+            Describe(describe).to_string()
+        }
+
+        let d = D1::new(1u8);
+        assert_eq!(describe(|f| D1::describe(&d, f)), "U8(1)");
+        let d = D1::new("a");
+        assert_eq!(describe(|f| D1::describe(&d, f)), "StaticStr(a)");
+        let d = D1::new(Some(1u8));
+        assert_eq!(describe(|f| D1::describe(&d, f)), "OptionU8(Some(1))");
+        let d = D1::from(Box::new(2u16));
+        assert_eq!(describe(|f| D1::describe(&d, f)), "BoxU16(2)");
+        let d = D1::new(NoFmt);
+        assert_eq!(describe(|f| D1::describe(&d, f)), "NoFmt(..)");
+        let d = D2::new(Some(()));
+        assert_eq!(describe(|f| D2::describe(&d, f)), "OptionT(Some(()))");
+        let d = D2::<()>::from(D1::new("b"));
+        assert_eq!(describe(|f| D2::describe(&d, f)), "D1(StaticStr(b))");
+
+        trait Named {
+            fn describe(&self) -> &'static str;
+        }
+
+        impl Named for u8 {
+            fn describe(&self) -> &'static str {
+                "byte"
+            }
+        }
+
+        trait_union! {
+            union N: Named = u8;
+        }
+
+        let n = N::new(1);
+        assert_eq!(n.describe(), "byte");
+        assert_eq!(describe(|f| N::describe(&n, f)), "U8(1)");
+    }
+
+    #[test]
+    fn provide() {
+        #[derive(Debug)]
+        struct Located(u32);
+
+        impl crate::Provide for Located {
+            fn provide<'a>(&'a self, request: &mut crate::Request<'a>) {
+                request
+                    .provide_ref::<u32>(&self.0)
+                    .provide_ref::<u32>(&0)
+                    .provide_ref::<dyn fmt::Debug>(self);
+            }
+        }
+
+        trait_union! {
+            union P1: fmt::Debug = u8 | #[trait_union(provide)] Located;
+
+            #[trait_union(impl = "enum")]
+            union P2: fmt::Debug = #[trait_union(provide)] box Located | #[trait_union(flatten)] P1;
+        }
+
+        assert_eq!(P1::new(Located(1)).provide::<u32>(), Some(&1));
+        assert_eq!(P1::new(Located(1)).provide::<u8>(), None);
+        assert_eq!(P1::new(2u8).provide::<u8>(), None);
+        let p = P1::new(Located(3));
+        let debug = p.provide::<dyn fmt::Debug>().unwrap();
+        assert_eq!(format!("{:?}", debug), "Located(3)");
+        assert_eq!(P2::from(Box::new(Located(4))).provide::<u32>(), Some(&4));
+        assert_eq!(P2::from(P1::new(Located(5))).provide::<u32>(), Some(&5));
+        assert_eq!(P2::from(P1::new(6u8)).provide::<u32>(), None);
+        assert_eq!(crate::request_ref::<u32, _>(&P1::new(Located(7))), Some(&7));
+
+        trait Supply {
+            fn provide(&self) -> u32;
+        }
+
+        impl Supply for u8 {
+            fn provide(&self) -> u32 {
+                *self as u32 + 1
+            }
+        }
+
+        trait_union! {
+            union S: Supply = u8;
+        }
+
+        assert_eq!(S::new(8u8).provide(), 9);
+    }
+
+    #[test]
+    fn fields() {
+        trait_union! {
+            union F1: fmt::Debug { priority: u8, pub(crate) seq: u16 } = u64 | String;
+
+            union F2<T>: fmt::Debug
+            where
+                T: fmt::Debug + Copy + Default + 'static
+            {
+                extra: T,
+            } = u32 | Option<T>;
+
+            #[trait_union(impl = "enum")]
+            union F3: fmt::Debug { extra: i8 } = u32 | #[trait_union(flatten)] F1;
+
+            union Plain: fmt::Debug = u64 | String;
+
+            #[trait_union(cell, reborrow)]
+            union F4<'a>: fmt::Debug + 'a { priority: u8, origin: Option<&'a u8> } = &'a u8 | &'a str;
+        }
+
+        assert_eq!(mem::size_of::<F1>(), mem::size_of::<Plain>());
+        let mut f = F1::new("a".to_string());
+        assert_eq!((f.priority, f.seq), (0, 0));
+        f.priority = 3;
+        f.seq = 4;
+        assert_eq!(format!("{:?}", &*f), "\"a\"");
+        assert_eq!((f.priority, f.seq), (3, 4));
+        f.priority = 7;
+        F1::transition(&mut f, |e| e);
+        assert_eq!(
+            (format!("{:?}", &*f), f.priority, f.seq),
+            ("\"a\"".to_string(), 7, 4)
+        );
+        F1::transition(&mut f, |_| F1Owned::U64(5));
+        assert_eq!(
+            (format!("{:?}", &*f), f.priority, f.seq),
+            ("5".to_string(), 7, 4)
+        );
+        let f = f.move_variant::<F3>().err().unwrap();
+        assert_eq!((f.priority, f.seq), (7, 4));
+        let byte = 1;
+        let mut f = F4::new(&byte);
+        f.priority = 2;
+        f.origin = Some(&byte);
+        f.set("b");
+        assert_eq!(
+            (format!("{:?}", &*f), f.priority, f.origin),
+            ("\"b\"".to_string(), 2, Some(&1))
+        );
+        let r = f.reborrow();
+        assert_eq!((r.priority, r.origin), (2, None));
+        let mut f = F2::new(Some(1i8));
+        assert_eq!(f.extra, 0);
+        f.extra = -1;
+        assert_eq!(format!("{:?}", &*f), "Some(1)");
+        assert_eq!(f.extra, -1);
+        let mut f = F3::new(1u32);
+        assert_eq!(f.extra, 0);
+        f.extra = -1;
+        assert_eq!(f.extra, -1);
+        let f = F3::from(F1::new(2u64));
+        assert_eq!((format!("{:?}", &*f), f.extra), ("2".to_string(), 0));
+    }
+
+    #[test]
+    fn enum_dispatch() {
+        trait Shape {
+            fn area(&self) -> u32;
+            fn scale(&mut self, by: u32);
+            fn describe<'a>(&'a self, value: &'a str) -> (&'a str, u32);
+        }
+
+        struct Square(u32);
+        struct Rect(u32, u32);
+
+        impl Shape for Square {
+            fn area(&self) -> u32 {
+                self.0 * self.0
+            }
+
+            fn scale(&mut self, by: u32) {
+                self.0 *= by;
+            }
+
+            fn describe<'a>(&'a self, value: &'a str) -> (&'a str, u32) {
+                (value, self.0)
+            }
+        }
+
+        impl Shape for Rect {
+            fn area(&self) -> u32 {
+                self.0 * self.1
+            }
+
+            fn scale(&mut self, by: u32) {
+                self.0 *= by;
+                self.1 *= by;
+            }
+
+            fn describe<'a>(&'a self, _: &'a str) -> (&'a str, u32) {
+                ("rect", self.0)
+            }
+        }
+
+        trait_union! {
+            #[trait_union(enum_dispatch(
+                fn area(&self) -> u32;
+                fn scale(&mut self, by: u32);
+                fn describe<'a>(&'a self, value: &'a str) -> (&'a str, u32);
+            ))]
+            union S1: Shape = Square | box Rect;
+
+            #[trait_union(phantom = fn())]
+            #[trait_union(enum_dispatch(
+                fn area(&self) -> u32;
+                fn scale(&mut self, by: u32);
+                fn describe<'a>(&'a self, value: &'a str) -> (&'a str, u32)
+            ))]
+            union S2: Shape = Rect | #[trait_union(flatten)] S1;
+
+            #[trait_union(enum_dispatch(fn next(&mut self) -> Option<u32>))]
+            union Counter: Iterator<Item = u32> = std::ops::Range<u32> | std::iter::Once<u32>;
+        }
+
+        let mut s = S1::into_enum(S1::new(Square(2)));
+        s.scale(3);
+        assert_eq!(s.area(), 36);
+        assert_eq!(s.describe("square"), ("square", 6));
+        let mut s = S1::into_enum(S1::from(Box::new(Rect(2, 3))));
+        s.scale(2);
+        assert_eq!(s.area(), 24);
+        assert_eq!(s.describe("square"), ("rect", 4));
+        assert_eq!(S1::from_enum(s).area(), 24);
+        assert_eq!(S2::into_enum(S2::new(Rect(1, 2))).area(), 2);
+        let mut s = S2::into_enum(S2::from(S1::new(Square(3))));
+        s.scale(2);
+        assert_eq!(s.area(), 36);
+        let counter = Counter::into_enum(Counter::new(1..4));
+        assert_eq!(
+            counter
+                .chain(Counter::into_enum(Counter::new(std::iter::once(7))))
+                .sum::<u32>(),
+            13
+        );
+    }
+
+    #[test]
+    fn send() {
+        trait Named: fmt::Debug {
+            fn rename(&mut self);
+        }
+
+        impl Named for String {
+            fn rename(&mut self) {
+                self.push('!');
+            }
+        }
+
+        impl Named for Rc<str> {
+            fn rename(&mut self) {}
+        }
+
+        impl Named for u8 {
+            fn rename(&mut self) {
+                *self += 1;
+            }
+        }
+
+        trait_union! {
+            #[trait_union(send)]
+            union Task: Named = u8 | String | Rc<str>;
+
+            #[trait_union(send, no_deref, impl = "enum")]
+            union Local: Named = u8 | Rc<str>;
+        }
+
+        let mut tasks = vec![Task::new_send(1u8), TaskSend::new("a".to_string())];
+        tasks[0].as_dyn_mut().rename();
+        let tasks = std::thread::spawn(move || {
+            for task in &mut tasks {
+                task.as_dyn_mut().rename();
+            }
+            tasks
+        })
+        .join()
+        .unwrap();
+        assert_eq!(Task::kind_of(&tasks[0]), TaskKind::U8);
+        assert_eq!(format!("{:?}", &**tasks[0]), "3");
+        let tasks = tasks.into_iter().map(Task::from).collect::<Vec<_>>();
+        assert_eq!(format!("{:?}", &*tasks[1]), "\"a!\"");
+        assert_eq!(format!("{:?}", &*Task::new(Rc::<str>::from("b"))), "\"b\"");
+        let mut local = Local::new_send(4u8);
+        local.as_dyn_mut().rename();
+        assert_eq!(format!("{:?}", local.into_inner().as_dyn()), "5");
+    }
+
+    #[test]
+    fn type_name_hash_tags() {
+        use crate::__support::{hash_bytes, HASH_OFFSET};
+
+        trait_union! {
+            #[trait_union(tag = "type_name_hash")]
+            union U1: fmt::Debug = u8 | Option<String> | box u16 | 7: &'static str;
+
+            #[trait_union(tag = "type_name_hash")]
+            union U2: fmt::Debug = 7: &'static str | u16 | Option < String > | u8;
+        }
+
+        let tag: u64 = U1::tag_of(&U1::new(1u8));
+        assert_eq!(tag, hash_bytes(HASH_OFFSET, b"u8"));
+        assert_eq!(
+            U1::tag_of(&U1::new(None)),
+            hash_bytes(HASH_OFFSET, b"Option<String>")
+        );
+        assert_eq!(
+            U1::tag_of(&U1::from(Box::new(1u16))),
+            hash_bytes(HASH_OFFSET, b"u16")
+        );
+        assert_eq!(U1::tag_of(&U1::new("")), 7);
+        assert_eq!(U1::tag_of(&U1::new(1u8)), U2::tag_of(&U2::new(1u8)));
+        assert_eq!(U1::tag_of(&U1::new(None)), U2::tag_of(&U2::new(None)));
+        assert_eq!(
+            U1::tag_of(&U1::from(Box::new(1u16))),
+            U2::tag_of(&U2::new(1u16))
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "bytemuck")]
+    fn pod() {
+        use crate::DecodeError;
+
+        trait_union! {
+            #[trait_union(pod)]
+            union U: fmt::Debug = 1: u8 | 2: [u16; 2];
+        }
+
+        let u = U::new([1u16, 2]);
+        let mut buf = [0; 8];
+        assert_eq!(u.encode(&mut buf), 4);
+        assert_eq!(u.as_bytes(), (UKind::U16Array, &buf[..4]));
+        let v = U::decode(U::tag_of(&u), &buf[..4]).unwrap();
+        assert_eq!(format!("{:?}", &*v), "[1, 2]");
+        let v = U::decode(1, &buf[..1]).unwrap();
+        assert_eq!(format!("{:?}", &*v), "1");
+        assert_eq!(U::decode(3, &[]).err(), Some(DecodeError::UnknownTag(3)));
+        assert_eq!(
+            U::decode(2, &buf[..3]).err(),
+            Some(DecodeError::Length {
+                expected: 4,
+                actual: 3
+            })
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "bytemuck")]
+    fn atomic() {
+        use std::sync::atomic::Ordering::SeqCst;
+
+        trait_union! {
+            #[trait_union(pod, atomic)]
+            union U: fmt::Debug = u8 | [u16; 2] | 5: u32;
+        }
+
+        let a = AtomicU::new(U::new(1u8));
+        assert_eq!(format!("{:?}", &*a.load(SeqCst)), "1");
+        a.store(U::new([2u16, 3]), SeqCst);
+        assert_eq!(format!("{:?}", &*a.swap(U::new(4u32), SeqCst)), "[2, 3]");
+        assert_eq!(U::kind_of(&a.load(SeqCst)), UKind::U32);
+        assert!(a
+            .compare_exchange(U::new(5u32), U::new(6u8), SeqCst, SeqCst)
+            .is_err());
+        let prev = a.compare_exchange(U::new(4u32), U::new(6u8), SeqCst, SeqCst);
+        assert!(matches!(prev, Ok(v) if format!("{:?}", &*v) == "4"));
+        assert_eq!(format!("{:?}", &*a.into_inner()), "6");
+    }
+
+    #[test]
+    fn debug_layout() {
+        let layout = U::debug_layout();
+        assert_eq!(layout.name, "U");
+        assert_eq!(layout.size, mem::size_of::<U>());
+        assert_eq!(layout.align, mem::align_of::<U>());
+        assert_eq!(layout.variants.len(), 3);
+        let v = &layout.variants[1];
+        assert_eq!(v.name, "String");
+        assert_eq!(v.tag, 1);
+        assert_eq!(v.size, mem::size_of::<String>());
+        assert_eq!(v.align, mem::align_of::<String>());
+        let u = U::new(1);
+        let offset = &*u as *const dyn F as *const u8 as usize - &u as *const U as usize;
+        assert_eq!(v.offset, offset);
+        assert!(layout.to_string().starts_with("U (size "));
+    }
+
+    #[test]
+    fn schema() {
+        #[repr(C)]
+        struct Pair(u16, u16);
+
+        impl fmt::Display for Pair {
+            fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+                write!(f, "{}", self.0)
+            }
+        }
+
+        trait_union! {
+            #[trait_union(schema, impl = "enum")]
+            union S: Display = 1: u8 | Pair | &'static str;
+        }
+
+        assert_eq!(S::SCHEMA.trait_, "Display + 'static");
+        assert_eq!(S::SCHEMA.kinds, ["U8", "Pair", "StaticStr"]);
+        let mut json = String::new();
+        S::SCHEMA.write_json(&mut json).unwrap();
+        let v = S::debug_layout().variants;
+        let expected = format!(
+            "{{\"name\":\"S\",\"trait\":\"Display + 'static\",\"size\":{},\"align\":{},\
+             \"variants\":[\
+             {{\"kind\":\"U8\",\"type\":\"u8\",\"tag\":1,\"size\":1,\"align\":1,\"offset\":{}}},\
+             {{\"kind\":\"Pair\",\"type\":\"Pair\",\"tag\":2,\"size\":4,\"align\":2,\"offset\":{}}},\
+             {{\"kind\":\"StaticStr\",\"type\":\"& 'static str\",\"tag\":3,\"size\":{},\"align\":{},\"offset\":{}}}]}}",
+            mem::size_of::<S>(),
+            mem::align_of::<S>(),
+            v[0].offset,
+            v[1].offset,
+            mem::size_of::<&str>(),
+            mem::align_of::<&str>(),
+            v[2].offset,
+        );
+        assert_eq!(json, expected);
+    }
+
+    #[test]
+    fn assert_sync() {
+        let _: &dyn Sync = &U::new(1);
+    }
+
+    #[test]
+    fn unpin() {
+        use std::marker::{PhantomData, PhantomPinned};
+
+        trait NotUnpin {
+            const UNPIN: bool = false;
+        }
+
+        struct Check<T: ?Sized>(PhantomData<T>);
+
+        impl<T: ?Sized> NotUnpin for Check<T> {}
+
+        impl<T: ?Sized + Unpin> Check<T> {
+            const UNPIN: bool = true;
+        }
+
+        #[derive(Debug)]
+        struct Pinned(PhantomPinned);
+
+        trait_union! {
+            union U: fmt::Debug = u8 | String;
+
+            union P: fmt::Debug = u8 | Pinned;
+
+            #[trait_union(impl = "enum")]
+            union E: fmt::Debug = u8 | Pinned;
+
+            union B: fmt::Debug = u8 | box Pinned;
+
+            union Flat: fmt::Debug = u8 | #[trait_union(flatten)] P;
+
+            #[trait_union(phantom = PhantomPinned)]
+            union Ph: fmt::Debug = u8;
+
+            union G<T: fmt::Debug + 'static>: fmt::Debug = T;
+
+            #[trait_union(open(size = 8))]
+            union O: fmt::Debug;
+
+            #[trait_union(open(size = 8))]
+            union OU: fmt::Debug + Unpin;
+        }
+
+        let unpin = [
+            Check::<U>::UNPIN,
+            Check::<P>::UNPIN,
+            Check::<E>::UNPIN,
+            Check::<B>::UNPIN,
+            Check::<Flat>::UNPIN,
+            Check::<Ph>::UNPIN,
+            Check::<G<u8>>::UNPIN,
+            Check::<G<Pinned>>::UNPIN,
+            Check::<O>::UNPIN,
+            Check::<OU>::UNPIN,
+        ];
+        assert_eq!(
+            unpin,
+            [true, false, false, true, false, false, true, false, false, true]
+        );
+    }
+
+    #[test]
+    fn drop_panic() {
+        use std::{
+            panic::{self, AssertUnwindSafe},
+            rc::Rc,
+        };
+
+        #[derive(Debug)]
+        struct Bomb(#[allow(dead_code)] Rc<()>);
+
+        impl Drop for Bomb {
+            fn drop(&mut self) {
+                panic!("bomb");
+            }
+        }
+
+        trait_union! {
+            #[trait_union(live_counts)]
+            union D: fmt::Debug = u8 | Bomb;
+
+            #[trait_union(impl = "enum")]
+            union E: fmt::Debug = u8 | Bomb;
+
+            union Outer: fmt::Debug = #[trait_union(flatten)] D | Bomb;
+        }
+
+        let rc = Rc::new(());
+        let hook = panic::take_hook();
+        panic::set_hook(Box::new(|_| {}));
+        let res = [
+            panic::catch_unwind(AssertUnwindSafe(|| drop(D::new(Bomb(rc.clone()))))),
+            panic::catch_unwind(AssertUnwindSafe(|| drop(E::new(Bomb(rc.clone()))))),
+            panic::catch_unwind(AssertUnwindSafe(|| {
+                drop(Outer::from(D::new(Bomb(rc.clone()))))
+            })),
+            panic::catch_unwind(AssertUnwindSafe(|| drop(Outer::new(Bomb(rc.clone()))))),
+        ];
+        panic::set_hook(hook);
+        assert!(res.iter().all(|r| r.is_err()));
+        assert_eq!(Rc::strong_count(&rc), 1);
+        assert_eq!(D::live_counts(), [0, 0]);
+    }
+
+    #[test]
+    fn default_variant() {
+        #[derive(Debug, Default)]
+        struct Idle;
+
+        #[derive(Debug)]
+        struct Running(String);
+
+        trait_union! {
+            union Machine: fmt::Debug = #[trait_union(default)] Idle | Running;
+
+            #[trait_union(impl = "enum")]
+            union E: fmt::Debug = u8 | #[trait_union(default)] box [u64; 4];
+
+            union Outer: fmt::Debug = u16 | #[trait_union(default, flatten)] Machine;
+        }
+
+        fn advance(m: &mut Machine) -> Option<String> {
+            let (next, output) = match Machine::into_enum(m.take()) {
+                MachineOwned::Idle(_) => (Machine::new(Running("job".to_string())), None),
+                MachineOwned::Running(r) => (Machine::new(Idle), Some(r.0)),
+            };
+            *m = next;
+            output
+        }
+
+        let mut m = Machine::default();
+        assert_eq!(advance(&mut m), None);
+        assert_eq!(Machine::kind_of(&m), MachineKind::Running);
+        assert_eq!(advance(&mut m), Some("job".to_string()));
+        assert_eq!(Machine::kind_of(&m), MachineKind::Idle);
+        let mut e = E::new(1);
+        assert_eq!(format!("{:?}", &*e.take()), "1");
+        assert_eq!(format!("{:?}", &*e), "[0, 0, 0, 0]");
+        assert_eq!(format!("{:?}", &*Outer::default()), "Idle");
+    }
+
+    #[test]
+    fn transition() {
+        #[derive(Debug)]
+        struct Closed(u32);
+
+        #[derive(Debug)]
+        struct Open(u32);
+
+        trait_union! {
+            union C: fmt::Debug = Closed | Open;
+
+            #[trait_union(impl = "enum")]
+            union E: fmt::Debug = u8 | box u64;
+
+            union Outer: fmt::Debug = u16 | #[trait_union(flatten)] C;
+        }
+
+        fn step(state: COwned) -> COwned {
+            match state {
+                COwned::Closed(c) => COwned::Open(Open(c.0 + 1)),
+                COwned::Open(o) => COwned::Closed(Closed(o.0)),
+            }
+        }
+
+        let mut c = C::new(Closed(0));
+        C::transition(&mut c, step);
+        assert_eq!(format!("{:?}", &*c), "Open(1)");
+        C::transition(&mut c, step);
+        C::transition(&mut c, step);
+        assert_eq!(format!("{:?}", &*c), "Open(2)");
+        assert_eq!(C::kind_of(&c), CKind::Open);
+        let mut e = E::new(1);
+        E::transition(&mut e, |e| match e {
+            EOwned::U8(n) => EOwned::BoxU64(Box::new(n as u64 * 2)),
+            e => e,
+        });
+        assert_eq!(format!("{:?}", &*e), "2");
+        let mut o = Outer::from_enum(OuterOwned::C(c));
+        Outer::transition(&mut o, |o| match o {
+            OuterOwned::C(mut c) => {
+                C::transition(&mut c, step);
+                OuterOwned::C(c)
+            }
+            o => o,
+        });
+        assert_eq!(format!("{:?}", &*o), "Closed(2)");
+    }
+
+    #[test]
+    fn move_variant() {
+        trait_union! {
+            union A: fmt::Debug = u8 | box u64 | Rc<u32>;
+
+            #[trait_union(impl = "enum")]
+            union B: fmt::Debug = u16 | Rc<u32> | box u64;
+        }
+
+        let rc = Rc::new(1);
+        let b = A::new(rc.clone()).move_variant::<B>().ok().unwrap();
+        assert_eq!(B::kind_of(&b), BKind::RcU32);
+        assert_eq!(Rc::strong_count(&rc), 2);
+        let a = b.move_variant::<A>().ok().unwrap();
+        assert_eq!(A::kind_of(&a), AKind::RcU32);
+        drop(a);
+        assert_eq!(Rc::strong_count(&rc), 1);
+        let b = A::from(Box::new(3u64)).move_variant::<B>().ok().unwrap();
+        assert_eq!(format!("{:?}", &*b), "3");
+        let a = A::new(4u8).move_variant::<B>().err().unwrap();
+        assert_eq!(format!("{:?}", &*a), "4");
+    }
+
+    #[test]
+    fn manual_impl() {
+        #[derive(Debug)]
+        struct Wrapper<T>(T);
+
+        trait_union! {
+            #[trait_union(manual_impl(Wrapper<T>))]
+            union U<T: fmt::Debug + 'static>: fmt::Debug = u8 | Wrapper<T>;
+        }
+
+        unsafe impl<T: fmt::Debug + Copy + 'static> UManualVariant<T> for Wrapper<T> {}
+
+        let u = U::<u16>::new(Wrapper(1));
+        assert_eq!(U::kind_of(&u), UKind::WrapperT);
+        assert_eq!(format!("{:?}", &*u), "Wrapper(1)");
+        let u = U::<String>::new(2);
+        assert_eq!(format!("{:?}", &*u), "2");
+    }
+
+    #[test]
+    fn dispatcher() {
+        trait Tick {
+            fn tick(&mut self, total: &mut u32);
+            fn reset(&mut self);
+        }
+
+        struct Counter(u32, #[allow(dead_code)] Rc<()>);
+
+        impl Tick for Counter {
+            fn tick(&mut self, total: &mut u32) {
+                self.0 += 1;
+                *total += self.0;
+            }
+
+            fn reset(&mut self) {
+                self.0 = 0;
+            }
+        }
+
+        impl Tick for u32 {
+            fn tick(&mut self, total: &mut u32) {
+                *total += *self;
+            }
+
+            fn reset(&mut self) {}
+        }
+
+        trait_union! {
+            #[trait_union(dispatcher(capacity = 3, method = tick, ctx = u32))]
+            union D: Tick = Counter | u32;
+
+            #[trait_union(no_deref, dispatcher(method = reset, capacity = 2))]
+            union N<T: Tick + 'static>: Tick = T;
+        }
+
+        let rc = Rc::new(());
+        let mut d = DDispatcher::new();
+        assert_eq!(DDispatcher::CAPACITY, 3);
+        assert!(d.is_empty());
+        assert!(d.push(D::new(Counter(0, rc.clone()))).is_ok());
+        assert!(d.push(D::new(10)).is_ok());
+        assert!(d.push(D::new(Counter(5, rc.clone()))).is_ok());
+        assert!(d.is_full());
+        assert!(d.push(D::new(1)).is_err());
+        let mut total = 0;
+        d.dispatch_all(&mut total);
+        assert_eq!(total, 1 + 10 + 6);
+        assert_eq!(d.pop().map(|v| D::kind_of(&v)), Some(DKind::Counter));
+        assert!(d.push(D::new(100)).is_ok());
+        total = 0;
+        d.dispatch_all(&mut total);
+        assert_eq!(total, 10 + 7 + 100);
+        assert_eq!(d.len(), 3);
+        assert_eq!(Rc::strong_count(&rc), 2);
+        drop(d);
+        assert_eq!(Rc::strong_count(&rc), 1);
+        let mut n = NDispatcher::default();
+        assert!(n.push(N::new(Counter(3, rc.clone()))).is_ok());
+        n.dispatch_all();
+        let mut total = 0;
+        n.pop().unwrap().as_dyn_mut().tick(&mut total);
+        assert_eq!(total, 1);
+        assert!(n.pop().is_none());
+    }
+
+    #[test]
+    fn non_exhaustive() {
+        trait_union! {
+            #[trait_union(non_exhaustive)]
+            union U: fmt::Debug = u8 | String;
+        }
+
+        // In the defining crate, the generated enums can still be matched exhaustively.
+        let u = U::new("a".to_string());
+        let kind = match U::kind_of(&u) {
+            UKind::U8 => 0,
+            UKind::String => 1,
+        };
+        assert_eq!(kind, 1);
+        assert!(matches!(U::as_ref_enum(&u), URef::String(s) if s == "a"));
+        match U::into_enum(u) {
+            UOwned::U8(_) => unreachable!(),
+            UOwned::String(s) => assert_eq!(s, "a"),
+        }
+    }
+
+    #[test]
+    fn parallel() {
+        trait_union! {
+            #[trait_union(require(Send, Sync))]
+            union P: F = u8 | String;
+
+            #[trait_union(impl = "enum", require(Send, Sync))]
+            union E<T: F + Send + Sync + 'static>: F = T;
+        }
+
+        let mut values = (0..8).map(|i| P::new(i as u8)).collect::<Vec<_>>();
+        values.push(P::new("abc".to_string()));
+        let sum = std::thread::scope(|s| {
+            let threads = values
+                .chunks(3)
+                .map(|chunk| {
+                    s.spawn(move || chunk.iter().map(|p| p.len()).sum::<usize>())
+                })
+                .collect::<Vec<_>>();
+            threads
+                .into_iter()
+                .map(|t| t.join().unwrap())
+                .sum::<usize>()
+        });
+        assert_eq!(sum, 28 + 3);
+        let e = E::new("ab".to_string());
+        assert_eq!(std::thread::spawn(move || e.len()).join().unwrap(), 2);
+    }
+
+    #[test]
+    fn array() {
+        let array = crate::trait_union_array!(U; [1, "abc".to_string(), 2,]);
+        let lens = array.iter().map(|u| u.len()).collect::<Vec<_>>();
+        assert_eq!(lens, [1, 3, 2]);
+        let empty: [U; 0] = crate::trait_union_array!(U; []);
+        assert!(empty.is_empty());
     }
 }
@@ -0,0 +1,87 @@
+//! Discovery of auxiliary views of variants
+
+use core::{any::TypeId, marker::PhantomData};
+
+/// Types that expose references to other types on request
+///
+/// This is a stable counterpart of the provider API of `core::error::Error`. Variants
+/// marked with `#[trait_union(provide)]` must implement it, and every trait-union
+/// implements it by forwarding to its active variant.
+///
+/// # Example
+///
+#[cfg_attr(feature = "proc-macro", doc = "```rust")]
+#[cfg_attr(not(feature = "proc-macro"), doc = "```rust,ignore")]
+/// # use trait_union::{trait_union, Provide, Request};
+/// # use std::fmt::Debug;
+/// #
+/// #[derive(Debug)]
+/// struct Located {
+///     line: u32,
+/// }
+///
+/// impl Provide for Located {
+///     fn provide<'a>(&'a self, request: &mut Request<'a>) {
+///         request.provide_ref::<u32>(&self.line).provide_ref::<dyn Debug>(self);
+///     }
+/// }
+///
+/// trait_union! {
+///     union Event: Debug = &'static str | #[trait_union(provide)] Located;
+/// }
+///
+/// assert_eq!(Event::new(Located { line: 3 }).provide::<u32>(), Some(&3));
+/// assert!(Event::new(Located { line: 3 }).provide::<dyn Debug>().is_some());
+/// assert_eq!(Event::new("started").provide::<u32>(), None);
+/// ```
+pub trait Provide {
+    /// Offers references to `request`
+    fn provide<'a>(&'a self, request: &mut Request<'a>);
+}
+
+/// A request for a reference of a single type
+///
+/// The requested type is only known at run time. [Request::provide_ref] fills the request
+/// if the types match.
+pub struct Request<'a> {
+    type_id: TypeId,
+    /// Points to an `Option<&'a T>` where `T` has the type id `type_id`
+    slot: *mut (),
+    /// `'a` is invariant so that shorter references cannot be provided
+    _marker: PhantomData<&'a mut &'a ()>,
+}
+
+impl<'a> Request<'a> {
+    /// Provides `value` if a reference to `T` is requested and none has been provided
+    pub fn provide_ref<T: ?Sized + 'static>(&mut self, value: &'a T) -> &mut Self {
+        if self.type_id == TypeId::of::<T>() {
+            // SAFETY: `slot` points to an `Option<&'a T>` since the type ids match.
+            let slot = unsafe { &mut *(self.slot as *mut Option<&'a T>) };
+            if slot.is_none() {
+                *slot = Some(value);
+            }
+        }
+        self
+    }
+
+    /// Returns whether a reference to `T` is requested
+    pub fn requests<T: ?Sized + 'static>(&self) -> bool {
+        self.type_id == TypeId::of::<T>()
+    }
+}
+
+/// Requests a reference to `T` from `provider`
+///
+/// The generated `provide` method of trait-unions calls this function.
+pub fn request_ref<'a, T: ?Sized + 'static, P: ?Sized + Provide>(
+    provider: &'a P,
+) -> Option<&'a T> {
+    let mut slot: Option<&'a T> = None;
+    let mut request = Request {
+        type_id: TypeId::of::<T>(),
+        slot: &mut slot as *mut Option<&'a T> as *mut (),
+        _marker: PhantomData,
+    };
+    provider.provide(&mut request);
+    slot
+}
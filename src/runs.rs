@@ -0,0 +1,30 @@
+/// Iterator over the maximal runs of values of the same kind in a slice
+///
+/// Returned by the generated `kind_runs` function.
+pub struct KindRuns<'s, T, K> {
+    rest: &'s [T],
+    kind: fn(&T) -> K,
+}
+
+impl<'s, T, K> KindRuns<'s, T, K> {
+    #[doc(hidden)]
+    pub fn new(values: &'s [T], kind: fn(&T) -> K) -> Self {
+        Self { rest: values, kind }
+    }
+}
+
+impl<'s, T, K: PartialEq> Iterator for KindRuns<'s, T, K> {
+    type Item = (K, &'s [T]);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let kind = (self.kind)(self.rest.first()?);
+        let len = self
+            .rest
+            .iter()
+            .position(|v| (self.kind)(v) != kind)
+            .unwrap_or(self.rest.len());
+        let (run, rest) = self.rest.split_at(len);
+        self.rest = rest;
+        Some((kind, run))
+    }
+}
@@ -0,0 +1,63 @@
+use crate::LayoutInfo;
+use core::fmt::{self, Write};
+
+/// Machine-readable description of a trait-union type
+///
+/// Generated as the `SCHEMA` constant by the `schema` option.
+#[derive(Clone, Copy, Debug)]
+pub struct Schema {
+    /// The trait object type without `dyn`
+    pub trait_: &'static str,
+    /// The layout of the type and its variants
+    pub layout: LayoutInfo,
+    /// The names of the kinds of the variants in declaration order
+    pub kinds: &'static [&'static str],
+}
+
+impl Schema {
+    /// Writes the schema as a JSON object
+    ///
+    /// The object has the fields `name`, `trait`, `size`, `align`, and `variants`. Each
+    /// variant is an object with the fields `kind`, `type`, `tag`, `size`, `align`, and
+    /// `offset`.
+    pub fn write_json(&self, out: &mut dyn Write) -> fmt::Result {
+        let layout = &self.layout;
+        out.write_str("{\"name\":")?;
+        write_json_str(out, layout.name)?;
+        out.write_str(",\"trait\":")?;
+        write_json_str(out, self.trait_)?;
+        write!(
+            out,
+            ",\"size\":{},\"align\":{},\"variants\":[",
+            layout.size, layout.align
+        )?;
+        for (i, (v, kind)) in layout.variants.iter().zip(self.kinds).enumerate() {
+            if i > 0 {
+                out.write_str(",")?;
+            }
+            out.write_str("{\"kind\":")?;
+            write_json_str(out, kind)?;
+            out.write_str(",\"type\":")?;
+            write_json_str(out, v.name)?;
+            write!(
+                out,
+                ",\"tag\":{},\"size\":{},\"align\":{},\"offset\":{}}}",
+                v.tag, v.size, v.align, v.offset
+            )?;
+        }
+        out.write_str("]}")
+    }
+}
+
+fn write_json_str(out: &mut dyn Write, s: &str) -> fmt::Result {
+    out.write_char('"')?;
+    for c in s.chars() {
+        match c {
+            '"' => out.write_str("\\\"")?,
+            '\\' => out.write_str("\\\\")?,
+            c if (c as u32) < 0x20 => write!(out, "\\u{:04x}", c as u32)?,
+            c => out.write_char(c)?,
+        }
+    }
+    out.write_char('"')
+}
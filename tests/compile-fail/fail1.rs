@@ -2,7 +2,7 @@ use trait_union::trait_union;
 use std::fmt::Debug;
 
 trait_union! {
-    union U<'a>: Debug = &'a str;
+    union U<'a, 'b>: Debug = &'a str | &'b [u8];
 }
 
 fn main() { }
@@ -0,0 +1,9 @@
+use trait_union::trait_union;
+use std::fmt::Display;
+
+trait_union! {
+    #[trait_union(abi_check = 0x40b9_32b8_9fde_2ea4)]
+    union U: Display = u16 | u8;
+}
+
+fn main() { }
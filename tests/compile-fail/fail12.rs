@@ -0,0 +1,8 @@
+use trait_union::trait_union;
+
+trait_union! {
+    #[trait_union(abi_chek)]
+    union U: std::fmt::Display = u16 | u8;
+}
+
+fn main() { }
@@ -0,0 +1,7 @@
+use trait_union::trait_union;
+
+trait_union! {
+    union U: std::fmt::Display = 1: u8 | 0: u16 | u32;
+}
+
+fn main() { }
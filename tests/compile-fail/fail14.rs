@@ -0,0 +1,7 @@
+use trait_union::trait_union;
+
+trait_union! {
+    union U: std::fmt::Debug + ?Sized = u8;
+}
+
+fn main() { }
@@ -0,0 +1,7 @@
+use trait_union::trait_union;
+
+trait_union! {
+    union U: std::fmt::Display = #[trait_union(name = A)] u8 | #[trait_union(name = A)] u16;
+}
+
+fn main() { }
@@ -0,0 +1,11 @@
+use trait_union::trait_union_copy;
+
+trait_union_copy! {
+    union Inner: std::fmt::Display = u8;
+}
+
+trait_union_copy! {
+    union Outer: std::fmt::Display = #[trait_union(flatten)] Inner | u16;
+}
+
+fn main() { }
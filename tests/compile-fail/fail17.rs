@@ -0,0 +1,7 @@
+use trait_union::trait_union;
+
+trait_union! {
+    union U<'a, 'b>: std::fmt::Debug + 'a + 'b = &'a str | &'b [u8];
+}
+
+fn main() { }
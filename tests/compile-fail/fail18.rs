@@ -0,0 +1,14 @@
+use trait_union::trait_union;
+use std::cell::Cell;
+
+trait F { }
+
+impl F for Cell<&'_ mut u8> { }
+
+trait_union! {
+    #[trait_union(covariant('a))]
+    union U<'a>: F+'a = Cell<&'a mut u8>;
+}
+
+fn main() {
+}
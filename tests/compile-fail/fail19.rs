@@ -0,0 +1,14 @@
+use trait_union::trait_union;
+use std::fmt::Debug;
+
+trait_union! {
+    #[trait_union(invariant('a))]
+    union U<'a>: Debug+'a = &'a u8;
+}
+
+fn f<'a, 'b: 'a>(u: &U<'b>) {
+    let _: &U<'a> = u;
+}
+
+fn main() {
+}
@@ -2,7 +2,7 @@ use trait_union::trait_union;
 use std::fmt::Debug;
 
 trait_union! {
-    union U<'a>: Debug = u8;
+    union U<'a, 'b>: Debug + 'a = &'a u8;
 }
 
 fn main() { }
@@ -0,0 +1,25 @@
+use trait_union::trait_union;
+use std::fmt::Debug;
+
+trait_union! {
+    #[trait_union(covariant('b))]
+    union U<'a>: Debug = &'a u8;
+}
+
+trait_union! {
+    #[trait_union(invariant(T))]
+    union V<'a>: Debug = &'a u8;
+}
+
+trait_union! {
+    #[trait_union(covariant('a), invariant('a))]
+    union W<'a>: Debug = &'a u8;
+}
+
+trait_union! {
+    #[trait_union(covariant(T))]
+    union X<T: Debug>: Debug = T;
+}
+
+fn main() {
+}
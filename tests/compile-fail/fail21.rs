@@ -0,0 +1,14 @@
+use trait_union::trait_union;
+use std::fmt::Debug;
+use std::marker::PhantomData;
+
+trait_union! {
+    #[trait_union(phantom = PhantomData<*const ()>)]
+    union U: Debug = u8 | u16;
+}
+
+fn assert_send<T: Send>() { }
+
+fn main() {
+    assert_send::<U>();
+}
@@ -0,0 +1,13 @@
+use trait_union::trait_union;
+use std::fmt::Debug;
+use std::panic::UnwindSafe;
+
+trait_union! {
+    union U<'a>: Debug = u8 | &'a mut u8;
+}
+
+fn assert_unwind_safe<T: UnwindSafe>() { }
+
+fn main() {
+    assert_unwind_safe::<U>();
+}
@@ -0,0 +1,10 @@
+use trait_union::trait_union;
+use std::fmt::Debug;
+
+trait_union! {
+    #[trait_union(atomic)]
+    union U: Debug = u8 | u16;
+}
+
+fn main() {
+}
@@ -0,0 +1,13 @@
+use trait_union::{trait_union, TraitUnionVariant};
+use std::fmt::Debug;
+
+trait_union! {
+    #[trait_union(open(size = 4))]
+    union Handler: Debug;
+}
+
+#[derive(Debug, TraitUnionVariant)]
+#[trait_union(Handler)]
+struct Large(u64);
+
+fn main() { }
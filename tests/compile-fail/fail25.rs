@@ -0,0 +1,15 @@
+use trait_union::{trait_union, TraitUnionVariant};
+use std::fmt::Debug;
+
+trait_union! {
+    #[trait_union(open(size = 4))]
+    union Handler: Debug;
+}
+
+#[derive(Debug, TraitUnionVariant)]
+#[trait_union(Handler)]
+struct Wrapper<T: Debug + 'static>(T);
+
+fn main() {
+    Handler::new(Wrapper(1u64));
+}
@@ -0,0 +1,10 @@
+use trait_union::trait_union;
+use std::fmt::Debug;
+
+trait_union! {
+    union Direct: Debug = u8 | Direct;
+
+    union Wrapped: Debug = u8 | Option<(u8, [Self; 2])>;
+}
+
+fn main() { }
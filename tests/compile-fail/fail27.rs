@@ -0,0 +1,8 @@
+use trait_union::trait_union_copy;
+use std::fmt::Debug;
+
+trait_union_copy! {
+    union U: Debug = u8 | box u16;
+}
+
+fn main() { }
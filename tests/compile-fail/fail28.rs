@@ -0,0 +1,23 @@
+use trait_union::{trait_union, trait_union_cow};
+use std::fmt::Debug;
+
+trait_union! {
+    union U: Debug = u8;
+
+    union U: Debug = u16;
+
+    union URef: Debug = u8;
+}
+
+trait_union! {
+    #[trait_union(open(size = 8))]
+    union O: Debug;
+
+    union OVariant: Debug = u8;
+}
+
+trait_union_cow! {
+    union B<'a>, B: Debug = &'a str => String;
+}
+
+fn main() { }
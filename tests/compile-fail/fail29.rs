@@ -0,0 +1,12 @@
+use trait_union::trait_union;
+use std::{fmt::Debug, rc::Rc};
+
+trait_union! {
+    #[trait_union(impl = "enum", require(Send))]
+    union U: Debug = u8 | Rc<u8>;
+
+    #[trait_union(impl = "enum", require(Sync))]
+    union V<T: Debug + 'static>: Debug = T;
+}
+
+fn main() { }
@@ -0,0 +1,26 @@
+use trait_union::trait_union;
+use std::fmt::Debug;
+
+mod handles {
+    use std::fmt::Debug;
+    use trait_union::trait_union;
+
+    trait_union! {
+        #[trait_union(new_vis = "pub(self)")]
+        pub union Handle: Debug = u8 | &'static str;
+    }
+
+    pub fn handle() -> Handle {
+        Handle::new(1)
+    }
+}
+
+trait_union! {
+    #[trait_union(new_vis = "pub(crate)")]
+    pub union U: Debug = u8 | #[trait_union(flatten)] handles::Handle;
+}
+
+fn main() {
+    let _ = format!("{:?}", &*handles::handle());
+    let _ = handles::Handle::new("handle");
+}
@@ -0,0 +1,12 @@
+use trait_union::trait_union;
+
+trait_union! {
+    #[trait_union(open(size = 8))]
+    union Callback: Fn() -> u64;
+}
+
+fn main() {
+    let (a, b) = (1u64, 2u64);
+    let _ = Callback::from_fn(move || a);
+    let _ = Callback::from_fn(move || a + b);
+}
@@ -0,0 +1,16 @@
+#![deny(private_interfaces)]
+
+mod handlers {
+    use std::fmt::Debug;
+    use trait_union::trait_union;
+
+    #[derive(Debug)]
+    struct Tcp;
+
+    trait_union! {
+        #[trait_union(impl = "enum", enums)]
+        pub union Handler: Debug = u8 | Tcp;
+    }
+}
+
+fn main() { }
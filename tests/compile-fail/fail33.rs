@@ -0,0 +1,21 @@
+use trait_union::trait_union;
+
+trait_union! {
+    #[trait_union(impl = "enum", phantom = u8)]
+    #[trait_union(phantom = u16, impl = "union")]
+    union U: std::fmt::Display = u16 | u8;
+
+    #[trait_union(open(size = 8, size = 16))]
+    union O: std::fmt::Display;
+}
+
+trait_union! {
+    union V: std::fmt::Display = #[trait_union(name = A, flaten)] u16 | u8;
+}
+
+trait_union! {
+    #[trait_union(frobnicate)]
+    union W: std::fmt::Display = u16 | u8;
+}
+
+fn main() { }
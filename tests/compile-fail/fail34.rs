@@ -0,0 +1,12 @@
+use std::future::{Future, Ready};
+use trait_union::trait_union;
+
+trait_union! {
+    #[trait_union(forward(Future))]
+    union F: Future<Output = u8> = Ready<u8> | box Ready<u8>;
+
+    #[trait_union(forward(Iterator))]
+    union I: Iterator<Item = u8> = std::iter::Empty<u8>;
+}
+
+fn main() { }
@@ -0,0 +1,12 @@
+use trait_union::trait_union;
+
+trait_union! {
+    union U: std::fmt::Debug = #[trait_union(default)] u8 | #[trait_union(default)] u16;
+
+    #[trait_union(new_vis = "pub(crate)")]
+    pub union V: std::fmt::Debug = #[trait_union(default)] u8 | u16;
+
+    union W: std::fmt::Debug = u8 | #[trait_union(default)] std::fs::File;
+}
+
+fn main() { }
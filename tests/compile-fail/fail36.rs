@@ -0,0 +1,21 @@
+use trait_union::trait_union;
+
+trait_union! {
+    #[trait_union(dispatcher(capacity = 4))]
+    union U: std::fmt::Display = u16 | u8;
+}
+
+trait_union! {
+    #[trait_union(dispatcher(capacity = 4, method = fmt, size = 2))]
+    union V: std::fmt::Display = u16 | u8;
+}
+
+trait_union! {
+    #[trait_union(dispatcher(capacity = 4, method = to_string))]
+    union W: std::fmt::Display = u16 | u8;
+
+    #[trait_union(dispatcher(capacity = 2, method = fmt))]
+    union WDispatcher: std::fmt::Display = u16 | u8;
+}
+
+fn main() { }
@@ -0,0 +1,25 @@
+use trait_union::{assert_union_covers, trait_union};
+
+trait_union! {
+    union Handler: std::fmt::Debug = Login | u8;
+}
+
+#[derive(Debug)]
+struct Login;
+
+#[derive(Debug)]
+struct Signup;
+
+trait Route: HandlerVariant {}
+
+assert_union_covers!(Handler: Route);
+
+impl Route for Login {}
+
+impl Route for Signup {}
+
+trait Unchecked {}
+
+assert_union_covers!(Handler: Unchecked);
+
+fn main() { }
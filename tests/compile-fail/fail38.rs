@@ -0,0 +1,19 @@
+use trait_union::{trait_union, trait_union_cow};
+
+trait_union! {
+    #[trait_union(no_implicit_static)]
+    union U: std::fmt::Debug = u8 | &'static str;
+
+    #[trait_union(no_implicit_static)]
+    union V<'a, 'b>: std::fmt::Debug = &'a u8 | &'b str;
+
+    #[trait_union(open(size = 8), no_implicit_static)]
+    union O: std::fmt::Debug;
+}
+
+trait_union_cow! {
+    #[trait_union(no_implicit_static)]
+    union B<'a>, C: std::fmt::Debug = &'a str => String;
+}
+
+fn main() { }
@@ -0,0 +1,27 @@
+use trait_union::{trait_union, trait_union_cow};
+
+struct Wrapper(u8);
+
+impl std::fmt::Debug for Wrapper {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+trait_union! {
+    #[trait_union(manual_impl(Wrapper))]
+    union U: std::fmt::Debug = u8 | Wrapper;
+
+    #[trait_union(manual_impl(u16))]
+    union V: std::fmt::Debug = u8 | box u16;
+
+    #[trait_union(manual_impl(u32))]
+    union W: std::fmt::Debug = u8;
+}
+
+trait_union_cow! {
+    #[trait_union(manual_impl(String))]
+    union B<'a>, C: std::fmt::Debug = &'a str => String;
+}
+
+fn main() { }
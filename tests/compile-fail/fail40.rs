@@ -0,0 +1,7 @@
+use trait_union::trait_union;
+
+trait_union! {
+    struct U: std::fmt::Debug = u8;
+}
+
+fn main() { }
@@ -0,0 +1,13 @@
+use trait_union::trait_union;
+use std::fmt::Debug;
+
+trait_union! {
+    #[trait_union(reborrow)]
+    union U<'a>: Debug + 'a = &'a str | u8;
+
+    #[trait_union(reborrow)]
+    union V: Debug = &'static str;
+}
+
+fn main() {
+}
@@ -0,0 +1,16 @@
+#![deny(deprecated)]
+
+use trait_union::trait_union;
+use std::fmt::Display;
+
+trait_union! {
+    #[trait_union(report_size = 2)]
+    union U: Display = u16 | u8;
+}
+
+trait_union! {
+    #[trait_union(report_size = 2)]
+    union G<T: Display + 'static>: Display = T | u8;
+}
+
+fn main() { }
@@ -0,0 +1,13 @@
+use trait_union::trait_union;
+use std::fmt::Display;
+
+trait_union! {
+    union U: Display { name: String } = u16 | u8;
+}
+
+trait_union! {
+    #[trait_union(open(size = 8, align = 8))]
+    union O: Display { priority: u8 };
+}
+
+fn main() { }
@@ -0,0 +1,13 @@
+use std::fmt::Debug;
+use std::rc::Rc;
+use trait_union::trait_union;
+
+trait_union! {
+    #[trait_union(send)]
+    union U: Debug = u8 | Rc<u8>;
+}
+
+fn main() {
+    let _ = U::new_send(Rc::new(1u8));
+    let _ = USend::new(Rc::new(1u8));
+}
@@ -0,0 +1,13 @@
+use std::fmt::Debug;
+use trait_union::trait_union;
+
+trait_union! {
+    union U: Debug = #[trait_union(forward(Hasher))] u8 | u16;
+}
+
+trait_union! {
+    #[trait_union(forward(Write))]
+    union V: Debug = #[trait_union(forward(std::io::Write))] Vec<u8> | u16;
+}
+
+fn main() { }
@@ -1,5 +1,3 @@
-#![feature(untagged_unions)]
-
 use trait_union::trait_union;
 use std::cell::Cell;
 
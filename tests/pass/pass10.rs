@@ -0,0 +1,28 @@
+use std::{fmt::Debug, marker::PhantomData};
+use trait_union::trait_union;
+
+trait_union! {
+    #[trait_union(phantom = PhantomData<*const ()>)]
+    union NotSend: Debug = u8 | u16;
+}
+
+trait_union! {
+    #[trait_union(phantom = PhantomData<&'a mut ()>, phantom = PhantomData<fn(T)>)]
+    union Tuned<'a, T: Debug + 'a>: Debug = T;
+}
+
+trait_union! {
+    #[trait_union(impl = "enum", phantom = PhantomData<&'a ()>)]
+    union EnumTuned<'a>: Debug + 'static = u8;
+}
+
+fn shorten<'x, 'a: 'x, T: Debug>(u: Tuned<'a, T>) -> Tuned<'x, T> {
+    u
+}
+
+fn main() {
+    let u = NotSend::new(1u8);
+    println!("{:?}", &*u);
+    let _ = shorten(Tuned::new(1u8));
+    let _ = EnumTuned::new(1u8);
+}
@@ -0,0 +1,43 @@
+use std::{
+    fmt::Debug,
+    panic::{RefUnwindSafe, UnwindSafe},
+};
+use trait_union::{trait_union, trait_union_copy, trait_union_cow, trait_union_decl};
+
+fn assert<T: UnwindSafe + RefUnwindSafe>() {}
+
+trait_union! {
+    union U: Debug = u8 | String;
+}
+
+trait_union! {
+    #[trait_union(impl = "enum")]
+    union E: Debug = u8 | String;
+}
+
+trait_union! {
+    union Outer: Debug = #[trait_union(flatten)] U | u16;
+}
+
+trait_union_copy! {
+    union C: Debug = u8 | u16;
+}
+
+trait_union_cow! {
+    union B<'a>, O: Debug = &'a str => String;
+}
+
+trait_union_decl! {
+    union D: Debug = u8 | String;
+}
+
+fn main() {
+    assert::<U>();
+    assert::<E>();
+    assert::<Outer>();
+    assert::<C>();
+    assert::<B<'static>>();
+    assert::<O>();
+    assert::<D>();
+    assert::<URef<'static>>();
+}
@@ -0,0 +1,61 @@
+use trait_union::trait_union;
+
+trait Handle {
+    fn handle(&self) -> u32;
+}
+
+mod net {
+    use super::Handle;
+    use trait_union::trait_union;
+
+    pub struct Tcp;
+    pub struct Udp;
+
+    impl Handle for Tcp {
+        fn handle(&self) -> u32 {
+            1
+        }
+    }
+
+    impl Handle for Udp {
+        fn handle(&self) -> u32 {
+            2
+        }
+    }
+
+    trait_union! {
+        pub(crate) union NetHandler: Handle = Tcp | Udp;
+    }
+}
+
+mod fs {
+    use super::Handle;
+    use trait_union::trait_union;
+
+    pub struct File;
+
+    impl Handle for File {
+        fn handle(&self) -> u32 {
+            3
+        }
+    }
+
+    trait_union! {
+        pub(crate) union FsHandler: Handle = File;
+    }
+}
+
+trait_union! {
+    union Handler: Handle =
+        #[trait_union(flatten)] net::NetHandler | #[trait_union(flatten)] fs::FsHandler;
+}
+
+fn main() {
+    let handlers = [
+        Handler::from(net::NetHandler::new(net::Tcp)),
+        Handler::from(net::NetHandler::new(net::Udp)),
+        Handler::from(fs::FsHandler::new(fs::File)),
+    ];
+    assert_eq!(handlers.iter().map(|h| h.handle()).sum::<u32>(), 6);
+    assert_eq!(Handler::kind_of(&handlers[2]), HandlerKind::FsHandler);
+}
@@ -0,0 +1,66 @@
+use trait_union::{trait_union, TraitUnionVariant};
+
+pub trait Handle {
+    fn handle(&self) -> u32;
+}
+
+mod handlers {
+    use super::Handle;
+    use trait_union::trait_union;
+
+    trait_union! {
+        #[trait_union(open(size = 16, align = 8))]
+        pub union Handler: Handle;
+    }
+}
+
+mod net {
+    use super::Handle;
+    use trait_union::TraitUnionVariant;
+
+    #[derive(TraitUnionVariant)]
+    #[trait_union(crate::handlers::Handler)]
+    pub struct Tcp(pub u64);
+
+    impl Handle for Tcp {
+        fn handle(&self) -> u32 {
+            self.0 as u32
+        }
+    }
+}
+
+#[derive(TraitUnionVariant)]
+#[trait_union(handlers::Handler)]
+struct Fixed<T: Copy + Into<u32> + 'static>(T);
+
+impl<T: Copy + Into<u32> + 'static> Handle for Fixed<T> {
+    fn handle(&self) -> u32 {
+        self.0.into()
+    }
+}
+
+trait_union! {
+    #[trait_union(open(size = 4))]
+    union Small: Handle;
+}
+
+#[derive(TraitUnionVariant)]
+#[trait_union(handlers::Handler, Small)]
+struct Unit;
+
+impl Handle for Unit {
+    fn handle(&self) -> u32 {
+        0
+    }
+}
+
+fn main() {
+    let handlers = [
+        handlers::Handler::new(net::Tcp(1)),
+        handlers::Handler::new(Fixed(2u8)),
+        handlers::Handler::new(Fixed(3u16)),
+        handlers::Handler::new(Unit),
+    ];
+    assert_eq!(handlers.iter().map(|h| h.handle()).sum::<u32>(), 6);
+    assert_eq!(Small::new(Unit).handle(), 0);
+}
@@ -0,0 +1,35 @@
+use trait_union::trait_union;
+use std::fmt::Debug;
+
+// Names of helper items generated by earlier versions
+#[allow(dead_code, non_snake_case)]
+fn __trait_union_U_to_trait_object() {}
+
+#[allow(dead_code, non_camel_case_types)]
+struct __trait_union_U_TraitObject;
+
+trait_union! {
+    union U: Debug = u8 | &'static str;
+}
+
+macro_rules! declare {
+    ($name:ident) => {
+        trait_union! {
+            union $name: Debug = u16 | String;
+        }
+    };
+}
+
+declare!(A);
+declare!(B);
+
+fn main() {
+    trait_union! {
+        union Local: Debug = u32 | bool;
+    }
+
+    assert_eq!(format!("{:?}", &*U::new(1u8)), "1");
+    assert_eq!(format!("{:?}", &*A::new(2u16)), "2");
+    assert_eq!(format!("{:?}", &*B::new(String::new())), "\"\"");
+    assert_eq!(format!("{:?}", &*Local::new(true)), "true");
+}
@@ -0,0 +1,36 @@
+use trait_union::trait_union;
+use std::fmt::Debug;
+
+mod a {
+    use super::*;
+
+    trait_union! {
+        pub union U: Debug = u8 | &'static str;
+    }
+}
+
+mod b {
+    use super::*;
+
+    trait_union! {
+        pub union U: Debug = u16 | String;
+    }
+}
+
+trait_union! {
+    #[trait_union(open(size = 8))]
+    union O: Debug;
+
+    union OKind: Debug = u8;
+}
+
+fn main() {
+    trait_union! {
+        union U: Debug = bool;
+    }
+
+    assert_eq!(a::U::kind_of(&a::U::new(1u8)), a::UKind::U8);
+    assert_eq!(b::U::kind_of(&b::U::new(String::new())), b::UKind::String);
+    assert_eq!(U::kind_of(&U::new(true)), UKind::Bool);
+    assert_eq!(format!("{:?}", &*OKind::new(2u8)), "2");
+}
@@ -0,0 +1,105 @@
+use trait_union::{trait_union, trait_union_copy};
+
+mod hal {
+    pub mod digital {
+        pub trait OutputPin {
+            type Error;
+
+            fn set_high(&mut self) -> Result<(), Self::Error>;
+        }
+    }
+
+    pub mod serial {
+        pub trait Write<Word> {
+            type Error;
+
+            fn write(&mut self, word: Word) -> Result<(), Self::Error>;
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug)]
+struct PinErrorCode(u8);
+
+struct GpioPin<E>(Option<E>);
+
+impl<E: Copy> hal::digital::OutputPin for GpioPin<E> {
+    type Error = E;
+
+    fn set_high(&mut self) -> Result<(), E> {
+        self.0.map_or(Ok(()), Err)
+    }
+}
+
+struct NoPin<E>(std::marker::PhantomData<E>);
+
+impl<E> hal::digital::OutputPin for NoPin<E> {
+    type Error = E;
+
+    fn set_high(&mut self) -> Result<(), E> {
+        Ok(())
+    }
+}
+
+#[derive(Copy, Clone)]
+struct Uart;
+
+impl<W: Into<u32>> hal::serial::Write<W> for Uart {
+    type Error = std::convert::Infallible;
+
+    fn write(&mut self, _: W) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+trait_union! {
+    union Pin<E: Copy + 'static>: hal::digital::OutputPin<Error = E> = GpioPin<E> | NoPin<E>;
+
+    #[trait_union(impl = "enum")]
+    union EnumPin<E: Copy + Send + 'static>: self::hal::digital::OutputPin<Error = E> + Send
+        = GpioPin<E> | NoPin<E>;
+
+    union Serial<W: Into<u32> + 'static>: crate::hal::serial::Write<W, Error = std::convert::Infallible>
+        = Uart;
+
+    union Writer<'a>: std::io::Write + 'a = &'a mut Vec<u8> | std::io::Sink;
+
+    union Bytes<'a, T: 'a>: ::core::convert::AsRef<[T]> + 'a = &'a [T] | Vec<T>;
+
+    union Numbers<T: 'static>: ::core::iter::Iterator<Item = T>
+        = std::iter::Empty<T> | std::iter::Once<T>;
+}
+
+trait_union_copy! {
+    #[derive(Copy, Clone)]
+    union SerialCopy<W: Into<u32> + 'static>: hal::serial::Write<W, Error = std::convert::Infallible>
+        = Uart;
+}
+
+fn set_high<E>(pin: &mut dyn hal::digital::OutputPin<Error = E>) -> Result<(), E> {
+    pin.set_high()
+}
+
+fn main() {
+    let mut pin = Pin::new(GpioPin(Some(PinErrorCode(1))));
+    assert!(set_high(&mut *pin).is_err());
+    assert!(set_high((&mut pin).into()).is_err());
+    pin = Pin::new(NoPin(std::marker::PhantomData));
+    assert!(pin.set_high().is_ok());
+    assert_eq!(Pin::kind_of(&pin), PinKind::NoPinE);
+
+    let mut pin = EnumPin::<PinErrorCode>::new(GpioPin(None));
+    assert!(pin.set_high().is_ok());
+
+    let mut serial = Serial::<u8>::new(Uart);
+    assert!(serial.write(1u8).is_ok());
+    let mut serial = SerialCopy::<u16>::new(Uart);
+    assert!(serial.write(1u16).is_ok());
+
+    let mut buf = vec![];
+    Writer::new(&mut buf).write_all(b"abc").unwrap();
+    assert_eq!(buf, b"abc");
+
+    assert_eq!(Bytes::new(vec![1, 2]).as_ref(), [1, 2]);
+    assert_eq!(Numbers::new(std::iter::once(3)).next(), Some(3));
+}
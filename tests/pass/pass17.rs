@@ -0,0 +1,42 @@
+#![forbid(unsafe_code)]
+
+use std::fmt::Debug;
+use trait_union::{trait_union, trait_union_copy, trait_union_cow, TraitUnionVariant};
+
+trait_union! {
+    #[trait_union(abi_check, constructors, live_counts, require(Send))]
+    union U: Debug = u8 | &'static str | box u16;
+
+    #[trait_union(impl = "enum", drop_tests)]
+    union E: Debug = u8 | #[trait_union(flatten)] U;
+
+    union Outer<'a, T: Debug + 'a>: Debug + 'a = 1: &'a T | #[trait_union(flatten)] E | box Self;
+
+    #[trait_union(open(size = 8))]
+    union O: Debug;
+}
+
+trait_union_copy! {
+    #[derive(Copy, Clone)]
+    union C: Debug = u8 | &'static str;
+}
+
+trait_union_cow! {
+    union B<'a>, Owned: Debug = &'a str => String | &'a [u8] => Vec<u8>;
+}
+
+#[derive(Debug, TraitUnionVariant)]
+#[trait_union(O)]
+struct Small(u32);
+
+fn main() {
+    let u = U::new(1);
+    assert_eq!(format!("{:?}", &*u), "1");
+    assert_eq!(U::live_counts(), [1, 0, 0]);
+    let e = E::from(u);
+    let o = Outer::<u8>::from(e);
+    assert_eq!(format!("{:?}", &*o), "1");
+    assert_eq!(format!("{:?}", &*O::new(Small(2))), "Small(2)");
+    assert_eq!(format!("{:?}", &*C::new("c")), "\"c\"");
+    assert_eq!(format!("{:?}", &*B::new("b").to_owned()), "\"b\"");
+}
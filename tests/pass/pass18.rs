@@ -0,0 +1,31 @@
+#![deny(missing_docs, unused_doc_comments)]
+//! Doc comments on variants
+
+use std::fmt::Debug;
+use trait_union::trait_union;
+
+trait_union! {
+    /// A value
+    pub union Value: Debug =
+        /// A small number
+        1: u8 |
+        /// A static string
+        &'static str |
+        #[trait_union(name = Boxed)]
+        /// A boxed number
+        box u64;
+
+    /// A value stored in an enum
+    #[trait_union(impl = "enum")]
+    pub union EnumValue: Debug =
+        /// A flattened value
+        #[trait_union(flatten)]
+        Value |
+        /// A number
+        u16;
+}
+
+fn main() {
+    assert_eq!(Value::kind_of(&Value::new(1u8)), ValueKind::U8);
+    assert_eq!(EnumValue::kind_of(&EnumValue::new(1u16)), EnumValueKind::U16);
+}
@@ -0,0 +1,20 @@
+use std::fmt::Debug;
+use trait_union::trait_union;
+
+trait_union! {
+    #[trait_union(no_implicit_static)]
+    union Static: Debug + 'static = u8 | &'static str;
+
+    #[trait_union(no_implicit_static)]
+    union Borrowed<'a>: Debug = &'a str | u8;
+
+    #[trait_union(open(size = 8), no_implicit_static)]
+    union Open: Debug + Send + 'static;
+}
+
+fn main() {
+    let s = String::from("abc");
+    let b = Borrowed::new(&*s);
+    assert_eq!(format!("{:?}", &*b), "\"abc\"");
+    assert_eq!(format!("{:?}", &*Static::new(1)), "1");
+}
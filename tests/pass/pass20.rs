@@ -0,0 +1,27 @@
+use std::fmt::Debug;
+use trait_union::{trait_union, trait_union_cow, trait_union_decl};
+
+trait_union! {
+    pub enumlike Keyword: Debug = u8 | &'static str;
+
+    #[trait_union(open(size = 8))]
+    enumlike Open: Debug;
+
+    union Mixed: Debug = u8 | bool;
+}
+
+trait_union_cow! {
+    enumlike Borrowed<'a>, Owned: Debug = &'a str => String;
+}
+
+trait_union_decl! {
+    enumlike Decl: Debug = u8 | bool;
+}
+
+fn main() {
+    assert_eq!(format!("{:?}", &*Keyword::new("a")), "\"a\"");
+    assert_eq!(format!("{:?}", &*Mixed::new(true)), "true");
+    let owned: Owned = Borrowed::new("b").to_owned();
+    assert_eq!(format!("{:?}", &*owned), "\"b\"");
+    assert_eq!(format!("{:?}", &*Decl::new(1u8)), "1");
+}
@@ -0,0 +1,56 @@
+use std::fmt::Debug;
+use trait_union::{trait_union, trait_union_cow};
+
+macro_rules! standard_union {
+    ($(#[$attr:meta])* $vis:vis $name:ident: $trait:path = $($ty:ty)|+) => {
+        trait_union! {
+            $(#[$attr])*
+            #[trait_union(constructors)]
+            $vis union $name: $trait = $($ty)|+;
+        }
+    };
+}
+
+macro_rules! cow_union {
+    ($borrowed:ident<$lt:lifetime>, $owned:ident = $($b:ty => $o:ty)|+) => {
+        trait_union_cow! {
+            union $borrowed<$lt>, $owned: Debug = $($b => $o)|+;
+        }
+    };
+}
+
+macro_rules! nested_union {
+    ($name:ident<$lt:lifetime> = $($(#[$vattr:meta])* $ty:ty)|+) => {
+        trait_union! {
+            union $name<$lt>: Debug + $lt = $($(#[$vattr])* $ty)|+;
+        }
+    };
+}
+
+standard_union! {
+    /// A union declared by a wrapper macro
+    pub Value: Debug = u8 | Option<String> | &'static str | [u16; 2] | Box<u32>
+}
+
+standard_union! {
+    #[trait_union(impl = "enum")]
+    Other: std::fmt::Display = u8
+}
+
+cow_union!(Borrowed<'a>, Owned = &'a str => String | &'a [u8] => Vec<u8>);
+
+nested_union!(Nested<'a> = #[trait_union(default)] u16 | #[trait_union(flatten)] Value | &'a str);
+
+fn main() {
+    let v = Value::new(Some("x".to_string()));
+    assert_eq!(Value::kind_of(&v), ValueKind::OptionString);
+    assert_eq!(format!("{:?}", &*v), "Some(\"x\")");
+    assert_eq!(format!("{:?}", &*Value::new([1u16, 2])), "[1, 2]");
+    assert_eq!(Value::CONSTRUCTORS.len(), 5);
+    assert_eq!(Other::new(3).to_string(), "3");
+    let o = Borrowed::new("a").to_owned();
+    assert_eq!(format!("{:?}", &*o.as_borrowed()), "\"a\"");
+    assert_eq!(format!("{:?}", &*Nested::default()), "0");
+    let n = Nested::from(Value::new(4u8));
+    assert_eq!(format!("{:?}", &*n), "4");
+}
@@ -0,0 +1,61 @@
+use std::fmt::{self, Display};
+use trait_union::trait_union;
+
+mod handlers {
+    use super::*;
+
+    struct Private(u8);
+
+    impl Display for Private {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "private {}", self.0)
+        }
+    }
+
+    trait_union! {
+        pub union Handler: Display = Private | u8;
+
+        #[trait_union(enums)]
+        pub union Exported: Display = u8 | &'static str;
+    }
+
+    pub fn private(n: u8) -> Handler {
+        Handler::new(Private(n))
+    }
+
+    pub fn value(handler: &Handler) -> u8 {
+        match Handler::as_ref_enum(handler) {
+            HandlerRef::Private(p) => p.0,
+            HandlerRef::U8(n) => *n,
+        }
+    }
+}
+
+struct Priv;
+
+impl Display for Priv {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("priv")
+    }
+}
+
+trait_union! {
+    pub union U: Display = Priv | u8;
+
+    #[trait_union(impl = "enum")]
+    pub union E: Display = Priv | u8;
+}
+
+fn main() {
+    let handler = handlers::private(1);
+    assert_eq!(handler.to_string(), "private 1");
+    assert_eq!(handlers::value(&handler), 1);
+    assert_eq!(handlers::value(&handlers::Handler::new(2u8)), 2);
+    match handlers::Exported::into_enum(handlers::Exported::new("a")) {
+        handlers::ExportedOwned::U8(_) => unreachable!(),
+        handlers::ExportedOwned::StaticStr(s) => assert_eq!(s, "a"),
+    }
+    assert_eq!(U::new(Priv).to_string(), "priv");
+    assert_eq!(E::new(Priv).to_string(), "priv");
+    assert_eq!(E::new(1u8).to_string(), "1");
+}
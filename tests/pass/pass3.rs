@@ -0,0 +1,39 @@
+use std::{
+    future::{self, Future},
+    iter,
+    slice,
+};
+use trait_union::trait_union;
+
+trait_union! {
+    union I<'a, T: 'a + Copy>: Iterator<Item = T> + 'a =
+        iter::Empty<T> | iter::Once<T> | iter::Copied<slice::Iter<'a, T>>;
+
+    union J<'a, T: 'a>: Iterator<Item = &'a T> + 'a = iter::Empty<&'a T> | slice::Iter<'a, T>;
+
+    union F<T: 'static>: Future<Output = T> + Unpin = future::Ready<T> | future::Pending<T>;
+}
+
+fn sum<'a>(mut i: I<'a, u32>) -> u32 {
+    let mut sum = 0;
+    while let Some(v) = i.next() {
+        sum += v;
+    }
+    sum
+}
+
+fn first<'a, T>(j: &mut J<'a, T>) -> Option<&'a T> {
+    j.next()
+}
+
+fn ready<T: 'static>(t: T) -> F<T> {
+    F::new(future::ready(t))
+}
+
+fn main() {
+    let v = [1, 2];
+    let _ = sum(I::new(iter::once(1)));
+    let _ = sum(I::new(v.iter().copied()));
+    let _ = first(&mut J::new(v.iter()));
+    let _ = ready(1);
+}
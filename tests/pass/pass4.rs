@@ -0,0 +1,20 @@
+use std::borrow::Borrow;
+use trait_union::trait_union;
+
+trait_union! {
+    union A: AsRef<[u8]> = &'static str | Vec<u8> | [u8; 4];
+
+    union E: PartialEq<u32> + Send = u32;
+
+    union B<T: ?Sized + 'static>: Borrow<T> = &'static T | Box<T>;
+
+    union C<'a>: Iterator<Item = Box<dyn AsRef<str> + 'a>> + 'a =
+        std::iter::Empty<Box<dyn AsRef<str> + 'a>>;
+}
+
+fn main() {
+    let _ = A::new(vec![1]).as_ref().len();
+    let _ = *E::new(1) == 1;
+    let _: &str = (*B::<str>::new("a")).borrow();
+    let _ = C::new(std::iter::empty()).next();
+}
@@ -0,0 +1,36 @@
+use trait_union::{trait_union, trait_union_copy, trait_union_cow};
+
+trait Convert<T> {
+    type Output;
+}
+
+impl<T, U> Convert<T> for U {
+    type Output = T;
+}
+
+trait_union! {
+    union A: Iterator<Item = Box<dyn Iterator<Item = u32>>> = std::iter::Empty<Box<dyn Iterator<Item = u32>>>;
+
+    union B<T: 'static>: Convert<T, Output = T> where T: Iterator<Item = u32> = u8 | Option<T>;
+
+    union C: Fn(u8) -> u8 + Send = fn(u8) -> u8;
+
+    union D: Iterator<Item = u32> + = std::iter::Empty<u32>;
+}
+
+trait_union_copy! {
+    union E: Convert<u32, Output = u32> = u8;
+}
+
+trait_union_cow! {
+    union F<'a>, G: PartialEq<&'static str> = &'a str => String;
+}
+
+fn main() {
+    let _ = A::new(std::iter::empty()).next();
+    let _ = B::<std::iter::Empty<u32>>::new(1);
+    let _ = C::new((|x| x) as fn(u8) -> u8)(1);
+    let _ = D::new(std::iter::empty()).next();
+    let _ = E::new(1);
+    let _ = F::new("a").to_owned();
+}
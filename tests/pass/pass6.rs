@@ -0,0 +1,25 @@
+use std::fmt::{Debug, Display};
+use trait_union::trait_union;
+
+trait_union! {
+    union A: (Debug) = u8;
+
+    union B: (dyn Display + Send) = u8 | &'static str;
+
+    union C: dyn Debug + Sync = u8;
+
+    union D<'a>: (Debug + 'a) + Send = &'a str;
+
+    union E: ((Debug) + (Send + Sync)) = u8;
+}
+
+fn check(_: &(dyn Debug + Send + Sync)) {}
+
+fn main() {
+    let _ = format!("{:?}", &*A::new(1));
+    let _ = B::new("a").to_string();
+    let _ = format!("{:?}", &*C::new(1));
+    let s = String::new();
+    let _ = format!("{:?}", &*D::new(&*s));
+    check(&*E::new(1));
+}
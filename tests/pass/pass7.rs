@@ -0,0 +1,22 @@
+use std::fmt::Debug;
+use trait_union::trait_union;
+
+trait_union! {
+    union U<'a>: Debug = &'a str | &'a [u8];
+
+    union V<'a, T: 'a + Debug>: Debug = &'a T | Option<T>;
+}
+
+fn f<'a>(s: &'a str) -> U<'a> {
+    U::new(s)
+}
+
+fn g<'a, T: Debug>(t: &'a T) -> V<'a, T> {
+    V::new(t)
+}
+
+fn main() {
+    let s = String::new();
+    let _ = f(&s);
+    let _ = g(&s);
+}
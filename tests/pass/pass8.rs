@@ -0,0 +1,33 @@
+use std::fmt::Debug;
+use trait_union::trait_union;
+
+trait_union! {
+    union U<'r, 'a: 'r, 'b: 'r>: Debug + 'r = &'a str | &'b [u8];
+}
+
+fn mixed<'r, 'a: 'r, 'b: 'r>(a: &'a str, b: &'b [u8], first: bool) -> U<'r, 'a, 'b> {
+    if first {
+        U::new(a)
+    } else {
+        U::new(b)
+    }
+}
+
+fn shorten<'x, 'a: 'x, 'b: 'x>(u: U<'a, 'a, 'b>) -> U<'x, 'x, 'x> {
+    u
+}
+
+fn deref<'s, 'r>(u: &'s U<'r, '_, '_>) -> &'s (dyn Debug + 'r) {
+    &**u
+}
+
+fn main() {
+    let a = String::new();
+    let b = vec![1u8];
+    let u = mixed(&a, &b, true);
+    let _ = deref(&u);
+    match U::as_ref_enum(&u) {
+        URef::AStr(_) | URef::BU8Slice(_) => {}
+    }
+    let _ = shorten(mixed(&a, &b, false));
+}
@@ -0,0 +1,45 @@
+use std::{cell::Cell, fmt::Debug};
+use trait_union::trait_union;
+
+trait_union! {
+    #[trait_union(covariant('a))]
+    union Covariant<'a>: Debug = &'a u8 | &'a str;
+}
+
+trait_union! {
+    #[trait_union(covariant('a, 'b))]
+    union Mixed<'r, 'a: 'r, 'b: 'r>: Debug + 'r = &'a str | &'b [u8];
+}
+
+trait_union! {
+    #[trait_union(impl = "enum", covariant('a))]
+    union EnumCovariant<'a>: Debug = &'a u8;
+}
+
+trait F {}
+
+impl F for Cell<&'_ mut u8> {}
+
+trait_union! {
+    #[trait_union(invariant('a))]
+    union Invariant<'a>: F = Cell<&'a mut u8>;
+}
+
+trait_union! {
+    #[trait_union(invariant('a, T))]
+    union Generic<'a, T: Debug + 'a>: Debug = &'a T | T;
+}
+
+fn shorten<'x, 'a: 'x>(u: Covariant<'a>) -> Covariant<'x> {
+    u
+}
+
+fn main() {
+    let x = 1;
+    let _ = shorten(Covariant::new(&x));
+    let _ = Mixed::<'_, '_, '_>::new("");
+    let _ = EnumCovariant::new(&x);
+    let mut y = 1;
+    let _ = Invariant::new(Cell::new(&mut y));
+    let _ = Generic::new(x);
+}